@@ -0,0 +1,2 @@
+-- 계정 비활성화(`POST /api/users/{user_id}/deactivate`)에 사용하는 열
+alter table user add column deactivated_at datetime null;
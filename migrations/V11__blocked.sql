@@ -0,0 +1,8 @@
+-- 사용자 차단(`POST /api/users/{user_id}/block`) 관계
+create table if not exists blocked (
+    blocker_id varchar(128) not null,
+    blocked_id varchar(128) not null,
+    create_at datetime not null default current_timestamp,
+    primary key (blocker_id, blocked_id),
+    index blocked_blocked_id (blocked_id)
+);
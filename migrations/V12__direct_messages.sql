@@ -0,0 +1,23 @@
+-- 두 사용자 사이의 대화와 메시지(`POST /api/messages`)
+create table if not exists conversation (
+    conversation_id bigint unsigned not null auto_increment primary key,
+    user_a varchar(128) not null,
+    user_b varchar(128) not null,
+    create_at datetime not null default current_timestamp,
+    updated_at datetime not null default current_timestamp,
+    unique index conversation_users (user_a, user_b),
+    index conversation_user_b (user_b)
+);
+
+create table if not exists message (
+    message_id bigint unsigned not null auto_increment primary key,
+    conversation_id bigint unsigned not null,
+    sender_id varchar(128) not null,
+    data text not null,
+    create_at datetime not null default current_timestamp,
+    read_at datetime null,
+    index message_conversation_id (conversation_id, message_id)
+);
+
+-- 메시지를 받았을 때 메일을 받을지 여부
+alter table user add column notify_messages boolean not null default true;
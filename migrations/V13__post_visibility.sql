@@ -0,0 +1,2 @@
+-- 포스트의 공개 범위(public, unlisted, private)를 저장하는 열
+alter table post add column visibility varchar(16) not null default 'public';
@@ -0,0 +1,10 @@
+-- 관리자가 등록하는 공지(`GET /api/announcements`)를 저장하는 테이블
+create table if not exists announcement (
+    announcement_id bigint unsigned not null auto_increment primary key,
+    title varchar(120) not null,
+    data text not null,
+    starts_at datetime not null default current_timestamp,
+    ends_at datetime null,
+    create_at datetime not null default current_timestamp,
+    index announcement_window (starts_at, ends_at)
+);
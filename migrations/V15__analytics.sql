@@ -0,0 +1,8 @@
+-- 관리자 통계(`GET /api/admin/analytics`)에 사용하는 사용자 가입 시각과 날짜별 집계를 위한 색인
+-- 기존 사용자는 가입 시각을 알 수 없으므로 비워두고, 새로 가입하는 사용자부터 기록한다.
+alter table user add column create_at datetime null;
+alter table user modify column create_at datetime null default current_timestamp;
+alter table user add index user_create_at (create_at);
+alter table post add index post_create_at (create_at);
+alter table comment add index comment_create_at (create_at);
+alter table react add index react_create_at (create_at);
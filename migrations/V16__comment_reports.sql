@@ -0,0 +1,13 @@
+-- 댓글 신고(`POST /api/comments/{comment_id}/report`) 기록과 신고 수
+create table if not exists comment_report (
+    comment_id bigint unsigned not null,
+    user_id varchar(128) not null,
+    reason varchar(16) not null,
+    detail varchar(500) null,
+    create_at datetime not null default current_timestamp,
+    primary key (comment_id, user_id),
+    index comment_report_user_id (user_id)
+);
+
+alter table comment add column report_count bigint unsigned not null default 0;
+create index comment_report_count on comment (report_count);
@@ -0,0 +1,15 @@
+-- 포스트 신고(`POST /api/posts/{post_id}/report`) 기록
+create table if not exists post_report (
+    post_id bigint unsigned not null,
+    user_id varchar(128) not null,
+    reason varchar(16) not null,
+    detail varchar(500) null,
+    create_at datetime not null default current_timestamp,
+    primary key (post_id, user_id),
+    index post_report_user_create_at (user_id, create_at)
+);
+
+-- 사용자별 시간당 신고 수를 세기 위한 인덱스
+alter table comment_report
+    drop index comment_report_user_id,
+    add index comment_report_user_create_at (user_id, create_at);
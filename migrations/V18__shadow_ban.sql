@@ -0,0 +1,2 @@
+-- 섀도 밴(`POST /api/admin/users/{user_id}/shadow-ban`) 여부
+alter table user add column shadow_banned boolean not null default false;
@@ -0,0 +1,9 @@
+-- 웹 클라이언트의 로그인 세션(`POST /api/auth/session`). 세션 토큰은 SHA-256 해시로만 저장한다.
+create table if not exists session (
+    session_id char(64) not null primary key,
+    user_id varchar(128) not null,
+    expires_at datetime not null,
+    create_at datetime not null default current_timestamp,
+    index session_user_id (user_id),
+    index session_expires_at (expires_at)
+);
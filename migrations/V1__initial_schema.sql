@@ -0,0 +1,115 @@
+create table if not exists user (
+    user_id varchar(128) not null primary key,
+    user_name varchar(64) not null,
+    banned_until datetime null,
+    is_moderator boolean not null default false,
+    likes_public boolean not null default true,
+    bio text null,
+    avatar_url varchar(512) null,
+    website varchar(512) null,
+    preferred_language varchar(30) null,
+    reputation bigint not null default 0
+);
+
+create table if not exists post (
+    post_id bigint unsigned not null auto_increment primary key,
+    user_id varchar(128) not null,
+    title varchar(255) not null,
+    language varchar(30) not null,
+    data mediumtext not null,
+    likes bigint unsigned not null default 0,
+    report_count bigint unsigned not null default 0,
+    views bigint unsigned not null default 0,
+    trending_score double not null default 0,
+    post_type varchar(16) not null default 'snippet',
+    accepted_comment_id bigint unsigned null,
+    publish_at datetime null,
+    published boolean not null default true,
+    deleted_at datetime null,
+    version bigint unsigned not null default 0,
+    create_at datetime not null default current_timestamp,
+    updated_at datetime null,
+    index post_user_id (user_id),
+    index post_trending_score (trending_score)
+);
+
+create table if not exists comment (
+    comment_id bigint unsigned not null auto_increment primary key,
+    post_id bigint unsigned not null,
+    user_id varchar(128) not null,
+    data text not null,
+    version bigint unsigned not null default 0,
+    create_at datetime not null default current_timestamp,
+    updated_at datetime null,
+    index comment_post_id (post_id),
+    index comment_user_id (user_id)
+);
+
+create table if not exists react (
+    user_id varchar(128) not null,
+    post_id bigint unsigned not null,
+    react_kind varchar(16) not null,
+    create_at datetime not null default current_timestamp,
+    primary key (user_id, post_id, react_kind),
+    index react_post_id (post_id)
+);
+
+create table if not exists upload (
+    upload_id bigint unsigned not null auto_increment primary key,
+    user_id varchar(128) not null,
+    storage_key varchar(512) not null,
+    bytes bigint unsigned not null,
+    create_at datetime not null default current_timestamp,
+    index upload_user_id (user_id)
+);
+
+create table if not exists shortlink (
+    code varchar(16) not null primary key,
+    post_id bigint unsigned not null unique,
+    clicks bigint unsigned not null default 0,
+    create_at datetime not null default current_timestamp
+);
+
+create table if not exists follow (
+    follower_id varchar(128) not null,
+    followee_id varchar(128) not null,
+    create_at datetime not null default current_timestamp,
+    primary key (follower_id, followee_id),
+    index follow_followee_id (followee_id)
+);
+
+create table if not exists post_revision (
+    post_id bigint unsigned not null,
+    revision int unsigned not null,
+    title varchar(255) not null,
+    language varchar(30) not null,
+    data mediumtext not null,
+    create_at datetime not null default current_timestamp,
+    primary key (post_id, revision)
+);
+
+create table if not exists language_subscription (
+    user_id varchar(128) not null,
+    language varchar(30) not null,
+    primary key (user_id, language)
+);
+
+create table if not exists bookmark (
+    user_id varchar(128) not null,
+    post_id bigint unsigned not null,
+    create_at datetime not null default current_timestamp,
+    primary key (user_id, post_id),
+    index bookmark_post_id (post_id)
+);
+
+create table if not exists tag (
+    tag_id bigint unsigned not null auto_increment primary key,
+    name varchar(30) not null unique
+);
+
+create table if not exists post_tag (
+    post_id bigint unsigned not null,
+    tag_id bigint unsigned not null,
+    primary key (post_id, tag_id),
+    index post_tag_tag_id (tag_id)
+);
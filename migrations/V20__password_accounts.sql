@@ -0,0 +1,6 @@
+-- 이메일과 비밀번호로 가입한 계정(`POST /api/auth/register`)의 argon2 해시와 로그인 실패 기록
+alter table user
+    add column password_hash varchar(255) null,
+    add column failed_logins int unsigned not null default 0,
+    add column locked_until datetime null,
+    add index user_email (email);
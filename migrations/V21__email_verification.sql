@@ -0,0 +1,2 @@
+-- 이메일 주소 인증 여부. 외부 인증 서비스로 가입한 기존 사용자는 인증된 것으로 처리한다.
+alter table user add column verified boolean not null default true;
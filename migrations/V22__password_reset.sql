@@ -0,0 +1,9 @@
+-- 비밀번호 재설정 토큰(`POST /api/auth/forgot`). 토큰은 SHA-256 해시로만 저장하며, 사용하면 지운다.
+create table if not exists password_reset (
+    token_hash char(64) not null primary key,
+    user_id varchar(128) not null,
+    expires_at datetime not null,
+    create_at datetime not null default current_timestamp,
+    index password_reset_user_id (user_id),
+    index password_reset_expires_at (expires_at)
+);
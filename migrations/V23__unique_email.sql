@@ -0,0 +1,4 @@
+-- 로그인과 알림에 사용하는 이메일 주소는 계정마다 달라야 한다. 같은 주소를 사용하는 계정이 있는 경우 먼저 정리해야 한다.
+alter table user
+    drop index user_email,
+    add unique index user_email (email);
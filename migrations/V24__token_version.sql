@@ -0,0 +1,2 @@
+-- 인증 토큰 버전. 비밀번호를 재설정하면 1 증가하여 이전에 발급된 토큰은 사용할 수 없게 된다.
+alter table user add column token_version int unsigned not null default 0;
@@ -0,0 +1,23 @@
+create table if not exists webhook (
+    webhook_id bigint unsigned not null auto_increment primary key,
+    url varchar(2048) not null,
+    secret varchar(64) not null,
+    events varchar(255) not null,
+    create_at datetime not null default current_timestamp
+);
+
+create table if not exists webhook_delivery (
+    delivery_id bigint unsigned not null auto_increment primary key,
+    webhook_id bigint unsigned not null,
+    event varchar(30) not null,
+    payload mediumtext not null,
+    status varchar(10) not null default 'pending',
+    attempts int unsigned not null default 0,
+    next_attempt_at datetime not null default current_timestamp,
+    response_status smallint unsigned,
+    last_error varchar(1024),
+    create_at datetime not null default current_timestamp,
+    delivered_at datetime,
+    index webhook_delivery_webhook_id (webhook_id),
+    index webhook_delivery_status (status, next_attempt_at)
+);
@@ -0,0 +1,2 @@
+-- 연동 API를 통해 봇이 작성한 포스트를 구분하기 위한 열
+alter table post add column bot boolean not null default false;
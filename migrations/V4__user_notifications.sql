@@ -0,0 +1,4 @@
+-- 메일 알림을 받을 주소와 알림 종류별 수신 여부
+alter table user add column email varchar(254) null;
+alter table user add column notify_comments boolean not null default true;
+alter table user add column notify_post_removed boolean not null default true;
@@ -0,0 +1,15 @@
+-- 관리자가 등록한 금칙어
+create table if not exists blocked_word (
+    word varchar(64) not null primary key,
+    create_at datetime not null default current_timestamp
+);
+
+-- 금칙어가 발견되어 관리자의 검토가 필요한 포스트와 댓글
+create table if not exists flagged_content (
+    flag_id bigint unsigned not null auto_increment primary key,
+    content_type varchar(16) not null,
+    content_id bigint unsigned not null,
+    words varchar(1024) not null,
+    create_at datetime not null default current_timestamp,
+    index flagged_content_content (content_type, content_id)
+);
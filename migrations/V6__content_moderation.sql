@@ -0,0 +1,3 @@
+-- 외부 검토 서비스의 판정(approved, pending, rejected)
+alter table post add column moderation varchar(10) not null default 'approved';
+alter table comment add column moderation varchar(10) not null default 'approved';
@@ -0,0 +1,9 @@
+-- 포스트 내용의 주소에서 읽어온 링크 미리보기(OpenGraph 메타데이터)
+-- 읽어오지 못한 주소도 다시 요청하지 않도록 모든 항목이 비어있는 행으로 저장한다.
+create table if not exists link_preview (
+    url varchar(512) not null primary key,
+    title varchar(300),
+    description varchar(1000),
+    image varchar(512),
+    fetched_at datetime not null default current_timestamp
+);
@@ -0,0 +1,12 @@
+-- 포스트에 첨부한 이미지와 파일
+create table if not exists attachment (
+    attachment_id bigint unsigned not null auto_increment primary key,
+    post_id bigint unsigned not null,
+    user_id varchar(128) not null,
+    file_name varchar(255) not null,
+    content_type varchar(64) not null,
+    bytes bigint unsigned not null,
+    url varchar(1024) not null,
+    create_at datetime not null default current_timestamp,
+    index attachment_post_id (post_id)
+);
@@ -0,0 +1,3 @@
+-- 검색어 자동 완성(`GET /api/search/suggest`)의 접두사 검색에 사용하는 인덱스
+alter table post add index post_title (title);
+alter table user add index user_user_name (user_name);
@@ -0,0 +1,162 @@
+//! # 사용자 활동 관련 동작을 정의하는 모듈
+//!
+//! `activity`는 프로필 화면에서 사용자의 최근 활동을 한 번에 보여주기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 최근 활동은 사용자가 작성한 포스트와 댓글, 사용자의 포스트가 받은 공감을 시간순으로 합친 목록이다.
+//! 누구나 볼 수 있는 목록이므로 공개된 포스트와 관련된 활동만 포함하며,
+//! 공감한 포스트 목록을 비공개로 설정한 사용자의 공감은 포함하지 않는다.
+
+use crate::auth::Identity;
+use crate::block::BlockList;
+use crate::db;
+use crate::error::ApiError;
+use crate::pagination::Pagination;
+use crate::repository::UserRepository;
+use crate::response;
+use crate::shadow_ban::ShadowBans;
+use crate::user;
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+
+/// 댓글 활동에 포함되는 댓글 내용의 최대 글자 수이다.
+const EXCERPT_CHARS: u32 = 200;
+
+/// 활동의 종류이다.
+#[derive(Serialize, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityKind {
+    /// 포스트를 작성한 경우
+    Post,
+    /// 댓글을 작성한 경우
+    Comment,
+    /// 작성한 포스트가 공감을 받은 경우
+    Like,
+}
+
+impl ActivityKind {
+    /// DB에서 읽은 문자열을 활동의 종류로 바꾸는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::activity::ActivityKind;
+    /// assert_eq!(ActivityKind::from_db("comment"), Some(ActivityKind::Comment));
+    /// assert_eq!(ActivityKind::from_db("bookmark"), None);
+    /// ```
+    pub fn from_db(kind: &str) -> Option<Self> {
+        match kind {
+            "post" => Some(Self::Post),
+            "comment" => Some(Self::Comment),
+            "like" => Some(Self::Like),
+            _ => None,
+        }
+    }
+}
+
+/// 사용자의 최근 활동 하나이다.
+#[derive(Serialize)]
+pub struct Activity {
+    /// 활동의 종류이다.
+    pub kind: ActivityKind,
+    /// 활동과 관련된 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 활동과 관련된 포스트의 제목이다.
+    pub post_title: String,
+    /// 작성한 댓글의 고유 ID이다. 댓글 활동이 아닌 경우 `null`이다.
+    pub comment_id: Option<u64>,
+    /// 활동한 사용자의 고유 ID이다. 공감 활동인 경우 공감한 사용자이다.
+    pub actor_id: String,
+    /// 댓글 내용의 앞부분이다. 댓글 활동이 아닌 경우 `null`이다.
+    pub excerpt: Option<String>,
+    /// 활동한 날짜 및 시간이다.
+    pub create_at: DateTime<Utc>,
+}
+
+impl Activity {
+    /// `user_id` 사용자의 최근 활동들을 최신순으로 반환하는 메서드이다.
+    ///
+    /// 삭제되었거나 공개되지 않은 포스트, 검토를 통과하지 않은 포스트와 댓글, 본인이 누른 공감은 포함되지 않는다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get(user_id: &str, page: &Pagination) -> Vec<Self> {
+        let mut conn = db::get_read_conn();
+        conn.exec_map(
+            r"select 'post' as kind, post_id, title, null as comment_id, user_id as actor_id,
+            null as excerpt, date_format(create_at, '%Y-%m-%d %H:%i:%s') as created
+            from post where user_id = :user_id and published and visibility = 'public'
+            and deleted_at is null and moderation = 'approved'
+            union all
+            select 'comment', post.post_id, post.title, comment.comment_id, comment.user_id,
+            left(comment.data, :excerpt_chars), date_format(comment.create_at, '%Y-%m-%d %H:%i:%s')
+            from comment join post on post.post_id = comment.post_id
+            where comment.user_id = :user_id and comment.moderation = 'approved'
+            and post.published and post.visibility = 'public' and post.deleted_at is null
+            union all
+            select 'like', post.post_id, post.title, null, react.user_id,
+            null, date_format(react.create_at, '%Y-%m-%d %H:%i:%s')
+            from react join post on post.post_id = react.post_id
+            join user liker on liker.user_id = react.user_id
+            where post.user_id = :user_id and react.react_kind = 'like' and react.user_id <> :user_id
+            and post.published and post.visibility = 'public' and post.deleted_at is null
+            and liker.likes_public and liker.deactivated_at is null
+            order by created desc
+            limit :limit offset :offset",
+            params! {
+                "user_id" => user_id,
+                "excerpt_chars" => EXCERPT_CHARS,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(kind, post_id, post_title, comment_id, actor_id, excerpt, create_at): (
+                String,
+                u64,
+                String,
+                Option<u64>,
+                String,
+                Option<String>,
+                String,
+            )| {
+                ActivityKind::from_db(&kind).map(|kind| Activity {
+                    kind,
+                    post_id,
+                    post_title,
+                    comment_id,
+                    actor_id,
+                    excerpt,
+                    create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+                })
+            },
+        )
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+#[get("/api/users/{user_id}/activity")]
+pub async fn get_user_activity_api(
+    req: HttpRequest,
+    user_id: web::Path<String>,
+    page: web::Query<Pagination>,
+    users: web::Data<dyn UserRepository>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if users.is_deactivated(&user_id) || users.get(&user_id).is_none() {
+        return Err(user::user_not_found());
+    }
+    let viewer_id = Identity::from_header(&req);
+    let mut activities = Activity::get(&user_id, &page);
+    BlockList::load(users.get_ref(), viewer_id.as_deref())
+        .extend(shadow_bans.hidden_from(viewer_id.as_deref()))
+        .retain(&mut activities, |activity| &activity.actor_id);
+    Ok(response::json(activities))
+}
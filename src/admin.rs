@@ -0,0 +1,617 @@
+//! # 관리자 전용 동작을 정의하는 모듈
+//!
+//! `admin`은 코드뮤니티에서 관리자만 수행할 수 있는 작업을 처리하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 관리자 API는 `ADMIN_TOKEN` 환경변수가 지정된 경우에만 사용할 수 있으며,
+//! 요청의 `X-Admin-Token` 헤더 값이 `ADMIN_TOKEN`과 일치해야 한다.
+
+use crate::analytics::{Analytics, AnalyticsRequest};
+use crate::announcement::{Announcement, AnnouncementRequest};
+use crate::backup;
+use crate::cache::Cache;
+use crate::comment::{self, Comment};
+use crate::error::ApiError;
+use crate::geo::RegionStats;
+use crate::import;
+use crate::leaderboard::Leaderboard;
+use crate::maintenance::Maintenance;
+use crate::moderation::{self, Verdict};
+use crate::pagination::Pagination;
+use crate::post::{self, Post};
+use crate::reindex::{Reindex, Step};
+use crate::report::{QueueActionRequest, Report};
+use crate::response;
+use crate::sanitize;
+use crate::search_index::SearchIndex;
+use crate::shadow_ban::ShadowBans;
+use crate::trending::Trending;
+use crate::user::{self, User};
+use crate::validation::is_datetime;
+use crate::webhook::{Webhook, WebhookRequest};
+use crate::word_filter::{self, ContentKind, WordFilter};
+use actix_web::middleware::Compress;
+use actix_web::web::{BytesMut, Json};
+use actix_web::{delete, get, post, rt, web, HttpRequest, HttpResponse};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::env;
+
+/// 포스트 가져오기와 백업 복원 요청 본문의 기본 최대 크기(바이트)이다.
+const DEFAULT_IMPORT_MAX_BYTES: usize = 16 * 1024 * 1024;
+
+/// 요청이 관리자에 의해 보내진 요청인지 확인하는 메서드이다.
+///
+/// `ADMIN_TOKEN` 환경변수가 지정되지 않았거나 비어있는 경우 항상 `false`를 반환하므로
+/// 관리자 API를 사용할 수 없게 된다.
+/// # 예제
+/// ```
+/// use actix_web::test::TestRequest;
+/// use code_mmunity_server::admin;
+/// std::env::set_var("ADMIN_TOKEN", "secret");
+/// let req = TestRequest::default().insert_header(("X-Admin-Token", "secret")).to_http_request();
+/// assert!(admin::is_admin(&req));
+/// let req = TestRequest::default().insert_header(("X-Admin-Token", "guess")).to_http_request();
+/// assert!(!admin::is_admin(&req));
+/// std::env::set_var("ADMIN_TOKEN", "");
+/// let req = TestRequest::default().insert_header(("X-Admin-Token", "")).to_http_request();
+/// assert!(!admin::is_admin(&req));
+/// ```
+pub fn is_admin(req: &HttpRequest) -> bool {
+    match env::var("ADMIN_TOKEN") {
+        Ok(token) if !token.is_empty() => req
+            .headers()
+            .get("X-Admin-Token")
+            .is_some_and(|value| constant_time_eq(value.as_bytes(), token.as_bytes())),
+        _ => false,
+    }
+}
+
+/// 두 값이 같은지 비교하는 데 걸리는 시간으로 토큰을 추측할 수 없도록 비교하는 메서드이다.
+///
+/// 길이가 달라도 같은 시간이 걸리도록 두 값의 SHA-256 해시를 모든 바이트에 걸쳐 비교한다.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    Sha256::digest(a)
+        .iter()
+        .zip(Sha256::digest(b).iter())
+        .fold(0, |difference, (x, y)| difference | (x ^ y))
+        == 0
+}
+
+/// JSON 을 통해 사용자 이용 정지 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct BanRequest {
+    /// 이용 정지가 해제되는 날짜이다. `YYYY-MM-DD HH:MM:SS`형식이며 생략하면 영구 정지된다.
+    pub until: Option<String>,
+}
+
+#[post("/api/admin/users/{user_id}/ban")]
+pub async fn ban_user_api(
+    req: HttpRequest,
+    user_id: web::Path<String>,
+    request: Json<BanRequest>,
+    cache: web::Data<Cache>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    let until = request.into_inner().until;
+    if !until.as_deref().is_none_or(is_datetime) {
+        return Err(ApiError::Validation(
+            "until은 YYYY-MM-DD HH:MM:SS 형식이어야 합니다.".to_string(),
+        ));
+    }
+    if !User::ban(&user_id, until)? {
+        return Err(user::user_not_found());
+    }
+    cache.invalidate_user(&user_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/api/admin/users/{user_id}/ban")]
+pub async fn unban_user_api(
+    req: HttpRequest,
+    user_id: web::Path<String>,
+    cache: web::Data<Cache>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    User::unban(&user_id)?;
+    cache.invalidate_user(&user_id);
+    Ok(response::no_content())
+}
+
+#[get("/api/admin/users/shadow-banned")]
+pub async fn get_shadow_bans_api(
+    req: HttpRequest,
+    shadow_bans: web::Data<ShadowBans>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(shadow_bans.list()))
+}
+
+#[post("/api/admin/users/{user_id}/shadow-ban")]
+pub async fn shadow_ban_user_api(
+    req: HttpRequest,
+    user_id: web::Path<String>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    if !shadow_bans.add(&user_id)? {
+        return Err(user::user_not_found());
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/api/admin/users/{user_id}/shadow-ban")]
+pub async fn unshadow_ban_user_api(
+    req: HttpRequest,
+    user_id: web::Path<String>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    shadow_bans.remove(&user_id)?;
+    Ok(response::no_content())
+}
+
+#[get("/api/admin/stats/regions")]
+pub async fn get_region_stats_api(
+    req: HttpRequest,
+    region_stats: web::Data<RegionStats>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(region_stats.snapshot()))
+}
+
+#[get("/api/admin/stats/database")]
+pub async fn get_database_stats_api(
+    req: HttpRequest,
+    maintenance: web::Data<Maintenance>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(maintenance.last_report()))
+}
+
+#[get("/api/admin/analytics")]
+pub async fn get_analytics_api(
+    req: HttpRequest,
+    request: web::Query<AnalyticsRequest>,
+    analytics: web::Data<Analytics>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    let days = request.days()?;
+    let counts = web::block(move || analytics.get(days))
+        .await
+        .map_err(|error| ApiError::Unavailable(error.to_string()))??;
+    Ok(response::json(counts))
+}
+
+#[get("/api/admin/export", wrap = "Compress::default()")]
+pub async fn export_backup_api(req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(HttpResponse::Ok()
+        .insert_header(("Content-Type", backup::NDJSON_CONTENT_TYPE))
+        .insert_header((
+            "Content-Disposition",
+            "attachment; filename=\"code_mmunity_backup.ndjson\"",
+        ))
+        .streaming(backup::ndjson()))
+}
+
+#[post("/api/admin/maintenance/reindex")]
+pub async fn start_reindex_api(
+    req: HttpRequest,
+    reindex: web::Data<Reindex>,
+    leaderboard: web::Data<Leaderboard>,
+    maintenance: web::Data<Maintenance>,
+    search_index: web::Data<SearchIndex>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    let leaderboard = leaderboard.into_inner();
+    let maintenance = maintenance.into_inner();
+    let search_index = search_index.into_inner();
+    let steps: Vec<Step> = vec![
+        ("statistics", Box::new(move || maintenance.run())),
+        ("trending", Box::new(Trending::refresh)),
+        ("leaderboard", Box::new(move || leaderboard.clear())),
+        ("search_index", Box::new(move || search_index.rebuild())),
+    ];
+    if !reindex.start(steps) {
+        return Err(ApiError::Conflict(
+            "이미 재구성 작업이 실행 중입니다.".to_string(),
+        ));
+    }
+    Ok(HttpResponse::Accepted()
+        .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+        .json(reindex.status()))
+}
+
+#[get("/api/admin/maintenance/reindex")]
+pub async fn get_reindex_status_api(
+    req: HttpRequest,
+    reindex: web::Data<Reindex>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(reindex.status()))
+}
+
+/// 포스트 가져오기와 백업 복원 요청 본문의 최대 크기(바이트)를 반환하는 메서드이다.
+///
+/// `IMPORT_MAX_BYTES` 환경변수가 주어지지 않았거나 올바르지 않은 경우 16MiB를 반환한다.
+fn import_max_bytes() -> usize {
+    env::var("IMPORT_MAX_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_IMPORT_MAX_BYTES)
+}
+
+/// 가져올 내용을 담은 요청 본문을 최대 크기까지 읽는 메서드이다.
+///
+/// 본문을 읽을 수 없는 경우 `ApiError::BadRequest`를, 최대 크기를 넘는 경우 `ApiError::RequestTooLarge`를 반환한다.
+async fn read_import_body(mut payload: web::Payload) -> Result<BytesMut, ApiError> {
+    let limit = import_max_bytes();
+    let mut body = BytesMut::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|error| ApiError::BadRequest(error.to_string()))?;
+        body.extend_from_slice(&chunk);
+        if body.len() > limit {
+            return Err(ApiError::RequestTooLarge(limit));
+        }
+    }
+    Ok(body)
+}
+
+#[post("/api/admin/import/posts")]
+pub async fn import_posts_api(
+    req: HttpRequest,
+    payload: web::Payload,
+    cache: web::Data<Cache>,
+    search_index: web::Data<SearchIndex>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    let body = read_import_body(payload).await?;
+    let items = import::split_items(&body).map_err(ApiError::BadRequest)?;
+    let summary = web::block(move || import::run(items))
+        .await
+        .map_err(|error| ApiError::Unavailable(error.to_string()))?;
+    if summary.imported > 0 {
+        cache.invalidate_feed();
+    }
+    for post_id in summary.results.iter().filter_map(|result| result.post_id) {
+        search_index.sync(post_id);
+    }
+    Ok(response::json(summary))
+}
+
+/// 쿼리 스트링을 통해 백업 복원 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct RestoreRequest {
+    /// 시험 실행 여부이다. `true`인 경우 복원 결과만 확인하고 기록한 내용은 되돌린다.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[post("/api/admin/import")]
+pub async fn restore_backup_api(
+    req: HttpRequest,
+    request: web::Query<RestoreRequest>,
+    payload: web::Payload,
+    cache: web::Data<Cache>,
+    search_index: web::Data<SearchIndex>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    let body = read_import_body(payload).await?;
+    let items = import::split_items(&body).map_err(ApiError::BadRequest)?;
+    let dry_run = request.dry_run;
+    let summary = web::block(move || backup::restore(items, dry_run))
+        .await
+        .map_err(|error| ApiError::Unavailable(error.to_string()))??;
+    if !dry_run && summary.restored > 0 {
+        cache.invalidate_feed();
+    }
+    // 복원된 행이 어느 포스트의 것인지 알 수 없으므로 포스트나 작성자가 복원된 경우 검색 색인을 다시 만든다.
+    if !dry_run && (summary.tables.contains_key("post") || summary.tables.contains_key("user")) {
+        let search_index = search_index.into_inner();
+        rt::spawn(async move {
+            if web::block(move || search_index.rebuild()).await.is_err() {
+                tracing::error!("복원 후 검색 색인을 다시 만들지 못했습니다.");
+            }
+        });
+    }
+    Ok(response::json(summary))
+}
+
+#[post("/api/admin/webhooks")]
+pub async fn register_webhook_api(
+    req: HttpRequest,
+    request: Json<WebhookRequest>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    if !(request.url.starts_with("http://") || request.url.starts_with("https://"))
+        || request.events.is_empty()
+    {
+        return Err(ApiError::Validation(
+            "웹훅 주소는 http:// 또는 https://로 시작해야 하며 이벤트를 하나 이상 구독해야 합니다."
+                .to_string(),
+        ));
+    }
+    Ok(HttpResponse::Created()
+        .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+        .json(Webhook::register(&request)?))
+}
+
+#[get("/api/admin/webhooks")]
+pub async fn get_webhooks_api(req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(Webhook::list()))
+}
+
+#[delete("/api/admin/webhooks/{webhook_id}")]
+pub async fn delete_webhook_api(
+    req: HttpRequest,
+    webhook_id: web::Path<u64>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    if !Webhook::remove(*webhook_id)? {
+        return Err(ApiError::NotFound(
+            "요청한 webhook_id는 존재하지 않는 웹훅 입니다.".to_string(),
+        ));
+    }
+    Ok(response::no_content())
+}
+
+#[get("/api/admin/webhooks/{webhook_id}/deliveries")]
+pub async fn get_webhook_deliveries_api(
+    req: HttpRequest,
+    webhook_id: web::Path<u64>,
+    page: web::Query<Pagination>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(Webhook::deliveries(*webhook_id, &page)))
+}
+
+#[post("/api/admin/announcements")]
+pub async fn create_announcement_api(
+    req: HttpRequest,
+    request: Json<AnnouncementRequest>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    let mut request = request.into_inner();
+    sanitize::clean(&mut request.title);
+    sanitize::clean(&mut request.data);
+    request.validate()?;
+    Ok(HttpResponse::Created()
+        .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+        .json(Announcement::create(&request)?))
+}
+
+#[get("/api/admin/announcements")]
+pub async fn get_all_announcements_api(req: HttpRequest) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(Announcement::list()))
+}
+
+#[delete("/api/admin/announcements/{announcement_id}")]
+pub async fn delete_announcement_api(
+    req: HttpRequest,
+    announcement_id: web::Path<u64>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    if !Announcement::remove(*announcement_id)? {
+        return Err(ApiError::NotFound(
+            "요청한 announcement_id는 존재하지 않는 공지 입니다.".to_string(),
+        ));
+    }
+    Ok(response::no_content())
+}
+
+/// JSON 을 통해 금칙어 등록 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct BlockedWordRequest {
+    /// 등록할 금칙어이다. 대소문자를 구분하지 않으며 글자와 숫자로만 이루어져야 한다.
+    pub word: String,
+}
+
+#[get("/api/admin/word-filter")]
+pub async fn get_word_filter_api(
+    req: HttpRequest,
+    word_filter: web::Data<WordFilter>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(word_filter.settings()))
+}
+
+#[post("/api/admin/word-filter/words")]
+pub async fn add_blocked_word_api(
+    req: HttpRequest,
+    request: Json<BlockedWordRequest>,
+    word_filter: web::Data<WordFilter>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    let word = word_filter::normalize(&request.word).ok_or_else(|| {
+        ApiError::Validation(format!(
+            "금칙어는 {}자 이하의 글자와 숫자로만 이루어져야 합니다.",
+            word_filter::MAX_WORD_CHARS
+        ))
+    })?;
+    if word_filter.add(&word)? {
+        Ok(HttpResponse::Created().finish())
+    } else {
+        Ok(HttpResponse::Ok().finish())
+    }
+}
+
+#[delete("/api/admin/word-filter/words/{word}")]
+pub async fn remove_blocked_word_api(
+    req: HttpRequest,
+    word: web::Path<String>,
+    word_filter: web::Data<WordFilter>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    if !word_filter.remove(&word.to_lowercase())? {
+        return Err(ApiError::NotFound(
+            "요청한 금칙어는 등록되어 있지 않습니다.".to_string(),
+        ));
+    }
+    Ok(response::no_content())
+}
+
+#[get("/api/admin/word-filter/flagged")]
+pub async fn get_flagged_content_api(
+    req: HttpRequest,
+    page: web::Query<Pagination>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(WordFilter::flagged(&page)))
+}
+
+/// JSON 을 통해 콘텐츠의 검토 판정 변경 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct VerdictRequest {
+    /// 새 판정(`approved`, `pending`, `rejected`)이다.
+    pub verdict: Verdict,
+}
+
+#[post("/api/admin/moderation/posts/{post_id}")]
+pub async fn set_post_verdict_api(
+    req: HttpRequest,
+    post_id: web::Path<u64>,
+    request: Json<VerdictRequest>,
+    cache: web::Data<Cache>,
+    search_index: web::Data<SearchIndex>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    if Post::get_post_type(*post_id).is_none() {
+        return Err(post::post_not_found());
+    }
+    moderation::set_verdict(ContentKind::Post, *post_id, request.verdict)?;
+    Report::resolve(ContentKind::Post, *post_id)?;
+    cache.invalidate_post(*post_id);
+    search_index.sync(*post_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[post("/api/admin/moderation/comments/{comment_id}")]
+pub async fn set_comment_verdict_api(
+    req: HttpRequest,
+    comment_id: web::Path<u64>,
+    request: Json<VerdictRequest>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    if Comment::get_owner(*comment_id).is_none() {
+        return Err(comment::comment_not_found());
+    }
+    moderation::set_verdict(ContentKind::Comment, *comment_id, request.verdict)?;
+    Report::resolve(ContentKind::Comment, *comment_id)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/api/admin/moderation/comments")]
+pub async fn get_comment_queue_api(
+    req: HttpRequest,
+    page: web::Query<Pagination>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(Report::comment_queue(&page)))
+}
+
+#[get("/api/admin/moderation/queue")]
+pub async fn get_moderation_queue_api(
+    req: HttpRequest,
+    page: web::Query<Pagination>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(Report::queue(&page)))
+}
+
+#[post("/api/admin/moderation/queue/posts/{post_id}")]
+pub async fn resolve_queued_post_api(
+    req: HttpRequest,
+    post_id: web::Path<u64>,
+    request: Json<QueueActionRequest>,
+    cache: web::Data<Cache>,
+    search_index: web::Data<SearchIndex>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    if Post::get_post_type(*post_id).is_none() {
+        return Err(post::post_not_found());
+    }
+    moderation::set_verdict(ContentKind::Post, *post_id, request.action.verdict())?;
+    Report::resolve(ContentKind::Post, *post_id)?;
+    cache.invalidate_post(*post_id);
+    search_index.sync(*post_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[post("/api/admin/moderation/queue/comments/{comment_id}")]
+pub async fn resolve_queued_comment_api(
+    req: HttpRequest,
+    comment_id: web::Path<u64>,
+    request: Json<QueueActionRequest>,
+) -> Result<HttpResponse, ApiError> {
+    if !is_admin(&req) {
+        return Err(ApiError::Forbidden);
+    }
+    if Comment::get_owner(*comment_id).is_none() {
+        return Err(comment::comment_not_found());
+    }
+    moderation::set_verdict(ContentKind::Comment, *comment_id, request.action.verdict())?;
+    Report::resolve(ContentKind::Comment, *comment_id)?;
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,163 @@
+//! # 관리자 통계 관련 동작을 정의하는 모듈
+//!
+//! `analytics`는 관리자가 DB에 직접 접속하지 않고도 커뮤니티의 성장 추이를 확인할 수 있도록
+//! 날짜별로 새로 작성된 포스트와 댓글, 새로 가입한 사용자, 새로 받은 공감의 수를 집계하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 집계 쿼리는 비용이 크기 때문에 결과를 `ANALYTICS_CACHE_SECONDS`초(기본값 300초)동안
+//! 메모리에 저장해두고 재사용한다. 집계 결과는 `admin` 모듈의 관리자 API로 조회한다.
+
+use crate::db;
+use crate::error::ApiError;
+use chrono::{NaiveDate, Utc};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 기간을 지정하지 않은 경우 집계하는 날짜 수이다.
+pub const DEFAULT_DAYS: u32 = 30;
+/// 한 번에 집계할 수 있는 최대 날짜 수이다.
+pub const MAX_DAYS: u32 = 365;
+/// 집계 결과를 저장해두는 기본 시간(초)이다.
+const DEFAULT_CACHE_SECONDS: u64 = 300;
+
+/// 쿼리 스트링을 통해 통계 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct AnalyticsRequest {
+    /// 오늘을 포함하여 집계할 최근 날짜 수이다. 생략하면 `DEFAULT_DAYS`일을 집계한다.
+    pub days: Option<u32>,
+}
+
+impl AnalyticsRequest {
+    /// 집계할 날짜 수를 반환하는 메서드이다.
+    ///
+    /// 날짜 수가 1 이상 `MAX_DAYS` 이하가 아닌 경우 `ApiError::Validation`을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::analytics::{AnalyticsRequest, DEFAULT_DAYS};
+    /// assert_eq!(AnalyticsRequest { days: None }.days().unwrap(), DEFAULT_DAYS);
+    /// assert_eq!(AnalyticsRequest { days: Some(7) }.days().unwrap(), 7);
+    /// assert!(AnalyticsRequest { days: Some(0) }.days().is_err());
+    /// assert!(AnalyticsRequest { days: Some(1000) }.days().is_err());
+    /// ```
+    pub fn days(&self) -> std::result::Result<u32, ApiError> {
+        match self.days.unwrap_or(DEFAULT_DAYS) {
+            days @ 1..=MAX_DAYS => Ok(days),
+            _ => Err(ApiError::Validation(format!(
+                "days는 1 이상 {} 이하여야 합니다.",
+                MAX_DAYS
+            ))),
+        }
+    }
+}
+
+/// 하루 동안 새로 생긴 항목들의 수이다.
+#[derive(Serialize, Clone, Default)]
+pub struct DailyCount {
+    /// 집계한 날짜(`YYYY-MM-DD`)이다.
+    pub date: String,
+    /// 새로 작성된 포스트 수이다.
+    pub posts: u64,
+    /// 새로 작성된 댓글 수이다.
+    pub comments: u64,
+    /// 새로 가입한 사용자 수이다.
+    pub users: u64,
+    /// 새로 받은 공감 수이다.
+    pub likes: u64,
+}
+
+/// 날짜별 집계에 사용하는 테이블과 조건, 집계 결과를 담을 항목이다.
+type Source = (&'static str, &'static str, fn(&mut DailyCount) -> &mut u64);
+
+/// 날짜별로 집계할 항목들이다.
+const SOURCES: [Source; 4] = [
+    ("post", "true", |count| &mut count.posts),
+    ("comment", "true", |count| &mut count.comments),
+    ("user", "true", |count| &mut count.users),
+    ("react", "react_kind = 'like'", |count| &mut count.likes),
+];
+
+/// 통계 집계 결과를 저장해두는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+#[derive(Default)]
+pub struct Analytics {
+    cache: Mutex<HashMap<u32, CachedCounts>>,
+}
+
+/// 집계된 시각과 집계 결과이다.
+type CachedCounts = (Instant, Vec<DailyCount>);
+
+impl Analytics {
+    /// 집계 결과를 저장해두는 시간을 반환하는 메서드이다.
+    fn cache_duration() -> Duration {
+        Duration::from_secs(
+            env::var("ANALYTICS_CACHE_SECONDS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_CACHE_SECONDS),
+        )
+    }
+    /// 오늘을 포함한 최근 `days`일의 날짜별 집계 결과를 오래된 날짜부터 반환하는 메서드이다.
+    ///
+    /// 저장된 집계 결과가 유효한 경우 DB에 접속하지 않고 저장된 결과를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get(&self, days: u32) -> Result<Vec<DailyCount>> {
+        if let Some((created, counts)) = self.cache.lock().unwrap().get(&days) {
+            if created.elapsed() < Self::cache_duration() {
+                return Ok(counts.clone());
+            }
+        }
+        let counts = Self::query(days)?;
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(days, (Instant::now(), counts.clone()));
+        Ok(counts)
+    }
+    /// DB에서 날짜별 항목 수를 집계하는 메서드이다. 항목이 없는 날짜는 0으로 채운다.
+    fn query(days: u32) -> Result<Vec<DailyCount>> {
+        let mut conn = db::get_read_conn();
+        let today = conn
+            .query_first::<String, _>("select date_format(curdate(), '%Y-%m-%d')")?
+            .and_then(|today| NaiveDate::parse_from_str(&today, "%Y-%m-%d").ok())
+            .unwrap_or_else(|| Utc::now().date_naive());
+        let mut counts: BTreeMap<String, DailyCount> = (0..days)
+            .map(|before| {
+                let date = (today - chrono::Duration::days(before.into()))
+                    .format("%Y-%m-%d")
+                    .to_string();
+                let count = DailyCount {
+                    date: date.clone(),
+                    ..Default::default()
+                };
+                (date, count)
+            })
+            .collect();
+        for (table, condition, field) in SOURCES {
+            let rows: Vec<(String, u64)> = conn.query(format!(
+                r"select date_format(create_at, '%Y-%m-%d') as day, count(*) from {}
+                where create_at >= curdate() - interval {} day and {}
+                group by day",
+                table,
+                days - 1,
+                condition
+            ))?;
+            for (date, count) in rows {
+                if let Some(daily) = counts.get_mut(&date) {
+                    *field(daily) = count;
+                }
+            }
+        }
+        Ok(counts.into_values().collect())
+    }
+}
@@ -0,0 +1,206 @@
+//! # 공지 관련 동작을 정의하는 모듈
+//!
+//! `announcement`는 관리자가 등록한 공지를 클라이언트의 배너로 보여주기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 공지는 일반 포스트와 따로 저장되며, 게시 시작 시각부터 종료 시각 전까지만 `GET /api/announcements`로 반환된다.
+//! 클라이언트는 사용자가 닫은 공지의 `announcement_id`를 기억해두고 다시 보여주지 않으면 된다.
+//! 공지의 등록과 삭제는 `admin` 모듈의 관리자 API로 처리한다.
+
+use crate::db;
+use crate::error::ApiError;
+use crate::response;
+use crate::sanitize;
+use crate::validation::{is_datetime, FieldErrors, MAX_ANNOUNCEMENT_CHARS};
+use actix_web::{get, HttpResponse};
+use chrono::{DateTime, Utc};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+
+/// 관리자가 등록한 공지이다.
+#[derive(Serialize)]
+pub struct Announcement {
+    /// 공지의 고유 ID이다.
+    pub announcement_id: u64,
+    /// 공지의 제목이다.
+    #[serde(with = "sanitize::escaped")]
+    pub title: String,
+    /// 공지의 내용이다.
+    #[serde(with = "sanitize::escaped")]
+    pub data: String,
+    /// 공지를 보여주기 시작하는 날짜 및 시간이다.
+    pub starts_at: DateTime<Utc>,
+    /// 공지를 더 이상 보여주지 않는 날짜 및 시간이다. `None`인 경우 삭제할 때까지 보여준다.
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// JSON 을 통해 등록할 공지를 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct AnnouncementRequest {
+    /// 공지의 제목이다.
+    pub title: String,
+    /// 공지의 내용이다.
+    pub data: String,
+    /// 공지를 보여주기 시작하는 시각(`YYYY-MM-DD HH:MM:SS`)이다. 주어지지 않은 경우 바로 보여준다.
+    #[serde(default)]
+    pub starts_at: Option<String>,
+    /// 공지를 더 이상 보여주지 않는 시각(`YYYY-MM-DD HH:MM:SS`)이다. 주어지지 않은 경우 삭제할 때까지 보여준다.
+    #[serde(default)]
+    pub ends_at: Option<String>,
+}
+
+impl AnnouncementRequest {
+    /// 등록할 공지의 입력값이 조건에 맞는지 확인하는 메서드이다.
+    ///
+    /// 종료 시각은 시작 시각보다 늦어야 한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::announcement::AnnouncementRequest;
+    /// let request = AnnouncementRequest {
+    ///     title: "서버 점검 안내".to_string(),
+    ///     data: "10월 20일 새벽 2시부터 30분간 점검합니다.".to_string(),
+    ///     starts_at: Some("2022-10-18 00:00:00".to_string()),
+    ///     ends_at: Some("2022-10-20 02:30:00".to_string()),
+    /// };
+    /// assert!(request.validate().is_ok());
+    /// let request = AnnouncementRequest { ends_at: Some("2022-10-17 00:00:00".to_string()), ..request };
+    /// let details = request.validate().unwrap_err().details().unwrap();
+    /// assert!(details.get("ends_at").is_some());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), ApiError> {
+        let mut errors = FieldErrors::default();
+        errors.check_title(&self.title);
+        errors.check_data(&self.data, MAX_ANNOUNCEMENT_CHARS);
+        for (field, value) in [("starts_at", &self.starts_at), ("ends_at", &self.ends_at)] {
+            errors.check(
+                field,
+                value.as_deref().is_none_or(is_datetime),
+                format!("{}은 YYYY-MM-DD HH:MM:SS 형식이어야 합니다.", field),
+            );
+        }
+        if let (Some(starts_at), Some(ends_at)) = (&self.starts_at, &self.ends_at) {
+            errors.check(
+                "ends_at",
+                ends_at > starts_at,
+                "ends_at은 starts_at보다 늦어야 합니다.",
+            );
+        }
+        errors.into_result()
+    }
+}
+
+/// 조회한 행을 공지로 바꾸는 메서드이다.
+fn from_row(
+    (announcement_id, title, data, starts_at, ends_at): (
+        u64,
+        String,
+        String,
+        String,
+        Option<String>,
+    ),
+) -> Announcement {
+    Announcement {
+        announcement_id,
+        title,
+        data,
+        starts_at: db::parse_datetime(&starts_at).unwrap_or_default(),
+        ends_at: ends_at.as_deref().and_then(db::parse_datetime),
+    }
+}
+
+impl Announcement {
+    /// 공지를 등록하고, 등록된 공지를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn create(request: &AnnouncementRequest) -> Result<Self> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"insert into announcement(title, data, starts_at, ends_at)
+            values(:title, :data, coalesce(:starts_at, now()), :ends_at)",
+            params! {
+                "title" => &request.title,
+                "data" => &request.data,
+                "starts_at" => &request.starts_at,
+                "ends_at" => &request.ends_at,
+            },
+        )?;
+        let announcement_id = conn.last_insert_id();
+        Ok(conn
+            .exec_first(
+                r"select announcement_id, title, data, date_format(starts_at, '%Y-%m-%d %H:%i:%s'),
+                date_format(ends_at, '%Y-%m-%d %H:%i:%s')
+                from announcement where announcement_id = :announcement_id",
+                params! {
+                    "announcement_id" => announcement_id,
+                },
+            )?
+            .map(from_row)
+            .expect("등록한 공지를 찾을 수 없습니다."))
+    }
+    /// 현재 보여줄 공지들을 최근에 시작한 순서로 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_active() -> Vec<Self> {
+        let mut conn = db::get_read_conn();
+        conn.query_map(
+            r"select announcement_id, title, data, date_format(starts_at, '%Y-%m-%d %H:%i:%s'),
+            date_format(ends_at, '%Y-%m-%d %H:%i:%s')
+            from announcement where starts_at <= now() and (ends_at is null or ends_at > now())
+            order by starts_at desc, announcement_id desc",
+            from_row,
+        )
+        .unwrap()
+    }
+    /// 예약되었거나 종료된 공지를 포함한 모든 공지를 최근에 등록한 순서로 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn list() -> Vec<Self> {
+        let mut conn = db::get_conn();
+        conn.query_map(
+            r"select announcement_id, title, data, date_format(starts_at, '%Y-%m-%d %H:%i:%s'),
+            date_format(ends_at, '%Y-%m-%d %H:%i:%s')
+            from announcement order by announcement_id desc",
+            from_row,
+        )
+        .unwrap()
+    }
+    /// 공지를 삭제하는 메서드이다. 존재하지 않는 공지인 경우 `Ok(false)`를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn remove(announcement_id: u64) -> Result<bool> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "delete from announcement where announcement_id = :announcement_id",
+            params! {
+                "announcement_id" => announcement_id,
+            },
+        )?;
+        Ok(conn.affected_rows() > 0)
+    }
+}
+
+#[get("/api/announcements")]
+pub async fn get_announcements_api() -> HttpResponse {
+    response::json(Announcement::get_active())
+}
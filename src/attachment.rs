@@ -0,0 +1,305 @@
+//! # 포스트 첨부 파일 관련 동작을 정의하는 모듈
+//!
+//! `attachment`는 사용자가 코드와 함께 보여줄 스크린샷이나 파일을 포스트에 첨부할 때
+//! 파일을 검사하고 저장소에 저장하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 첨부 파일은 `POST /api/posts/{post_id}/attachments`에 `multipart/form-data`의 `file` 필드로 올리며,
+//! 프로필 이미지와 같은 `Storage`에 저장되고 업로드 용량(`QUOTA_UPLOAD_BYTES`)에 포함된다.
+//! 첨부된 파일들은 단일 포스트를 조회할 때 `attachments` 항목으로 함께 반환된다.
+
+use crate::auth::Identity;
+use crate::avatar::ImageKind;
+use crate::cache::Cache;
+use crate::db;
+use crate::error::ApiError;
+use crate::post::post_not_found;
+use crate::repository::PostRepository;
+use crate::response;
+use crate::storage::Storage;
+use crate::usage::Usage;
+use actix_multipart::Multipart;
+use actix_web::web::BytesMut;
+use actix_web::{post, web, HttpResponse};
+use futures_util::StreamExt;
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use uuid::Uuid;
+
+/// 첨부 파일 크기의 기본 최대값(5MB)이다.
+const DEFAULT_MAX_BYTES: usize = 5 * 1024 * 1024;
+/// 포스트 하나에 첨부할 수 있는 최대 파일 수이다.
+pub const MAX_ATTACHMENTS: usize = 10;
+/// 첨부 파일 이름의 최대 글자 수이다.
+const MAX_FILE_NAME_CHARS: usize = 255;
+
+/// 첨부할 수 있는 파일 형식이다.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FileKind {
+    /// PNG, JPEG, GIF, WebP 이미지
+    Image(ImageKind),
+    /// PDF 문서
+    Pdf,
+    /// UTF-8 텍스트 파일(로그, 설정 파일 등)
+    Text,
+}
+
+impl FileKind {
+    /// 파일 내용을 통해 파일 형식을 확인하는 메서드이다.
+    ///
+    /// 클라이언트가 보낸 `Content-Type`과 파일 이름은 신뢰할 수 없으므로 실제 파일 내용으로 형식을 판단한다.
+    /// 널 문자가 없는 UTF-8 파일은 텍스트 파일로 보며, 지원하지 않는 형식인 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::attachment::FileKind;
+    /// use code_mmunity_server::avatar::ImageKind;
+    /// assert_eq!(FileKind::detect(b"\x89PNG\r\n\x1a\n...."), Some(FileKind::Image(ImageKind::Png)));
+    /// assert_eq!(FileKind::detect(b"%PDF-1.7\n..."), Some(FileKind::Pdf));
+    /// assert_eq!(FileKind::detect("error: 빌드 실패".as_bytes()), Some(FileKind::Text));
+    /// assert_eq!(FileKind::detect(b"\x7fELF\x02\x01\x01\x00"), None);
+    /// ```
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if let Some(kind) = ImageKind::detect(bytes) {
+            Some(Self::Image(kind))
+        } else if bytes.starts_with(b"%PDF-") {
+            Some(Self::Pdf)
+        } else if !bytes.contains(&0) && std::str::from_utf8(bytes).is_ok() {
+            Some(Self::Text)
+        } else {
+            None
+        }
+    }
+    /// 파일 형식에 맞는 확장자를 반환하는 메서드이다.
+    ///
+    /// 저장소의 파일 경로에 사용하며, 로컬 디스크 저장소는 확장자로 `Content-Type`을 정하므로
+    /// 텍스트 파일이 HTML로 해석되지 않도록 원래 파일 이름의 확장자는 사용하지 않는다.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Image(kind) => kind.extension(),
+            Self::Pdf => "pdf",
+            Self::Text => "txt",
+        }
+    }
+    /// 파일 형식에 맞는 MIME 타입을 반환하는 메서드이다.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Image(kind) => kind.mime_type(),
+            Self::Pdf => "application/pdf",
+            Self::Text => "text/plain; charset=utf-8",
+        }
+    }
+}
+
+/// 포스트에 첨부된 파일이다.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Attachment {
+    /// 첨부 파일의 고유 ID이다.
+    pub attachment_id: u64,
+    /// 사용자가 올린 파일의 이름이다.
+    pub file_name: String,
+    /// 파일의 MIME 타입이다.
+    pub content_type: String,
+    /// 파일의 크기(바이트)이다.
+    pub bytes: u64,
+    /// 저장된 파일의 주소이다.
+    pub url: String,
+}
+
+/// 첨부 파일 크기의 최대값을 반환하는 메서드이다.
+///
+/// `ATTACHMENT_MAX_BYTES` 환경변수로 변경할 수 있으며 기본값은 5MB이다.
+pub fn max_bytes() -> usize {
+    env::var("ATTACHMENT_MAX_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_BYTES)
+}
+
+/// 사용자가 보낸 파일 이름을 저장할 수 있는 이름으로 바꾸는 메서드이다.
+///
+/// 경로를 제외한 파일 이름만 남기고 제어 문자를 제거하며, 남은 이름이 없는 경우 `None`을 반환한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::attachment::clean_file_name;
+/// assert_eq!(clean_file_name("C:\\Users\\sun\\스크린샷.png").as_deref(), Some("스크린샷.png"));
+/// assert_eq!(clean_file_name("../../etc/passwd\n").as_deref(), Some("passwd"));
+/// assert_eq!(clean_file_name(" / "), None);
+/// ```
+pub fn clean_file_name(file_name: &str) -> Option<String> {
+    let file_name: String = file_name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_FILE_NAME_CHARS)
+        .collect();
+    let file_name = file_name.trim();
+    (!file_name.is_empty()).then(|| file_name.to_string())
+}
+
+impl Attachment {
+    /// 포스트에 첨부된 파일들을 첨부한 순서대로 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn list(post_id: u64) -> Vec<Self> {
+        let mut conn = db::get_read_conn();
+        conn.exec_map(
+            r"select attachment_id, file_name, content_type, bytes, url from attachment
+            where post_id = :post_id order by attachment_id",
+            params! {
+                "post_id" => post_id,
+            },
+            |(attachment_id, file_name, content_type, bytes, url)| Self {
+                attachment_id,
+                file_name,
+                content_type,
+                bytes,
+                url,
+            },
+        )
+        .unwrap()
+    }
+    /// 포스트에 첨부된 파일 수를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn count(post_id: u64) -> usize {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            "select count(*) from attachment where post_id = :post_id",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()
+        .unwrap_or_default()
+    }
+    /// 포스트에 첨부된 파일을 기록하고, 고유 ID가 채워진 첨부 파일을 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn insert(post_id: u64, user_id: &str, attachment: Self) -> Result<Self> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"insert into attachment(post_id, user_id, file_name, content_type, bytes, url)
+            values(:post_id, :user_id, :file_name, :content_type, :bytes, :url)",
+            params! {
+                "post_id" => post_id,
+                "user_id" => user_id,
+                "file_name" => &attachment.file_name,
+                "content_type" => &attachment.content_type,
+                "bytes" => attachment.bytes,
+                "url" => &attachment.url,
+            },
+        )?;
+        Ok(Self {
+            attachment_id: conn.last_insert_id(),
+            ..attachment
+        })
+    }
+}
+
+#[post("/api/posts/{post_id}/attachments")]
+pub async fn upload_attachment_api(
+    identity: Identity,
+    post_id: web::Path<u64>,
+    mut payload: Multipart,
+    storage: web::Data<Storage>,
+    cache: web::Data<Cache>,
+    posts: web::Data<dyn PostRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let post_id = post_id.into_inner();
+    let owner = posts
+        .get_owner(&post_id.to_string())
+        .ok_or_else(post_not_found)?;
+    if !identity.can_modify(&owner) {
+        return Err(ApiError::Forbidden);
+    }
+    if Attachment::count(post_id) >= MAX_ATTACHMENTS {
+        return Err(ApiError::Validation(format!(
+            "포스트 하나에 파일을 {}개까지 첨부할 수 있습니다.",
+            MAX_ATTACHMENTS
+        )));
+    }
+    let limit = max_bytes();
+    let mut file = BytesMut::new();
+    let mut file_name = None;
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|error| ApiError::BadRequest(error.to_string()))?;
+        if field.name() != Some("file") {
+            continue;
+        }
+        file_name = field
+            .content_disposition()
+            .and_then(|disposition| disposition.get_filename())
+            .and_then(clean_file_name);
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|error| ApiError::BadRequest(error.to_string()))?;
+            file.extend_from_slice(&chunk);
+            if file.len() > limit {
+                return Err(ApiError::RequestTooLarge(limit));
+            }
+        }
+        break;
+    }
+    if file.is_empty() {
+        return Err(ApiError::BadRequest(
+            "file 필드에 파일이 없습니다.".to_string(),
+        ));
+    }
+    let kind = FileKind::detect(&file).ok_or_else(|| {
+        ApiError::Validation(
+            "PNG, JPEG, GIF, WebP 이미지와 PDF, 텍스트 파일만 첨부할 수 있습니다.".to_string(),
+        )
+    })?;
+    let size = file.len() as u64;
+    let key = format!(
+        "attachments/{}/{}.{}",
+        post_id,
+        Uuid::new_v4(),
+        kind.extension()
+    );
+    if !Usage::reserve_upload(&identity.user_id, &key, size)? {
+        return Err(ApiError::PayloadTooLarge);
+    }
+    let url = match storage.put(&key, file.freeze(), kind.mime_type()).await {
+        Ok(url) => url,
+        Err(error) => {
+            tracing::error!(%error, key = key.as_str(), "첨부 파일을 저장하지 못했습니다.");
+            Usage::remove_upload(&key)?;
+            return Err(ApiError::Unavailable(
+                "파일을 저장할 수 없습니다. 잠시 후 다시 시도해주세요.".to_string(),
+            ));
+        }
+    };
+    let attachment = Attachment::insert(
+        post_id,
+        &identity.user_id,
+        Attachment {
+            attachment_id: 0,
+            file_name: file_name.unwrap_or_else(|| format!("file.{}", kind.extension())),
+            content_type: kind.mime_type().to_string(),
+            bytes: size,
+            url,
+        },
+    )?;
+    cache.invalidate_post(post_id);
+    Ok(HttpResponse::Created()
+        .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+        .json(attachment))
+}
@@ -0,0 +1,158 @@
+//! # 사용자 인증 관련 동작을 정의하는 모듈
+//!
+//! `auth`는 코드뮤니티에서 요청을 보낸 사용자가 누구인지 확인하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 클라이언트는 `Authorization: Bearer <토큰>` 헤더에 `AUTH_SECRET` 환경변수로 서명된
+//! JWT(HS256)를 담아 요청해야 하며, 토큰의 `sub`에는 사용자의 고유 ID가 들어있어야 한다.
+//! 토큰의 `ver`는 사용자의 토큰 버전과 같아야 하며, 비밀번호를 재설정하면 이전에 발급된 토큰은 사용할 수 없다.
+//! 웹 클라이언트는 헤더 대신 `session` 모듈에서 발급한 세션 쿠키로 요청할 수 있다.
+
+use crate::error::ApiError;
+use crate::logging::RequestUser;
+use crate::repository::UserRepository;
+use crate::session::Session;
+use actix_web::dev::Payload;
+use actix_web::{web, FromRequest, HttpMessage, HttpRequest};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::future::{ready, Ready};
+
+/// 인증 토큰에 담기는 정보이다.
+#[derive(Deserialize, Serialize)]
+pub struct Claims {
+    /// 토큰을 발급받은 사용자의 고유 ID이다.
+    pub sub: String,
+    /// 토큰이 만료되는 시각(UNIX 시간)이다.
+    pub exp: u64,
+    /// 토큰을 발급할 때 사용자의 토큰 버전이다. 주어지지 않은 경우 0이다.
+    ///
+    /// 비밀번호를 재설정하면 사용자의 토큰 버전이 바뀌므로 이전에 발급된 토큰은 사용할 수 없다.
+    #[serde(default)]
+    pub ver: u32,
+}
+
+/// 인증된 사용자를 나타내는 객체이다.
+///
+/// 핸들러의 인자로 사용하면 `Authorization` 헤더의 토큰이나 세션 쿠키를 검증한 뒤 생성되며,
+/// 토큰이 없거나 올바르지 않은 경우, 또는 계정을 비활성화한 사용자인 경우 `401 Unauthorized`를 응답한다.
+/// 운영자 여부는 앱 상태로 등록된 `UserRepository`를 통해 확인한다.
+/// # 예제
+/// ```ignore
+/// #[delete("/api/posts")]
+/// pub async fn delete_post_api(identity: Identity) -> impl Responder {
+///     println!("{}님의 요청입니다.", identity.user_id);
+///     HttpResponse::Ok()
+/// }
+/// ```
+pub struct Identity {
+    /// 인증된 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 인증된 사용자가 운영자인지 여부이다.
+    pub is_moderator: bool,
+}
+
+impl Identity {
+    /// 인증 토큰을 검증하여 토큰에 담긴 정보를 반환하는 메서드이다.
+    ///
+    /// `AUTH_SECRET` 환경변수가 지정되지 않았거나 토큰이 올바르지 않은 경우 `None`을 반환한다.
+    fn decode_token(token: &str) -> Option<Claims> {
+        let secret = env::var("AUTH_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty())?;
+        decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        )
+        .ok()
+        .map(|data| data.claims)
+    }
+    /// 인증 토큰을 검증하여 토큰에 담긴 사용자의 고유 ID를 반환하는 메서드이다.
+    ///
+    /// `AUTH_SECRET` 환경변수가 지정되지 않았거나 토큰이 올바르지 않은 경우 `None`을 반환한다.
+    /// 토큰 버전은 확인하지 않으므로 요청을 보낸 사용자를 인증할 때는 `from_header`를 사용해야 한다.
+    pub fn verify_token(token: &str) -> Option<String> {
+        Identity::decode_token(token).map(|claims| claims.sub)
+    }
+    /// `user_id` 사용자의 인증 토큰을 `exp`(UNIX 시간)까지 유효하도록 발급하는 메서드이다.
+    ///
+    /// 이메일과 비밀번호로 로그인한 사용자에게 토큰을 줄 때 사용하며, `token_version`에는 사용자의 현재 토큰 버전을 넘긴다.
+    /// `AUTH_SECRET` 환경변수가 지정되지 않은 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::auth::Identity;
+    /// std::env::set_var("AUTH_SECRET", "secret");
+    /// let token = Identity::issue_token("unique_id_for_user", 0, 4102444800).unwrap();
+    /// assert_eq!(Identity::verify_token(&token).as_deref(), Some("unique_id_for_user"));
+    /// ```
+    pub fn issue_token(user_id: &str, token_version: u32, exp: u64) -> Option<String> {
+        let secret = env::var("AUTH_SECRET")
+            .ok()
+            .filter(|secret| !secret.is_empty())?;
+        encode(
+            &Header::new(Algorithm::HS256),
+            &Claims {
+                sub: user_id.to_string(),
+                exp,
+                ver: token_version,
+            },
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .ok()
+    }
+    /// 요청의 `Authorization` 헤더에 담긴 토큰을 검증하여 토큰에 담긴 사용자의 고유 ID를 반환하는 메서드이다.
+    ///
+    /// 토큰의 버전이 앱 상태로 등록된 `UserRepository`에 저장된 사용자의 토큰 버전과 다른 경우,
+    /// 즉 비밀번호를 재설정하기 전에 발급된 토큰인 경우 `None`을 반환한다.
+    /// 헤더가 없는 경우 세션 쿠키를 확인하여 세션의 사용자 고유 ID를 반환한다.
+    ///
+    /// 계정의 상태는 확인하지 않으므로, 비활성화한 계정을 다시 활성화하는 요청과 같이
+    /// `Identity`를 사용할 수 없는 경우에만 사용한다.
+    pub fn from_header(req: &HttpRequest) -> Option<String> {
+        match req.headers().get("Authorization") {
+            Some(value) => {
+                let claims = value
+                    .to_str()
+                    .ok()
+                    .and_then(|value| value.strip_prefix("Bearer "))
+                    .and_then(Identity::decode_token)?;
+                let users = req.app_data::<web::Data<dyn UserRepository>>();
+                if users.is_some_and(|users| users.token_version(&claims.sub) != claims.ver) {
+                    return None;
+                }
+                Some(claims.sub)
+            }
+            None => Session::from_cookie(req),
+        }
+    }
+    /// 인증된 사용자가 `owner_id`의 사용자가 작성한 콘텐츠를 수정하거나 삭제할 수 있는지 확인하는 메서드이다.
+    ///
+    /// 콘텐츠의 작성자 본인이거나 운영자인 경우 `true`를 반환한다.
+    pub fn can_modify(&self, owner_id: &str) -> bool {
+        self.user_id == owner_id || self.is_moderator
+    }
+}
+
+impl FromRequest for Identity {
+    type Error = ApiError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(match Identity::from_header(req) {
+            Some(user_id) => {
+                req.extensions_mut().insert(RequestUser(user_id.clone()));
+                let users = req.app_data::<web::Data<dyn UserRepository>>();
+                if users.is_some_and(|users| users.is_deactivated(&user_id)) {
+                    return ready(Err(ApiError::Unauthorized));
+                }
+                Ok(Identity {
+                    is_moderator: users.is_some_and(|users| users.is_moderator(&user_id)),
+                    user_id,
+                })
+            }
+            None => Err(ApiError::Unauthorized),
+        })
+    }
+}
@@ -0,0 +1,177 @@
+//! # 프로필 이미지 관련 동작을 정의하는 모듈
+//!
+//! `avatar`는 코드뮤니티에서 사용자가 프로필 이미지를 올릴 때 이미지를 검사하고
+//! 저장소에 저장하기 위한 요소 및 메서드들로 이루어져 있다.
+
+use crate::auth::Identity;
+use crate::cache::Cache;
+use crate::error::ApiError;
+use crate::response;
+use crate::storage::Storage;
+use crate::usage::Usage;
+use crate::user::{UpdateProfileRequest, User};
+use actix_multipart::Multipart;
+use actix_web::web::BytesMut;
+use actix_web::{post, web, HttpResponse};
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::env;
+use uuid::Uuid;
+
+/// 프로필 이미지 크기의 기본 최대값(2MB)이다.
+const DEFAULT_MAX_BYTES: usize = 2 * 1024 * 1024;
+/// 저장소 안에서 프로필 이미지가 저장되는 경로의 앞부분이다.
+const KEY_PREFIX: &str = "avatars/";
+
+/// 프로필 이미지로 사용할 수 있는 이미지 형식이다.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ImageKind {
+    /// PNG 이미지
+    Png,
+    /// JPEG 이미지
+    Jpeg,
+    /// GIF 이미지
+    Gif,
+    /// WebP 이미지
+    Webp,
+}
+
+impl ImageKind {
+    /// 파일의 앞부분을 통해 이미지 형식을 확인하는 메서드이다.
+    ///
+    /// 클라이언트가 보낸 `Content-Type`은 신뢰할 수 없으므로 실제 파일 내용으로 형식을 판단한다.
+    /// 지원하지 않는 형식인 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::avatar::ImageKind;
+    /// assert_eq!(ImageKind::detect(b"\x89PNG\r\n\x1a\n...."), Some(ImageKind::Png));
+    /// assert_eq!(ImageKind::detect(b"<svg></svg>"), None);
+    /// ```
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if bytes.starts_with(b"\xFF\xD8\xFF") {
+            Some(Self::Jpeg)
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Some(Self::Gif)
+        } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            Some(Self::Webp)
+        } else {
+            None
+        }
+    }
+    /// 이미지 형식에 맞는 확장자를 반환하는 메서드이다.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Gif => "gif",
+            Self::Webp => "webp",
+        }
+    }
+    /// 이미지 형식에 맞는 MIME 타입을 반환하는 메서드이다.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+        }
+    }
+}
+
+/// 프로필 이미지 업로드 결과를 반환할 때 사용하는 구조체이다.
+#[derive(Serialize)]
+pub struct AvatarResponse {
+    /// 저장된 프로필 이미지의 주소이다.
+    pub avatar_url: String,
+}
+
+/// 프로필 이미지 크기의 최대값을 반환하는 메서드이다.
+///
+/// `AVATAR_MAX_BYTES` 환경변수로 변경할 수 있으며 기본값은 2MB이다.
+pub fn max_bytes() -> usize {
+    env::var("AVATAR_MAX_BYTES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_BYTES)
+}
+
+/// 새 프로필 이미지로 바뀐 이전 프로필 이미지를 저장소와 업로드 기록에서 지우는 메서드이다.
+///
+/// 저장소에서 지우지 못한 경우 업로드 기록을 남겨두어 다음에 프로필 이미지를 바꿀 때 다시 지운다.
+async fn remove_previous(storage: &Storage, key: &str) -> std::result::Result<(), ApiError> {
+    match storage.delete(key).await {
+        Ok(()) | Err(object_store::Error::NotFound { .. }) => Usage::remove_upload(key)?,
+        Err(error) => tracing::warn!(%error, key, "이전 프로필 이미지를 지우지 못했습니다."),
+    }
+    Ok(())
+}
+
+#[post("/api/users/{user_id}/avatar")]
+pub async fn upload_avatar_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    mut payload: Multipart,
+    storage: web::Data<Storage>,
+    cache: web::Data<Cache>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if !identity.can_modify(&user_id) {
+        return Err(ApiError::Forbidden);
+    }
+    let limit = max_bytes();
+    let mut image = BytesMut::new();
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|error| ApiError::BadRequest(error.to_string()))?;
+        if field.name() != Some("avatar") {
+            continue;
+        }
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|error| ApiError::BadRequest(error.to_string()))?;
+            image.extend_from_slice(&chunk);
+            if image.len() > limit {
+                return Err(ApiError::RequestTooLarge(limit));
+            }
+        }
+        break;
+    }
+    if image.is_empty() {
+        return Err(ApiError::BadRequest(
+            "avatar 필드에 이미지가 없습니다.".to_string(),
+        ));
+    }
+    let kind = ImageKind::detect(&image).ok_or_else(|| {
+        ApiError::Validation("PNG, JPEG, GIF, WebP 이미지만 사용할 수 있습니다.".to_string())
+    })?;
+    let key = format!("{}{}.{}", KEY_PREFIX, Uuid::new_v4(), kind.extension());
+    if !Usage::reserve_upload(&user_id, &key, image.len() as u64)? {
+        return Err(ApiError::PayloadTooLarge);
+    }
+    let avatar_url = match storage.put(&key, image.freeze(), kind.mime_type()).await {
+        Ok(url) => url,
+        Err(error) => {
+            tracing::error!(%error, key = key.as_str(), "프로필 이미지를 저장하지 못했습니다.");
+            Usage::remove_upload(&key)?;
+            return Err(ApiError::Unavailable(
+                "프로필 이미지를 저장할 수 없습니다. 잠시 후 다시 시도해주세요.".to_string(),
+            ));
+        }
+    };
+    let previous_keys = Usage::get_keys(&user_id, KEY_PREFIX)?;
+    let request = UpdateProfileRequest {
+        user_name: None,
+        bio: None,
+        avatar_url: Some(avatar_url.clone()),
+        website: None,
+        preferred_language: None,
+    };
+    User::update_profile(&user_id, request)?;
+    cache.invalidate_user(&user_id);
+    for previous_key in previous_keys
+        .iter()
+        .filter(|previous_key| **previous_key != key)
+    {
+        remove_previous(&storage, previous_key).await?;
+    }
+    Ok(response::json(AvatarResponse { avatar_url }))
+}
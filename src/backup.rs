@@ -0,0 +1,390 @@
+//! # 관리자 데이터 백업 관련 동작을 정의하는 모듈
+//!
+//! `backup`은 MySQL 서버에 직접 접속할 수 없는 소규모 배포 환경에서도 관리자가 DB의 논리 백업을 받을 수 있도록
+//! 모든 테이블의 행을 NDJSON(한 줄에 JSON 객체 하나) 형식으로 내보내기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 각 줄은 `{"table": "post", "row": {"post_id": 1, ...}}` 형태이며, 테이블은 이름 순서로 기록된다.
+//! 모든 테이블은 하나의 읽기 전용 트랜잭션에서 읽으므로 내보내는 도중에 변경된 내용은 포함되지 않는다.
+//! `export` 모듈과 같이 DB에서 한 행씩 읽어 바로 응답으로 흘려보내므로 데이터가 많아도 메모리를 많이 사용하지 않는다.
+//!
+//! 내보낸 파일은 다른 서버에서 다시 복원할 수 있다. 복원할 행은 `BATCH_SIZE`개씩 하나의 트랜잭션으로 기록되며,
+//! 기본 키가 같은 행이 이미 있는 경우 백업의 값으로 덮어쓴다. 시험 실행(dry run)은 같은 작업을 한 뒤 트랜잭션을 되돌린다.
+
+use crate::db;
+use crate::import::ImportItem;
+use actix_web::web::Bytes;
+use futures_util::{stream, Stream};
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+use serde_json::{json, Map, Value as JsonValue};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io;
+use std::thread;
+use tokio::sync::mpsc;
+
+/// 백업 응답의 `Content-Type` 헤더 값이다.
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+/// 하나의 트랜잭션에서 복원할 최대 행 수이다.
+const BATCH_SIZE: usize = 500;
+/// 복원 결과에 담는 최대 오류 수이다.
+const MAX_REPORTED_ERRORS: usize = 100;
+/// 복원하지 않는 테이블이다. 마이그레이션 기록은 복원할 서버에 적용된 스키마를 따라야 한다.
+const SKIPPED_TABLE: &str = "schema_migrations";
+
+/// 테이블 이름별 열 이름들이다.
+pub type Schema = HashMap<String, HashSet<String>>;
+
+/// DB의 값을 JSON 값으로 바꾸는 메서드이다.
+///
+/// 날짜 및 시간은 `YYYY-MM-DD HH:MM:SS` 형식의 문자열로, 문자열과 바이너리 값은 UTF-8 문자열로 바꾼다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::backup::to_json;
+/// use mysql::Value;
+/// use serde_json::json;
+/// assert_eq!(to_json(&Value::NULL), json!(null));
+/// assert_eq!(to_json(&Value::Int(-3)), json!(-3));
+/// assert_eq!(to_json(&Value::Bytes(b"rust".to_vec())), json!("rust"));
+/// assert_eq!(to_json(&Value::Date(2022, 10, 17, 9, 5, 0, 0)), json!("2022-10-17 09:05:00"));
+/// ```
+pub fn to_json(value: &Value) -> JsonValue {
+    match value {
+        Value::NULL => JsonValue::Null,
+        Value::Bytes(bytes) => json!(String::from_utf8_lossy(bytes)),
+        Value::Int(value) => json!(value),
+        Value::UInt(value) => json!(value),
+        Value::Float(value) => json!(value),
+        Value::Double(value) => json!(value),
+        Value::Date(year, month, day, hour, minute, second, micros) => {
+            let mut date = format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                year, month, day, hour, minute, second
+            );
+            if *micros > 0 {
+                date.push_str(&format!(".{:06}", micros));
+            }
+            json!(date)
+        }
+        Value::Time(negative, days, hours, minutes, seconds, _) => json!(format!(
+            "{}{:02}:{:02}:{:02}",
+            if *negative { "-" } else { "" },
+            *days * 24 + u32::from(*hours),
+            minutes,
+            seconds
+        )),
+    }
+}
+
+/// 내보낸 줄들을 응답으로 전송하는 객체이다.
+struct BackupWriter {
+    sender: mpsc::Sender<io::Result<Bytes>>,
+}
+
+impl BackupWriter {
+    /// 한 줄을 전송하는 메서드이다.
+    ///
+    /// 클라이언트의 연결이 끊어진 경우 `false`를 반환한다.
+    fn write(&self, line: String) -> bool {
+        self.sender.blocking_send(Ok(Bytes::from(line))).is_ok()
+    }
+    /// 모든 테이블의 행을 한 줄씩 전송하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    fn write_tables(&self) -> Result<()> {
+        let mut conn = db::get_read_conn();
+        let mut tx = conn.start_transaction(
+            TxOpts::default()
+                .set_with_consistent_snapshot(true)
+                .set_access_mode(Some(AccessMode::ReadOnly)),
+        )?;
+        let tables: Vec<String> = tx.query(
+            r"select table_name from information_schema.tables
+            where table_schema = database() and table_type = 'BASE TABLE'
+            order by table_name",
+        )?;
+        for table in tables {
+            let rows = tx.exec_iter(format!("select * from `{}`", table.replace('`', "``")), ())?;
+            for row in rows {
+                let row = row?;
+                let mut object = Map::new();
+                for (index, column) in row.columns_ref().iter().enumerate() {
+                    let value = row.as_ref(index).map_or(JsonValue::Null, to_json);
+                    object.insert(column.name_str().into_owned(), value);
+                }
+                let line = json!({ "table": table, "row": object }).to_string() + "\n";
+                if !self.write(line) {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 모든 테이블을 NDJSON 형식으로 내보내는 응답 본문을 반환하는 메서드이다.
+///
+/// DB 작업은 별도의 스레드에서 실행되며, 도중에 오류가 발생하면 응답을 중단한다.
+pub fn ndjson() -> impl Stream<Item = io::Result<Bytes>> {
+    let (sender, receiver) = mpsc::channel(64);
+    thread::spawn(move || {
+        let writer = BackupWriter { sender };
+        if let Err(error) = writer.write_tables() {
+            tracing::error!(%error, "DB 백업을 내보내지 못했습니다.");
+            let _ = writer.sender.blocking_send(Err(io::Error::other(error)));
+        }
+    });
+    stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|line| (line, receiver))
+    })
+}
+
+/// JSON 값을 DB의 값으로 바꾸는 메서드이다.
+///
+/// 배열과 객체는 JSON 문자열로, `true`와 `false`는 1과 0으로 바꾼다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::backup::from_json;
+/// use mysql::Value;
+/// use serde_json::json;
+/// assert_eq!(from_json(&json!(null)), Value::NULL);
+/// assert_eq!(from_json(&json!(true)), Value::Int(1));
+/// assert_eq!(from_json(&json!(-3)), Value::Int(-3));
+/// assert_eq!(from_json(&json!("rust")), Value::Bytes(b"rust".to_vec()));
+/// assert_eq!(from_json(&json!(["a"])), Value::Bytes(b"[\"a\"]".to_vec()));
+/// ```
+pub fn from_json(value: &JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::NULL,
+        JsonValue::Bool(value) => Value::Int(i64::from(*value)),
+        JsonValue::Number(number) => match (number.as_i64(), number.as_u64()) {
+            (Some(value), _) => Value::Int(value),
+            (None, Some(value)) => Value::UInt(value),
+            _ => Value::Double(number.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(text) => Value::Bytes(text.clone().into_bytes()),
+        JsonValue::Array(_) | JsonValue::Object(_) => Value::Bytes(value.to_string().into_bytes()),
+    }
+}
+
+/// 복원할 행 하나이다.
+pub struct BackupRow {
+    /// 행을 기록할 테이블 이름이다.
+    pub table: String,
+    /// 값을 기록할 열 이름들이다.
+    pub columns: Vec<String>,
+    /// 열 순서대로 나열된 값들이다.
+    pub values: Vec<Value>,
+}
+
+/// 백업 파일의 한 줄이 복원할 수 있는 행인지 확인하는 메서드이다.
+///
+/// 복원할 수 있는 경우 행을, 복원하지 않는 테이블의 행인 경우 `None`을, 그렇지 않은 경우 그 이유를 반환한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::backup::{parse_row, Schema};
+/// use serde_json::json;
+/// let schema = Schema::from([("tag".to_string(), ["tag_id", "name"].map(String::from).into())]);
+/// let row = parse_row(json!({"table": "tag", "row": {"tag_id": 1, "name": "rust"}}), &schema)
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(row.table, "tag");
+/// assert_eq!(row.columns.len(), 2);
+/// assert!(parse_row(json!({"table": "schema_migrations", "row": {"version": 1}}), &schema).unwrap().is_none());
+/// assert!(parse_row(json!({"table": "tag", "row": {"color": "red"}}), &schema).is_err());
+/// assert!(parse_row(json!({"table": "secret", "row": {"id": 1}}), &schema).is_err());
+/// ```
+pub fn parse_row(
+    line: JsonValue,
+    schema: &Schema,
+) -> std::result::Result<Option<BackupRow>, String> {
+    let JsonValue::Object(mut line) = line else {
+        return Err("각 줄은 table과 row를 담은 객체여야 합니다.".to_string());
+    };
+    let (Some(JsonValue::String(table)), Some(JsonValue::Object(row))) =
+        (line.remove("table"), line.remove("row"))
+    else {
+        return Err("각 줄은 table과 row를 담은 객체여야 합니다.".to_string());
+    };
+    if table == SKIPPED_TABLE {
+        return Ok(None);
+    }
+    let known = schema
+        .get(&table)
+        .ok_or_else(|| format!("존재하지 않는 테이블입니다: {}", table))?;
+    if row.is_empty() {
+        return Err("row는 비어있을 수 없습니다.".to_string());
+    }
+    if let Some(column) = row.keys().find(|column| !known.contains(*column)) {
+        return Err(format!(
+            "{} 테이블에 존재하지 않는 열입니다: {}",
+            table, column
+        ));
+    }
+    let (columns, values) = row
+        .iter()
+        .map(|(column, value)| (column.clone(), from_json(value)))
+        .unzip();
+    Ok(Some(BackupRow {
+        table,
+        columns,
+        values,
+    }))
+}
+
+/// 테이블 이름이나 열 이름을 SQL문에 넣을 수 있도록 감싸는 메서드이다.
+fn quote(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+impl BackupRow {
+    /// 행을 기록하고, 기본 키가 같은 행이 있는 경우 덮어쓰는 SQL문을 반환하는 메서드이다.
+    fn upsert_query(&self) -> String {
+        let columns: Vec<String> = self.columns.iter().map(|column| quote(column)).collect();
+        let updates: Vec<String> = columns
+            .iter()
+            .map(|column| format!("{0} = values({0})", column))
+            .collect();
+        format!(
+            "insert into {} ({}) values ({}) on duplicate key update {}",
+            quote(&self.table),
+            columns.join(", "),
+            vec!["?"; columns.len()].join(", "),
+            updates.join(", ")
+        )
+    }
+}
+
+/// 복원하지 못한 줄과 그 이유이다.
+#[derive(Serialize)]
+pub struct RestoreError {
+    /// 요청 본문에서 줄의 순서이다. 0부터 시작하며, 빈 줄을 제외한 줄의 순서이다.
+    pub index: usize,
+    /// 복원하지 못한 이유이다.
+    pub error: String,
+}
+
+/// 백업 복원 요청 전체의 결과이다.
+#[derive(Serialize, Default)]
+pub struct RestoreSummary {
+    /// 시험 실행 여부이다. `true`인 경우 기록한 내용은 모두 되돌려졌다.
+    pub dry_run: bool,
+    /// 복원한 행 수이다.
+    pub restored: usize,
+    /// 복원하지 않는 테이블이어서 건너뛴 행 수이다.
+    pub skipped: usize,
+    /// 복원하지 못한 행 수이다.
+    pub failed: usize,
+    /// 테이블별로 복원한 행 수이다.
+    pub tables: BTreeMap<String, usize>,
+    /// 복원하지 못한 줄들이다. 최대 `MAX_REPORTED_ERRORS`개까지 담긴다.
+    pub errors: Vec<RestoreError>,
+}
+
+impl RestoreSummary {
+    /// 복원하지 못한 줄을 기록하는 메서드이다.
+    fn fail(&mut self, index: usize, error: impl ToString) {
+        self.failed += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(RestoreError {
+                index,
+                error: error.to_string(),
+            });
+        }
+    }
+}
+
+/// 현재 DB의 테이블별 열 이름들을 읽는 메서드이다.
+fn load_schema(conn: &mut PooledConn) -> Result<Schema> {
+    let mut schema = Schema::new();
+    let columns: Vec<(String, String)> = conn.query(
+        "select table_name, column_name from information_schema.columns where table_schema = database()",
+    )?;
+    for (table, column) in columns {
+        schema.entry(table).or_default().insert(column);
+    }
+    Ok(schema)
+}
+
+/// 백업 파일의 줄들을 확인한 뒤 `BATCH_SIZE`개씩 나누어 복원하고 결과를 반환하는 메서드이다.
+///
+/// 각 행은 세이브포인트 안에서 기록되므로 DB 작업 중 실패한 행만 되돌리고 나머지 행은 복원된다.
+/// `dry_run`이 `true`인 경우 같은 작업을 한 뒤 커밋하지 않고 되돌린다.
+/// # 예제
+/// ```ignore
+/// let items = import::split_items(&body)?;
+/// let summary = backup::restore(items, true)?;
+/// println!("{}개의 행을 복원할 수 있습니다.", summary.restored);
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn restore(items: Vec<ImportItem>, dry_run: bool) -> Result<RestoreSummary> {
+    let mut summary = RestoreSummary {
+        dry_run,
+        ..Default::default()
+    };
+    let mut conn = db::get_conn();
+    let schema = load_schema(&mut conn)?;
+    let mut rows = Vec::with_capacity(items.len());
+    for (index, item) in items.into_iter().enumerate() {
+        match item.and_then(|line| parse_row(line, &schema)) {
+            Ok(Some(row)) => rows.push((index, row)),
+            Ok(None) => summary.skipped += 1,
+            Err(error) => summary.fail(index, error),
+        }
+    }
+    for batch in rows.chunks(BATCH_SIZE) {
+        let mut results = Vec::with_capacity(batch.len());
+        let finished = conn
+            .start_transaction(TxOpts::default())
+            .and_then(|mut tx| {
+                for (index, row) in batch {
+                    results.push((*index, &row.table, restore_one(&mut tx, row)));
+                }
+                if dry_run {
+                    tx.rollback()
+                } else {
+                    tx.commit()
+                }
+            });
+        if let Err(error) = finished {
+            tracing::error!(%error, "백업 복원 트랜잭션이 실패했습니다.");
+            for (index, _) in batch {
+                summary.fail(*index, "DB 작업 중 문제가 발생했습니다.");
+            }
+            continue;
+        }
+        for (index, table, result) in results {
+            match result {
+                Ok(()) => {
+                    summary.restored += 1;
+                    *summary.tables.entry(table.clone()).or_default() += 1;
+                }
+                Err(error) => summary.fail(index, error),
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// 행 하나를 세이브포인트 안에서 기록하는 메서드이다.
+fn restore_one(tx: &mut Transaction, row: &BackupRow) -> Result<()> {
+    tx.query_drop("savepoint restore_row")?;
+    match tx.exec_drop(row.upsert_query(), row.values.clone()) {
+        Ok(()) => tx.query_drop("release savepoint restore_row"),
+        Err(error) => {
+            tx.query_drop("rollback to savepoint restore_row")?;
+            Err(error)
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! # 사용자 차단 관련 동작을 정의하는 모듈
+//!
+//! `block`은 코드뮤니티에서 사용자가 다른 사용자를 차단하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 차단한 사용자의 포스트와 댓글은 차단한 사용자가 요청한 피드와 댓글 목록에서 제외된다.
+//! 차단 목록은 요청마다 `BlockList::load()`로 한 번 조회한 뒤 조회한 항목들에 적용한다.
+//! 차단하면 차단한 사용자에 대한 팔로우도 함께 해제된다.
+
+use crate::auth::Identity;
+use crate::db;
+use crate::error::ApiError;
+use crate::repository::UserRepository;
+use crate::response;
+use actix_web::{delete, post, web, HttpResponse};
+use mysql::prelude::*;
+use mysql::*;
+use std::collections::HashSet;
+
+/// 사용자 간의 차단 관계를 처리하는 객체이다.
+pub struct Block;
+
+impl Block {
+    /// `blocker_id` 사용자가 `blocked_id` 사용자를 차단하는 메서드이다.
+    ///
+    /// 차단할 사용자가 존재하지 않는 경우 `Ok(false)`를 반환한다.
+    /// 이미 차단한 경우에도 오류 없이 `Ok(true)`를 반환하며, 차단한 사용자에 대한 팔로우는 해제된다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn block(blocker_id: &str, blocked_id: &str) -> Result<bool> {
+        let mut conn = db::get_conn();
+        let exists: Option<String> = conn.exec_first(
+            "select user_id from user where user_id = :user_id",
+            params! {
+                "user_id" => blocked_id,
+            },
+        )?;
+        if exists.is_none() {
+            return Ok(false);
+        }
+        let mut tx = conn.start_transaction(TxOpts::default())?;
+        tx.exec_drop(
+            r"insert ignore into blocked(blocker_id, blocked_id)
+            values(:blocker_id, :blocked_id)",
+            params! {
+                "blocker_id" => blocker_id,
+                "blocked_id" => blocked_id,
+            },
+        )?;
+        tx.exec_drop(
+            "delete from follow where follower_id = :blocker_id and followee_id = :blocked_id",
+            params! {
+                "blocker_id" => blocker_id,
+                "blocked_id" => blocked_id,
+            },
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+    /// `blocker_id` 사용자가 `blocked_id` 사용자의 차단을 해제하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn unblock(blocker_id: &str, blocked_id: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "delete from blocked where blocker_id = :blocker_id and blocked_id = :blocked_id",
+            params! {
+                "blocker_id" => blocker_id,
+                "blocked_id" => blocked_id,
+            },
+        )
+    }
+    /// 사용자가 차단한 사용자들의 고유 ID를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_blocked(user_id: &str) -> Vec<String> {
+        let mut conn = db::get_read_conn();
+        conn.exec(
+            "select blocked_id from blocked where blocker_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+    }
+}
+
+/// 요청한 사용자가 차단한 사용자들의 목록이다.
+///
+/// 요청마다 한 번 만든 뒤 조회한 포스트나 댓글에서 차단한 사용자의 항목을 제외할 때 사용한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::block::BlockList;
+/// let blocked = BlockList::from(vec!["u2".to_string()]);
+/// let mut authors = vec!["u1", "u2", "u3"];
+/// blocked.retain(&mut authors, |user_id| user_id);
+/// assert_eq!(authors, vec!["u1", "u3"]);
+/// assert!(BlockList::default().is_empty());
+/// ```
+#[derive(Default)]
+pub struct BlockList(HashSet<String>);
+
+impl From<Vec<String>> for BlockList {
+    fn from(user_ids: Vec<String>) -> Self {
+        Self(user_ids.into_iter().collect())
+    }
+}
+
+impl BlockList {
+    /// `viewer_id` 사용자가 차단한 사용자들의 목록을 조회하는 메서드이다.
+    ///
+    /// 요청한 사용자를 알 수 없는 경우 빈 목록을 반환한다.
+    pub fn load(users: &dyn UserRepository, viewer_id: Option<&str>) -> Self {
+        viewer_id
+            .map(|viewer_id| users.get_blocked(viewer_id).into())
+            .unwrap_or_default()
+    }
+    /// 차단한 사용자 외에 `user_ids` 사용자들도 제외하도록 목록에 더하는 메서드이다.
+    ///
+    /// 섀도 밴된 사용자들을 함께 제외할 때 사용한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::block::BlockList;
+    /// let hidden = BlockList::default().extend(vec!["u2".to_string()]);
+    /// let mut authors = vec!["u1", "u2"];
+    /// hidden.retain(&mut authors, |user_id| user_id);
+    /// assert_eq!(authors, vec!["u1"]);
+    /// ```
+    pub fn extend(mut self, user_ids: impl IntoIterator<Item = String>) -> Self {
+        self.0.extend(user_ids);
+        self
+    }
+    /// 차단한 사용자가 없는지 확인하는 메서드이다.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// `items`에서 작성자(`user_id`)가 차단한 사용자인 항목을 제외하는 메서드이다.
+    pub fn retain<T>(&self, items: &mut Vec<T>, user_id: impl Fn(&T) -> &str) {
+        if !self.is_empty() {
+            items.retain(|item| !self.0.contains(user_id(item)));
+        }
+    }
+}
+
+#[post("/api/users/{user_id}/block")]
+pub async fn block_user_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if identity.user_id == *user_id {
+        return Err(ApiError::BadRequest(
+            "자기 자신은 차단할 수 없습니다.".to_string(),
+        ));
+    }
+    if !users.block(&identity.user_id, &user_id)? {
+        return Err(ApiError::NotFound(
+            "요청한 user_id는 존재하지 않는 사용자입니다.".to_string(),
+        ));
+    }
+    Ok(HttpResponse::Created().finish())
+}
+
+#[delete("/api/users/{user_id}/block")]
+pub async fn unblock_user_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    users.unblock(&identity.user_id, &user_id)?;
+    Ok(response::no_content())
+}
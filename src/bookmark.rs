@@ -0,0 +1,204 @@
+//! # 북마크 관련 동작을 정의하는 모듈
+//!
+//! `bookmark`는 코드뮤니티에서 사용자가 나중에 다시 읽을 포스트를 저장해두기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 북마크 목록은 본인만 확인할 수 있다.
+
+use crate::auth::Identity;
+use crate::db;
+use crate::error::ApiError;
+use crate::pagination::Pagination;
+use crate::post::{self, Post};
+use crate::response;
+use crate::summary::{self, BodyRequest};
+use actix_web::{delete, get, post, web, HttpResponse};
+use mysql::prelude::*;
+use mysql::*;
+
+/// 북마크를 처리하는 객체이다.
+pub struct Bookmark;
+
+impl Bookmark {
+    /// 사용자가 포스트를 북마크하는 메서드이다.
+    ///
+    /// 존재하지 않거나 다른 사용자의 비공개 포스트인 경우 `Ok(false)`를 반환한다.
+    /// 이미 북마크한 포스트인 경우에도 오류 없이 `Ok(true)`를 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// match Bookmark::add("unique_id_for_user", 1) {
+    ///     Ok(true) => println!("북마크 했습니다."),
+    ///     Ok(false) => println!("존재하지 않는 포스트입니다."),
+    ///     Err(_) => println!("북마크에 실패했습니다."),
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn add(user_id: &str, post_id: u64) -> Result<bool> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"insert ignore into bookmark(user_id, post_id)
+            select :user_id, post_id from post where post_id = :post_id and published and deleted_at is null
+            and (visibility <> 'private' or user_id = :user_id)",
+            params! {
+                "user_id" => user_id,
+                "post_id" => post_id,
+            },
+        )?;
+        if conn.affected_rows() > 0 {
+            return Ok(true);
+        }
+        let exists: Option<u64> = conn.exec_first(
+            r"select post_id from post where post_id = :post_id and published and deleted_at is null
+            and (visibility <> 'private' or user_id = :user_id)",
+            params! {
+                "post_id" => post_id,
+                "user_id" => user_id,
+            },
+        )?;
+        Ok(exists.is_some())
+    }
+    /// 사용자가 포스트 북마크를 취소하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn remove(user_id: &str, post_id: u64) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "delete from bookmark where user_id = :user_id and post_id = :post_id",
+            params! {
+                "user_id" => user_id,
+                "post_id" => post_id,
+            },
+        )
+    }
+    /// 사용자가 북마크한 포스트들을 최근에 북마크한 순으로 반환하는 메서드이다.
+    ///
+    /// `page`를 통해 반환할 페이지를 지정할 수 있다.
+    /// # 예제
+    /// ```ignore
+    /// let page = Pagination { page: Some(1), per_page: Some(20) };
+    /// for post in Bookmark::get_posts("unique_id_for_user", &page) {
+    ///     println!("북마크한 포스트의 제목은 {} 입니다.", post.title);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_posts(user_id: &str, page: &Pagination) -> Vec<Post> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select post.post_id, post.user_id, title, language, data, likes, report_count,
+            date_format(post.create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+            date_format(post.updated_at, '%Y-%m-%d %H:%i:%s')
+            from bookmark join post on bookmark.post_id = post.post_id
+            where bookmark.user_id = :user_id and post.deleted_at is null
+            and (post.visibility <> 'private' or post.user_id = :user_id)
+            order by bookmark.create_at desc, post.post_id desc
+            limit :limit offset :offset",
+            params! {
+                "user_id" => user_id,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(
+                post_id,
+                user_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+            )| {
+                Post::from_db(
+                    post_id,
+                    user_id,
+                    title,
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at,
+                    post_type,
+                    accepted_comment_id,
+                    updated_at,
+                )
+            },
+        )
+        .unwrap()
+    }
+    /// 사용자가 북마크한 포스트(`get_posts`)의 수를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn count_posts(user_id: &str) -> u64 {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            r"select count(*) from bookmark join post on bookmark.post_id = post.post_id
+            where bookmark.user_id = :user_id and post.deleted_at is null
+            and (post.visibility <> 'private' or post.user_id = :user_id)",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+        .unwrap_or_default()
+    }
+}
+
+#[post("/api/posts/{post_id}/bookmark")]
+pub async fn add_bookmark_api(
+    identity: Identity,
+    post_id: web::Path<u64>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if !Bookmark::add(&identity.user_id, *post_id)? {
+        return Err(post::post_not_found());
+    }
+    Ok(HttpResponse::Created().finish())
+}
+
+#[delete("/api/posts/{post_id}/bookmark")]
+pub async fn remove_bookmark_api(
+    identity: Identity,
+    post_id: web::Path<u64>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    Bookmark::remove(&identity.user_id, *post_id)?;
+    Ok(response::no_content())
+}
+
+#[get("/api/users/{user_id}/bookmarks")]
+pub async fn get_bookmarks_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    page: web::Query<Pagination>,
+    body: web::Query<BodyRequest>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if identity.user_id != *user_id {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(summary::apply(
+        Bookmark::get_posts(&user_id, &page),
+        body.body,
+    )))
+}
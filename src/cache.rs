@@ -0,0 +1,211 @@
+//! # 조회 결과 캐시 관련 동작을 정의하는 모듈
+//!
+//! `cache`는 요청이 몰릴 때 DB의 부담을 줄이기 위해 홈 피드, 포스트, 사용자 조회 결과를
+//! Redis에 잠시 저장해두기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 캐시는 `REDIS_URL`이 설정된 경우에만 사용된다. 저장된 결과는 `CACHE_TTL_SECONDS`가 지나면 사라지며,
+//! 포스트가 작성, 수정, 삭제되거나 공감 수가 바뀌면 관련된 결과를 바로 지운다.
+//! Redis 서버에 접근할 수 없는 경우 경고를 남기고 DB에서 직접 조회한다.
+
+use crate::config::CacheConfig;
+use crate::pagination::Pagination;
+use redis::{Client, Commands, Connection, RedisResult};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+
+/// 이 서버가 저장하는 모든 키의 접두사이다.
+const KEY_PREFIX: &str = "codemmunity";
+/// Redis 서버 접속 제한시간이다. 캐시 서버의 문제로 요청이 오래 걸리지 않도록 짧게 설정한다.
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Redis에 조회 결과를 저장하는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+pub struct Cache {
+    /// Redis 클라이언트이다. `None`인 경우 캐시를 사용하지 않는다.
+    client: Option<Client>,
+    /// 조회 결과를 저장해둘 시간이다.
+    ttl: Duration,
+}
+
+impl Cache {
+    /// 캐시 설정을 통해 객체를 생성하는 메서드이다.
+    ///
+    /// `config`가 `None`인 경우 캐시를 사용하지 않는 객체를 반환한다.
+    /// Redis 서버 주소가 올바르지 않은 경우 원인을 설명하는 메세지를 반환한다.
+    pub fn new(config: Option<&CacheConfig>) -> Result<Self, String> {
+        let Some(config) = config else {
+            return Ok(Self::disabled());
+        };
+        let client = Client::open(config.redis_url.as_str()).map_err(|error| error.to_string())?;
+        Ok(Self {
+            client: Some(client),
+            ttl: config.ttl,
+        })
+    }
+    /// 캐시를 사용하지 않는 객체를 생성하는 메서드이다.
+    pub fn disabled() -> Self {
+        Self {
+            client: None,
+            ttl: Duration::ZERO,
+        }
+    }
+    /// Redis 서버에 접속하여 `command`를 실행하는 메서드이다.
+    ///
+    /// 캐시를 사용하지 않거나 실행에 실패한 경우 `None`을 반환한다.
+    fn run<T>(&self, command: impl FnOnce(&mut Connection) -> RedisResult<T>) -> Option<T> {
+        let client = self.client.as_ref()?;
+        let result = client
+            .get_connection_with_timeout(CONNECT_TIMEOUT)
+            .and_then(|mut conn| {
+                conn.set_read_timeout(Some(CONNECT_TIMEOUT))?;
+                conn.set_write_timeout(Some(CONNECT_TIMEOUT))?;
+                command(&mut conn)
+            });
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                tracing::warn!(%error, "캐시 서버에 접근할 수 없습니다.");
+                None
+            }
+        }
+    }
+    /// 저장된 조회 결과를 반환하는 메서드이다. 저장된 결과가 없는 경우 `None`을 반환한다.
+    fn fetch<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.run(|conn| conn.get::<_, Option<String>>(key))
+            .flatten()
+            .and_then(|json| serde_json::from_str(&json).ok())
+    }
+    /// 조회 결과를 저장하는 메서드이다.
+    fn store<T: Serialize>(&self, key: &str, value: &T) {
+        if let Ok(json) = serde_json::to_string(value) {
+            let seconds = self.ttl.as_secs() as usize;
+            self.run(|conn| conn.set_ex::<_, _, ()>(key, json, seconds));
+        }
+    }
+    /// 저장된 조회 결과를 반환하고, 없는 경우 `load`로 조회한 결과를 저장한 뒤 반환하는 메서드이다.
+    ///
+    /// `load`가 `None`을 반환한 경우 저장하지 않는다.
+    fn get_or_load<T, F>(&self, key: &str, load: F) -> Option<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Option<T>,
+    {
+        if self.client.is_none() {
+            return load();
+        }
+        if let Some(value) = self.fetch(key) {
+            return Some(value);
+        }
+        let value = load()?;
+        self.store(key, &value);
+        Some(value)
+    }
+    /// 홈 피드 조회 결과를 반환하는 메서드이다.
+    ///
+    /// 피드는 요청한 사용자, 태그, 페이지별로 저장된다.
+    pub fn feed<T, F>(
+        &self,
+        viewer_id: Option<&str>,
+        tag: Option<&str>,
+        page: &Pagination,
+        load: F,
+    ) -> T
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> T,
+    {
+        if self.client.is_none() {
+            return load();
+        }
+        let generation: u64 = self
+            .run(|conn| conn.get::<_, Option<u64>>(feed_generation_key()))
+            .flatten()
+            .unwrap_or_default();
+        let key = format!(
+            "{}:feed:{}:{}:{}:{}:{}",
+            KEY_PREFIX,
+            generation,
+            viewer_id.unwrap_or_default(),
+            tag.unwrap_or_default(),
+            page.offset(),
+            page.limit()
+        );
+        if let Some(value) = self.fetch(&key) {
+            return value;
+        }
+        let value = load();
+        self.store(&key, &value);
+        value
+    }
+    /// 포스트 조회 결과를 반환하는 메서드이다.
+    pub fn post<T, F>(&self, post_id: &str, load: F) -> Option<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Option<T>,
+    {
+        self.get_or_load(&post_key(post_id), load)
+    }
+    /// 사용자 조회 결과를 반환하는 메서드이다.
+    pub fn user<T, F>(&self, user_id: &str, load: F) -> Option<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Option<T>,
+    {
+        self.get_or_load(&user_key(user_id), load)
+    }
+    /// 포스트가 바뀌었을 때 해당 포스트와 모든 홈 피드의 조회 결과를 지우는 메서드이다.
+    ///
+    /// 홈 피드는 키에 포함된 세대 번호를 올려 이전 결과를 더 이상 사용하지 않도록 한다.
+    pub fn invalidate_post(&self, post_id: impl ToString) {
+        let key = post_key(&post_id.to_string());
+        self.run(|conn| {
+            redis::pipe()
+                .del(key)
+                .ignore()
+                .incr(feed_generation_key(), 1)
+                .ignore()
+                .query::<()>(conn)
+        });
+    }
+    /// 새 포스트가 한꺼번에 등록되었을 때 모든 홈 피드의 조회 결과를 지우는 메서드이다.
+    pub fn invalidate_feed(&self) {
+        self.run(|conn| conn.incr::<_, _, ()>(feed_generation_key(), 1));
+    }
+    /// 사용자의 이름이 바뀌었을 때 해당 사용자와 사용자가 작성한 포스트, 모든 홈 피드의 조회 결과를 지우는 메서드이다.
+    ///
+    /// 포스트와 홈 피드의 조회 결과에는 작성자의 이름이 함께 저장되어 있기 때문에 필요하다.
+    /// `load_post_ids`는 캐시를 사용하는 경우에만 호출된다.
+    pub fn invalidate_author(&self, user_id: &str, load_post_ids: impl FnOnce() -> Vec<u64>) {
+        if self.client.is_none() {
+            return;
+        }
+        let mut pipe = redis::pipe();
+        pipe.del(user_key(user_id)).ignore();
+        for post_id in load_post_ids() {
+            pipe.del(post_key(&post_id.to_string())).ignore();
+        }
+        pipe.incr(feed_generation_key(), 1).ignore();
+        self.run(|conn| pipe.query::<()>(conn));
+    }
+    /// 사용자가 바뀌었을 때 해당 사용자의 조회 결과를 지우는 메서드이다.
+    pub fn invalidate_user(&self, user_id: &str) {
+        self.run(|conn| conn.del::<_, ()>(user_key(user_id)));
+    }
+}
+
+/// 포스트 조회 결과의 키를 반환하는 메서드이다.
+fn post_key(post_id: &str) -> String {
+    format!("{}:post:{}", KEY_PREFIX, post_id)
+}
+
+/// 사용자 조회 결과의 키를 반환하는 메서드이다.
+fn user_key(user_id: &str) -> String {
+    format!("{}:user:{}", KEY_PREFIX, user_id)
+}
+
+/// 홈 피드의 세대 번호가 저장된 키를 반환하는 메서드이다.
+fn feed_generation_key() -> String {
+    format!("{}:feed:generation", KEY_PREFIX)
+}
@@ -0,0 +1,116 @@
+//! # 고아 데이터 정리 관련 동작을 정의하는 모듈
+//!
+//! `cleanup`은 포스트나 사용자가 제거된 뒤에도 남아있는 댓글, 공감 및 신고, 북마크 등의 데이터를
+//! 주기적으로 찾아 지우기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 삭제된 상태(`deleted_at`)의 포스트는 복구될 수 있으므로 정리 대상이 아니며,
+//! DB에서 완전히 사라진 포스트와 사용자를 참조하는 데이터만 지운다.
+
+use crate::db;
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+
+/// 한 번의 `delete`문으로 지우는 최대 행 수이다. 오랫동안 테이블이 잠기지 않도록 나누어 지운다.
+const BATCH_SIZE: u64 = 1000;
+
+/// 정리 대상인 테이블과 참조하는 열, 그리고 해당 열이 가리키는 부모 테이블과 열이다.
+const ORPHAN_RULES: &[(&str, &str, &str, &str)] = &[
+    ("comment", "post_id", "post", "post_id"),
+    ("comment_report", "comment_id", "comment", "comment_id"),
+    ("comment_report", "user_id", "user", "user_id"),
+    ("react", "post_id", "post", "post_id"),
+    ("react", "user_id", "user", "user_id"),
+    ("bookmark", "post_id", "post", "post_id"),
+    ("bookmark", "user_id", "user", "user_id"),
+    ("post_tag", "post_id", "post", "post_id"),
+    ("post_revision", "post_id", "post", "post_id"),
+    ("post_report", "post_id", "post", "post_id"),
+    ("post_report", "user_id", "user", "user_id"),
+    ("session", "user_id", "user", "user_id"),
+    ("password_reset", "user_id", "user", "user_id"),
+    ("shortlink", "post_id", "post", "post_id"),
+    ("follow", "follower_id", "user", "user_id"),
+    ("follow", "followee_id", "user", "user_id"),
+    ("language_subscription", "user_id", "user", "user_id"),
+    ("webhook_delivery", "webhook_id", "webhook", "webhook_id"),
+];
+
+/// 테이블 하나에서 지운 고아 데이터의 수이다.
+#[derive(Serialize)]
+pub struct Removed {
+    /// 데이터를 지운 테이블의 이름이다.
+    pub table: &'static str,
+    /// 부모 테이블을 참조하는 열의 이름이다.
+    pub column: &'static str,
+    /// 지운 행의 수이다.
+    pub rows: u64,
+}
+
+/// 부모 행이 없는 데이터를 찾는 `delete`문을 반환하는 메서드이다.
+///
+/// # 예제
+/// ```
+/// use code_mmunity_server::cleanup::orphan_statement;
+/// assert_eq!(
+///     orphan_statement("comment", "post_id", "post", "post_id"),
+///     "delete from comment where not exists (select 1 from post where post.post_id = comment.post_id) limit :batch"
+/// );
+/// ```
+pub fn orphan_statement(table: &str, column: &str, parent: &str, parent_column: &str) -> String {
+    format!(
+        "delete from {table} where not exists (select 1 from {parent} where {parent}.{parent_column} = {table}.{column}) limit :batch"
+    )
+}
+
+/// 부모 행이 없는 데이터를 모두 지우고 테이블별로 지운 행의 수를 반환하는 메서드이다.
+///
+/// 지운 데이터가 없는 테이블은 결과에 포함되지 않는다. 각 테이블의 데이터는 `BATCH_SIZE`개씩 나누어 지우며,
+/// 채택된 답변 댓글이 사라진 포스트는 채택되지 않은 상태로 되돌린다.
+/// `scheduler`를 통해 주기적으로 호출된다.
+/// # 예제
+/// ```ignore
+/// for removed in cleanup::run().unwrap() {
+///     println!("{}에서 {}개의 행을 지웠습니다.", removed.table, removed.rows);
+/// }
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn run() -> Result<Vec<Removed>> {
+    let mut conn = db::get_conn();
+    let mut removed = Vec::new();
+    for (table, column, parent, parent_column) in ORPHAN_RULES {
+        let statement = orphan_statement(table, column, parent, parent_column);
+        let mut rows = 0;
+        loop {
+            conn.exec_drop(
+                &statement,
+                params! {
+                    "batch" => BATCH_SIZE,
+                },
+            )?;
+            rows += conn.affected_rows();
+            if conn.affected_rows() < BATCH_SIZE {
+                break;
+            }
+        }
+        if rows > 0 {
+            removed.push(Removed {
+                table,
+                column,
+                rows,
+            });
+        }
+    }
+    conn.query_drop(
+        r"update post set accepted_comment_id = null
+        where accepted_comment_id is not null
+        and not exists (select 1 from comment where comment.comment_id = post.accepted_comment_id)",
+    )?;
+    Ok(removed)
+}
@@ -3,18 +3,39 @@
 //! `comment`는 코드뮤니티에서 댓글 관련 기능 처리를 위한
 //! 메서드들로 구성되어 있다.
 
-use std::{env, path::Path};
-
 use actix_web::web::Json;
-use actix_web::{get, post, web, HttpResponse, Responder};
+use actix_web::{delete, get, patch, post, web, HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
 use mysql::prelude::*;
-use mysql::{params, OptsBuilder, Pool, Result, SslOpts};
+use mysql::{params, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::auth::Identity;
+use crate::block::BlockList;
+use crate::cache::Cache;
+use crate::conditional;
+use crate::db;
+use crate::email;
+use crate::error::ApiError;
+use crate::events::PostEvents;
+use crate::idempotency::{self, Idempotency};
+use crate::moderation::{self, Moderator};
+use crate::outbox::Outbox;
+use crate::pagination::{Count, Cursor, CursorPage};
+use crate::post::{post_not_found, Post, Visibility};
+use crate::repository::{CommentRepository, PostRepository, UserRepository};
+use crate::response;
+use crate::sanitize;
+use crate::search_index::SearchIndex;
+use crate::shadow_ban::ShadowBans;
 use crate::user::User;
+use crate::validation::{FieldErrors, MAX_COMMENT_CHARS};
+use crate::word_filter::{ContentKind, WordFilter};
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Comment {
+    /// 댓글의 고유 ID이다. DB에서 auto_increment에 의해 값이 자동으로 증가한다.
+    pub comment_id: u64,
     /// 게시글의 고유 ID이다.
     pub post_id: u32,
     /// 사용자의 고유 ID이다.
@@ -22,9 +43,83 @@ pub struct Comment {
     /// 사용자의 이름이다.
     pub user_name: String,
     /// 댓글의 내용이다.
+    #[serde(with = "sanitize::escaped")]
     pub data: String,
     /// 댓글 작성 날짜 및 시간이다.
-    pub create_at: String,
+    pub create_at: DateTime<Utc>,
+    /// 질문 포스트의 채택된 답변인지 여부이다.
+    #[serde(default)]
+    pub is_accepted: bool,
+    /// 댓글이 마지막으로 수정된 날짜 및 시간이다. 수정된 적이 없는 경우 `None`이다.
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// 댓글이 수정된 횟수이다. 수정을 요청할 때 함께 보내야 한다.
+    #[serde(default)]
+    pub version: u64,
+}
+
+/// 댓글 목록의 정렬 방식이다.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum CommentSort {
+    /// 최신순
+    #[default]
+    Newest,
+    /// 오래된 순
+    Oldest,
+}
+
+impl CommentSort {
+    /// SQL의 `order by`에 사용할 정렬 방향을 반환하는 메서드이다.
+    pub fn direction(&self) -> &'static str {
+        match self {
+            Self::Newest => "desc",
+            Self::Oldest => "asc",
+        }
+    }
+    /// 커서 방식에서 `after`와 댓글 ID를 비교할 연산자를 반환하는 메서드이다.
+    ///
+    /// 최신순인 경우 `after`보다 작은 ID의, 오래된 순인 경우 큰 ID의 댓글부터 반환한다.
+    pub fn after_operator(&self) -> &'static str {
+        match self {
+            Self::Newest => "<",
+            Self::Oldest => ">",
+        }
+    }
+}
+
+/// 쿼리 스트링을 통해 댓글 목록의 정렬 방식을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct CommentSortRequest {
+    /// 정렬 방식(`newest`, `oldest`, `top`)이다. 생략하면 최신순으로 정렬한다.
+    pub sort: Option<String>,
+}
+
+impl CommentSortRequest {
+    /// 요청한 정렬 방식을 반환하는 메서드이다.
+    ///
+    /// 공감순(`top`)은 댓글 공감 기능이 필요하므로 아직 사용할 수 없으며, 다른 정렬 방식과 함께
+    /// 알 수 없는 값인 경우 `ApiError::Validation`을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::comment::{CommentSort, CommentSortRequest};
+    /// assert_eq!(CommentSortRequest { sort: None }.sort().unwrap(), CommentSort::Newest);
+    /// let request = CommentSortRequest { sort: Some("oldest".to_string()) };
+    /// assert_eq!(request.sort().unwrap(), CommentSort::Oldest);
+    /// assert!(CommentSortRequest { sort: Some("top".to_string()) }.sort().is_err());
+    /// assert!(CommentSortRequest { sort: Some("random".to_string()) }.sort().is_err());
+    /// ```
+    pub fn sort(&self) -> std::result::Result<CommentSort, ApiError> {
+        match self.sort.as_deref() {
+            None | Some("newest") => Ok(CommentSort::Newest),
+            Some("oldest") => Ok(CommentSort::Oldest),
+            Some("top") => Err(ApiError::Validation(
+                "댓글 공감 기능이 없어 top 정렬은 사용할 수 없습니다.".to_string(),
+            )),
+            Some(_) => Err(ApiError::Validation(
+                "sort는 newest, oldest 중 하나여야 합니다.".to_string(),
+            )),
+        }
+    }
 }
 
 impl Comment {
@@ -32,74 +127,159 @@ impl Comment {
     ///
     /// `comment_id`, `post_id`, `user_id`를 입력받아서 댓글 객체를 생성한다.
     /// 생성된 댓글 객체는 DB에 등록과 같은 동작이 가능하다.
-    pub fn new(
+    pub fn new(post_id: u32, user_id: String, data: String, create_at: Option<String>) -> Self {
+        Self {
+            comment_id: 0,
+            post_id,
+            user_id: user_id.clone(),
+            user_name: User::get_user_name(&user_id).expect("Unknown User"),
+            data,
+            create_at: create_at
+                .as_deref()
+                .and_then(db::parse_datetime)
+                .unwrap_or_else(Utc::now),
+            is_accepted: false,
+            updated_at: None,
+            version: 0,
+        }
+    }
+    /// DB에서 댓글을 가져올 때 사용하는 메서드이다.
+    ///
+    /// `new()`와 달리 DB에서 부여된 `comment_id`, 채택된 답변인지 여부, 마지막으로 수정된 시각을 함께 받는다.
+    ///
+    /// # Panics
+    ///
+    /// `Comment`의 `user_id`가 유효한 사용자 고유 ID가 아닌 경우 패닉이 발생한다.
+    pub fn from_db(
+        comment_id: u64,
         post_id: u32,
         user_id: String,
         data: String,
         create_at: Option<String>,
+        is_accepted: bool,
+        updated_at: Option<String>,
     ) -> Self {
         Self {
-            post_id,
-            user_id: user_id.clone(),
-            user_name: User::get_user(user_id).expect("Unknown User").user_name,
-            data,
-            create_at: create_at.unwrap_or("".to_string()),
+            comment_id,
+            is_accepted,
+            updated_at: updated_at.as_deref().and_then(db::parse_datetime),
+            ..Self::new(post_id, user_id, data, create_at)
         }
     }
 
-    pub fn get(post_id: u32) -> Vec<Self> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
-        conn.query_map(
+    /// 포스트에 달린 댓글들을 반환하는 메서드이다.
+    ///
+    /// 채택된 답변이 있는 경우 가장 먼저 오고, 나머지 댓글은 `sort`에 따라 정렬된다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get(post_id: u32, sort: CommentSort) -> Vec<Self> {
+        let mut conn = db::get_read_conn();
+        conn.exec_map(
+            format!(
+                r"select comment_id, post_id, user_id, data, date_format(create_at, '%Y-%m-%d %H:%i:%s'),
+                date_format(updated_at, '%Y-%m-%d %H:%i:%s'), version,
+                comment_id = coalesce((select accepted_comment_id from post where post.post_id = comment.post_id), 0) as is_accepted
+                from comment where post_id = :post_id and moderation = 'approved'
+                order by is_accepted desc, comment.create_at {0}, comment_id {0}",
+                sort.direction()
+            ),
+            params! {
+                "post_id" => post_id,
+            },
+            |(comment_id, post_id, user_id, data, create_at, updated_at, version, is_accepted)| Self {
+                version,
+                ..Self::from_db(
+                    comment_id,
+                    post_id,
+                    user_id,
+                    data,
+                    create_at,
+                    is_accepted,
+                    updated_at,
+                )
+            },
+        )
+        .unwrap()
+    }
+    /// 포스트에 달린 댓글들을 커서 방식으로 `sort`에 따라 반환하는 메서드이다.
+    ///
+    /// 최신순인 경우 `cursor.after`보다 작은 ID의, 오래된 순인 경우 큰 ID의 댓글부터 반환하며,
+    /// 채택된 답변도 다른 댓글과 같은 순서로 정렬된다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_after(post_id: u32, cursor: &Cursor, sort: CommentSort) -> Vec<Self> {
+        let mut conn = db::get_read_conn();
+        conn.exec_map(
             format!(
-                "select * from comment where post_id = {} order by create_at desc",
-                post_id
+                r"select comment_id, post_id, user_id, data, date_format(create_at, '%Y-%m-%d %H:%i:%s'),
+                date_format(updated_at, '%Y-%m-%d %H:%i:%s'), version,
+                comment_id = coalesce((select accepted_comment_id from post where post.post_id = comment.post_id), 0) as is_accepted
+                from comment where post_id = :post_id and moderation = 'approved'
+                and (:after is null or comment_id {} :after)
+                order by comment_id {} limit :limit",
+                sort.after_operator(),
+                sort.direction()
             ),
-            |(post_id, user_id, data, create_at)| {
-                Self::new(post_id, user_id, data, create_at)
+            params! {
+                "post_id" => post_id,
+                "after" => cursor.after,
+                "limit" => cursor.limit(),
+            },
+            |(comment_id, post_id, user_id, data, create_at, updated_at, version, is_accepted)| Self {
+                version,
+                ..Self::from_db(
+                    comment_id,
+                    post_id,
+                    user_id,
+                    data,
+                    create_at,
+                    is_accepted,
+                    updated_at,
+                )
             },
         )
         .unwrap()
     }
+    /// 포스트에 달린 댓글(`get`)의 수를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn count(post_id: u32) -> u64 {
+        let mut conn = db::get_read_conn();
+        conn.exec_first(
+            "select count(*) from comment where post_id = :post_id and moderation = 'approved'",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()
+        .unwrap_or_default()
+    }
     /// 댓글 객체를 DB에 삽입하는 메서드이다.
     ///
+    /// DB에서 부여된 `comment_id`와 `create_at`이 채워진 댓글 객체를 반환한다.
     /// Sql명령이 정상적으로 작동되지 않은 경우에 예외 처리를 할 수 있도록
-    /// `Result<()>`로 값을 반환한다.
+    /// `Result<Comment>`로 값을 반환한다.
     /// # 예제
-    /// ```
+    /// ```no_run
     /// use code_mmunity_server::comment::Comment;
-    ///     /// let new_comment = Comment::new(0, "unique_id_for_post".to_string(), "".to_string(), "".to_string(), None    ///  /// );
-    /// new_comment.insert_db().expect("Sql작업 중 문제가 발생하였습니다.")
+    /// let new_comment = Comment::new(0, "unique_id_for_user".to_string(), "댓글 내용".to_string(), None);
+    /// let comment = new_comment.insert_db().expect("Sql작업 중 문제가 발생하였습니다.");
+    /// println!("{}번 댓글이 작성되었습니다.", comment.comment_id);
     /// ```
     /// # Panics
     ///
@@ -107,79 +287,429 @@ impl Comment {
     /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
-    pub fn insert_db(self) -> Result<()> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
+    pub fn insert_db(mut self) -> Result<Self> {
+        let mut conn = db::get_conn();
         conn.exec_drop(
-            r"insert into comment(post_id, user_id, data)
-        values(:post_id, :user_id, :data)",
+            r"insert into comment(post_id, user_id, data, create_at)
+        values(:post_id, :user_id, :data, now())",
             params! {
                 "post_id" => self.post_id,
-                "user_id" => self.user_id,
-                "data" => self.data,
+                "user_id" => &self.user_id,
+                "data" => &self.data,
+            },
+        )?;
+        self.comment_id = conn.last_insert_id();
+        let create_at: Option<String> = conn.exec_first(
+            "select date_format(create_at, '%Y-%m-%d %H:%i:%s') from comment where comment_id = :comment_id",
+            params! {
+                "comment_id" => self.comment_id,
+            },
+        )?;
+        if let Some(create_at) = create_at.as_deref().and_then(db::parse_datetime) {
+            self.create_at = create_at;
+        }
+        Ok(self)
+    }
+    /// 댓글을 작성한 사용자의 고유 ID를 반환하는 메서드이다.
+    ///
+    /// 댓글을 수정하거나 삭제하기 전에 요청한 사용자가 작성자인지 확인할 때 사용한다.
+    /// 존재하지 않는 댓글의 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_owner(comment_id: u64) -> Option<String> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            "select user_id from comment where comment_id = :comment_id",
+            params! {
+                "comment_id" => comment_id,
             },
         )
+        .unwrap()
+    }
+    /// 댓글이 달린 포스트의 고유 ID를 반환하는 메서드이다.
+    ///
+    /// 존재하지 않는 댓글의 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_post_id(comment_id: u64) -> Option<u64> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            "select post_id from comment where comment_id = :comment_id",
+            params! {
+                "comment_id" => comment_id,
+            },
+        )
+        .unwrap()
+    }
+    /// 댓글의 내용을 수정하는 메서드이다.
+    ///
+    /// `version`이 DB에 저장된 값과 다른 경우 다른 곳에서 먼저 수정된 것이므로
+    /// 수정하지 않고 `false`를 반환한다.
+    /// Sql명령이 정상적으로 작동되지 않은 경우에 예외 처리를 할 수 있도록
+    /// `Result<bool>`로 값을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn update_comment(comment_id: u64, data: String, version: u64) -> Result<bool> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"update comment set data = :data, version = version + 1, updated_at = now()
+            where comment_id = :comment_id and version = :version",
+            params! {
+                "data" => data,
+                "comment_id" => comment_id,
+                "version" => version,
+            },
+        )?;
+        Ok(conn.affected_rows() > 0)
+    }
+    /// 댓글을 DB에서 제거하는 메서드이다.
+    ///
+    /// 제거된 댓글이 달려있던 포스트의 고유 ID를 반환하며, 이미 제거된 댓글인 경우 `None`을 반환한다.
+    /// 채택된 답변인 댓글을 제거하면 채택도 함께 취소된다.
+    /// Sql명령이 정상적으로 작동되지 않은 경우에 예외 처리를 할 수 있도록
+    /// `Result<Option<u32>>`로 값을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn delete_comment(comment_id: u64) -> Result<Option<u32>> {
+        let mut conn = db::get_conn();
+        let post_id = conn.exec_first(
+            "select post_id from comment where comment_id = :comment_id",
+            params! {
+                "comment_id" => comment_id,
+            },
+        )?;
+        let accepted_post: Option<u64> = conn.exec_first(
+            "select post_id from post where accepted_comment_id = :comment_id",
+            params! {
+                "comment_id" => comment_id,
+            },
+        )?;
+        if let Some(accepted_post) = accepted_post {
+            Post::set_accepted_answer(accepted_post, None)?;
+        }
+        conn.exec_drop(
+            "delete from comment where comment_id = :comment_id",
+            params! {
+                "comment_id" => comment_id,
+            },
+        )?;
+        Ok(post_id)
     }
 }
 
 /// JSON 을 통해 새로 등록해야 할 댓글을 받을 때 필요한 구조체이다.
 #[derive(Deserialize, Serialize)]
 pub struct CommentRequest {
-    post_id: u32,
-    user_id: String,
-    data: String,
+    /// 댓글을 작성할 포스트의 고유 ID이다.
+    pub post_id: u32,
+    /// 댓글을 작성한 사용자의 고유 ID이다. 인증된 사용자로 채워지며 요청 본문으로는 지정할 수 없다.
+    #[serde(skip_deserializing)]
+    pub user_id: String,
+    /// 댓글의 내용이다.
+    pub data: String,
+}
+
+impl CommentRequest {
+    /// 요청받은 댓글의 입력값이 조건에 맞는지 확인하는 메서드이다.
+    ///
+    /// 사용자 ID 형식이 올바르지 않거나 댓글 내용이 비어있거나 너무 긴 경우 항목별 이유를 담은
+    /// `ApiError::InvalidFields`를 반환한다. 저장소마다 같은 규칙으로 댓글을 검증하도록 공통으로 사용한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::comment::CommentRequest;
+    /// let request = CommentRequest { post_id: 1, user_id: "u1".to_string(), data: "Use cargo".to_string() };
+    /// assert!(request.validate().is_ok());
+    /// let request = CommentRequest { data: "\u{200B}".to_string(), ..request };
+    /// assert!(request.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), ApiError> {
+        let mut errors = FieldErrors::default();
+        errors.check_user_id(&self.user_id);
+        errors.check_data(&self.data, MAX_COMMENT_CHARS);
+        errors.into_result()
+    }
+    /// 요청받은 댓글을 검증한 뒤 DB에 등록하고, 등록된 댓글을 반환하는 메서드이다.
+    ///
+    /// 이용 정지되었거나 비활성화된 사용자이거나 입력값이 조건에 맞지 않는 경우 그 이유를 `ApiError`로 반환한다.
+    /// 다른 사용자의 비공개 포스트에는 댓글을 작성할 수 없다.
+    pub fn submit(&self) -> std::result::Result<Comment, ApiError> {
+        if User::is_restricted(&self.user_id)? {
+            return Err(ApiError::Forbidden);
+        }
+        self.validate()?;
+        if Post::get_visibility(self.post_id as u64) == Some(Visibility::Private)
+            && Post::get_owner(&self.post_id.to_string()).as_deref() != Some(self.user_id.as_str())
+        {
+            return Err(post_not_found());
+        }
+        let new_comment = Comment::new(self.post_id, self.user_id.clone(), self.data.clone(), None);
+        Ok(new_comment.insert_db()?)
+    }
+}
+
+/// JSON 을 통해 댓글의 수정할 내용을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct UpdateCommentRequest {
+    /// 댓글의 새 내용이다.
+    pub data: String,
+    /// 수정을 시작할 때 받은 댓글의 `version`이다.
+    pub version: u64,
+}
+
+impl UpdateCommentRequest {
+    /// 댓글의 새 내용이 댓글을 작성할 때와 같은 조건에 맞는지 확인하는 메서드이다.
+    pub fn validate(&self) -> std::result::Result<(), ApiError> {
+        let mut errors = FieldErrors::default();
+        errors.check_data(&self.data, MAX_COMMENT_CHARS);
+        errors.into_result()
+    }
 }
 
 #[get("/api/comments/{post_id}")]
-pub async fn get_comment_api(post_id: web::Path<u32>) -> impl Responder {
-    println!("GET /api/comments");
-    let result = Comment::get(post_id.clone());
-    HttpResponse::Ok()
-        .insert_header(("Content-Type", "application/json;charset=utf-8"))
-        .json(result)
+// 댓글 목록의 공개 범위, 차단, 섀도 밴 확인에 필요한 저장소와 상태를 각각의 추출자로 받는다.
+#[allow(clippy::too_many_arguments)]
+pub async fn get_comment_api(
+    req: HttpRequest,
+    post_id: web::Path<u32>,
+    cursor: web::Query<Cursor>,
+    sort: web::Query<CommentSortRequest>,
+    posts: web::Data<dyn PostRepository>,
+    comments: web::Data<dyn CommentRepository>,
+    users: web::Data<dyn UserRepository>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let sort = sort.sort()?;
+    let viewer_id = Identity::from_header(&req);
+    let post = posts.get(&post_id.to_string()).ok_or_else(post_not_found)?;
+    if !post.is_visible_to(viewer_id.as_deref()) {
+        return Err(post_not_found());
+    }
+    let mut page = if cursor.is_requested() {
+        CursorPage::new(
+            comments.get_page_by_post(*post_id, &cursor, sort),
+            &cursor,
+            |comment| comment.comment_id,
+        )
+    } else {
+        CursorPage {
+            items: comments.get_by_post(*post_id, sort),
+            next_cursor: None,
+        }
+    };
+    BlockList::load(users.get_ref(), viewer_id.as_deref())
+        .extend(shadow_bans.hidden_from(viewer_id.as_deref()))
+        .retain(&mut page.items, |comment| &comment.user_id);
+    let modified = page
+        .items
+        .iter()
+        .map(|comment| comment.updated_at.unwrap_or(comment.create_at))
+        .max();
+    let mut builder = match modified {
+        Some(modified) if conditional::is_not_modified(&req, modified) => {
+            return Ok(HttpResponse::NotModified()
+                .insert_header(conditional::last_modified(modified))
+                .finish());
+        }
+        _ => HttpResponse::Ok(),
+    };
+    if let Some(modified) = modified {
+        builder.insert_header(conditional::last_modified(modified));
+    }
+    builder.insert_header(("Content-Type", response::JSON_CONTENT_TYPE));
+    if cursor.is_requested() {
+        return Ok(builder.json(page));
+    }
+    Ok(builder.json(page.items))
+}
+
+#[get("/api/comments/{post_id}/count")]
+pub async fn count_comments_api(
+    post_id: web::Path<u32>,
+    comments: web::Data<dyn CommentRepository>,
+) -> impl Responder {
+    response::json(Count {
+        count: comments.count_by_post(*post_id),
+    })
 }
 
 #[post("/api/comments")]
-pub async fn insert_comment_api(request: Json<CommentRequest>) -> impl Responder {
-    println!("POST /api/comments");
-    let new_comment = Comment::new(
-        request.post_id,
-        request.user_id.clone(),
+// 댓글 작성 후 이벤트, 알림, 검토, 캐시 처리에 필요한 공유 상태를 각각의 추출자로 받는다.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_comment_api(
+    req: HttpRequest,
+    identity: Identity,
+    request: Json<CommentRequest>,
+    idempotency: web::Data<Idempotency>,
+    events: web::Data<PostEvents>,
+    outbox: web::Data<Outbox>,
+    word_filter: web::Data<WordFilter>,
+    moderator: web::Data<dyn Moderator>,
+    cache: web::Data<Cache>,
+    search_index: web::Data<SearchIndex>,
+    comments: web::Data<dyn CommentRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let key = idempotency::key(&req, &identity.user_id)?;
+    if let Some(replayed) = idempotency.begin(key.as_deref())? {
+        return Ok(replayed);
+    }
+    let request = CommentRequest {
+        user_id: identity.user_id,
+        ..request.into_inner()
+    };
+    let result = insert_comment(
+        request,
+        &events,
+        outbox,
+        &word_filter,
+        moderator,
+        cache,
+        search_index,
+        comments.get_ref(),
+    )
+    .map(|comment| (format!("/api/comments/{}", comment.comment_id), comment));
+    idempotency.finish(key.as_deref(), &result);
+    let (location, comment) = result?;
+    Ok(response::created(location, comment))
+}
+
+/// 댓글을 작성하고 작성된 댓글을 반환하는 메서드이다.
+#[allow(clippy::too_many_arguments)]
+fn insert_comment(
+    mut request: CommentRequest,
+    events: &PostEvents,
+    outbox: web::Data<Outbox>,
+    word_filter: &WordFilter,
+    moderator: web::Data<dyn Moderator>,
+    cache: web::Data<Cache>,
+    search_index: web::Data<SearchIndex>,
+    comments: &dyn CommentRepository,
+) -> std::result::Result<Comment, ApiError> {
+    sanitize::clean(&mut request.data);
+    let flagged = word_filter.screen(&mut [("data", &mut request.data)])?;
+    let comment = comments.create(&request)?;
+    WordFilter::flag(ContentKind::Comment, comment.comment_id, &flagged);
+    moderation::submit(
+        moderator,
+        cache,
+        search_index,
+        ContentKind::Comment,
+        comment.comment_id,
         request.data.clone(),
-        None,
     );
-    match new_comment.insert_db() {
-        Ok(_) => HttpResponse::Created(),
-        Err(_) => HttpResponse::InternalServerError(),
+    events.publish(request.post_id as u64);
+    email::notify_comment(outbox, request.post_id as u64, request.user_id);
+    Ok(comment)
+}
+
+#[patch("/api/comments/{comment_id}")]
+// 수정된 댓글의 검토에 필요한 공유 상태를 각각의 추출자로 받는다.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_comment_api(
+    identity: Identity,
+    comment_id: web::Path<u64>,
+    request: Json<UpdateCommentRequest>,
+    word_filter: web::Data<WordFilter>,
+    moderator: web::Data<dyn Moderator>,
+    cache: web::Data<Cache>,
+    search_index: web::Data<SearchIndex>,
+    comments: web::Data<dyn CommentRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let mut request = request.into_inner();
+    sanitize::clean(&mut request.data);
+    request.validate()?;
+    let owner = comments
+        .get_owner(*comment_id)
+        .ok_or_else(comment_not_found)?;
+    if !identity.can_modify(&owner) {
+        return Err(ApiError::Forbidden);
+    }
+    let flagged = word_filter.screen(&mut [("data", &mut request.data)])?;
+    if !comments.update(*comment_id, request.data.clone(), request.version)? {
+        return Err(ApiError::Conflict(
+            "다른 곳에서 먼저 수정된 댓글입니다.".to_string(),
+        ));
+    }
+    WordFilter::flag(ContentKind::Comment, *comment_id, &flagged);
+    moderation::submit(
+        moderator,
+        cache,
+        search_index,
+        ContentKind::Comment,
+        *comment_id,
+        request.data,
+    );
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/api/comments/{comment_id}")]
+pub async fn delete_comment_api(
+    identity: Identity,
+    comment_id: web::Path<u64>,
+    events: web::Data<PostEvents>,
+    comments: web::Data<dyn CommentRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let owner = comments
+        .get_owner(*comment_id)
+        .ok_or_else(comment_not_found)?;
+    if !identity.can_modify(&owner) {
+        return Err(ApiError::Forbidden);
+    }
+    if let Some(post_id) = comments.delete(*comment_id)? {
+        events.publish(post_id as u64);
+    }
+    Ok(response::no_content())
+}
+
+/// 댓글이 존재하지 않을 때 반환하는 오류이다.
+pub(crate) fn comment_not_found() -> ApiError {
+    ApiError::NotFound("요청한 comment_id는 존재하지 않는 댓글 입니다.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{self, MemoryRepository};
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use serde_json::Value;
+    use std::sync::Arc;
+
+    #[actix_web::test]
+    async fn get_comment_rejects_hostile_post_id() {
+        let app =
+            test::init_service(testing::app(Arc::new(MemoryRepository::default()).into())).await;
+        for hostile in [
+            "0%20or%201=1",
+            "0%20union%20select%20*%20from%20user",
+            "1;%20drop%20table%20comment",
+            "'%20or%20''='",
+        ] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/api/comments/{}", hostile))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let error: Value = test::read_body_json(response).await;
+            assert_eq!(error["code"], "bad_request");
+        }
     }
 }
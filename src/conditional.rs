@@ -0,0 +1,46 @@
+//! # 조건부 요청 관련 동작을 정의하는 모듈
+//!
+//! `conditional`은 API 앞에 있는 HTTP 캐시나 CDN이 이미 가지고 있는 응답을 다시 받지 않도록
+//! `Last-Modified`와 `If-Modified-Since` 헤더를 처리하기 위한 메서드들로 이루어져 있다.
+
+use actix_web::http::header::{Header, HttpDate, IfModifiedSince, LastModified};
+use actix_web::HttpRequest;
+use chrono::{DateTime, Utc};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// `Last-Modified` 헤더에 사용할 값을 만드는 메서드이다.
+///
+/// HTTP 날짜 형식은 초 단위까지만 표현할 수 있으므로 초 미만은 버린다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::conditional;
+/// let time = code_mmunity_server::db::parse_datetime("2022-10-11 21:29:30").unwrap();
+/// assert_eq!(
+///     conditional::last_modified(time).to_string(),
+///     "Tue, 11 Oct 2022 21:29:30 GMT"
+/// );
+/// ```
+pub fn last_modified(time: DateTime<Utc>) -> LastModified {
+    let seconds = u64::try_from(time.timestamp()).unwrap_or(0);
+    LastModified(HttpDate::from(UNIX_EPOCH + Duration::from_secs(seconds)))
+}
+
+/// 요청의 `If-Modified-Since` 이후로 항목이 변경되지 않았는지 확인하는 메서드이다.
+///
+/// 헤더가 없거나 올바르지 않은 경우 `false`를 반환한다.
+/// `true`인 경우 본문 없이 `304 Not Modified`를 응답하면 된다.
+/// # 예제
+/// ```ignore
+/// if conditional::is_not_modified(&req, post.updated_at.unwrap_or(post.create_at)) {
+///     return HttpResponse::NotModified().finish();
+/// }
+/// ```
+pub fn is_not_modified(req: &HttpRequest, time: DateTime<Utc>) -> bool {
+    match IfModifiedSince::parse(req) {
+        Ok(IfModifiedSince(since)) => {
+            let LastModified(modified) = last_modified(time);
+            SystemTime::from(modified) <= SystemTime::from(since)
+        }
+        Err(_) => false,
+    }
+}
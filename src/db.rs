@@ -0,0 +1,302 @@
+//! # DB 접속 관련 동작을 정의하는 모듈
+//!
+//! `db`는 코드뮤니티의 각 모듈에서 MySQL서버에 접속할 때 공통으로 사용하는
+//! 메서드들로 이루어져 있다.
+//!
+//! 연결은 모든 워커가 공유하는 풀에서 얻으며, 연결을 얻지 못한 경우 간격을 늘려가며 몇 번 더 시도한다.
+//! 그래도 실패하면 차단기가 열려 일정 시간 동안은 접속을 시도하지 않고 바로 실패하므로,
+//! DB 서버가 잠시 내려간 동안 요청들이 접속 제한시간만큼 기다리지 않고 `503 Service Unavailable`을 응답한다.
+//!
+//! 읽기 전용 복제본이 설정된 경우 포스트, 사용자, 댓글 조회는 `get_read_conn()`으로 복제본의 연결을 얻으며,
+//! 복제본에 접속할 수 없으면 주 서버의 연결을 대신 사용한다.
+
+use crate::config::{self, DbConfig};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use mysql::prelude::*;
+use mysql::*;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use std::{panic, thread};
+
+/// 서버 시작 시 확인된 DB 서버 접속 설정이다.
+static CONFIG: OnceLock<DbConfig> = OnceLock::new();
+/// 모든 워커가 공유하는 연결 풀이다. 처음으로 연결을 얻을 때 만들어진다.
+static POOL: Mutex<Option<Pool>> = Mutex::new(None);
+/// 읽기 전용 복제본의 연결 풀이다. 처음으로 복제본의 연결을 얻을 때 만들어진다.
+static REPLICA_POOL: Mutex<Option<Pool>> = Mutex::new(None);
+/// 복제본에 접속할 수 없었던 경우 다시 복제본에 접속을 시도할 수 있는 시각이다.
+static REPLICA_DOWN_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+/// 차단기가 열려 있는 경우 다시 접속을 시도할 수 있는 시각이다.
+static OPEN_UNTIL: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// 연결을 얻지 못했을 때 시도하는 최대 횟수이다.
+const CONNECT_ATTEMPTS: u32 = 3;
+/// 처음 다시 시도하기까지 기다리는 시간이다. 다시 시도할 때마다 두 배로 늘어난다.
+const BASE_RETRY_DELAY: Duration = Duration::from_millis(100);
+/// 연결을 얻지 못한 뒤 차단기가 열려 있는 시간이다.
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// DB 서버에 접속할 수 없어 요청을 처리하지 못한 경우 패닉에 담기는 값이다.
+///
+/// 요청 처리 중 발생한 이 패닉은 `503 Service Unavailable` 응답으로 바뀐다.
+#[derive(Debug)]
+pub struct Unavailable {
+    /// 다시 요청해볼 수 있을 때까지의 시간이다.
+    pub retry_after: Duration,
+}
+
+/// 서버 시작 시 확인된 DB 서버 접속 설정을 저장하는 메서드이다.
+///
+/// 이후 `get_conn()`은 환경변수를 다시 읽지 않고 저장된 설정을 사용한다.
+/// 이미 설정이 저장된 경우 아무 작업도 하지 않는다.
+pub fn init(config: DbConfig) {
+    let _ = CONFIG.set(config);
+}
+
+/// DB 서버에 접속한 뒤 연결을 반환하는 메서드이다.
+///
+/// `init()`으로 저장된 설정을 사용하며, 저장된 설정이 없는 경우 설정 파일과 환경변수에서 읽는다.
+/// 연결을 얻지 못한 경우 간격을 두 배씩 늘려가며 최대 `CONNECT_ATTEMPTS`번 시도하고,
+/// 모두 실패하면 차단기를 열고 `Unavailable`을 담은 패닉을 발생시킨다.
+/// SSL을 사용하도록 설정된 경우 설정된 인증서를 사용하여 접속한다.
+/// 모든 연결의 시간대는 UTC로 설정되므로 `now()`와 `DATETIME` 열의 값은 UTC 기준이다.
+/// # 예제
+/// ```no_run
+/// use code_mmunity_server::db;
+/// use mysql::prelude::*;
+/// let mut conn = db::get_conn();
+/// conn.query_drop("select 1").unwrap();
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속할 수 없거나 차단기가 열려 있는 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn get_conn() -> PooledConn {
+    if let Some(retry_after) = circuit_open() {
+        panic::panic_any(Unavailable { retry_after });
+    }
+    let mut attempt = 0;
+    loop {
+        match pool().and_then(|pool| pool.get_conn()) {
+            Ok(conn) => {
+                close_circuit();
+                return conn;
+            }
+            Err(error) if attempt + 1 < CONNECT_ATTEMPTS => {
+                tracing::warn!(%error, attempt, "DB 연결을 얻지 못해 다시 시도합니다.");
+                thread::sleep(BASE_RETRY_DELAY * 2u32.pow(attempt));
+                attempt += 1;
+            }
+            Err(error) => {
+                tracing::error!(%error, "DB 서버에 접속할 수 없어 차단기를 엽니다.");
+                *OPEN_UNTIL.lock().unwrap() = Some(Instant::now() + BREAKER_COOLDOWN);
+                panic::panic_any(Unavailable {
+                    retry_after: BREAKER_COOLDOWN,
+                });
+            }
+        }
+    }
+}
+
+/// 여러 질의를 하나의 트랜잭션으로 실행하는 메서드이다.
+///
+/// `work`가 `Ok`를 반환하면 커밋하고, 오류를 반환하거나 패닉이 발생하면 그때까지의 변경을 모두 되돌리므로
+/// 함께 성공해야 하는 질의들이 일부만 반영되지 않는다. 커밋에 실패한 경우에도 오류를 반환한다.
+/// # 예제
+/// ```ignore
+/// use code_mmunity_server::db;
+/// db::transaction(|tx| {
+///     tx.exec_drop("delete from post_tag where post_id = :post_id", params! { "post_id" => 1 })?;
+///     tx.exec_drop("delete from post where post_id = :post_id", params! { "post_id" => 1 })
+/// })?;
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속할 수 없거나 차단기가 열려 있는 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn transaction<T>(work: impl FnOnce(&mut Transaction<'_>) -> Result<T>) -> Result<T> {
+    let mut conn = get_conn();
+    let mut tx = conn.start_transaction(TxOpts::default())?;
+    let value = work(&mut tx)?;
+    tx.commit()?;
+    Ok(value)
+}
+
+/// 읽기 전용 복제본에 접속한 뒤 연결을 반환하는 메서드이다.
+///
+/// 조회만 하는 질의에 사용하며, 복제본이 설정되지 않은 경우 `get_conn()`과 같다.
+/// 복제본에 접속할 수 없는 경우 `BREAKER_COOLDOWN` 동안은 복제본에 접속을 시도하지 않고 주 서버의 연결을 반환한다.
+/// 복제본은 주 서버보다 늦게 갱신될 수 있으므로 방금 변경한 데이터를 다시 읽을 때는 `get_conn()`을 사용해야 한다.
+/// # 예제
+/// ```ignore
+/// use code_mmunity_server::db;
+/// let mut conn = db::get_read_conn();
+/// let count: Option<u64> = conn.query_first("select count(*) from post").unwrap();
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - 복제본과 주 서버에 모두 접속할 수 없거나 차단기가 열려 있는 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn get_read_conn() -> PooledConn {
+    let Some(url) = config().replica_url.as_deref() else {
+        return get_conn();
+    };
+    let mut down_until = REPLICA_DOWN_UNTIL.lock().unwrap();
+    if matches!(*down_until, Some(until) if until > Instant::now()) {
+        drop(down_until);
+        return get_conn();
+    }
+    match replica_pool(url).and_then(|pool| pool.get_conn()) {
+        Ok(conn) => {
+            if down_until.take().is_some() {
+                tracing::info!("복제본에 다시 접속되어 조회에 복제본을 사용합니다.");
+            }
+            conn
+        }
+        Err(error) => {
+            tracing::warn!(%error, "복제본에 접속할 수 없어 주 서버에서 조회합니다.");
+            *down_until = Some(Instant::now() + BREAKER_COOLDOWN);
+            drop(down_until);
+            get_conn()
+        }
+    }
+}
+
+/// 공유하는 연결 풀을 반환하는 메서드이다. 풀이 없는 경우 새로 만든다.
+fn pool() -> Result<Pool> {
+    let mut pool = POOL.lock().unwrap();
+    if let Some(pool) = pool.as_ref() {
+        return Ok(pool.clone());
+    }
+    let created = Pool::new(opts())?;
+    *pool = Some(created.clone());
+    Ok(created)
+}
+
+/// 복제본의 연결 풀을 반환하는 메서드이다. 풀이 없는 경우 새로 만든다.
+fn replica_pool(url: &str) -> Result<Pool> {
+    let mut pool = REPLICA_POOL.lock().unwrap();
+    if let Some(pool) = pool.as_ref() {
+        return Ok(pool.clone());
+    }
+    let builder = OptsBuilder::from_opts(Opts::from_url(url)?);
+    let created = Pool::new(with_settings(builder, config()))?;
+    *pool = Some(created.clone());
+    Ok(created)
+}
+
+/// 차단기가 열려 있는 경우 다시 접속을 시도할 수 있을 때까지 남은 시간을 반환하는 메서드이다.
+///
+/// 차단기가 닫혀 있거나 열려 있는 시간이 지난 경우 `None`을 반환한다.
+pub fn circuit_open() -> Option<Duration> {
+    let open_until = (*OPEN_UNTIL.lock().unwrap())?;
+    open_until
+        .checked_duration_since(Instant::now())
+        .filter(|remaining| !remaining.is_zero())
+}
+
+/// 차단기를 닫는 메서드이다.
+fn close_circuit() {
+    let mut open_until = OPEN_UNTIL.lock().unwrap();
+    if open_until.take().is_some() {
+        tracing::info!("DB 서버에 다시 접속되어 차단기를 닫습니다.");
+    }
+}
+
+/// 저장된 설정을 반환하는 메서드이다. 저장된 설정이 없는 경우 설정 파일과 환경변수에서 읽는다.
+fn config() -> &'static DbConfig {
+    CONFIG.get_or_init(|| config::load_db().unwrap_or_else(|message| panic!("{}", message)))
+}
+
+/// 저장된 설정으로 주 서버 접속 옵션을 만드는 메서드이다.
+fn opts() -> OptsBuilder {
+    let config = config();
+    let builder = OptsBuilder::new()
+        .ip_or_hostname(Some(config.server.clone()))
+        .tcp_port(config.port)
+        .user(Some(config.user.clone()))
+        .pass(Some(config.password.clone()))
+        .db_name(Some(config.database.clone()));
+    with_settings(builder, config)
+}
+
+/// 주 서버와 복제본이 함께 사용하는 접속 옵션을 설정하는 메서드이다.
+///
+/// 연결 풀의 크기와 접속 제한시간, 연결마다 캐시할 준비된 질의의 수를 설정하며, 쿼리 제한시간이 주어진 경우 소켓 읽기·쓰기 제한시간과
+/// `select`문의 최대 실행 시간(`max_execution_time`)으로 사용한다.
+fn with_settings(builder: OptsBuilder, config: &DbConfig) -> OptsBuilder {
+    let ssl = config
+        .ssl_cert
+        .as_ref()
+        .map(|path| SslOpts::default().with_root_cert_path(Some(path.clone())));
+    let constraints = PoolConstraints::new(config.pool_min, config.pool_max)
+        .expect("DB_POOL_MIN이 DB_POOL_MAX보다 큼");
+    let mut init = vec!["set time_zone = '+00:00'".to_string()];
+    if let Some(timeout) = config.query_timeout {
+        init.push(format!(
+            "set session max_execution_time = {}",
+            timeout.as_millis()
+        ));
+    }
+    builder
+        .ssl_opts(ssl)
+        .init(init)
+        .pool_opts(PoolOpts::default().with_constraints(constraints))
+        .tcp_connect_timeout(config.connect_timeout)
+        .read_timeout(config.query_timeout)
+        .write_timeout(config.query_timeout)
+        .stmt_cache_size(config.stmt_cache_size)
+}
+
+/// DB 서버에 접속하여 `select 1`을 실행해보는 메서드이다.
+///
+/// 접속과 쿼리 실행이 각각 `timeout` 안에 끝나지 않거나 문제가 발생한 경우 오류를 반환한다.
+/// `get_conn()`과 달리 DB 서버에 접속할 수 없어도 패닉이 발생하지 않으며, 차단기가 열려 있어도 접속을 시도한다.
+/// 접속에 성공하면 차단기를 닫는다.
+/// # 예제
+/// ```ignore
+/// if db::ping(Duration::from_secs(2)).is_err() {
+///     println!("DB 서버에 접속할 수 없습니다.");
+/// }
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+pub fn ping(timeout: Duration) -> Result<()> {
+    let opts = opts()
+        .tcp_connect_timeout(Some(timeout))
+        .read_timeout(Some(timeout))
+        .write_timeout(Some(timeout));
+    let mut conn = Conn::new(opts)?;
+    conn.query_drop("select 1")?;
+    close_circuit();
+    Ok(())
+}
+
+/// DB에서 가져온 `DATETIME` 값을 UTC 시각으로 바꾸는 메서드이다.
+///
+/// `YYYY-MM-DD HH:MM:SS` 형식이 아닌 경우 `None`을 반환한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::db::parse_datetime;
+/// let time = parse_datetime("2022-10-11 21:29:30").unwrap();
+/// assert_eq!(time.to_rfc3339(), "2022-10-11T21:29:30+00:00");
+/// assert!(parse_datetime("2022-10-11 21:29:30.5").is_some());
+/// assert!(parse_datetime("yesterday").is_none());
+/// ```
+pub fn parse_datetime(text: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|time| time.and_utc())
+}
@@ -0,0 +1,210 @@
+//! # API 오류 관련 동작을 정의하는 모듈
+//!
+//! `error`는 코드뮤니티의 각 모듈에서 요청을 처리하지 못한 경우 반환하는 오류와,
+//! 오류를 일정한 형태의 JSON 응답으로 바꾸기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 모든 오류 응답의 본문은 아래와 같은 형태이다.
+//! ```json
+//! { "code": "not_found", "message": "요청한 포스트가 존재하지 않습니다.", "details": null, "request_id": "..." }
+//! ```
+//!
+//! 입력값이 올바르지 않은 항목이 있는 경우 `details`는 항목별 이유를 담은 객체이다.
+//! ```json
+//! { "code": "validation_failed", "message": "입력값이 올바르지 않습니다.", "details": { "title": "제목은 비어있을 수 없습니다." }, "request_id": "..." }
+//! ```
+
+use crate::request_id;
+use crate::response;
+use actix_web::error::JsonPayloadError;
+use actix_web::http::StatusCode;
+use actix_web::{HttpResponse, ResponseError};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+/// 요청을 처리하지 못한 이유를 나타내는 오류이다.
+#[derive(Debug)]
+pub enum ApiError {
+    /// 요청의 형식이 올바르지 않은 경우
+    BadRequest(String),
+    /// 요청의 형식은 올바르지만 값이 조건에 맞지 않는 경우
+    Validation(String),
+    /// 요청의 항목들이 조건에 맞지 않는 경우로, 항목별로 맞지 않는 이유를 담고 있다.
+    InvalidFields(BTreeMap<&'static str, String>),
+    /// 인증 토큰이 없거나 올바르지 않은 경우
+    Unauthorized,
+    /// 요청한 작업을 수행할 권한이 없는 경우
+    Forbidden,
+    /// 요청한 항목이 존재하지 않는 경우
+    NotFound(String),
+    /// 다른 요청에 의해 항목이 먼저 변경된 경우
+    Conflict(String),
+    /// 사용자의 저장 공간 한도를 초과한 경우
+    PayloadTooLarge,
+    /// 요청 본문이 허용된 최대 크기(바이트)를 초과한 경우
+    RequestTooLarge(usize),
+    /// 짧은 시간에 너무 많은 요청을 보낸 경우로, 다시 요청할 수 있을 때까지의 시간을 담고 있다.
+    TooManyRequests(Duration),
+    /// DB 작업 중 문제가 발생한 경우
+    Db(mysql::Error),
+    /// SQLite DB 작업 중 문제가 발생한 경우
+    Sqlite(rusqlite::Error),
+    /// DB 서버에 일시적으로 접속할 수 없는 경우로, 다시 요청해볼 수 있을 때까지의 시간을 담고 있다.
+    DbUnavailable(Duration),
+    /// 요청을 처리하는 데 필요한 외부 프로그램이나 서비스를 사용할 수 없는 경우
+    Unavailable(String),
+}
+
+/// 오류 응답의 본문이다.
+#[derive(Serialize)]
+struct ErrorBody {
+    /// 오류의 종류를 나타내는 문자열이다.
+    code: &'static str,
+    /// 사용자에게 보여줄 수 있는 오류 메세지이다.
+    message: String,
+    /// 오류의 자세한 내용이다. 없는 경우 `null`이다.
+    details: Option<Value>,
+    /// 오류가 발생한 요청의 ID이다. 서버 로그에서 해당 요청을 찾을 때 사용한다.
+    request_id: Option<String>,
+}
+
+impl ApiError {
+    /// 오류의 종류를 나타내는 문자열을 반환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::error::ApiError;
+    /// assert_eq!(ApiError::NotFound("포스트".to_string()).code(), "not_found");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Validation(_) | ApiError::InvalidFields(_) => "validation_failed",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::Forbidden => "forbidden",
+            ApiError::NotFound(_) => "not_found",
+            ApiError::Conflict(_) => "conflict",
+            ApiError::PayloadTooLarge => "payload_too_large",
+            ApiError::RequestTooLarge(_) => "request_too_large",
+            ApiError::TooManyRequests(_) => "too_many_requests",
+            ApiError::Db(_) | ApiError::Sqlite(_) => "database_error",
+            ApiError::DbUnavailable(_) => "database_unavailable",
+            ApiError::Unavailable(_) => "service_unavailable",
+        }
+    }
+    /// 오류의 자세한 내용을 반환하는 메서드이다.
+    ///
+    /// 항목별 오류인 경우 항목 이름을 키로 하는 객체이며, 그 외의 경우 문자열이다.
+    /// DB 오류의 내용은 내부 구조가 드러나지 않도록 응답에 포함하지 않는다.
+    pub fn details(&self) -> Option<Value> {
+        match self {
+            ApiError::BadRequest(details) | ApiError::Validation(details) => {
+                Some(Value::from(details.as_str()))
+            }
+            ApiError::InvalidFields(fields) => Some(Value::from_iter(
+                fields
+                    .iter()
+                    .map(|(field, message)| (*field, message.as_str())),
+            )),
+            ApiError::RequestTooLarge(limit) => Some(Value::from(format!("최대 {}바이트", limit))),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::BadRequest(_) => write!(f, "요청의 형식이 올바르지 않습니다."),
+            ApiError::Validation(_) | ApiError::InvalidFields(_) => {
+                write!(f, "입력값이 올바르지 않습니다.")
+            }
+            ApiError::Unauthorized => write!(f, "인증 토큰이 없거나 올바르지 않습니다."),
+            ApiError::Forbidden => write!(f, "요청한 작업을 수행할 권한이 없습니다."),
+            ApiError::NotFound(message)
+            | ApiError::Conflict(message)
+            | ApiError::Unavailable(message) => write!(f, "{}", message),
+            ApiError::PayloadTooLarge => write!(f, "저장 공간 한도를 초과했습니다."),
+            ApiError::RequestTooLarge(_) => write!(f, "요청 본문이 너무 큽니다."),
+            ApiError::TooManyRequests(_) => {
+                write!(f, "요청이 너무 많습니다. 잠시 후 다시 시도해주세요.")
+            }
+            ApiError::Db(_) | ApiError::Sqlite(_) => write!(f, "DB 작업 중 문제가 발생했습니다."),
+            ApiError::DbUnavailable(_) => write!(
+                f,
+                "DB 서버에 일시적으로 접속할 수 없습니다. 잠시 후 다시 시도해주세요."
+            ),
+        }
+    }
+}
+
+impl ApiError {
+    /// JSON 요청 본문을 읽지 못한 이유를 오류로 바꾸는 메서드이다.
+    ///
+    /// 본문이 `limit`바이트를 초과한 경우 `RequestTooLarge`, 그 외의 경우 `BadRequest`를 반환한다.
+    pub fn from_json_error(error: JsonPayloadError, limit: usize) -> Self {
+        match error {
+            JsonPayloadError::OverflowKnownLength { .. } | JsonPayloadError::Overflow { .. } => {
+                ApiError::RequestTooLarge(limit)
+            }
+            error => ApiError::BadRequest(error.to_string()),
+        }
+    }
+}
+
+impl From<mysql::Error> for ApiError {
+    fn from(error: mysql::Error) -> Self {
+        ApiError::Db(error)
+    }
+}
+
+impl From<rusqlite::Error> for ApiError {
+    fn from(error: rusqlite::Error) -> Self {
+        ApiError::Sqlite(error)
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Validation(_) | ApiError::InvalidFields(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ApiError::Unauthorized => StatusCode::UNAUTHORIZED,
+            ApiError::Forbidden => StatusCode::FORBIDDEN,
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::PayloadTooLarge | ApiError::RequestTooLarge(_) => {
+                StatusCode::PAYLOAD_TOO_LARGE
+            }
+            ApiError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+            ApiError::Db(_) | ApiError::Sqlite(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::DbUnavailable(_) | ApiError::Unavailable(_) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            ApiError::Db(error) => tracing::error!(%error, "DB 오류"),
+            ApiError::Sqlite(error) => tracing::error!(%error, "DB 오류"),
+            _ => {}
+        }
+        let mut builder = HttpResponse::build(self.status_code());
+        if let ApiError::TooManyRequests(wait) | ApiError::DbUnavailable(wait) = self {
+            builder.insert_header(("Retry-After", (wait.as_secs() + 1).to_string()));
+        }
+        builder
+            .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+            .json(ErrorBody {
+                code: self.code(),
+                message: self.to_string(),
+                details: self.details(),
+                request_id: request_id::current(),
+            })
+    }
+}
@@ -0,0 +1,99 @@
+//! # 포스트의 실시간 변경 알림 관련 동작을 정의하는 모듈
+//!
+//! `events`는 코드뮤니티에서 포스트의 공감 수나 댓글 수가 바뀌었을 때
+//! 해당 포스트를 보고 있는 클라이언트에게 SSE(Server-Sent Events)로 알리기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+
+use crate::error::ApiError;
+use crate::post::{self, Post};
+use actix_web::web::Bytes;
+use actix_web::{get, web, HttpResponse};
+use futures_util::{stream, StreamExt};
+use serde::Serialize;
+use std::io;
+use tokio::sync::broadcast;
+
+/// 포스트의 공감 수와 댓글 수를 나타내는 객체이다.
+#[derive(Clone, Serialize)]
+pub struct PostCounts {
+    /// 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 포스트의 공감 수이다.
+    pub likes: u64,
+    /// 포스트에 달린 댓글 수이다.
+    pub comments: u64,
+}
+
+/// 포스트의 변경 알림을 구독자들에게 전달하는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+pub struct PostEvents {
+    sender: broadcast::Sender<PostCounts>,
+}
+
+impl Default for PostEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PostEvents {
+    /// 새 알림 객체를 생성하는 생성자이다.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(256);
+        Self { sender }
+    }
+    /// `post_id`에 해당하는 포스트의 현재 공감 수와 댓글 수를 구독자들에게 알리는 메서드이다.
+    ///
+    /// 존재하지 않는 포스트이거나 구독자가 없는 경우 아무 동작도 하지 않는다.
+    /// # 예제
+    /// ```ignore
+    /// events.publish(post_id);
+    /// ```
+    pub fn publish(&self, post_id: u64) {
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        if let Some(counts) = Post::get_counts(post_id) {
+            let _ = self.sender.send(counts);
+        }
+    }
+    /// 알림을 구독하는 메서드이다.
+    pub fn subscribe(&self) -> broadcast::Receiver<PostCounts> {
+        self.sender.subscribe()
+    }
+}
+
+/// 공감 수와 댓글 수를 SSE 이벤트 형식으로 변환하는 메서드이다.
+fn to_event(counts: &PostCounts) -> io::Result<Bytes> {
+    Ok(Bytes::from(format!(
+        "event: counts\ndata: {}\n\n",
+        serde_json::to_string(counts).unwrap()
+    )))
+}
+
+#[get("/api/posts/{post_id}/stream")]
+pub async fn post_stream_api(
+    post_id: web::Path<u64>,
+    events: web::Data<PostEvents>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let post_id = *post_id;
+    let initial = Post::get_counts(post_id).ok_or_else(post::post_not_found)?;
+    let receiver = events.subscribe();
+    let updates = stream::unfold(receiver, move |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(counts) if counts.post_id == post_id => {
+                    return Some((to_event(&counts), receiver))
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    let body = stream::once(async move { to_event(&initial) }).chain(updates);
+    Ok(HttpResponse::Ok()
+        .insert_header(("Content-Type", "text/event-stream"))
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(body))
+}
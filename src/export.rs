@@ -0,0 +1,220 @@
+//! # 사용자 데이터 내보내기 관련 동작을 정의하는 모듈
+//!
+//! `export`는 코드뮤니티에서 사용자가 자신의 모든 데이터(프로필, 포스트, 댓글, 공감 내역)를
+//! 하나의 JSON 문서로 내려받을 수 있도록 하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 작성한 포스트가 많은 사용자도 메모리를 많이 사용하지 않도록 DB에서 한 행씩 읽어
+//! 바로 응답으로 흘려보내는 방식으로 동작한다.
+
+use crate::auth::Identity;
+use crate::comment::Comment;
+use crate::db;
+use crate::error::ApiError;
+use crate::post::Post;
+use crate::response;
+use crate::tag::Tag;
+use crate::user::{self, User};
+use actix_web::web::Bytes;
+use actix_web::{get, web, HttpResponse};
+use futures_util::stream;
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+use std::io;
+use std::thread;
+use tokio::sync::mpsc;
+
+/// 내보내기 문서에 포함되는 공감 내역이다.
+#[derive(Serialize)]
+pub struct ExportedReaction {
+    /// 공감한 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 공감의 종류이다.
+    pub react_kind: String,
+}
+
+/// 내보내기 문서를 조각 단위로 전송하는 객체이다.
+struct ExportWriter {
+    sender: mpsc::Sender<io::Result<Bytes>>,
+}
+
+impl ExportWriter {
+    /// 문서 조각을 전송하는 메서드이다.
+    ///
+    /// 클라이언트의 연결이 끊어진 경우 `false`를 반환한다.
+    fn write(&self, chunk: String) -> bool {
+        self.sender.blocking_send(Ok(Bytes::from(chunk))).is_ok()
+    }
+    /// `query`의 결과를 한 행씩 JSON 배열의 원소로 전송하는 메서드이다.
+    fn write_array<T, F>(
+        &self,
+        conn: &mut PooledConn,
+        query: &str,
+        user_id: &str,
+        mut map: F,
+    ) -> Result<bool>
+    where
+        T: FromRow,
+        F: FnMut(T) -> String,
+    {
+        if !self.write("[".to_string()) {
+            return Ok(false);
+        }
+        let result = conn.exec_iter(query, params! { "user_id" => user_id })?;
+        for (index, row) in result.enumerate() {
+            let item = map(from_row(row?));
+            let chunk = if index == 0 {
+                item
+            } else {
+                format!(",{}", item)
+            };
+            if !self.write(chunk) {
+                return Ok(false);
+            }
+        }
+        Ok(self.write("]".to_string()))
+    }
+    /// 사용자의 모든 데이터를 JSON 문서로 전송하는 메서드이다.
+    ///
+    /// 문서는 `profile`, `posts`, `comments`, `likes` 항목으로 이루어져 있다.
+    fn write_user(&self, user: User) -> Result<()> {
+        let mut conn = db::get_conn();
+        let user_name = user.user_name.clone();
+        let user_id = user.user_id.clone();
+        if !self.write(format!(
+            "{{\"profile\":{},\"posts\":",
+            serde_json::to_string(&user).unwrap()
+        )) {
+            return Ok(());
+        }
+        let written = self.write_array(
+            &mut conn,
+            r"select post_id, title, language, data, likes, report_count,
+            date_format(create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+            date_format(updated_at, '%Y-%m-%d %H:%i:%s'), date_format(publish_at, '%Y-%m-%d %H:%i:%s'), version
+            from post where user_id = :user_id order by post_id",
+            &user_id,
+            |(
+                post_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+                publish_at,
+                version,
+            ): (_, _, _, _, _, _, String, String, _, Option<String>, _, _)| {
+                serde_json::to_string(&Post {
+                    post_id,
+                    user_id: user_id.clone(),
+                    title,
+                    user_name: user_name.clone(),
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+                    post_type: post_type.parse().unwrap_or_default(),
+                    accepted_comment_id,
+                    updated_at: updated_at.as_deref().and_then(db::parse_datetime),
+                    tags: Tag::get_names(post_id),
+                    publish_at,
+                    version,
+                    bot: false,
+                    visibility: Post::get_visibility(post_id).unwrap_or_default(),
+                    attachments: Vec::new(),
+                    is_truncated: false,
+                })
+                .unwrap()
+            },
+        )?;
+        if !written || !self.write(",\"comments\":".to_string()) {
+            return Ok(());
+        }
+        let written = self.write_array(
+            &mut conn,
+            r"select comment_id, post_id, user_id, data, date_format(create_at, '%Y-%m-%d %H:%i:%s'),
+            exists(select 1 from post where post.accepted_comment_id = comment.comment_id),
+            date_format(updated_at, '%Y-%m-%d %H:%i:%s'), version
+            from comment where user_id = :user_id order by comment_id",
+            &user_id,
+            |(comment_id, post_id, user_id, data, create_at, is_accepted, updated_at, version): (
+                _,
+                _,
+                _,
+                _,
+                Option<String>,
+                _,
+                Option<String>,
+                _,
+            )| {
+                serde_json::to_string(&Comment {
+                    comment_id,
+                    post_id,
+                    user_id,
+                    user_name: user_name.clone(),
+                    data,
+                    create_at: create_at
+                        .as_deref()
+                        .and_then(db::parse_datetime)
+                        .unwrap_or_default(),
+                    is_accepted,
+                    updated_at: updated_at.as_deref().and_then(db::parse_datetime),
+                    version,
+                })
+                .unwrap()
+            },
+        )?;
+        if !written || !self.write(",\"likes\":".to_string()) {
+            return Ok(());
+        }
+        let written = self.write_array(
+            &mut conn,
+            "select post_id, react_kind from react where user_id = :user_id order by post_id",
+            &user_id,
+            |(post_id, react_kind)| {
+                serde_json::to_string(&ExportedReaction {
+                    post_id,
+                    react_kind,
+                })
+                .unwrap()
+            },
+        )?;
+        if written {
+            self.write("}".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[get("/api/users/{user_id}/export")]
+pub async fn export_user_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if identity.user_id != *user_id {
+        return Err(ApiError::Forbidden);
+    }
+    let user = User::get_user(user_id.clone()).ok_or_else(user::user_not_found)?;
+    let (sender, receiver) = mpsc::channel(16);
+    thread::spawn(move || {
+        let writer = ExportWriter { sender };
+        if let Err(error) = writer.write_user(user) {
+            let _ = writer.sender.blocking_send(Err(io::Error::other(error)));
+        }
+    });
+    let body = stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    });
+    Ok(HttpResponse::Ok()
+        .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+        .insert_header((
+            "Content-Disposition",
+            "attachment; filename=\"code_mmunity_export.json\"",
+        ))
+        .streaming(body))
+}
@@ -0,0 +1,308 @@
+//! # 포스트 목록의 항목 선택(sparse fieldset) 관련 동작을 정의하는 모듈
+//!
+//! `fields`는 데이터 사용량을 줄여야 하는 모바일 클라이언트가 `GET /api/posts?fields=post_id,title,likes`와 같이
+//! 필요한 항목만 요청할 수 있도록, 요청한 항목을 확인하고 해당 열만 DB에서 조회하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 최신순 피드와 태그 피드는 요청한 항목의 열만 조회하는 질의문을 만들어 실행하며,
+//! 다른 순위 방식은 순위를 정한 포스트들에서 요청한 항목만 골라 반환한다.
+
+use crate::db;
+use crate::pagination::Pagination;
+use crate::post::Post;
+use crate::sanitize;
+use crate::summary::{self, BodyMode};
+use mysql::prelude::*;
+use mysql::*;
+use serde_json::{Map, Value};
+
+/// 포스트에서 선택할 수 있는 항목이다.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PostField {
+    /// 포스트의 고유 ID
+    PostId,
+    /// 작성자의 고유 ID
+    UserId,
+    /// 작성자의 이름
+    UserName,
+    /// 제목
+    Title,
+    /// 프로그래밍 언어 종류
+    Language,
+    /// 내용
+    Data,
+    /// 공감 수
+    Likes,
+    /// 신고당한 횟수
+    ReportCount,
+    /// 작성된 날짜
+    CreateAt,
+    /// 포스트의 종류
+    PostType,
+    /// 채택된 답변 댓글의 고유 ID
+    AcceptedCommentId,
+    /// 마지막으로 수정된 날짜
+    UpdatedAt,
+    /// 태그들
+    Tags,
+    /// 수정된 횟수
+    Version,
+}
+
+/// 선택할 수 있는 모든 항목이다.
+const ALL_FIELDS: &[PostField] = &[
+    PostField::PostId,
+    PostField::UserId,
+    PostField::UserName,
+    PostField::Title,
+    PostField::Language,
+    PostField::Data,
+    PostField::Likes,
+    PostField::ReportCount,
+    PostField::CreateAt,
+    PostField::PostType,
+    PostField::AcceptedCommentId,
+    PostField::UpdatedAt,
+    PostField::Tags,
+    PostField::Version,
+];
+
+impl PostField {
+    /// 응답에 사용하는 항목 이름을 반환하는 메서드이다. `Post`를 JSON으로 직렬화했을 때의 이름과 같다.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PostId => "post_id",
+            Self::UserId => "user_id",
+            Self::UserName => "user_name",
+            Self::Title => "title",
+            Self::Language => "language",
+            Self::Data => "data",
+            Self::Likes => "likes",
+            Self::ReportCount => "report_count",
+            Self::CreateAt => "create_at",
+            Self::PostType => "post_type",
+            Self::AcceptedCommentId => "accepted_comment_id",
+            Self::UpdatedAt => "updated_at",
+            Self::Tags => "tags",
+            Self::Version => "version",
+        }
+    }
+    /// 항목을 조회하는 SQL 식을 반환하는 메서드이다. `post` 테이블을 기준으로 한다.
+    fn column(&self) -> &'static str {
+        match self {
+            Self::PostId => "post.post_id",
+            Self::UserId => "post.user_id",
+            Self::UserName => {
+                "(select if(deactivated_at is null, user_name, '비활성화된 사용자') from user where user.user_id = post.user_id)"
+            }
+            Self::Title => "post.title",
+            Self::Language => "post.language",
+            Self::Data => "post.data",
+            Self::Likes => "post.likes",
+            Self::ReportCount => "post.report_count",
+            Self::CreateAt => "date_format(post.create_at, '%Y-%m-%d %H:%i:%s')",
+            Self::PostType => "post.post_type",
+            Self::AcceptedCommentId => "post.accepted_comment_id",
+            Self::UpdatedAt => "date_format(post.updated_at, '%Y-%m-%d %H:%i:%s')",
+            Self::Tags => {
+                r"(select group_concat(tag.name order by tag.name separator ',')
+                from post_tag join tag on post_tag.tag_id = tag.tag_id where post_tag.post_id = post.post_id)"
+            }
+            Self::Version => "post.version",
+        }
+    }
+    /// 조회한 열의 값을 `Post`를 JSON으로 직렬화했을 때와 같은 형태의 값으로 바꾸는 메서드이다.
+    fn to_json(self, value: mysql::Value) -> Value {
+        let text = || {
+            from_value_opt::<Option<String>>(value.clone())
+                .ok()
+                .flatten()
+        };
+        let number = || from_value_opt::<Option<u64>>(value.clone()).ok().flatten();
+        match self {
+            Self::PostId | Self::Likes | Self::ReportCount | Self::Version => {
+                Value::from(number().unwrap_or_default())
+            }
+            Self::AcceptedCommentId => number().map_or(Value::Null, Value::from),
+            Self::Title | Self::Data => Value::from(sanitize::output(&text().unwrap_or_default())),
+            Self::UserId | Self::UserName | Self::Language | Self::PostType => {
+                Value::from(text().unwrap_or_default())
+            }
+            Self::CreateAt => Value::from(
+                text()
+                    .as_deref()
+                    .and_then(db::parse_datetime)
+                    .unwrap_or_default()
+                    .to_rfc3339(),
+            ),
+            Self::UpdatedAt => text()
+                .as_deref()
+                .and_then(db::parse_datetime)
+                .map_or(Value::Null, |time| Value::from(time.to_rfc3339())),
+            Self::Tags => Value::from(
+                text()
+                    .map(|tags| tags.split(',').map(str::to_string).collect::<Vec<_>>())
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// 요청한 항목들이다. 요청한 순서를 유지하며 중복된 항목은 한 번만 포함된다.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FieldSet(Vec<PostField>);
+
+impl FieldSet {
+    /// 쉼표로 구분된 항목 이름들을 읽는 메서드이다.
+    ///
+    /// 선택할 수 없는 항목이 있거나 항목이 하나도 없는 경우 이유를 담은 메세지를 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::fields::FieldSet;
+    /// let fields = FieldSet::parse("post_id, title,likes,title").unwrap();
+    /// assert_eq!(fields.names(), vec!["post_id", "title", "likes"]);
+    /// assert!(FieldSet::parse("post_id,password").is_err());
+    /// assert!(FieldSet::parse(" , ").is_err());
+    /// ```
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let mut fields = Vec::new();
+        for name in text
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            let field = ALL_FIELDS
+                .iter()
+                .find(|field| field.name() == name)
+                .ok_or_else(|| {
+                    format!(
+                        "선택할 수 없는 항목입니다: {}. 선택할 수 있는 항목은 {}입니다.",
+                        name,
+                        ALL_FIELDS
+                            .iter()
+                            .map(PostField::name)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })?;
+            if !fields.contains(field) {
+                fields.push(*field);
+            }
+        }
+        if fields.is_empty() {
+            return Err("항목을 하나 이상 지정해야 합니다.".to_string());
+        }
+        Ok(Self(fields))
+    }
+    /// 요청한 항목 이름들을 반환하는 메서드이다.
+    pub fn names(&self) -> Vec<&'static str> {
+        self.0.iter().map(PostField::name).collect()
+    }
+    /// 요청한 항목들의 열만 조회하는 `select` 목록을 반환하는 메서드이다.
+    ///
+    /// 내용을 요약하는 경우 내용은 요약할 글자 수까지만 조회하고, 잘렸는지 여부를 마지막 열로 함께 조회한다.
+    fn select_list(&self, body: BodyMode) -> String {
+        let mut columns: Vec<String> = self
+            .0
+            .iter()
+            .map(|field| match (field, body) {
+                (PostField::Data, BodyMode::Summary) => {
+                    format!("substr(post.data, 1, {})", summary::length())
+                }
+                _ => field.column().to_string(),
+            })
+            .collect();
+        if self.has_summary(body) {
+            columns.push(format!("char_length(post.data) > {}", summary::length()));
+        }
+        columns.join(", ")
+    }
+    /// 내용을 요약하여 `is_truncated`를 함께 반환해야 하는지 확인하는 메서드이다.
+    fn has_summary(&self, body: BodyMode) -> bool {
+        body == BodyMode::Summary && self.0.contains(&PostField::Data)
+    }
+    /// 조회한 행을 요청한 항목들로 이루어진 JSON 객체로 바꾸는 메서드이다.
+    fn project_row(&self, row: Row, body: BodyMode) -> Map<String, Value> {
+        let mut values = row.unwrap().into_iter();
+        let mut object: Map<String, Value> = self
+            .0
+            .iter()
+            .zip(values.by_ref())
+            .map(|(field, value)| (field.name().to_string(), field.to_json(value)))
+            .collect();
+        if self.has_summary(body) {
+            let is_truncated = values
+                .next()
+                .and_then(|value| from_value_opt::<bool>(value).ok())
+                .unwrap_or_default();
+            object.insert("is_truncated".to_string(), Value::from(is_truncated));
+        }
+        object
+    }
+    /// 이미 조회한 포스트에서 요청한 항목들만 골라 JSON 객체로 바꾸는 메서드이다.
+    ///
+    /// 포스트의 내용이 요약된 경우 `data`와 함께 `is_truncated`를 반환한다.
+    pub fn pick(&self, post: &Post) -> Map<String, Value> {
+        let Ok(Value::Object(mut values)) = serde_json::to_value(post) else {
+            return Map::new();
+        };
+        let mut object: Map<String, Value> = self
+            .0
+            .iter()
+            .filter_map(|field| {
+                values
+                    .remove(field.name())
+                    .map(|value| (field.name().to_string(), value))
+            })
+            .collect();
+        if post.is_truncated && self.0.contains(&PostField::Data) {
+            object.insert("is_truncated".to_string(), Value::from(true));
+        }
+        object
+    }
+    /// 최신순 피드 또는 태그 피드에서 요청한 항목의 열만 조회하여 반환하는 메서드이다.
+    ///
+    /// `tag`를 지정한 경우 해당 태그가 붙은 포스트만 최신순으로 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_posts(
+        &self,
+        tag: Option<&str>,
+        page: &Pagination,
+        body: BodyMode,
+    ) -> Vec<Map<String, Value>> {
+        let mut conn = db::get_read_conn();
+        let mut params: Vec<(&str, mysql::Value)> = vec![
+            ("limit", page.limit().into()),
+            ("offset", page.offset().into()),
+        ];
+        let filter = match tag {
+            Some(tag) => {
+                params.push(("tag", tag.into()));
+                r"join post_tag on post.post_id = post_tag.post_id
+                join tag on post_tag.tag_id = tag.tag_id
+                where tag.name = :tag and"
+            }
+            None => "where",
+        };
+        let rows: Vec<Row> = conn
+            .exec(
+                format!(
+                    r"select {} from post {} post.published and post.visibility = 'public' and post.deleted_at is null
+                    order by post.post_id desc limit :limit offset :offset",
+                    self.select_list(body),
+                    filter
+                ),
+                Params::from(params),
+            )
+            .unwrap();
+        rows.into_iter()
+            .map(|row| self.project_row(row, body))
+            .collect()
+    }
+}
@@ -0,0 +1,379 @@
+//! # 팔로우 관련 동작을 정의하는 모듈
+//!
+//! `follow`는 코드뮤니티에서 사용자 간의 팔로우 관계를 처리하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 팔로우한 사용자들의 포스트만 모아보는 피드를 제공하며,
+//! 다른 인스턴스에서 옮겨올 때 사용할 수 있도록 팔로우 목록을 내보내거나 가져올 수 있다.
+
+use crate::auth::Identity;
+use crate::block::BlockList;
+use crate::db;
+use crate::error::ApiError;
+use crate::pagination::Pagination;
+use crate::post::Post;
+use crate::repository::UserRepository;
+use crate::response;
+use crate::shadow_ban::ShadowBans;
+use crate::summary::{self, BodyRequest};
+use crate::user;
+use actix_web::web::Json;
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 한 번에 가져올 수 있는 팔로우 수의 기본 최대값이다.
+const DEFAULT_IMPORT_MAX: usize = 1000;
+/// 같은 사용자가 팔로우 목록을 다시 가져오기 위해 기다려야 하는 시간이다.
+const IMPORT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// 사용자 간의 팔로우 관계를 처리하는 객체이다.
+pub struct Follow;
+
+/// 팔로우 목록을 가져온 결과이다.
+#[derive(Serialize, Default)]
+pub struct ImportResult {
+    /// 새로 팔로우한 사용자 수이다.
+    pub imported: u64,
+    /// 이미 팔로우 중이거나 목록에 중복되어 건너뛴 사용자 수이다.
+    pub skipped: u64,
+    /// 존재하지 않아 팔로우하지 못한 사용자들의 고유 ID이다.
+    pub unknown_users: Vec<String>,
+}
+
+impl Follow {
+    /// `follower_id` 사용자가 `followee_id` 사용자를 팔로우하는 메서드이다.
+    ///
+    /// 팔로우할 사용자가 존재하지 않는 경우 `Ok(false)`를 반환한다.
+    /// 이미 팔로우 중인 경우에도 오류 없이 `Ok(true)`를 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// match Follow::follow("unique_id_for_user", "other_user") {
+    ///     Ok(true) => println!("팔로우 했습니다."),
+    ///     Ok(false) => println!("존재하지 않는 사용자입니다."),
+    ///     Err(_) => println!("팔로우에 실패했습니다."),
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn follow(follower_id: &str, followee_id: &str) -> Result<bool> {
+        let mut conn = db::get_conn();
+        let exists: Option<String> = conn.exec_first(
+            "select user_id from user where user_id = :user_id",
+            params! {
+                "user_id" => followee_id,
+            },
+        )?;
+        if exists.is_none() {
+            return Ok(false);
+        }
+        conn.exec_drop(
+            r"insert ignore into follow(follower_id, followee_id)
+            values(:follower_id, :followee_id)",
+            params! {
+                "follower_id" => follower_id,
+                "followee_id" => followee_id,
+            },
+        )?;
+        Ok(true)
+    }
+    /// `follower_id` 사용자가 `followee_id` 사용자를 더 이상 팔로우하지 않도록 하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn unfollow(follower_id: &str, followee_id: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "delete from follow where follower_id = :follower_id and followee_id = :followee_id",
+            params! {
+                "follower_id" => follower_id,
+                "followee_id" => followee_id,
+            },
+        )
+    }
+    /// 사용자가 팔로우한 사용자들의 포스트를 최신순으로 반환하는 메서드이다.
+    ///
+    /// `page`를 통해 반환할 페이지를 지정할 수 있다.
+    /// # 예제
+    /// ```ignore
+    /// let page = Pagination { page: Some(1), per_page: Some(20) };
+    /// for post in Follow::get_feed("unique_id_for_user", &page) {
+    ///     println!("{}님의 포스트: {}", post.user_name, post.title);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_feed(user_id: &str, page: &Pagination) -> Vec<Post> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select post.post_id, post.user_id, title, language, data, likes, report_count,
+            date_format(post.create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+            date_format(post.updated_at, '%Y-%m-%d %H:%i:%s')
+            from follow join post on follow.followee_id = post.user_id
+            where follow.follower_id = :user_id and post.published and post.visibility = 'public' and post.deleted_at is null
+            order by post.post_id desc
+            limit :limit offset :offset",
+            params! {
+                "user_id" => user_id,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(
+                post_id,
+                user_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+            )| {
+                Post::from_db(
+                    post_id,
+                    user_id,
+                    title,
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at,
+                    post_type,
+                    accepted_comment_id,
+                    updated_at,
+                )
+            },
+        )
+        .unwrap()
+    }
+    /// 사용자의 피드(`get_feed`)에 포함되는 포스트의 수를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn count_feed(user_id: &str) -> u64 {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            r"select count(*) from follow join post on follow.followee_id = post.user_id
+            where follow.follower_id = :user_id and post.published and post.visibility = 'public' and post.deleted_at is null",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+        .unwrap_or_default()
+    }
+    /// 사용자가 팔로우 중인 사용자들의 고유 ID를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_following(user_id: &str) -> Vec<String> {
+        let mut conn = db::get_conn();
+        conn.exec(
+            "select followee_id from follow where follower_id = :user_id order by create_at",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+    }
+    /// 팔로우 목록을 가져오는 메서드이다.
+    ///
+    /// `user_ids`에서 중복된 값과 자기 자신은 제외하고, 존재하는 사용자만 팔로우한다.
+    /// 모든 작업은 하나의 트랜잭션에서 수행된다.
+    /// # 예제
+    /// ```ignore
+    /// let result = Follow::import("unique_id_for_user", vec!["other_user".to_string()])?;
+    /// println!("{}명을 새로 팔로우했습니다.", result.imported);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn import(user_id: &str, user_ids: Vec<String>) -> Result<ImportResult> {
+        let total = user_ids.len() as u64;
+        let unique: BTreeSet<String> = user_ids
+            .into_iter()
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty() && id != user_id)
+            .collect();
+        let mut result = ImportResult::default();
+        let mut conn = db::get_conn();
+        let mut tx = conn.start_transaction(TxOpts::default())?;
+        for followee_id in unique {
+            let exists: Option<String> = tx.exec_first(
+                "select user_id from user where user_id = :user_id",
+                params! {
+                    "user_id" => &followee_id,
+                },
+            )?;
+            if exists.is_none() {
+                result.unknown_users.push(followee_id);
+                continue;
+            }
+            tx.exec_drop(
+                r"insert ignore into follow(follower_id, followee_id)
+                values(:follower_id, :followee_id)",
+                params! {
+                    "follower_id" => user_id,
+                    "followee_id" => &followee_id,
+                },
+            )?;
+            result.imported += tx.affected_rows();
+        }
+        tx.commit()?;
+        result.skipped = total - result.imported - result.unknown_users.len() as u64;
+        Ok(result)
+    }
+}
+
+/// 사용자별로 마지막으로 팔로우 목록을 가져온 시각을 저장하는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+#[derive(Default)]
+pub struct ImportThrottle {
+    last_import: Mutex<HashMap<String, Instant>>,
+}
+
+impl ImportThrottle {
+    /// 사용자가 지금 팔로우 목록을 가져올 수 있는지 확인하는 메서드이다.
+    ///
+    /// 가져올 수 있는 경우 현재 시각을 기록하고 `None`을, 그렇지 않은 경우 기다려야 하는 시간을 반환한다.
+    pub fn acquire(&self, user_id: &str) -> Option<Duration> {
+        let mut last_import = self.last_import.lock().unwrap();
+        let now = Instant::now();
+        last_import.retain(|_, time| now.duration_since(*time) < IMPORT_COOLDOWN);
+        match last_import.get(user_id) {
+            Some(time) => Some(IMPORT_COOLDOWN - now.duration_since(*time)),
+            None => {
+                last_import.insert(user_id.to_string(), now);
+                None
+            }
+        }
+    }
+}
+
+/// JSON 을 통해 가져올 팔로우 목록을 받을 때 필요한 구조체이다.
+#[derive(Deserialize, Serialize)]
+pub struct FollowingList {
+    /// 팔로우할 사용자들의 고유 ID이다.
+    pub user_ids: Vec<String>,
+}
+
+/// 한 번에 가져올 수 있는 팔로우 수의 최대값을 반환하는 메서드이다.
+///
+/// `FOLLOW_IMPORT_MAX` 환경변수로 변경할 수 있으며 기본값은 1000이다.
+fn import_max() -> usize {
+    env::var("FOLLOW_IMPORT_MAX")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_IMPORT_MAX)
+}
+
+#[post("/api/users/{user_id}/follow")]
+pub async fn follow_user_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if identity.user_id == *user_id {
+        return Err(ApiError::Validation(
+            "자기 자신은 팔로우할 수 없습니다.".to_string(),
+        ));
+    }
+    if !Follow::follow(&identity.user_id, &user_id)? {
+        return Err(user::user_not_found());
+    }
+    Ok(HttpResponse::Created().finish())
+}
+
+#[delete("/api/users/{user_id}/follow")]
+pub async fn unfollow_user_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    Follow::unfollow(&identity.user_id, &user_id)?;
+    Ok(response::no_content())
+}
+
+#[get("/api/feed")]
+pub async fn get_feed_api(
+    identity: Identity,
+    page: web::Query<Pagination>,
+    body: web::Query<BodyRequest>,
+    users: web::Data<dyn UserRepository>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> impl Responder {
+    let mut posts = Follow::get_feed(&identity.user_id, &page);
+    BlockList::load(users.get_ref(), Some(&identity.user_id))
+        .extend(shadow_bans.hidden_from(Some(&identity.user_id)))
+        .retain(&mut posts, |post| &post.user_id);
+    response::json(summary::apply(posts, body.body))
+}
+
+#[get("/api/users/{user_id}/following/export")]
+pub async fn export_following_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if !identity.can_modify(&user_id) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(FollowingList {
+        user_ids: Follow::get_following(&user_id),
+    }))
+}
+
+#[post("/api/users/{user_id}/following/import")]
+pub async fn import_following_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    request: Json<FollowingList>,
+    throttle: web::Data<ImportThrottle>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if identity.user_id != *user_id {
+        return Err(ApiError::Forbidden);
+    }
+    let limit = import_max();
+    if request.user_ids.len() > limit {
+        return Err(ApiError::Validation(format!(
+            "한 번에 {}명까지만 가져올 수 있습니다.",
+            limit
+        )));
+    }
+    if let Some(wait) = throttle.acquire(&user_id) {
+        return Err(ApiError::TooManyRequests(wait));
+    }
+    let result = Follow::import(&user_id, request.into_inner().user_ids)?;
+    Ok(response::json(result))
+}
@@ -0,0 +1,197 @@
+//! # 코드 정리 관련 동작을 정의하는 모듈
+//!
+//! `formatter`는 클라이언트가 포스트를 작성하기 전에 코드를 언어별 포매터로 정리할 수 있도록
+//! `POST /api/tools/format` 요청을 처리하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 포매터는 `Formatter` 트레이트로 추상화되어 있으며, 언어마다 하나씩 `Formatters`에 등록한다.
+//! 기본으로 Rust 코드를 `rustfmt`로 정리하며, 표준 입력으로 코드를 받아 표준 출력으로 정리된 코드를 내보내는
+//! 다른 포매터는 `CommandFormatter`로 등록할 수 있다.
+
+use crate::error::ApiError;
+use crate::response;
+use crate::validation::{FieldErrors, MAX_POST_CHARS};
+use actix_web::web::Json;
+use actix_web::{post, web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// 코드를 정리하지 못한 이유이다.
+#[derive(Debug)]
+pub enum FormatError {
+    /// 코드에 문법 오류가 있는 경우로, 포매터가 출력한 오류 메세지를 담고 있다.
+    Invalid(String),
+    /// 포매터를 실행할 수 없는 경우
+    Unavailable(String),
+}
+
+/// 언어별 포매터가 구현해야 하는 트레이트이다.
+pub trait Formatter: Send + Sync {
+    /// 정리할 수 있는 프로그래밍 언어 이름이다. 포스트의 `language`와 같은 값이다.
+    fn language(&self) -> &'static str;
+    /// 코드를 정리한 결과를 반환하는 메서드이다.
+    fn format(&self, code: &str) -> Result<String, FormatError>;
+}
+
+/// 표준 입력으로 코드를 받아 표준 출력으로 정리된 코드를 내보내는 외부 프로그램을 실행하는 포매터이다.
+///
+/// 프로그램이 `0`이 아닌 종료 코드로 끝난 경우 표준 오류 출력을 문법 오류로 반환한다.
+pub struct CommandFormatter {
+    /// 정리할 수 있는 프로그래밍 언어 이름이다.
+    language: &'static str,
+    /// 실행할 프로그램의 경로이다.
+    program: String,
+    /// 프로그램에 넘길 인자들이다.
+    args: Vec<String>,
+}
+
+impl CommandFormatter {
+    /// 언어 이름, 프로그램 경로, 인자들로 포매터를 생성하는 메서드이다.
+    pub fn new(language: &'static str, program: impl Into<String>, args: &[&str]) -> Self {
+        Self {
+            language,
+            program: program.into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+    /// `rustfmt`로 Rust 코드를 정리하는 포매터를 생성하는 메서드이다.
+    ///
+    /// `RUSTFMT_PATH` 환경변수로 `rustfmt`의 경로를 지정할 수 있다.
+    pub fn rustfmt() -> Self {
+        Self::new(
+            "rust",
+            env::var("RUSTFMT_PATH").unwrap_or_else(|_| "rustfmt".to_string()),
+            &["--edition", "2021", "--emit", "stdout", "--quiet"],
+        )
+    }
+}
+
+impl Formatter for CommandFormatter {
+    fn language(&self) -> &'static str {
+        self.language
+    }
+    fn format(&self, code: &str) -> Result<String, FormatError> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|error| FormatError::Unavailable(format!("{}: {}", self.program, error)))?;
+        // 출력을 읽기 전에 입력을 모두 쓰면 파이프가 가득 차 멈출 수 있으므로 별도의 스레드에서 쓴다.
+        let mut stdin = child.stdin.take().expect("표준 입력이 열려있지 않음");
+        let input = code.to_string();
+        let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let output = child
+            .wait_with_output()
+            .map_err(|error| FormatError::Unavailable(error.to_string()))?;
+        // 포매터가 입력을 모두 읽기 전에 끝난 경우 쓰기가 실패하지만, 결과는 종료 코드로 판단한다.
+        let _ = writer.join();
+        if !output.status.success() {
+            return Err(FormatError::Invalid(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        String::from_utf8(output.stdout)
+            .map_err(|error| FormatError::Unavailable(error.to_string()))
+    }
+}
+
+/// 언어별로 등록된 포매터들이다.
+///
+/// 서버 시작 시 `from_env()`로 한 번만 생성한 뒤 앱 상태로 등록한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::formatter::{CommandFormatter, Formatters};
+/// let mut formatters = Formatters::default();
+/// formatters.register(Box::new(CommandFormatter::new("text", "cat", &[])));
+/// assert_eq!(formatters.languages(), vec!["text"]);
+/// ```
+#[derive(Default)]
+pub struct Formatters {
+    /// 언어 이름별 포매터이다.
+    formatters: BTreeMap<&'static str, Box<dyn Formatter>>,
+}
+
+impl Formatters {
+    /// 기본 포매터들을 등록하여 생성하는 메서드이다. 현재는 Rust(`rustfmt`)만 등록된다.
+    pub fn from_env() -> Self {
+        let mut formatters = Self::default();
+        formatters.register(Box::new(CommandFormatter::rustfmt()));
+        formatters
+    }
+    /// 포매터를 등록하는 메서드이다. 같은 언어의 포매터가 이미 등록된 경우 대체한다.
+    pub fn register(&mut self, formatter: Box<dyn Formatter>) {
+        self.formatters.insert(formatter.language(), formatter);
+    }
+    /// 정리할 수 있는 언어 이름들을 반환하는 메서드이다.
+    pub fn languages(&self) -> Vec<&'static str> {
+        self.formatters.keys().copied().collect()
+    }
+    /// 언어의 포매터를 반환하는 메서드이다. 등록되지 않은 언어인 경우 `None`을 반환한다.
+    pub fn get(&self, language: &str) -> Option<&dyn Formatter> {
+        self.formatters
+            .get(language.to_lowercase().as_str())
+            .map(Box::as_ref)
+    }
+}
+
+/// JSON 을 통해 코드 정리 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct FormatRequest {
+    /// 코드의 프로그래밍 언어 이름이다.
+    pub language: String,
+    /// 정리할 코드이다.
+    pub data: String,
+}
+
+/// 코드 정리 결과를 반환할 때 사용하는 구조체이다.
+#[derive(Serialize)]
+pub struct FormatResponse {
+    /// 정리된 코드이다.
+    pub data: String,
+}
+
+#[post("/api/tools/format")]
+pub async fn format_code_api(
+    request: Json<FormatRequest>,
+    formatters: web::Data<Formatters>,
+) -> Result<HttpResponse, ApiError> {
+    let request = request.into_inner();
+    let mut errors = FieldErrors::default();
+    errors.check(
+        "language",
+        formatters.get(&request.language).is_some(),
+        format!(
+            "정리할 수 있는 언어는 {}입니다.",
+            formatters.languages().join(", ")
+        ),
+    );
+    errors.check_data(&request.data, MAX_POST_CHARS);
+    errors.into_result()?;
+    let result = web::block(move || {
+        formatters
+            .get(&request.language)
+            .map(|formatter| formatter.format(&request.data))
+    })
+    .await
+    .map_err(|error| ApiError::Unavailable(error.to_string()))?;
+    match result {
+        Some(Ok(data)) => Ok(response::json(FormatResponse { data })),
+        Some(Err(FormatError::Invalid(message))) => {
+            Err(ApiError::InvalidFields(BTreeMap::from([("data", message)])))
+        }
+        Some(Err(FormatError::Unavailable(error))) => {
+            tracing::error!(error = error.as_str(), "포매터를 실행할 수 없습니다.");
+            Err(ApiError::Unavailable(
+                "코드를 정리할 수 없습니다. 잠시 후 다시 시도해주세요.".to_string(),
+            ))
+        }
+        None => Err(ApiError::Unavailable(
+            "코드를 정리할 수 없습니다.".to_string(),
+        )),
+    }
+}
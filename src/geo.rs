@@ -0,0 +1,89 @@
+//! # 지역별 이용 통계 관련 동작을 정의하는 모듈
+//!
+//! `geo`는 코드뮤니티에 들어온 요청의 IP주소를 통해 국가를 확인하고
+//! 국가별 요청 수를 집계하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! `GEOIP_DB_PATH` 환경변수에 MaxMind의 GeoIP2(또는 GeoLite2) Country DB 경로가 지정된 경우에만
+//! 동작하며, 국가별 요청 수만 메모리에 집계할 뿐 IP주소나 사용자 정보는 저장하지 않는다.
+//! 요청에 `DNT: 1` 또는 `Sec-GPC: 1` 헤더가 포함된 경우 해당 요청은 집계하지 않는다.
+
+use actix_web::dev::ServiceRequest;
+use maxminddb::{geoip2, Reader};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+
+/// 국가별 요청 수를 집계하는 객체이다.
+///
+/// 서버 시작 시 `from_env()`를 통해 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+pub struct RegionStats {
+    /// 국가 확인에 사용하는 GeoIP DB이다. 지정되지 않은 경우 집계를 하지 않는다.
+    reader: Option<Reader<Vec<u8>>>,
+    /// 국가 코드(ISO 3166-1)별 요청 수이다.
+    counts: Mutex<HashMap<String, u64>>,
+}
+
+impl RegionStats {
+    /// 환경변수를 통해 지역별 이용 통계 객체를 생성하는 메서드이다.
+    ///
+    /// `GEOIP_DB_PATH`가 지정되지 않은 경우 집계를 하지 않는 객체를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// `GEOIP_DB_PATH`에 지정된 파일이 존재하지 않거나 올바른 GeoIP DB가 아닌 경우 패닉이 발생한다.
+    pub fn from_env() -> Self {
+        let reader = env::var("GEOIP_DB_PATH")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(|path| {
+                Reader::open_readfile(path).expect("GEOIP_DB_PATH가 올바른 GeoIP DB가 아님")
+            });
+        Self {
+            reader,
+            counts: Mutex::new(HashMap::new()),
+        }
+    }
+    /// 요청의 IP주소를 통해 국가를 확인한 뒤 해당 국가의 요청 수를 1 증가시키는 메서드이다.
+    ///
+    /// 집계가 비활성화 되어있거나 요청에 `DNT: 1` 또는 `Sec-GPC: 1` 헤더가 포함된 경우 아무 동작도 하지 않는다.
+    /// 국가를 확인할 수 없는 경우 `unknown`으로 집계한다.
+    pub fn record(&self, req: &ServiceRequest) {
+        let reader = match &self.reader {
+            Some(reader) => reader,
+            None => return,
+        };
+        let opted_out = ["DNT", "Sec-GPC"].iter().any(|header| {
+            req.headers()
+                .get(*header)
+                .map(|value| value.as_bytes() == b"1")
+                .unwrap_or(false)
+        });
+        if opted_out {
+            return;
+        }
+        let country = req
+            .connection_info()
+            .realip_remote_addr()
+            .and_then(|addr| {
+                addr.parse::<IpAddr>()
+                    .ok()
+                    .or_else(|| addr.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+            })
+            .and_then(|ip| reader.lookup::<geoip2::Country>(ip).ok())
+            .and_then(|result| result.country)
+            .and_then(|country| country.iso_code)
+            .unwrap_or("unknown")
+            .to_string();
+        *self.counts.lock().unwrap().entry(country).or_insert(0) += 1;
+    }
+    /// 현재까지 집계된 국가별 요청 수를 국가 코드 순으로 반환하는 메서드이다.
+    pub fn snapshot(&self) -> BTreeMap<String, u64> {
+        self.counts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(country, count)| (country.clone(), *count))
+            .collect()
+    }
+}
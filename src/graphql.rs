@@ -0,0 +1,479 @@
+//! # GraphQL API 관련 동작을 정의하는 모듈
+//!
+//! `graphql`은 REST API와 함께 제공되는 GraphQL API(`POST /api/graphql`)의 스키마와
+//! 요청을 처리하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 포스트 상세 화면처럼 포스트, 작성자, 댓글을 함께 보여줘야 하는 경우 한 번의 요청으로 필요한 항목만 받을 수 있다.
+//! ```graphql
+//! query {
+//!   post(postId: 1) {
+//!     title data likes
+//!     author { userName avatarUrl }
+//!     comments { userName data createAt }
+//!   }
+//! }
+//! ```
+//! 포스트 작성, 공감, 댓글 작성은 REST API와 같은 규칙으로 검증되며,
+//! `Authorization: Bearer <토큰>` 헤더로 인증된 사용자의 이름으로 처리된다.
+
+use crate::auth::Identity;
+use crate::block::BlockList;
+use crate::cache::Cache;
+use crate::comment::{Comment, CommentRequest, CommentSort};
+use crate::email;
+use crate::error::ApiError;
+use crate::events::PostEvents;
+use crate::likes::{LikeMode, LikeRequest, LikeThrottle};
+use crate::moderation::{self, Moderator};
+use crate::outbox::Outbox;
+use crate::pagination::Pagination;
+use crate::post::{post_not_found, Post, PostRequest};
+use crate::ranking::Ranker;
+use crate::repository::UserRepository;
+use crate::sanitize;
+use crate::search_index::SearchIndex;
+use crate::shadow_ban::ShadowBans;
+use crate::summary;
+use crate::tag::Tag;
+use crate::user::User;
+use crate::word_filter::{ContentKind, WordFilter};
+use actix_web::{post, web};
+use async_graphql::{
+    Context, EmptySubscription, Error, ErrorExtensions, InputObject, Object, Result, Schema, Value,
+};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use chrono::{DateTime, Utc};
+use std::borrow::Cow;
+
+/// 코드뮤니티의 GraphQL 스키마이다.
+pub type AppSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
+
+/// GraphQL 스키마를 생성하는 메서드이다.
+///
+/// 리졸버가 REST 핸들러와 같은 상태를 사용하도록 앱 상태에 등록된 객체들을 함께 전달받는다.
+#[allow(clippy::too_many_arguments)]
+pub fn schema(
+    cache: web::Data<Cache>,
+    events: web::Data<PostEvents>,
+    throttle: web::Data<LikeThrottle>,
+    ranker: web::Data<dyn Ranker>,
+    outbox: web::Data<Outbox>,
+    word_filter: web::Data<WordFilter>,
+    moderator: web::Data<dyn Moderator>,
+    users: web::Data<dyn UserRepository>,
+    shadow_bans: web::Data<ShadowBans>,
+    search_index: web::Data<SearchIndex>,
+) -> AppSchema {
+    Schema::build(QueryRoot, MutationRoot, EmptySubscription)
+        .data(cache)
+        .data(events)
+        .data(throttle)
+        .data(ranker)
+        .data(outbox)
+        .data(word_filter)
+        .data(moderator)
+        .data(users)
+        .data(shadow_bans)
+        .data(search_index)
+        .finish()
+}
+
+/// `ApiError`를 REST API와 같은 `code`를 확장 항목으로 가진 GraphQL 오류로 바꾸는 메서드이다.
+fn to_graphql_error(error: ApiError) -> Error {
+    Error::new(error.to_string()).extend_with(|_, extensions| {
+        extensions.set("code", error.code());
+        if let Some(details) = error
+            .details()
+            .and_then(|details| Value::from_json(details).ok())
+        {
+            extensions.set("details", details);
+        }
+    })
+}
+
+/// 요청을 보낸 사용자를 반환하는 메서드이다. 인증되지 않은 경우 `unauthorized` 오류를 반환한다.
+fn identity<'a>(ctx: &Context<'a>) -> Result<&'a Identity> {
+    ctx.data_opt::<Identity>()
+        .ok_or_else(|| to_graphql_error(ApiError::Unauthorized))
+}
+
+/// 요청을 보낸 사용자가 차단했거나 섀도 밴되어 보이지 않아야 하는 사용자들을 반환하는 메서드이다.
+fn hidden_users(ctx: &Context<'_>) -> Result<BlockList> {
+    let viewer_id = ctx
+        .data_opt::<Identity>()
+        .map(|identity| identity.user_id.as_str());
+    let users = ctx.data::<web::Data<dyn UserRepository>>()?;
+    let shadow_bans = ctx.data::<web::Data<ShadowBans>>()?;
+    Ok(BlockList::load(users.get_ref(), viewer_id).extend(shadow_bans.hidden_from(viewer_id)))
+}
+
+/// GraphQL로 제공되는 포스트이다.
+pub struct PostNode(Post);
+
+#[Object(name = "Post")]
+impl PostNode {
+    /// 포스트의 고유 ID이다.
+    async fn post_id(&self) -> u64 {
+        self.0.post_id
+    }
+    /// 포스트를 작성한 사용자의 고유 ID이다.
+    async fn user_id(&self) -> &str {
+        &self.0.user_id
+    }
+    /// 포스트를 작성한 사용자의 이름이다.
+    async fn user_name(&self) -> &str {
+        &self.0.user_name
+    }
+    /// 포스트의 제목이다.
+    async fn title(&self) -> Cow<'_, str> {
+        sanitize::output(&self.0.title)
+    }
+    /// 포스트에 작성된 프로그래밍 언어 종류이다.
+    async fn language(&self) -> &str {
+        &self.0.language
+    }
+    /// 포스트 내용이다.
+    async fn data(&self) -> Cow<'_, str> {
+        sanitize::output(&self.0.data)
+    }
+    /// 포스트의 공감 수이다.
+    async fn likes(&self) -> u64 {
+        self.0.likes
+    }
+    /// 포스트의 종류(`snippet`, `question`, `discussion`)이다.
+    async fn post_type(&self) -> &str {
+        self.0.post_type.as_str()
+    }
+    /// 포스트에 붙은 태그들이다.
+    async fn tags(&self) -> &[String] {
+        &self.0.tags
+    }
+    /// 채택된 답변 댓글의 고유 ID이다.
+    async fn accepted_comment_id(&self) -> Option<u64> {
+        self.0.accepted_comment_id
+    }
+    /// 포스트가 생성된 날짜이다.
+    async fn create_at(&self) -> DateTime<Utc> {
+        self.0.create_at
+    }
+    /// 포스트가 마지막으로 수정된 날짜이다.
+    async fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.0.updated_at
+    }
+    /// 포스트가 수정된 횟수이다.
+    async fn version(&self) -> u64 {
+        self.0.version
+    }
+    /// 포스트 목록에서 내용이 요약되어 잘렸는지 여부이다.
+    async fn is_truncated(&self) -> bool {
+        self.0.is_truncated
+    }
+    /// 포스트를 작성한 사용자이다.
+    async fn author(&self) -> Option<UserNode> {
+        User::get_user(self.0.user_id.clone()).map(UserNode)
+    }
+    /// 포스트에 달린 댓글들이다. 채택된 답변이 가장 먼저 온다.
+    ///
+    /// 요청한 사용자가 차단한 사용자와 섀도 밴된 사용자의 댓글은 제외된다.
+    async fn comments(&self, ctx: &Context<'_>) -> Result<Vec<CommentNode>> {
+        let mut comments = Comment::get(self.0.post_id as u32, CommentSort::Newest);
+        hidden_users(ctx)?.retain(&mut comments, |comment| &comment.user_id);
+        Ok(comments.into_iter().map(CommentNode).collect())
+    }
+}
+
+/// GraphQL로 제공되는 댓글이다.
+pub struct CommentNode(Comment);
+
+#[Object(name = "Comment")]
+impl CommentNode {
+    /// 댓글의 고유 ID이다.
+    async fn comment_id(&self) -> u64 {
+        self.0.comment_id
+    }
+    /// 댓글이 달린 포스트의 고유 ID이다.
+    async fn post_id(&self) -> u32 {
+        self.0.post_id
+    }
+    /// 댓글을 작성한 사용자의 고유 ID이다.
+    async fn user_id(&self) -> &str {
+        &self.0.user_id
+    }
+    /// 댓글을 작성한 사용자의 이름이다.
+    async fn user_name(&self) -> &str {
+        &self.0.user_name
+    }
+    /// 댓글의 내용이다.
+    async fn data(&self) -> Cow<'_, str> {
+        sanitize::output(&self.0.data)
+    }
+    /// 질문 포스트의 채택된 답변인지 여부이다.
+    async fn is_accepted(&self) -> bool {
+        self.0.is_accepted
+    }
+    /// 댓글 작성 날짜이다.
+    async fn create_at(&self) -> DateTime<Utc> {
+        self.0.create_at
+    }
+    /// 댓글이 마지막으로 수정된 날짜이다.
+    async fn updated_at(&self) -> Option<DateTime<Utc>> {
+        self.0.updated_at
+    }
+}
+
+/// GraphQL로 제공되는 사용자이다.
+pub struct UserNode(User);
+
+#[Object(name = "User")]
+impl UserNode {
+    /// 사용자의 고유 ID이다.
+    async fn user_id(&self) -> &str {
+        &self.0.user_id
+    }
+    /// 사용자의 표시 이름이다.
+    async fn user_name(&self) -> &str {
+        &self.0.user_name
+    }
+    /// 사용자의 자기소개이다.
+    async fn bio(&self) -> Option<&str> {
+        self.0.bio.as_deref()
+    }
+    /// 사용자의 프로필 이미지 주소이다.
+    async fn avatar_url(&self) -> Option<&str> {
+        self.0.avatar_url.as_deref()
+    }
+    /// 사용자의 웹사이트 주소이다.
+    async fn website(&self) -> Option<&str> {
+        self.0.website.as_deref()
+    }
+    /// 사용자의 평판 점수이다.
+    async fn reputation(&self) -> i64 {
+        self.0.reputation
+    }
+    /// 사용자가 현재 이용 정지 상태인지 여부이다.
+    async fn is_banned(&self) -> bool {
+        self.0.is_banned
+    }
+}
+
+/// 포스트를 작성할 때 필요한 입력값이다.
+#[derive(InputObject)]
+pub struct CreatePostInput {
+    /// 포스트의 제목이다.
+    pub title: String,
+    /// 포스트에 작성된 프로그래밍 언어 종류이다.
+    pub language: String,
+    /// 포스트 내용이다.
+    pub data: String,
+    /// 포스트의 종류(`snippet`, `question`, `discussion`)이다. 주어지지 않은 경우 `snippet`이다.
+    pub post_type: Option<String>,
+    /// 포스트에 붙일 태그들이다.
+    #[graphql(default)]
+    pub tags: Vec<String>,
+    /// 포스트의 공개 범위(`public`, `unlisted`, `private`)이다. 주어지지 않은 경우 `public`이다.
+    pub visibility: Option<String>,
+    /// 예약 게시 시각(`YYYY-MM-DD HH:MM:SS`)이다. 주어지지 않은 경우 바로 게시된다.
+    pub publish_at: Option<String>,
+}
+
+/// GraphQL의 조회 항목들이다.
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// `postId`에 해당하는 포스트를 반환한다.
+    ///
+    /// 예약 게시 전의 포스트와 비공개 포스트는 작성자 본인과 운영자에게만 반환된다.
+    async fn post(&self, ctx: &Context<'_>, post_id: u64) -> Option<PostNode> {
+        let post = Post::get_post(post_id.to_string().into()).filter(|post| {
+            (Post::is_published(post.post_id) && post.is_visible_to(None))
+                || ctx
+                    .data_opt::<Identity>()
+                    .is_some_and(|identity| identity.can_modify(&post.user_id))
+        })?;
+        Post::record_view(post.post_id);
+        Some(PostNode(post))
+    }
+    /// 홈 피드의 포스트들을 반환한다. `tag`를 지정하면 해당 태그가 붙은 포스트만 최신순으로 반환한다.
+    ///
+    /// `body`가 `full`이 아닌 경우 포스트 내용은 요약되어 반환된다.
+    /// 요청한 사용자가 차단한 사용자와 섀도 밴된 사용자의 포스트는 제외된다.
+    async fn posts(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<u64>,
+        per_page: Option<u64>,
+        tag: Option<String>,
+        viewer_id: Option<String>,
+        body: Option<String>,
+    ) -> Result<Vec<PostNode>> {
+        let body = match body.as_deref() {
+            Some(body) => body.parse().map_err(|_| {
+                to_graphql_error(ApiError::Validation(
+                    "body는 summary 또는 full이어야 합니다.".to_string(),
+                ))
+            })?,
+            None => Default::default(),
+        };
+        let page = Pagination { page, per_page };
+        let cache = ctx.data::<web::Data<Cache>>()?;
+        let mut posts = match tag.as_deref().map(Tag::normalize) {
+            Some(Some(tag)) => cache.feed(None, Some(&tag), &page, || Tag::get_posts(&tag, &page)),
+            Some(None) => Vec::new(),
+            None => {
+                let ranker = ctx.data::<web::Data<dyn Ranker>>()?;
+                cache.feed(viewer_id.as_deref(), None, &page, || {
+                    ranker.rank(viewer_id.as_deref(), &page)
+                })
+            }
+        };
+        hidden_users(ctx)?.retain(&mut posts, |post| &post.user_id);
+        Ok(summary::apply(posts, body)
+            .into_iter()
+            .map(PostNode)
+            .collect())
+    }
+    /// `userId`에 해당하는 사용자를 반환한다.
+    async fn user(&self, user_id: String) -> Option<UserNode> {
+        User::get_user(user_id).map(UserNode)
+    }
+}
+
+/// GraphQL의 변경 항목들이다.
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    /// 인증된 사용자의 이름으로 포스트를 작성하고, 작성된 포스트를 반환한다.
+    async fn create_post(&self, ctx: &Context<'_>, input: CreatePostInput) -> Result<PostNode> {
+        let identity = identity(ctx)?;
+        let post_type = match input.post_type.as_deref() {
+            Some(post_type) => post_type.parse().map_err(|_| {
+                to_graphql_error(ApiError::Validation(
+                    "postType은 snippet, question, discussion 중 하나여야 합니다.".to_string(),
+                ))
+            })?,
+            None => Default::default(),
+        };
+        let visibility = match input.visibility.as_deref() {
+            Some(visibility) => visibility.parse().map_err(|_| {
+                to_graphql_error(ApiError::Validation(
+                    "visibility는 public, unlisted, private 중 하나여야 합니다.".to_string(),
+                ))
+            })?,
+            None => Default::default(),
+        };
+        let mut request = PostRequest {
+            user_id: identity.user_id.clone(),
+            title: input.title,
+            language: input.language,
+            data: input.data,
+            post_type,
+            tags: input.tags,
+            visibility,
+            publish_at: input.publish_at,
+            bot: false,
+        };
+        sanitize::clean(&mut request.title);
+        sanitize::clean(&mut request.data);
+        let flagged = ctx
+            .data::<web::Data<WordFilter>>()?
+            .screen(&mut [("title", &mut request.title), ("data", &mut request.data)])
+            .map_err(to_graphql_error)?;
+        let post = request.submit().map_err(to_graphql_error)?;
+        WordFilter::flag(ContentKind::Post, post.post_id, &flagged);
+        let cache = ctx.data::<web::Data<Cache>>()?;
+        cache.invalidate_post(post.post_id);
+        let search_index = ctx.data::<web::Data<SearchIndex>>()?;
+        search_index.sync(post.post_id);
+        moderation::submit(
+            ctx.data::<web::Data<dyn Moderator>>()?.clone(),
+            cache.clone(),
+            search_index.clone(),
+            ContentKind::Post,
+            post.post_id,
+            format!("{}\n\n{}", request.title, request.data),
+        );
+        Ok(PostNode(post))
+    }
+    /// 인증된 사용자의 이름으로 포스트에 공감하거나(`liked: true`) 공감을 취소하고, 바뀐 포스트를 반환한다.
+    async fn set_like(&self, ctx: &Context<'_>, post_id: u32, liked: bool) -> Result<PostNode> {
+        let identity = identity(ctx)?;
+        if User::is_restricted(&identity.user_id).map_err(|error| to_graphql_error(error.into()))? {
+            return Err(to_graphql_error(ApiError::Forbidden));
+        }
+        if let Some(wait) = ctx
+            .data::<web::Data<LikeThrottle>>()?
+            .acquire(&identity.user_id, post_id)
+        {
+            return Err(to_graphql_error(ApiError::TooManyRequests(wait)));
+        }
+        LikeRequest::modify_likes(
+            &identity.user_id,
+            web::Query(LikeRequest {
+                post_id,
+                mode: if liked {
+                    LikeMode::Increment
+                } else {
+                    LikeMode::Decrement
+                },
+            }),
+        )
+        .map_err(|error| to_graphql_error(error.into()))?
+        .ok_or_else(|| to_graphql_error(post_not_found()))?;
+        ctx.data::<web::Data<Cache>>()?.invalidate_post(post_id);
+        ctx.data::<web::Data<PostEvents>>()?.publish(post_id as u64);
+        Post::get_post(post_id.to_string().into())
+            .map(PostNode)
+            .ok_or_else(|| to_graphql_error(post_not_found()))
+    }
+    /// 인증된 사용자의 이름으로 포스트에 댓글을 작성하고, 작성된 댓글을 반환한다.
+    async fn create_comment(
+        &self,
+        ctx: &Context<'_>,
+        post_id: u32,
+        data: String,
+    ) -> Result<CommentNode> {
+        let identity = identity(ctx)?;
+        let mut request = CommentRequest {
+            post_id,
+            user_id: identity.user_id.clone(),
+            data,
+        };
+        sanitize::clean(&mut request.data);
+        let flagged = ctx
+            .data::<web::Data<WordFilter>>()?
+            .screen(&mut [("data", &mut request.data)])
+            .map_err(to_graphql_error)?;
+        let comment = request.submit().map_err(to_graphql_error)?;
+        WordFilter::flag(ContentKind::Comment, comment.comment_id, &flagged);
+        moderation::submit(
+            ctx.data::<web::Data<dyn Moderator>>()?.clone(),
+            ctx.data::<web::Data<Cache>>()?.clone(),
+            ctx.data::<web::Data<SearchIndex>>()?.clone(),
+            ContentKind::Comment,
+            comment.comment_id,
+            request.data,
+        );
+        ctx.data::<web::Data<PostEvents>>()?.publish(post_id as u64);
+        email::notify_comment(
+            ctx.data::<web::Data<Outbox>>()?.clone(),
+            post_id as u64,
+            identity.user_id.clone(),
+        );
+        Ok(CommentNode(comment))
+    }
+}
+
+#[post("/api/graphql")]
+pub async fn graphql_api(
+    schema: web::Data<AppSchema>,
+    identity: Option<Identity>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let mut request = request.into_inner();
+    if let Some(identity) = identity {
+        request = request.data(identity);
+    }
+    schema.execute(request).await.into()
+}
@@ -0,0 +1,104 @@
+//! # 서버 상태 확인 관련 동작을 정의하는 모듈
+//!
+//! `health`는 Kubernetes와 같은 오케스트레이터가 서버 인스턴스에 요청을 보내도 되는지
+//! 판단할 수 있도록 서버가 의존하는 요소들의 상태를 확인하기 위한 요소 및 메서드들로 이루어져 있다.
+
+use crate::db;
+use crate::response;
+use crate::storage::Storage;
+use actix_web::rt::time::timeout;
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// 의존 요소 하나의 상태를 확인할 때 기다리는 최대 시간이다.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 의존 요소 하나의 상태이다.
+#[derive(Serialize)]
+pub struct CheckResult {
+    /// 의존 요소를 사용할 수 있는 경우 `up`, 아닌 경우 `down`이다.
+    pub status: &'static str,
+    /// 상태를 확인하는 데 걸린 시간(밀리초)이다.
+    pub latency_ms: u128,
+    /// 사용할 수 없는 경우 그 원인이다.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 서버의 준비 상태이다.
+#[derive(Serialize)]
+pub struct Readiness {
+    /// 모든 의존 요소를 사용할 수 있는 경우 `ready`, 아닌 경우 `unavailable`이다.
+    pub status: &'static str,
+    /// 의존 요소별 상태이다.
+    pub checks: BTreeMap<&'static str, CheckResult>,
+    /// DB 접속 차단기의 상태이다. 접속에 계속 실패하여 요청을 바로 거절하는 중인 경우 `open`, 아닌 경우 `closed`이다.
+    pub circuit: &'static str,
+}
+
+impl CheckResult {
+    /// 확인 결과와 시작 시각으로 상태를 만드는 메서드이다.
+    fn new(result: Result<(), String>, started: Instant) -> Self {
+        Self {
+            status: if result.is_ok() { "up" } else { "down" },
+            latency_ms: started.elapsed().as_millis(),
+            error: result.err(),
+        }
+    }
+    /// 의존 요소를 사용할 수 있는지 여부를 반환하는 메서드이다.
+    pub fn is_up(&self) -> bool {
+        self.status == "up"
+    }
+}
+
+impl Readiness {
+    /// 서버가 의존하는 요소들의 상태를 확인하는 메서드이다.
+    ///
+    /// DB 서버에 `select 1`을 실행해보며, 로컬 디스크 저장소를 사용하는 경우 저장 폴더가 있는지 확인한다.
+    /// 각 요소는 2초 안에 응답하지 않으면 사용할 수 없는 것으로 판단한다.
+    /// DB 접속 차단기가 열려 있는 경우에도 DB 서버에 접속해보며, 접속에 성공하면 차단기가 닫힌다.
+    pub async fn check() -> Self {
+        let mut checks = BTreeMap::new();
+        let started = Instant::now();
+        let database = match timeout(CHECK_TIMEOUT, web::block(|| db::ping(CHECK_TIMEOUT))).await {
+            Ok(Ok(Ok(()))) => Ok(()),
+            Ok(Ok(Err(error))) => Err(error.to_string()),
+            Ok(Err(error)) => Err(error.to_string()),
+            Err(_) => Err("제한시간 초과".to_string()),
+        };
+        checks.insert("database", CheckResult::new(database, started));
+        if Storage::is_local() {
+            let started = Instant::now();
+            let storage = if Path::new(&Storage::local_dir()).is_dir() {
+                Ok(())
+            } else {
+                Err("저장 폴더가 존재하지 않음".to_string())
+            };
+            checks.insert("storage", CheckResult::new(storage, started));
+        }
+        let circuit_open = db::circuit_open().is_some();
+        let ready = checks.values().all(CheckResult::is_up) && !circuit_open;
+        Self {
+            status: if ready { "ready" } else { "unavailable" },
+            checks,
+            circuit: if circuit_open { "open" } else { "closed" },
+        }
+    }
+}
+
+#[get("/ready")]
+pub async fn ready_api() -> impl Responder {
+    let readiness = Readiness::check().await;
+    let mut builder = if readiness.status == "ready" {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::ServiceUnavailable()
+    };
+    builder
+        .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+        .insert_header(("Cache-Control", "no-store"))
+        .json(readiness)
+}
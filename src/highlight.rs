@@ -0,0 +1,57 @@
+//! # 서버 측 구문 강조 관련 동작을 정의하는 모듈
+//!
+//! `highlight`는 포스트의 코드를 작성된 언어에 맞게 구문 강조한 HTML로 바꾸기 위한
+//! 요소 및 메서드들로 이루어져 있다. 구문 강조기를 직접 포함하기 어려운 가벼운 클라이언트를 위한 것으로,
+//! `GET /api/posts/{post_id}?render=html`로 요청한 경우에 사용된다.
+//!
+//! 구문 정의와 테마는 처음 사용할 때 한 번만 읽어오며, 지원하지 않는 언어는 일반 텍스트로 처리된다.
+
+use crate::sanitize;
+use serde::Deserialize;
+use std::sync::OnceLock;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::html::highlighted_html_for_string;
+use syntect::parsing::SyntaxSet;
+
+/// 구문 강조에 사용하는 테마의 이름이다. 스타일이 HTML에 직접 들어가므로 클라이언트에 CSS가 필요하지 않다.
+const THEME: &str = "InspiredGitHub";
+
+/// 처음 사용할 때 읽어오는 구문 정의들이다.
+static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+/// 처음 사용할 때 읽어오는 테마이다.
+static THEME_DATA: OnceLock<Theme> = OnceLock::new();
+
+/// 포스트를 조회할 때 내용을 함께 변환하여 받을 형식이다.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderFormat {
+    /// 구문 강조된 HTML
+    Html,
+}
+
+/// 코드를 언어에 맞게 구문 강조한 HTML(`<pre>` 요소)을 반환하는 메서드이다.
+///
+/// 언어는 이름(`rust`, `c#`)이나 확장자(`rs`, `py`)로 찾으며 대소문자를 구분하지 않는다.
+/// 지원하지 않는 언어인 경우 일반 텍스트로 처리하며, 코드의 HTML 특수 문자는 항상 이스케이프된다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::highlight;
+/// let html = highlight::to_html("fn main() {}", "rust");
+/// assert!(html.starts_with("<pre"));
+/// assert!(html.contains("main"));
+/// assert!(!highlight::to_html("<script>", "unknown").contains("<script>"));
+/// ```
+pub fn to_html(code: &str, language: &str) -> String {
+    let syntaxes = SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines);
+    let theme = THEME_DATA.get_or_init(|| {
+        ThemeSet::load_defaults()
+            .themes
+            .remove(THEME)
+            .expect("기본 테마가 존재하지 않음")
+    });
+    let syntax = syntaxes
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    highlighted_html_for_string(code, syntaxes, syntax, theme)
+        .unwrap_or_else(|_| format!("<pre>{}</pre>", sanitize::escape(code)))
+}
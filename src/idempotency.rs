@@ -0,0 +1,159 @@
+//! # 멱등 키 관련 동작을 정의하는 모듈
+//!
+//! `idempotency`는 네트워크가 불안정한 클라이언트가 같은 작성 요청을 다시 보내더라도
+//! 포스트나 댓글이 두 번 작성되지 않도록 하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! `POST /api/posts`와 `POST /api/comments` 요청에 `Idempotency-Key` 헤더가 있는 경우,
+//! 처음 요청의 응답을 `KEY_TTL` 동안 저장해두고 같은 키로 다시 요청하면 작성하지 않고 저장된 응답을 그대로 반환한다.
+//! 키는 요청 경로와 작성자별로 구분되며, 저장된 응답은 서버 프로세스의 메모리에만 보관된다.
+
+use crate::error::ApiError;
+use crate::response;
+use actix_web::{HttpRequest, HttpResponse};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 멱등 키를 담는 요청 헤더의 이름이다.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+/// 저장된 응답을 재사용했음을 알리는 응답 헤더의 이름이다.
+pub const REPLAYED_HEADER: &str = "Idempotent-Replayed";
+/// 처리한 요청의 응답을 저장해두는 시간이다.
+pub const KEY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// 처리 중인 요청이 끝나지 않은 경우 같은 키의 요청을 다시 받기까지의 시간이다.
+const PENDING_TIMEOUT: Duration = Duration::from_secs(60);
+/// 멱등 키의 최대 길이이다.
+const MAX_KEY_CHARS: usize = 255;
+
+/// 멱등 키로 받은 요청의 처리 상태이다.
+enum Entry {
+    /// 요청을 처리하고 있는 상태로, 처리를 시작한 시각을 담고 있다.
+    Pending(Instant),
+    /// 요청을 처리한 상태로, 처리한 시각과 만든 항목의 주소 및 응답 본문을 담고 있다.
+    Done(Instant, String, Value),
+}
+
+impl Entry {
+    /// 저장된 상태가 더 이상 유효하지 않은지 확인하는 메서드이다.
+    fn is_expired(&self, now: Instant) -> bool {
+        match self {
+            Entry::Pending(at) => now.duration_since(*at) >= PENDING_TIMEOUT,
+            Entry::Done(at, _, _) => now.duration_since(*at) >= KEY_TTL,
+        }
+    }
+}
+
+/// 멱등 키별로 처리한 요청의 응답을 저장하는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+#[derive(Default)]
+pub struct Idempotency {
+    /// 요청 경로, 작성자, 멱등 키를 합친 값별 처리 상태이다.
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+/// 요청의 `Idempotency-Key` 헤더를 읽어 요청 경로와 작성자별로 구분되는 키를 반환하는 메서드이다.
+///
+/// 헤더가 없는 경우 `None`을, 헤더가 비어있거나 255자를 넘는 경우 `ApiError::BadRequest`를 반환한다.
+/// # 예제
+/// ```
+/// use actix_web::test::TestRequest;
+/// use code_mmunity_server::idempotency;
+/// let req = TestRequest::post()
+///     .uri("/api/posts")
+///     .insert_header(("Idempotency-Key", "a1b2c3"))
+///     .to_http_request();
+/// assert_eq!(idempotency::key(&req, "u1").unwrap().unwrap(), "/api/posts u1 a1b2c3");
+/// let req = TestRequest::post().uri("/api/posts").to_http_request();
+/// assert!(idempotency::key(&req, "u1").unwrap().is_none());
+/// ```
+pub fn key(req: &HttpRequest, user_id: &str) -> Result<Option<String>, ApiError> {
+    let Some(value) = req.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(None);
+    };
+    match value.to_str().map(str::trim) {
+        Ok(value) if !value.is_empty() && value.chars().count() <= MAX_KEY_CHARS => {
+            Ok(Some(format!("{} {} {}", req.path(), user_id, value)))
+        }
+        _ => Err(ApiError::BadRequest(format!(
+            "{} 헤더는 {}자 이하의 문자열이어야 합니다.",
+            IDEMPOTENCY_KEY_HEADER, MAX_KEY_CHARS
+        ))),
+    }
+}
+
+impl Idempotency {
+    /// 객체를 생성하는 메서드이다.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 멱등 키로 받은 요청의 처리를 시작하는 메서드이다.
+    ///
+    /// 같은 키로 처리한 요청이 있는 경우 저장된 응답을, 처음 받은 키이거나 키가 없는 경우 `None`을 반환한다.
+    /// 같은 키의 요청을 아직 처리하고 있는 경우 `ApiError::Conflict`를 반환한다.
+    /// # 예제
+    /// ```
+    /// use actix_web::http::StatusCode;
+    /// use code_mmunity_server::error::ApiError;
+    /// use code_mmunity_server::idempotency::Idempotency;
+    /// use serde_json::json;
+    /// let idempotency = Idempotency::new();
+    /// let key = Some("/api/posts u1 a1b2c3");
+    /// assert!(idempotency.begin(key).unwrap().is_none());
+    /// assert!(matches!(idempotency.begin(key), Err(ApiError::Conflict(_))));
+    ///
+    /// let result = Ok(("/api/posts/1".to_string(), json!({"post_id": 1})));
+    /// idempotency.finish(key, &result);
+    /// let replayed = idempotency.begin(key).unwrap().unwrap();
+    /// assert_eq!(replayed.status(), StatusCode::CREATED);
+    /// assert_eq!(replayed.headers().get("Location").unwrap(), "/api/posts/1");
+    /// assert_eq!(replayed.headers().get("Idempotent-Replayed").unwrap(), "true");
+    /// ```
+    pub fn begin(&self, key: Option<&str>) -> Result<Option<HttpResponse>, ApiError> {
+        let Some(key) = key else {
+            return Ok(None);
+        };
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        entries.retain(|_, entry| !entry.is_expired(now));
+        match entries.get(key) {
+            Some(Entry::Pending(_)) => Err(ApiError::Conflict(
+                "같은 Idempotency-Key의 요청을 처리하고 있습니다.".to_string(),
+            )),
+            Some(Entry::Done(_, location, body)) => Ok(Some(
+                HttpResponse::Created()
+                    .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+                    .insert_header(("Location", location.as_str()))
+                    .insert_header((REPLAYED_HEADER, "true"))
+                    .json(body),
+            )),
+            None => {
+                entries.insert(key.to_string(), Entry::Pending(now));
+                Ok(None)
+            }
+        }
+    }
+    /// 멱등 키로 받은 요청의 처리를 마치는 메서드이다.
+    ///
+    /// 항목을 만든 경우 주소와 항목을 저장하고, 실패한 경우 같은 키로 다시 요청할 수 있도록 처리 상태를 지운다.
+    pub fn finish<T: Serialize>(&self, key: Option<&str>, result: &Result<(String, T), ApiError>) {
+        let Some(key) = key else {
+            return;
+        };
+        let done = result.as_ref().ok().and_then(|(location, body)| {
+            let body = serde_json::to_value(body).ok()?;
+            Some(Entry::Done(Instant::now(), location.clone(), body))
+        });
+        let mut entries = self.entries.lock().unwrap();
+        match done {
+            Some(entry) => {
+                entries.insert(key.to_string(), entry);
+            }
+            None => {
+                entries.remove(key);
+            }
+        }
+    }
+}
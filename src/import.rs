@@ -0,0 +1,277 @@
+//! # 포스트 일괄 가져오기 관련 동작을 정의하는 모듈
+//!
+//! `import`는 이전 포럼의 콘텐츠를 코드뮤니티로 옮길 때 포스트와 댓글을 한 번에 등록하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 요청 본문은 포스트들의 JSON 배열이거나, 한 줄에 포스트 하나씩 담긴 NDJSON이다.
+//! 포스트는 `BATCH_SIZE`개씩 하나의 트랜잭션으로 등록되며, 잘못된 항목이 있어도 나머지 항목은 등록되고
+//! 항목별 결과로 실패한 이유를 알려준다.
+
+use crate::db;
+use crate::post::PostType;
+use crate::tag::{Tag, MAX_TAGS};
+use crate::user::User;
+use crate::validation::{is_blank, is_datetime};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 하나의 트랜잭션에서 등록할 최대 포스트 수이다.
+const BATCH_SIZE: usize = 100;
+
+/// 가져올 댓글 하나의 정보이다.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportComment {
+    /// 댓글을 작성한 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 댓글의 내용이다.
+    pub data: String,
+    /// 댓글이 작성된 시각(`YYYY-MM-DD HH:MM:SS`, UTC)이다. 주어지지 않은 경우 가져온 시각이 된다.
+    #[serde(default)]
+    pub create_at: Option<String>,
+}
+
+/// 가져올 포스트 하나의 정보이다.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportPost {
+    /// 포스트를 작성한 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 포스트의 제목이다.
+    pub title: String,
+    /// 포스트에 작성된 프로그래밍 언어 종류이다.
+    pub language: String,
+    /// 포스트 내용이다.
+    pub data: String,
+    /// 포스트의 종류이다.
+    #[serde(default)]
+    pub post_type: PostType,
+    /// 포스트에 붙일 태그들이다.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 포스트가 작성된 시각(`YYYY-MM-DD HH:MM:SS`, UTC)이다. 주어지지 않은 경우 가져온 시각이 된다.
+    #[serde(default)]
+    pub create_at: Option<String>,
+    /// 포스트에 달린 댓글들이다.
+    #[serde(default)]
+    pub comments: Vec<ImportComment>,
+}
+
+/// 항목 하나를 가져온 결과이다.
+#[derive(Serialize)]
+pub struct ImportResult {
+    /// 요청 본문에서 항목의 순서이다. 0부터 시작하며, NDJSON의 경우 빈 줄을 제외한 줄의 순서이다.
+    pub index: usize,
+    /// 등록된 포스트의 고유 ID이다. 실패한 경우 `None`이다.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_id: Option<u64>,
+    /// 함께 등록된 댓글 수이다.
+    pub comments: usize,
+    /// 실패한 이유이다. 성공한 경우 `None`이다.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ImportResult {
+    /// 실패한 항목의 결과를 생성하는 메서드이다.
+    fn failed(index: usize, error: impl ToString) -> Self {
+        Self {
+            index,
+            post_id: None,
+            comments: 0,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// 요청 본문에서 읽은 항목 하나이다. 읽을 수 없는 항목은 그 이유를 담고 있다.
+pub type ImportItem = std::result::Result<Value, String>;
+
+/// 가져오기 요청 전체의 결과이다.
+#[derive(Serialize, Default)]
+pub struct ImportSummary {
+    /// 등록된 포스트 수이다.
+    pub imported: usize,
+    /// 등록하지 못한 항목 수이다.
+    pub failed: usize,
+    /// 항목별 결과이다.
+    pub results: Vec<ImportResult>,
+}
+
+/// 요청 본문을 항목별 JSON 값으로 나누는 메서드이다.
+///
+/// 본문이 `[`로 시작하면 JSON 배열로, 그렇지 않으면 NDJSON으로 읽는다. NDJSON의 빈 줄은 건너뛴다.
+/// 본문 전체를 읽을 수 없는 경우 `Err`를, 특정 줄을 읽을 수 없는 경우 해당 항목에 `Err`를 담아 반환한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::import::split_items;
+/// let items = split_items(b"{\"a\":1}\n\n{\"a\":2}\nnot json").unwrap();
+/// assert_eq!(items.len(), 3);
+/// assert!(items[0].is_ok() && items[1].is_ok() && items[2].is_err());
+/// assert_eq!(split_items(b" [{\"a\":1}, {\"a\":2}]").unwrap().len(), 2);
+/// assert!(split_items(b"[{\"a\":1}").is_err());
+/// ```
+pub fn split_items(body: &[u8]) -> std::result::Result<Vec<ImportItem>, String> {
+    let text = std::str::from_utf8(body).map_err(|error| error.to_string())?;
+    if text.trim_start().starts_with('[') {
+        let items: Vec<Value> = serde_json::from_str(text).map_err(|error| error.to_string())?;
+        return Ok(items.into_iter().map(Ok).collect());
+    }
+    Ok(text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(line).map_err(|error| error.to_string()))
+        .collect())
+}
+
+/// 가져올 포스트가 등록 조건에 맞는지 확인하는 메서드이다.
+///
+/// 조건에 맞는 경우 정규화된 태그들을, 그렇지 않은 경우 그 이유를 반환한다.
+fn validate(post: &ImportPost) -> std::result::Result<Vec<String>, String> {
+    if is_blank(&post.title) || is_blank(&post.data) {
+        return Err("제목과 내용은 비어있을 수 없습니다.".to_string());
+    }
+    let tags = Tag::normalize_all(&post.tags)
+        .ok_or_else(|| format!("태그는 공백 없이 최대 {}개까지 붙일 수 있습니다.", MAX_TAGS))?;
+    let datetimes = post.create_at.iter().chain(
+        post.comments
+            .iter()
+            .filter_map(|comment| comment.create_at.as_ref()),
+    );
+    for datetime in datetimes {
+        if !is_datetime(datetime) {
+            return Err("create_at은 YYYY-MM-DD HH:MM:SS 형식이어야 합니다.".to_string());
+        }
+    }
+    if post.comments.iter().any(|comment| is_blank(&comment.data)) {
+        return Err("댓글 내용은 비어있을 수 없습니다.".to_string());
+    }
+    let authors =
+        std::iter::once(&post.user_id).chain(post.comments.iter().map(|comment| &comment.user_id));
+    for user_id in authors {
+        if User::get_user_name(user_id).is_none() {
+            return Err(format!("존재하지 않는 사용자입니다: {}", user_id));
+        }
+    }
+    Ok(tags)
+}
+
+/// 검증된 포스트 하나와 댓글들을 트랜잭션 안에서 등록하고 포스트의 고유 ID를 반환하는 메서드이다.
+fn insert(tx: &mut Transaction, post: &ImportPost, tags: &[String]) -> Result<u64> {
+    tx.exec_drop(
+        r"insert into post(user_id, title, language, data, likes, report_count, post_type, published, create_at)
+        values(:user_id, :title, :language, :data, 0, 0, :post_type, true, coalesce(:create_at, now()))",
+        params! {
+            "user_id" => &post.user_id,
+            "title" => &post.title,
+            "language" => &post.language,
+            "data" => &post.data,
+            "post_type" => post.post_type.as_str(),
+            "create_at" => &post.create_at,
+        },
+    )?;
+    let post_id = tx.last_insert_id().unwrap_or_default();
+    Tag::attach(tx, post_id, tags)?;
+    tx.exec_batch(
+        r"insert into comment(post_id, user_id, data, create_at)
+        values(:post_id, :user_id, :data, coalesce(:create_at, now()))",
+        post.comments.iter().map(|comment| {
+            params! {
+                "post_id" => post_id,
+                "user_id" => &comment.user_id,
+                "data" => &comment.data,
+                "create_at" => &comment.create_at,
+            }
+        }),
+    )?;
+    Ok(post_id)
+}
+
+/// 항목들을 `BATCH_SIZE`개씩 나누어 등록하고 항목별 결과를 반환하는 메서드이다.
+///
+/// 각 항목은 세이브포인트 안에서 등록되므로 DB 작업 중 실패한 항목만 되돌리고 나머지 항목은 등록된다.
+/// 트랜잭션을 시작하거나 커밋하지 못한 경우 해당 묶음의 모든 항목이 실패로 기록된다.
+/// # 예제
+/// ```ignore
+/// let items = import::split_items(&body)?;
+/// let summary = import::run(items);
+/// println!("포스트 {}개를 가져왔습니다.", summary.imported);
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn run(items: Vec<ImportItem>) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    let mut conn = db::get_conn();
+    let items: Vec<_> = items.into_iter().enumerate().collect();
+    for batch in items.chunks(BATCH_SIZE) {
+        let mut results = Vec::with_capacity(batch.len());
+        let committed = conn
+            .start_transaction(TxOpts::default())
+            .and_then(|mut tx| {
+                for (index, item) in batch {
+                    results.push(import_one(&mut tx, *index, item));
+                }
+                tx.commit()
+            });
+        if let Err(error) = committed {
+            tracing::error!(%error, "포스트 가져오기 트랜잭션이 실패했습니다.");
+            results = batch
+                .iter()
+                .map(|(index, _)| ImportResult::failed(*index, "DB 작업 중 문제가 발생했습니다."))
+                .collect();
+        }
+        for result in results {
+            if result.error.is_some() {
+                summary.failed += 1;
+            } else {
+                summary.imported += 1;
+            }
+            summary.results.push(result);
+        }
+    }
+    summary
+}
+
+/// 항목 하나를 검증한 뒤 세이브포인트 안에서 등록하는 메서드이다.
+fn import_one(tx: &mut Transaction, index: usize, item: &ImportItem) -> ImportResult {
+    let post = match item.clone().and_then(|value| {
+        serde_json::from_value::<ImportPost>(value).map_err(|error| error.to_string())
+    }) {
+        Ok(post) => post,
+        Err(error) => return ImportResult::failed(index, error),
+    };
+    let tags = match validate(&post) {
+        Ok(tags) => tags,
+        Err(error) => return ImportResult::failed(index, error),
+    };
+    let inserted =
+        tx.query_drop("savepoint import_item")
+            .and_then(|_| match insert(tx, &post, &tags) {
+                Ok(post_id) => tx
+                    .query_drop("release savepoint import_item")
+                    .map(|_| post_id),
+                Err(error) => {
+                    tx.query_drop("rollback to savepoint import_item")?;
+                    Err(error)
+                }
+            });
+    match inserted {
+        Ok(post_id) => ImportResult {
+            index,
+            post_id: Some(post_id),
+            comments: post.comments.len(),
+            error: None,
+        },
+        Err(error) => {
+            tracing::warn!(%error, index, "포스트를 가져오지 못했습니다.");
+            ImportResult::failed(index, "DB 작업 중 문제가 발생했습니다.")
+        }
+    }
+}
@@ -0,0 +1,165 @@
+//! # 외부 연동 관련 동작을 정의하는 모듈
+//!
+//! `integration`은 CI 봇과 같은 자동화 도구가 API 키로 인증하여 포스트를 게시하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! API 키는 `INTEGRATION_API_KEYS` 환경변수에 `사용자ID:키` 형태로 쉼표로 구분하여 지정하며,
+//! 요청의 `X-Api-Key` 헤더 값과 일치하는 키에 연결된 사용자의 이름으로 포스트가 작성된다.
+//! 봇이 작성한 포스트는 일반 포스트보다 엄격하게 검증되며 `bot` 값이 `true`로 표시된다.
+
+use crate::cache::Cache;
+use crate::error::ApiError;
+use crate::link_preview::LinkPreviews;
+use crate::moderation::{self, Moderator};
+use crate::post::{PostRequest, PostType, Visibility};
+use crate::response;
+use crate::sanitize;
+use crate::search_index::SearchIndex;
+use crate::word_filter::{ContentKind, WordFilter};
+use actix_web::web::Json;
+use actix_web::{post, web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use std::env;
+
+/// 봇이 작성하는 포스트 제목의 최대 글자 수이다.
+pub const MAX_TITLE_CHARS: usize = 100;
+/// 봇이 작성하는 포스트 내용의 최대 글자 수이다.
+pub const MAX_DATA_CHARS: usize = 20_000;
+/// 봇이 작성하는 포스트 언어 이름의 최대 글자 수이다.
+pub const MAX_LANGUAGE_CHARS: usize = 30;
+
+/// `INTEGRATION_API_KEYS` 형식의 문자열에서 API 키에 연결된 사용자 고유 ID를 찾는 메서드이다.
+///
+/// 각 항목은 `사용자ID:키` 형태이며, 형식이 올바르지 않거나 키가 비어있는 항목은 무시된다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::integration::find_key_owner;
+/// let keys = "snippet-bot:abc123, release-bot:def456,broken";
+/// assert_eq!(find_key_owner(keys, "def456"), Some("release-bot".to_string()));
+/// assert_eq!(find_key_owner(keys, "broken"), None);
+/// assert_eq!(find_key_owner("empty-bot:", ""), None);
+/// ```
+pub fn find_key_owner(keys: &str, api_key: &str) -> Option<String> {
+    keys.split(',')
+        .filter_map(|entry| entry.trim().split_once(':'))
+        .find(|(user_id, key)| !user_id.is_empty() && !key.is_empty() && *key == api_key)
+        .map(|(user_id, _)| user_id.to_string())
+}
+
+/// 요청의 `X-Api-Key` 헤더로 인증된 봇 사용자의 고유 ID를 반환하는 메서드이다.
+///
+/// `INTEGRATION_API_KEYS` 환경변수가 지정되지 않았거나 일치하는 키가 없는 경우 `None`을 반환한다.
+fn authenticate(req: &HttpRequest) -> Option<String> {
+    let keys = env::var("INTEGRATION_API_KEYS").ok()?;
+    let api_key = req.headers().get("X-Api-Key")?.to_str().ok()?;
+    find_key_owner(&keys, api_key)
+}
+
+/// JSON 을 통해 봇이 등록할 포스트를 받을 때 필요한 구조체이다.
+///
+/// 알 수 없는 항목이 포함된 요청은 거부된다.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BotPostRequest {
+    /// 포스트의 제목이다.
+    pub title: String,
+    /// 포스트에 작성된 프로그래밍 언어 종류이다. 소문자, 숫자, `+`, `#`, `-`만 사용할 수 있다.
+    pub language: String,
+    /// 포스트 내용이다.
+    pub data: String,
+    /// 포스트에 붙일 태그들이다.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl BotPostRequest {
+    /// 봇이 등록할 포스트가 일반 포스트보다 엄격한 조건에 맞는지 확인하는 메서드이다.
+    ///
+    /// 조건에 맞지 않는 경우 그 이유를 반환한다. 비어있는 제목이나 태그 형식처럼 일반 포스트와 같은 조건은
+    /// `PostRequest::submit()`에서 확인한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::integration::BotPostRequest;
+    /// let request = BotPostRequest {
+    ///     title: "오늘의 코드 조각".to_string(),
+    ///     language: "c++".to_string(),
+    ///     data: "int main() {}".to_string(),
+    ///     tags: Vec::new(),
+    /// };
+    /// assert!(request.validate().is_ok());
+    /// let request = BotPostRequest { language: "Rust Lang".to_string(), ..request };
+    /// assert!(request.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), String> {
+        if self.title.chars().count() > MAX_TITLE_CHARS {
+            return Err(format!("제목은 {}자를 넘을 수 없습니다.", MAX_TITLE_CHARS));
+        }
+        if self.data.chars().count() > MAX_DATA_CHARS {
+            return Err(format!("내용은 {}자를 넘을 수 없습니다.", MAX_DATA_CHARS));
+        }
+        if self.language.is_empty()
+            || self.language.len() > MAX_LANGUAGE_CHARS
+            || !self
+                .language
+                .bytes()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || b"+#-".contains(&c))
+        {
+            return Err(format!(
+                "언어는 소문자, 숫자, +, #, -로 이루어진 {}자 이하의 이름이어야 합니다.",
+                MAX_LANGUAGE_CHARS
+            ));
+        }
+        if self.data.contains('\0') || self.title.contains(char::is_control) {
+            return Err("제목과 내용에 제어 문자를 사용할 수 없습니다.".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[post("/api/integrations/posts")]
+pub async fn create_bot_post_api(
+    req: HttpRequest,
+    request: Json<BotPostRequest>,
+    cache: web::Data<Cache>,
+    word_filter: web::Data<WordFilter>,
+    moderator: web::Data<dyn Moderator>,
+    link_previews: web::Data<LinkPreviews>,
+    search_index: web::Data<SearchIndex>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = authenticate(&req).ok_or(ApiError::Unauthorized)?;
+    let request = request.into_inner();
+    request.validate().map_err(ApiError::Validation)?;
+    let mut post = PostRequest {
+        user_id,
+        title: request.title,
+        language: request.language,
+        data: request.data,
+        post_type: PostType::Snippet,
+        tags: request.tags,
+        visibility: Visibility::Public,
+        publish_at: None,
+        bot: true,
+    };
+    sanitize::clean(&mut post.title);
+    sanitize::clean(&mut post.data);
+    let flagged =
+        word_filter.screen(&mut [("title", &mut post.title), ("data", &mut post.data)])?;
+    let text = format!("{}\n\n{}", post.title, post.data);
+    let post = post.submit()?;
+    link_previews.prefetch(&post.data);
+    WordFilter::flag(ContentKind::Post, post.post_id, &flagged);
+    cache.invalidate_post(post.post_id);
+    search_index.sync(post.post_id);
+    moderation::submit(
+        moderator,
+        cache,
+        search_index,
+        ContentKind::Post,
+        post.post_id,
+        text,
+    );
+    Ok(response::created(
+        format!("/api/posts/{}", post.post_id),
+        post,
+    ))
+}
@@ -0,0 +1,204 @@
+//! # 프로그래밍 언어 관련 동작을 정의하는 모듈
+//!
+//! `language`는 포스트에 지정할 수 있는 프로그래밍 언어 목록을 관리하고,
+//! 클라이언트가 언어 선택 목록을 서버에서 받아갈 수 있도록 제공하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 언어 목록은 설정 파일의 `languages` 항목이나 `LANGUAGES` 환경변수로 바꿀 수 있으며,
+//! 지정하지 않은 경우 `DEFAULT_LANGUAGES`를 사용한다.
+//!
+//! 홈 화면에서 활발한 언어를 보여줄 수 있도록 언어별 포스트 수와 평균 공감 수도 집계하며,
+//! 집계 결과는 `LANGUAGE_STATS_CACHE_SECONDS`초(기본값 300초)동안 메모리에 저장해두고 재사용한다.
+
+use crate::db;
+use crate::leaderboard::Period;
+use crate::response;
+use actix_web::{get, web, Responder};
+use mysql::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 기본으로 지원하는 프로그래밍 언어 이름들이다.
+pub const DEFAULT_LANGUAGES: &[&str] = &[
+    "bash",
+    "c",
+    "c#",
+    "c++",
+    "dart",
+    "go",
+    "haskell",
+    "java",
+    "javascript",
+    "kotlin",
+    "lua",
+    "php",
+    "python",
+    "ruby",
+    "rust",
+    "scala",
+    "sql",
+    "swift",
+    "text",
+    "typescript",
+];
+
+/// 집계 결과를 저장해두는 기본 시간(초)이다.
+const DEFAULT_STATS_CACHE_SECONDS: u64 = 300;
+
+/// 서버 시작 시 설정된 언어 목록이다.
+static LANGUAGES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// 서버 시작 시 설정된 언어 목록을 저장하는 메서드이다.
+///
+/// 이미 목록이 저장된 경우 아무 작업도 하지 않는다.
+pub fn init(languages: Vec<String>) {
+    let _ = LANGUAGES.set(languages);
+}
+
+/// 포스트에 지정할 수 있는 언어 목록을 반환하는 메서드이다.
+///
+/// `init()`으로 저장된 목록이 없는 경우 `DEFAULT_LANGUAGES`를 반환한다.
+pub fn supported() -> &'static [String] {
+    LANGUAGES.get_or_init(|| {
+        DEFAULT_LANGUAGES
+            .iter()
+            .map(|name| name.to_string())
+            .collect()
+    })
+}
+
+/// 포스트에 지정할 수 있는 언어인지 확인하는 메서드이다.
+///
+/// # 예제
+/// ```
+/// use code_mmunity_server::language;
+/// assert!(language::is_supported("rust"));
+/// assert!(language::is_supported("c++"));
+/// assert!(!language::is_supported("Rust Lang"));
+/// ```
+pub fn is_supported(name: &str) -> bool {
+    supported().iter().any(|language| language == name)
+}
+
+/// 언어 이름이 저장되는 형태인지 확인하는 메서드이다.
+///
+/// 설정으로 언어 목록을 받을 때 사용하며, 소문자, 숫자, `+`, `#`, `-`로 이루어진 30자 이하의 이름만 허용한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::language::is_valid_name;
+/// assert!(is_valid_name("objective-c"));
+/// assert!(!is_valid_name("Rust Lang"));
+/// ```
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 30
+        && name
+            .bytes()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || b"+#-".contains(&c))
+}
+
+/// 쿼리 스트링을 통해 언어별 통계 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct LanguageStatsRequest {
+    /// 집계할 기간(`week`, `month`, `all`)이다. 생략하면 전체 기간으로 집계한다.
+    #[serde(default)]
+    pub period: Period,
+}
+
+/// 언어 하나의 포스트 통계이다.
+#[derive(Serialize, Clone)]
+pub struct LanguageStat {
+    /// 언어 이름이다.
+    pub language: String,
+    /// 기간 동안 작성된 포스트 수이다.
+    pub posts: u64,
+    /// 기간 동안 작성된 포스트들이 받은 평균 공감 수이다. 소수점 아래 둘째 자리까지 반올림한다.
+    pub average_likes: f64,
+}
+
+/// 언어별 통계 집계 결과를 저장해두는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+#[derive(Default)]
+pub struct LanguageStats {
+    cache: Mutex<HashMap<Period, (Instant, Vec<LanguageStat>)>>,
+}
+
+impl LanguageStats {
+    /// 집계 결과를 저장해두는 시간을 반환하는 메서드이다.
+    fn cache_duration() -> Duration {
+        Duration::from_secs(
+            env::var("LANGUAGE_STATS_CACHE_SECONDS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_STATS_CACHE_SECONDS),
+        )
+    }
+    /// 기간 동안 작성된 포스트의 언어별 통계를 포스트 수가 많은 순으로 반환하는 메서드이다.
+    ///
+    /// 저장된 집계 결과가 유효한 경우 DB에 접속하지 않고 저장된 결과를 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// for stat in language_stats.get(Period::Month) {
+    ///     println!("{}: {}개 (평균 공감 {})", stat.language, stat.posts, stat.average_likes);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get(&self, period: Period) -> Vec<LanguageStat> {
+        if let Some((created, stats)) = self.cache.lock().unwrap().get(&period) {
+            if created.elapsed() < Self::cache_duration() {
+                return stats.clone();
+            }
+        }
+        let stats = Self::query(period);
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(period, (Instant::now(), stats.clone()));
+        stats
+    }
+    /// DB에서 언어별 통계를 집계하는 메서드이다.
+    ///
+    /// 공개되지 않았거나 삭제된 포스트, 검토를 통과하지 않은 포스트는 포함되지 않는다.
+    fn query(period: Period) -> Vec<LanguageStat> {
+        let mut conn = db::get_read_conn();
+        conn.query_map(
+            format!(
+                r"select language, count(*) as posts, avg(likes) * 1e0
+                from post
+                where published and visibility = 'public' and deleted_at is null
+                and moderation = 'approved' and {}
+                group by language
+                order by posts desc, language",
+                period.condition("create_at")
+            ),
+            |(language, posts, average_likes): (String, u64, f64)| LanguageStat {
+                language,
+                posts,
+                average_likes: (average_likes * 100.0).round() / 100.0,
+            },
+        )
+        .unwrap()
+    }
+}
+
+#[get("/api/languages")]
+pub async fn get_languages_api() -> impl Responder {
+    response::json(supported())
+}
+
+#[get("/api/stats/languages")]
+pub async fn get_language_stats_api(
+    request: web::Query<LanguageStatsRequest>,
+    language_stats: web::Data<LanguageStats>,
+) -> impl Responder {
+    response::json(language_stats.get(request.period))
+}
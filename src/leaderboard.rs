@@ -0,0 +1,190 @@
+//! # 사용자 순위 관련 동작을 정의하는 모듈
+//!
+//! `leaderboard`는 코드뮤니티에서 일정 기간동안 공감을 많이 받았거나 포스트를 많이 작성한
+//! 사용자의 순위를 집계하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 집계 쿼리는 비용이 크기 때문에 결과를 `LEADERBOARD_CACHE_SECONDS`초(기본값 300초)동안
+//! 메모리에 저장해두고 재사용한다.
+
+use crate::db;
+use crate::response;
+use actix_web::{get, web, Responder};
+use mysql::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 순위에 포함되는 최대 사용자 수이다.
+const LEADERBOARD_SIZE: u32 = 50;
+/// 집계 결과를 저장해두는 기본 시간(초)이다.
+const DEFAULT_CACHE_SECONDS: u64 = 300;
+
+/// 순위를 집계할 기간이다.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    /// 최근 7일
+    Week,
+    /// 최근 30일
+    Month,
+    /// 전체 기간
+    #[default]
+    All,
+}
+
+impl Period {
+    /// 기간에 해당하는 SQL 조건문을 반환하는 메서드이다.
+    ///
+    /// `column`에는 날짜를 비교할 열 이름이 들어간다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::leaderboard::Period;
+    /// assert_eq!(Period::Month.condition("create_at"), "create_at >= now() - interval 30 day");
+    /// assert_eq!(Period::All.condition("create_at"), "true");
+    /// ```
+    pub fn condition(&self, column: &str) -> String {
+        match self {
+            Self::Week => format!("{} >= now() - interval 7 day", column),
+            Self::Month => format!("{} >= now() - interval 30 day", column),
+            Self::All => "true".to_string(),
+        }
+    }
+}
+
+/// 순위를 매기는 기준이다.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RankBy {
+    /// 받은 공감 수
+    #[default]
+    Likes,
+    /// 작성한 포스트 수
+    Posts,
+}
+
+/// 쿼리 스트링을 통해 순위 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct LeaderboardRequest {
+    /// 순위를 집계할 기간이다. 생략하면 전체 기간으로 집계한다.
+    #[serde(default)]
+    pub period: Period,
+    /// 순위를 매기는 기준이다. 생략하면 받은 공감 수로 순위를 매긴다.
+    #[serde(default)]
+    pub by: RankBy,
+}
+
+/// 순위에 포함된 사용자 한 명의 정보이다.
+#[derive(Serialize, Clone)]
+pub struct LeaderboardEntry {
+    /// 순위이다. 1부터 시작한다.
+    pub rank: u32,
+    /// 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 사용자의 이름이다.
+    pub user_name: String,
+    /// 기준에 따라 집계된 값(받은 공감 수 또는 작성한 포스트 수)이다.
+    pub score: u64,
+}
+
+/// 순위 집계 결과를 저장해두는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+#[derive(Default)]
+pub struct Leaderboard {
+    cache: Mutex<HashMap<(Period, RankBy), CachedEntries>>,
+}
+
+/// 집계된 시각과 집계 결과이다.
+type CachedEntries = (Instant, Vec<LeaderboardEntry>);
+
+impl Leaderboard {
+    /// 집계 결과를 저장해두는 시간을 반환하는 메서드이다.
+    fn cache_duration() -> Duration {
+        Duration::from_secs(
+            env::var("LEADERBOARD_CACHE_SECONDS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_CACHE_SECONDS),
+        )
+    }
+    /// 순위를 반환하는 메서드이다.
+    ///
+    /// 저장된 집계 결과가 유효한 경우 DB에 접속하지 않고 저장된 결과를 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// for entry in leaderboard.get(Period::Week, RankBy::Likes) {
+    ///     println!("{}위: {} ({})", entry.rank, entry.user_name, entry.score);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get(&self, period: Period, by: RankBy) -> Vec<LeaderboardEntry> {
+        if let Some((created, entries)) = self.cache.lock().unwrap().get(&(period, by)) {
+            if created.elapsed() < Self::cache_duration() {
+                return entries.clone();
+            }
+        }
+        let entries = Self::query(period, by);
+        self.cache
+            .lock()
+            .unwrap()
+            .insert((period, by), (Instant::now(), entries.clone()));
+        entries
+    }
+    /// 저장된 집계 결과를 모두 지우는 메서드이다.
+    ///
+    /// 다음 요청 시 DB에서 다시 집계한다.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+    /// DB에서 순위를 집계하는 메서드이다.
+    fn query(period: Period, by: RankBy) -> Vec<LeaderboardEntry> {
+        let mut conn = db::get_conn();
+        let query = match by {
+            RankBy::Likes => format!(
+                r"select post.user_id, user.user_name, count(*) as score
+                from react join post on react.post_id = post.post_id join user on post.user_id = user.user_id
+                where react.react_kind = 'like' and post.deleted_at is null and user.deactivated_at is null and {}
+                group by post.user_id, user.user_name
+                order by score desc limit {}",
+                period.condition("react.create_at"),
+                LEADERBOARD_SIZE
+            ),
+            RankBy::Posts => format!(
+                r"select post.user_id, user.user_name, count(*) as score
+                from post join user on post.user_id = user.user_id
+                where post.deleted_at is null and user.deactivated_at is null and {}
+                group by post.user_id, user.user_name
+                order by score desc limit {}",
+                period.condition("post.create_at"),
+                LEADERBOARD_SIZE
+            ),
+        };
+        conn.query::<(String, String, u64), _>(query)
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .map(|(index, (user_id, user_name, score))| LeaderboardEntry {
+                rank: index as u32 + 1,
+                user_id,
+                user_name,
+                score,
+            })
+            .collect()
+    }
+}
+
+#[get("/api/leaderboard")]
+pub async fn get_leaderboard_api(
+    request: web::Query<LeaderboardRequest>,
+    leaderboard: web::Data<Leaderboard>,
+) -> impl Responder {
+    response::json(leaderboard.get(request.period, request.by))
+}
@@ -1,4 +1,79 @@
+pub mod activity;
+pub mod admin;
+pub mod analytics;
+pub mod announcement;
+pub mod attachment;
+pub mod auth;
+pub mod avatar;
+pub mod backup;
+pub mod block;
+pub mod bookmark;
+pub mod cache;
+pub mod cleanup;
 pub mod comment;
+pub mod conditional;
+pub mod config;
+pub mod db;
+pub mod email;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod fields;
+pub mod follow;
+pub mod formatter;
+pub mod geo;
+pub mod graphql;
+pub mod health;
+pub mod highlight;
+pub mod idempotency;
+pub mod import;
+pub mod integration;
+pub mod language;
+pub mod leaderboard;
 pub mod likes;
+pub mod link_preview;
+pub mod logging;
+pub mod maintenance;
+pub mod message;
+pub mod migrate;
+pub mod moderation;
+pub mod outbox;
+pub mod pagination;
+pub mod password;
 pub mod post;
+pub mod ranking;
+pub mod rate_limit;
+pub mod reindex;
+pub mod report;
+pub mod reporting;
+pub mod repository;
+pub mod request_id;
+pub mod response;
+pub mod revision;
+pub mod sandbox;
+pub mod sanitize;
+pub mod scheduler;
+pub mod search;
+pub mod search_index;
+pub mod seed;
+pub mod session;
+pub mod shadow_ban;
+pub mod shortlink;
+pub mod sitemap;
+pub mod sqlite;
+pub mod storage;
+pub mod subscription;
+pub mod summary;
+pub mod tag;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod tls;
+pub mod trending;
+pub mod usage;
 pub mod user;
+pub mod user_names;
+pub mod v1;
+pub mod validation;
+pub mod verification;
+pub mod webhook;
+pub mod word_filter;
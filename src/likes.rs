@@ -3,12 +3,28 @@
 //! `likes`는 코드뮤니티에서 공감 관련 기능 처리를 위한
 //! 메서드들로 구성되어 있다.
 
-use actix_web::{patch, web, HttpResponse, Responder};
+use crate::auth::Identity;
+use crate::cache::Cache;
+use crate::db;
+use crate::error::ApiError;
+use crate::events::PostEvents;
+use crate::pagination::Pagination;
+use crate::post::{self, Post};
+use crate::response;
+use crate::summary::{self, BodyRequest};
+use crate::user::{self, User, REPUTATION_PER_LIKE};
+use actix_web::{get, patch, web, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
 use mysql::prelude::*;
 use mysql::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::env;
-use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 같은 포스트에 다시 공감하거나 공감을 취소하기까지 기다려야 하는 기본 시간(초)이다.
+const DEFAULT_LIKE_COOLDOWN_SECONDS: u64 = 5;
 
 /// 공감 수를 늘릴지 줄일지 선택하는 모드이다.
 #[derive(Deserialize)]
@@ -26,8 +42,6 @@ pub enum LikeMode {
 ///
 #[derive(Deserialize)]
 pub struct LikeRequest {
-    /// 사용자의 고유 ID이다.
-    pub user_id: String,
     /// 포스트의 고유 ID이다.
     pub post_id: u32,
     /// 공감 수를 늘릴지 줄일지 선택하는 모드이다.
@@ -37,14 +51,23 @@ pub struct LikeRequest {
 impl LikeRequest {
     /// 공감 수를 조작하는 메서드
     ///
+    /// `liker_id`에는 공감한 사용자의 고유 ID가 들어가며, 요청에 담긴 값이 아닌 인증된 사용자의 고유 ID를 사용해야 한다.
     /// `info`에는 쿼리 스트링을 통해 `LikeRequest` 구조체에 명시된 값을 받아 동작을 처리한다.
-    /// 공감 수 조작 실패에 대한 예외처리를 할 수 있도록 `Result<()>`로 반환한다.
+    /// 공감 기록과 공감 수, 작성자의 평판 점수는 하나의 트랜잭션으로 함께 변경되며,
+    /// 이미 공감한 포스트에 다시 공감하거나 공감하지 않은 포스트의 공감을 취소한 경우에는 아무것도 바뀌지 않는다.
+    /// 공감 수는 0보다 작아지지 않는다.
+    ///
+    /// 변경된 뒤의 공감 수를 반환하며, 존재하지 않거나 삭제된 포스트인 경우 `None`을 반환한다.
     ///
     /// # 예제
-    /// ```
-    /// match LikeRequest::modify_likes(info) {
-    ///     Ok(_) => println!("공감 수 업데이트 됨"),
-    ///     Err(error) => panic!(error)
+    /// ```no_run
+    /// # use actix_web::web;
+    /// # use code_mmunity_server::likes::LikeRequest;
+    /// # let info = web::Query::<LikeRequest>::from_query("post_id=1&mode=Increment").unwrap();
+    /// match LikeRequest::modify_likes("unique_id_for_user", info) {
+    ///     Ok(Some(likes)) => println!("공감 수가 {}개가 되었습니다.", likes),
+    ///     Ok(None) => println!("존재하지 않는 포스트입니다."),
+    ///     Err(error) => panic!("{}", error),
     /// }
     /// ```
     /// # Panics
@@ -53,93 +76,331 @@ impl LikeRequest {
     /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
-    pub fn modify_likes(info: web::Query<LikeRequest>) {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
+    pub fn modify_likes(liker_id: &str, info: web::Query<LikeRequest>) -> Result<Option<u64>> {
+        db::transaction(|tx| {
+            let likes: Option<u64> = tx.exec_first(
+                "select likes from post where post_id = :post_id and deleted_at is null for update",
+                params! {
+                    "post_id" => info.post_id,
+                },
+            )?;
+            let Some(likes) = likes else {
+                return Ok(None);
+            };
+            let (update_react, delta) = match info.mode {
+                LikeMode::Increment => (
+                    r"insert ignore into react (user_id, post_id, react_kind)
+                    values (:user_id, :post_id, :react_kind)",
+                    1,
+                ),
+                LikeMode::Decrement => (
+                    r"delete from react
+                    where user_id = :user_id and post_id = :post_id and react_kind = :react_kind",
+                    -1,
+                ),
+            };
+            tx.exec_drop(
+                update_react,
+                params! {
+                    "user_id" => liker_id,
+                    "post_id" => info.post_id,
+                    "react_kind" => "like",
+                },
+            )?;
+            if tx.affected_rows() == 0 {
+                return Ok(Some(likes));
             }
-            Err(_) => None,
+            tx.exec_drop(
+                "update post set likes = greatest(cast(likes as signed) + :delta, 0) where post_id = :post_id",
+                params! {
+                    "delta" => delta,
+                    "post_id" => info.post_id,
+                },
+            )?;
+            LikeRequest::update_author_reputation(
+                tx,
+                liker_id,
+                info.post_id,
+                delta * REPUTATION_PER_LIKE,
+            )?;
+            Ok(Some(likes.saturating_add_signed(delta)))
+        })
+    }
+    /// 공감을 받은 포스트 작성자의 평판 점수를 `delta`만큼 변경하는 메서드이다.
+    ///
+    /// `liker_id` 사용자가 자신의 포스트에 공감한 경우에는 평판 점수가 변하지 않는다.
+    fn update_author_reputation<Q: Queryable>(
+        conn: &mut Q,
+        liker_id: &str,
+        post_id: u32,
+        delta: i64,
+    ) -> Result<()> {
+        conn.exec_drop(
+            r"update user
+            set reputation = reputation + :delta
+            where user_id = (select user_id from post where post_id = :post_id) and user_id <> :liker_id",
+            params! {
+                "delta" => delta,
+                "post_id" => post_id,
+                "liker_id" => liker_id,
+            },
+        )
+    }
+}
+
+/// 공감을 변경한 뒤의 공감 수이다.
+#[derive(Serialize)]
+pub struct LikeCount {
+    /// 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 변경된 뒤의 공감 수이다.
+    pub likes: u64,
+}
+
+/// 포스트가 받은 공감을 종류별로 집계한 결과이다.
+#[derive(Serialize)]
+pub struct LikeSummary {
+    /// 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 모든 종류의 공감 수를 합친 값이다.
+    pub total: u64,
+    /// 공감 종류별 공감 수이다.
+    pub reactions: BTreeMap<String, u64>,
+    /// 요청한 사용자가 포스트에 공감했는지 여부이다. 로그인하지 않은 경우 `false`이다.
+    pub reacted: bool,
+    /// 요청한 사용자가 포스트에 남긴 공감 종류들이다.
+    pub viewer_reactions: Vec<String>,
+}
+
+impl LikeSummary {
+    /// 포스트가 받은 공감을 종류별로 집계하는 메서드이다.
+    ///
+    /// 존재하지 않거나 게시되지 않은 포스트, 다른 사용자의 비공개 포스트인 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// if let Some(summary) = LikeSummary::get(1, Some("unique_id_for_user"))? {
+    ///     println!("{}개의 공감을 받았습니다.", summary.total);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get(post_id: u64, viewer_id: Option<&str>) -> Result<Option<Self>> {
+        let mut conn = db::get_conn();
+        if !is_post_visible(&mut conn, post_id, viewer_id)? {
+            return Ok(None);
+        }
+        let counts: Vec<(String, u64, bool)> = conn.exec(
+            r"select react_kind, count(*), coalesce(sum(user_id = :viewer_id), 0) > 0 from react
+            where post_id = :post_id group by react_kind order by react_kind",
+            params! {
+                "post_id" => post_id,
+                "viewer_id" => viewer_id,
+            },
+        )?;
+        let mut summary = Self {
+            post_id,
+            total: 0,
+            reactions: BTreeMap::new(),
+            reacted: false,
+            viewer_reactions: Vec::new(),
         };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
-        match info.mode {
-            LikeMode::Increment => {
-                conn.exec_drop(
-                    r"update post
-            set likes = likes + 1
-            where post_id = :post_id",
-                    params! {
-                        "post_id" => info.post_id
-                    },
-                )
-                .expect("Increment like error");
-                conn.exec_drop(
-                    r"insert into react (user_id, post_id, react_kind)
-            values (:user_id, :post_id, :like)
-            ",
-                    params! {
-                        "user_id" => info.user_id.clone(),
-                        "post_id" => info.post_id,
-                        "like" => "like"
-                    },
-                )
-                .expect("Update react table error");
+        for (react_kind, count, by_viewer) in counts {
+            summary.total += count;
+            if by_viewer {
+                summary.reacted = true;
+                summary.viewer_reactions.push(react_kind.clone());
             }
-            LikeMode::Decrement => {
-                conn.exec_drop(
-                    r"update post
-            set likes = likes - 1
-            where post_id = :post_id",
-                    params! {
-                        "post_id" => info.post_id.clone()
-                    },
-                )
-                .expect("Decrement like error");
-                conn.exec_drop(
-                    r"delete from react
-            where user_id = :user_id and post_id = :post_id and react_kind = :react_kind
-            ",
-                    params! {
-                        "user_id" => info.user_id.clone(),
-                        "post_id" => info.post_id,
-                        "react_kind" => "like",
-                    },
-                )
-                .expect("Update react table error");
+            summary.reactions.insert(react_kind, count);
+        }
+        Ok(Some(summary))
+    }
+}
+
+/// 포스트에 공감한 사용자이다.
+#[derive(Serialize)]
+pub struct Liker {
+    /// 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 사용자의 이름이다.
+    pub user_name: String,
+    /// 사용자의 프로필 이미지 주소이다.
+    pub avatar_url: Option<String>,
+    /// 공감한 날짜 및 시간이다.
+    pub liked_at: DateTime<Utc>,
+}
+
+impl Liker {
+    /// 포스트에 공감한 사용자들을 최근에 공감한 순으로 반환하는 메서드이다.
+    ///
+    /// 공감한 포스트 목록을 비공개로 설정했거나 비활성화된 사용자는 제외된다.
+    /// 존재하지 않거나 게시되지 않은 포스트, 다른 사용자의 비공개 포스트인 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get(
+        post_id: u64,
+        viewer_id: Option<&str>,
+        page: &Pagination,
+    ) -> Result<Option<Vec<Self>>> {
+        let mut conn = db::get_conn();
+        if !is_post_visible(&mut conn, post_id, viewer_id)? {
+            return Ok(None);
+        }
+        conn.exec_map(
+            r"select react.user_id, user.user_name, user.avatar_url,
+            date_format(react.create_at, '%Y-%m-%d %H:%i:%s')
+            from react join user on user.user_id = react.user_id
+            where react.post_id = :post_id and react.react_kind = 'like'
+            and user.likes_public and user.deactivated_at is null
+            order by react.create_at desc, react.user_id
+            limit :limit offset :offset",
+            params! {
+                "post_id" => post_id,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(user_id, user_name, avatar_url, liked_at): (
+                String,
+                String,
+                Option<String>,
+                String,
+            )| {
+                Self {
+                    user_id,
+                    user_name,
+                    avatar_url,
+                    liked_at: db::parse_datetime(&liked_at).unwrap_or_default(),
+                }
+            },
+        )
+        .map(Some)
+    }
+}
+
+/// 포스트가 존재하고 게시되었으며 요청한 사용자가 볼 수 있는지 확인하는 메서드이다.
+fn is_post_visible(conn: &mut PooledConn, post_id: u64, viewer_id: Option<&str>) -> Result<bool> {
+    let exists: Option<u64> = conn.exec_first(
+        r"select post_id from post where post_id = :post_id and published and deleted_at is null
+        and (visibility <> 'private' or user_id = :viewer_id)",
+        params! {
+            "post_id" => post_id,
+            "viewer_id" => viewer_id,
+        },
+    )?;
+    Ok(exists.is_some())
+}
+
+/// 사용자가 같은 포스트의 공감을 짧은 시간에 반복하여 바꾸지 못하도록 막는 객체이다.
+///
+/// 사용자는 요청에 담긴 값이 아닌 인증된 사용자의 고유 ID로 구분한다.
+/// 서버 시작 시 `from_env()`를 통해 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+pub struct LikeThrottle {
+    /// 같은 포스트의 공감을 다시 바꾸기까지 기다려야 하는 시간이다.
+    cooldown: Duration,
+    /// 사용자와 포스트별로 마지막으로 공감을 바꾼 시각이다.
+    last_change: Mutex<HashMap<(String, u32), Instant>>,
+}
+
+impl LikeThrottle {
+    /// 환경변수를 통해 객체를 생성하는 메서드이다.
+    ///
+    /// `LIKE_COOLDOWN_SECONDS` 환경변수로 기다려야 하는 시간(초)을 변경할 수 있으며 기본값은 5초이다.
+    pub fn from_env() -> Self {
+        let seconds = env::var("LIKE_COOLDOWN_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_LIKE_COOLDOWN_SECONDS);
+        Self {
+            cooldown: Duration::from_secs(seconds),
+            last_change: Mutex::new(HashMap::new()),
+        }
+    }
+    /// 사용자가 지금 포스트의 공감을 바꿀 수 있는지 확인하는 메서드이다.
+    ///
+    /// 바꿀 수 있는 경우 현재 시각을 기록하고 `None`을, 그렇지 않은 경우 기다려야 하는 시간을 반환한다.
+    pub fn acquire(&self, user_id: &str, post_id: u32) -> Option<Duration> {
+        let mut last_change = self.last_change.lock().unwrap();
+        let now = Instant::now();
+        last_change.retain(|_, time| now.duration_since(*time) < self.cooldown);
+        let key = (user_id.to_string(), post_id);
+        match last_change.get(&key) {
+            Some(time) => Some(self.cooldown - now.duration_since(*time)),
+            None => {
+                last_change.insert(key, now);
+                None
             }
         }
     }
 }
 
+#[get("/api/users/{user_id}/likes")]
+pub async fn get_user_likes_api(
+    req: HttpRequest,
+    user_id: web::Path<String>,
+    page: web::Query<Pagination>,
+    body: web::Query<BodyRequest>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let likes_public = User::is_likes_public(&user_id).ok_or_else(user::user_not_found)?;
+    // 비공개 목록은 인증 토큰으로 확인한 본인만 볼 수 있다.
+    if !likes_public && Identity::from_header(&req).as_deref() != Some(user_id.as_str()) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(summary::apply(
+        Post::get_liked_posts(&user_id, &page),
+        body.body,
+    )))
+}
+
+#[get("/api/posts/{post_id}/likes")]
+pub async fn get_post_likes_api(
+    req: HttpRequest,
+    post_id: web::Path<u64>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let viewer_id = Identity::from_header(&req);
+    let summary =
+        LikeSummary::get(*post_id, viewer_id.as_deref())?.ok_or_else(post::post_not_found)?;
+    Ok(response::json(summary))
+}
+
+#[get("/api/posts/{post_id}/likes/users")]
+pub async fn get_post_likers_api(
+    req: HttpRequest,
+    post_id: web::Path<u64>,
+    page: web::Query<Pagination>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let viewer_id = Identity::from_header(&req);
+    let likers =
+        Liker::get(*post_id, viewer_id.as_deref(), &page)?.ok_or_else(post::post_not_found)?;
+    Ok(response::json(likers))
+}
+
 #[patch("/api/likes")]
-pub async fn modify_likes_api(info: web::Query<LikeRequest>) -> impl Responder {
-    println!("PATCH /api/likes");
-    LikeRequest::modify_likes(info);
-    HttpResponse::Created()
-        .insert_header(("Content-Type", "application/text;charset=utf-8;"))
-        .body("Like Request Submitied")
+pub async fn modify_likes_api(
+    identity: Identity,
+    info: web::Query<LikeRequest>,
+    events: web::Data<PostEvents>,
+    throttle: web::Data<LikeThrottle>,
+    cache: web::Data<Cache>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if User::is_restricted(&identity.user_id)? {
+        return Err(ApiError::Forbidden);
+    }
+    if let Some(wait) = throttle.acquire(&identity.user_id, info.post_id) {
+        return Err(ApiError::TooManyRequests(wait));
+    }
+    let post_id = info.post_id as u64;
+    let likes =
+        LikeRequest::modify_likes(&identity.user_id, info)?.ok_or_else(post::post_not_found)?;
+    cache.invalidate_post(post_id);
+    events.publish(post_id);
+    Ok(response::json(LikeCount { post_id, likes }))
 }
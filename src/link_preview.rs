@@ -0,0 +1,582 @@
+//! # 링크 미리보기 관련 동작을 정의하는 모듈
+//!
+//! `link_preview`는 포스트 내용에 포함된 주소의 OpenGraph 메타데이터(제목, 설명, 이미지)를
+//! 서버에서 읽어와 `link_preview` 테이블에 저장해두고, 포스트를 조회할 때 `link_previews` 항목으로
+//! 함께 반환하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 메타데이터는 요청 처리가 늦어지지 않도록 포스트를 저장하거나 조회한 뒤 별도의 스레드에서 읽어오며,
+//! 서버가 내부망에 요청을 보내는 데 악용되지 않도록(SSRF) 아래와 같이 제한한다.
+//! - `http`, `https` 주소의 기본 포트(80, 443)만 요청한다.
+//! - 주소의 호스트가 사설, 루프백, 링크 로컬 등 공인되지 않은 IP 주소로 해석되는 경우 요청하지 않는다.
+//!   확인한 IP 주소로만 접속하도록 HTTP 클라이언트의 DNS 조회에서 확인하므로, 확인한 뒤 DNS 응답을 바꾸는 공격(DNS rebinding)도 막는다.
+//! - 리다이렉트는 최대 3번까지, 이동할 주소도 같은 방법으로 확인한 뒤 따라간다.
+//! - HTML 응답만 처리하며, 응답 본문은 앞부분 512KiB만 읽는다.
+
+use crate::db;
+use crate::sanitize;
+use actix_web::{rt, web};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+use ureq::config::Config;
+use ureq::http::Uri;
+use ureq::unversioned::resolver::{DefaultResolver, ResolvedSocketAddrs, Resolver};
+use ureq::unversioned::transport::{DefaultConnector, NextTimeout};
+
+/// 포스트 하나에서 미리보기를 만드는 최대 주소 수이다.
+pub const MAX_PREVIEWS: usize = 3;
+/// 미리보기를 만들 수 있는 주소의 최대 길이이다.
+const MAX_URL_CHARS: usize = 512;
+/// 미리보기 제목의 최대 글자 수이다.
+const MAX_TITLE_CHARS: usize = 300;
+/// 미리보기 설명의 최대 글자 수이다.
+const MAX_DESCRIPTION_CHARS: usize = 1000;
+/// 응답 본문에서 읽는 최대 크기(바이트)이다. OpenGraph 메타데이터는 `<head>`에 있으므로 앞부분만 읽는다.
+const MAX_BODY_BYTES: u64 = 512 * 1024;
+/// 따라가는 최대 리다이렉트 횟수이다.
+const MAX_REDIRECTS: usize = 3;
+/// 주소 하나를 읽어오는 기본 제한 시간(밀리초)이다.
+const DEFAULT_TIMEOUT_MS: u64 = 3000;
+/// 저장된 미리보기를 다시 읽어오기까지의 기간(일)이다.
+const REFRESH_AFTER_DAYS: u32 = 7;
+/// 주소를 요청할 때 보내는 `User-Agent` 헤더의 값이다.
+const USER_AGENT: &str = "code_mmunity_server (link preview)";
+
+/// 주소 하나의 링크 미리보기이다.
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct LinkPreview {
+    /// 미리보기를 만든 주소이다.
+    pub url: String,
+    /// 페이지의 제목(`og:title`, 없는 경우 `<title>`)이다.
+    pub title: Option<String>,
+    /// 페이지의 설명(`og:description`, 없는 경우 `description`)이다.
+    pub description: Option<String>,
+    /// 페이지의 대표 이미지 주소(`og:image`)이다.
+    pub image: Option<String>,
+}
+
+impl LinkPreview {
+    /// 표시할 내용이 하나도 없는지 확인하는 메서드이다. 읽어오지 못한 주소의 미리보기가 이에 해당한다.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.image.is_none()
+    }
+}
+
+/// 내용에 포함된 `http`, `https` 주소들을 나타난 순서대로 최대 `MAX_PREVIEWS`개 반환하는 메서드이다.
+///
+/// 주소 뒤에 붙은 문장 부호는 주소에 포함하지 않으며, 중복된 주소와 너무 긴 주소는 제외한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::link_preview::extract_urls;
+/// let urls = extract_urls("문서(https://doc.rust-lang.org/book/)와 http://example.com. 참고 https://doc.rust-lang.org/book/");
+/// assert_eq!(urls, vec!["https://doc.rust-lang.org/book/", "http://example.com"]);
+/// assert!(extract_urls("ftp://example.com https://").is_empty());
+/// ```
+pub fn extract_urls(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    let mut index = 0;
+    while urls.len() < MAX_PREVIEWS {
+        let Some(start) = ["http://", "https://"]
+            .iter()
+            .filter_map(|scheme| text[index..].find(scheme))
+            .min()
+            .map(|offset| index + offset)
+        else {
+            break;
+        };
+        let end = text[start..]
+            .find(|c: char| c.is_whitespace() || "<>\"'`()[]{}".contains(c))
+            .map_or(text.len(), |offset| start + offset);
+        index = end;
+        let url = text[start..end].trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        let has_host = url.parse::<Uri>().is_ok_and(|uri| uri.host().is_some());
+        if !has_host || url.chars().count() > MAX_URL_CHARS {
+            continue;
+        }
+        if !urls.iter().any(|known| known == url) {
+            urls.push(url.to_string());
+        }
+    }
+    urls
+}
+
+/// 태그 안의 속성 값을 반환하는 메서드이다. 속성 이름은 대소문자를 구분하지 않는다.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut index = 0;
+    while let Some(offset) = lower[index..].find(name) {
+        let start = index + offset;
+        index = start + name.len();
+        let before = lower[..start].chars().next_back();
+        if !before.is_some_and(|c| c.is_ascii_whitespace()) {
+            continue;
+        }
+        let rest = lower[index..].trim_start();
+        let Some(rest) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let value_start = lower.len() - rest.trim_start().len();
+        let value = &tag[value_start..];
+        return match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let value = &value[1..];
+                Some(value.find(quote).map_or(value, |end| &value[..end]))
+            }
+            _ => value
+                .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                .map(|end| &value[..end])
+                .or(Some(value)),
+        };
+    }
+    None
+}
+
+/// 메타데이터 값을 정리하는 메서드이다. HTML 특수 문자의 이스케이프를 되돌리고 공백을 정리한다.
+fn clean_value(value: &str, max_chars: usize) -> Option<String> {
+    let value = sanitize::unescape(value)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!value.is_empty()).then(|| value.chars().take(max_chars).collect())
+}
+
+/// HTML 문서에서 링크 미리보기를 만드는 메서드이다.
+///
+/// OpenGraph 메타데이터가 없는 경우 `<title>` 요소와 `description` 메타데이터를 사용한다.
+/// 이미지 주소가 상대 주소인 경우 `url`을 기준으로 바꾸며, `http`, `https`가 아닌 이미지 주소는 무시한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::link_preview::parse_html;
+/// let html = r#"<html><head><title>무시됨</title>
+/// <meta property="og:title" content="Rust &amp; 코드뮤니티">
+/// <META name=description content='짧은 설명'>
+/// <meta property="og:image" content="/logo.png"></head></html>"#;
+/// let preview = parse_html("https://example.com/posts/1", html);
+/// assert_eq!(preview.title.as_deref(), Some("Rust & 코드뮤니티"));
+/// assert_eq!(preview.description.as_deref(), Some("짧은 설명"));
+/// assert_eq!(preview.image.as_deref(), Some("https://example.com/logo.png"));
+/// ```
+pub fn parse_html(url: &str, html: &str) -> LinkPreview {
+    let lower = html.to_ascii_lowercase();
+    let mut og_title = None;
+    let mut og_description = None;
+    let mut description = None;
+    let mut image = None;
+    let mut index = 0;
+    while let Some(offset) = lower[index..].find("<meta") {
+        let start = index + offset;
+        let end = lower[start..]
+            .find('>')
+            .map_or(lower.len(), |offset| start + offset);
+        index = end;
+        let tag = &html[start..end];
+        let Some(content) = attribute(tag, "content") else {
+            continue;
+        };
+        let key = attribute(tag, "property")
+            .or_else(|| attribute(tag, "name"))
+            .map(str::to_ascii_lowercase);
+        match key.as_deref() {
+            Some("og:title") => og_title = og_title.or(clean_value(content, MAX_TITLE_CHARS)),
+            Some("og:description") => {
+                og_description = og_description.or(clean_value(content, MAX_DESCRIPTION_CHARS))
+            }
+            Some("description") => {
+                description = description.or(clean_value(content, MAX_DESCRIPTION_CHARS))
+            }
+            Some("og:image") => image = image.or(resolve(url, content.trim())),
+            _ => {}
+        }
+    }
+    let title = og_title.or_else(|| {
+        let start = lower.find("<title")?;
+        let start = start + lower[start..].find('>')? + 1;
+        let end = start + lower[start..].find("</title")?;
+        clean_value(&html[start..end], MAX_TITLE_CHARS)
+    });
+    LinkPreview {
+        url: url.to_string(),
+        title,
+        description: og_description.or(description),
+        image,
+    }
+}
+
+/// `base` 주소를 기준으로 `target` 주소를 절대 주소로 바꾸는 메서드이다.
+///
+/// 결과가 `http`, `https` 주소가 아니거나 너무 긴 경우 `None`을 반환한다.
+fn resolve(base: &str, target: &str) -> Option<String> {
+    let resolved = if target.starts_with("http://") || target.starts_with("https://") {
+        target.to_string()
+    } else {
+        let base: Uri = base.parse().ok()?;
+        let scheme = base.scheme_str()?;
+        let authority = base.authority()?;
+        if let Some(rest) = target.strip_prefix("//") {
+            format!("{}://{}", scheme, rest)
+        } else if target.starts_with('/') {
+            format!("{}://{}{}", scheme, authority, target)
+        } else {
+            let path = base.path();
+            let directory = &path[..path.rfind('/').map_or(0, |end| end + 1)];
+            format!("{}://{}{}{}", scheme, authority, directory, target)
+        }
+    };
+    (resolved.chars().count() <= MAX_URL_CHARS && resolved.parse::<Uri>().is_ok())
+        .then_some(resolved)
+}
+
+/// 공인 IP 주소인지 확인하는 메서드이다.
+///
+/// 사설, 루프백, 링크 로컬, 공유(CGNAT), 문서용, 멀티캐스트, 지정되지 않은 주소는 공인 주소가 아니다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::link_preview::is_public_ip;
+/// assert!(is_public_ip("93.184.216.34".parse().unwrap()));
+/// assert!(!is_public_ip("127.0.0.1".parse().unwrap()));
+/// assert!(!is_public_ip("10.0.0.1".parse().unwrap()));
+/// assert!(!is_public_ip("169.254.169.254".parse().unwrap()));
+/// assert!(!is_public_ip("::ffff:192.168.0.1".parse().unwrap()));
+/// assert!(!is_public_ip("fd00::1".parse().unwrap()));
+/// ```
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+/// 공인 IPv4 주소인지 확인하는 메서드이다.
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || first == 0
+        || (first == 100 && (64..128).contains(&second))
+        || first >= 240)
+}
+
+/// 공인 IPv6 주소인지 확인하는 메서드이다.
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// 요청해도 되는 주소인지 확인하는 메서드이다.
+///
+/// `http`, `https` 주소의 기본 포트이고 호스트가 있는 경우에만 `Ok`를 반환한다.
+/// 호스트가 공인 IP 주소로 해석되는지는 요청할 때 `PublicResolver`가 확인한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::link_preview::check_url;
+/// assert!(check_url("https://example.com/posts/1").is_ok());
+/// assert!(check_url("http://example.com:8080/").is_err());
+/// assert!(check_url("file:///etc/passwd").is_err());
+/// ```
+pub fn check_url(url: &str) -> std::result::Result<(), String> {
+    let uri: Uri = url.parse().map_err(|_| "올바르지 않은 주소".to_string())?;
+    match (uri.scheme_str(), uri.port_u16()) {
+        (Some("http"), None | Some(80)) | (Some("https"), None | Some(443)) => {}
+        _ => return Err("허용되지 않는 스킴 또는 포트".to_string()),
+    }
+    if uri.host().is_none_or(str::is_empty) {
+        return Err("호스트가 없는 주소".to_string());
+    }
+    Ok(())
+}
+
+/// 호스트가 해석되는 모든 IP 주소가 공인 주소인 경우에만 그 주소들을 반환하는 DNS 조회 방식이다.
+///
+/// HTTP 클라이언트는 여기서 확인한 IP 주소로만 접속하므로, 확인과 접속 사이에 DNS 응답이 바뀌어도 내부망에 접속하지 않는다.
+/// 리다이렉트를 따라갈 때도 새 주소마다 다시 확인한다.
+#[derive(Debug)]
+struct PublicResolver;
+
+impl Resolver for PublicResolver {
+    fn resolve(
+        &self,
+        uri: &Uri,
+        config: &Config,
+        timeout: NextTimeout,
+    ) -> std::result::Result<ResolvedSocketAddrs, ureq::Error> {
+        let addresses = DefaultResolver::default().resolve(uri, config, timeout)?;
+        if addresses.iter().all(|address| is_public_ip(address.ip())) {
+            Ok(addresses)
+        } else {
+            Err(ureq::Error::BadUri(
+                "공인 IP 주소가 아닌 호스트".to_string(),
+            ))
+        }
+    }
+}
+
+/// DB에서 읽은 링크 미리보기이다. 주소, 제목, 설명, 이미지 주소, 다시 읽어와야 하는지 여부 순이다.
+type PreviewRow = (String, Option<String>, Option<String>, Option<String>, bool);
+
+/// 링크 미리보기를 읽어오는 설정이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 앱 상태로 등록한다.
+#[derive(Clone)]
+pub struct LinkPreviews {
+    /// 링크 미리보기를 사용하는지 여부이다. `false`인 경우 DB와 외부 주소에 접근하지 않는다.
+    enabled: bool,
+    /// 요청에 사용할 HTTP 클라이언트이다.
+    agent: ureq::Agent,
+}
+
+impl LinkPreviews {
+    /// 링크 미리보기를 사용하지 않는 설정을 생성하는 메서드이다. MySQL을 사용하지 않는 경우와 테스트에 사용한다.
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            agent: ureq::Agent::new_with_defaults(),
+        }
+    }
+    /// 환경변수를 통해 설정을 생성하는 메서드이다.
+    ///
+    /// `LINK_PREVIEW_ENABLED`를 `false`로 지정하면 사용하지 않으며,
+    /// `LINK_PREVIEW_TIMEOUT_MS`로 주소 하나를 읽어오는 제한 시간을 바꿀 수 있다.
+    pub fn from_env() -> Self {
+        let timeout = env::var("LINK_PREVIEW_TIMEOUT_MS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .filter(|&value| value > 0)
+            .unwrap_or(DEFAULT_TIMEOUT_MS);
+        Self {
+            enabled: env::var("LINK_PREVIEW_ENABLED").as_deref() != Ok("false"),
+            // 리다이렉트는 이동할 주소를 확인한 뒤 직접 따라간다.
+            agent: ureq::Agent::with_parts(
+                ureq::Agent::config_builder()
+                    .timeout_global(Some(Duration::from_millis(timeout)))
+                    .http_status_as_error(false)
+                    .max_redirects(0)
+                    .max_redirects_will_error(false)
+                    .build(),
+                DefaultConnector::new(),
+                PublicResolver,
+            ),
+        }
+    }
+    /// 링크 미리보기를 사용하는지 확인하는 메서드이다.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+    /// 주소의 HTML 문서를 읽어와 링크 미리보기를 만드는 메서드이다. 읽어올 수 없는 경우 그 이유를 반환한다.
+    ///
+    /// 상대 주소로 된 이미지 주소는 리다이렉트를 따라간 뒤의 최종 주소를 기준으로 바꾼다.
+    pub fn fetch(&self, url: &str) -> std::result::Result<LinkPreview, String> {
+        let mut current = url.to_string();
+        for _ in 0..=MAX_REDIRECTS {
+            check_url(&current)?;
+            let mut response = self
+                .agent
+                .get(&current)
+                .header("Accept", "text/html")
+                .header("User-Agent", USER_AGENT)
+                .call()
+                .map_err(|error| error.to_string())?;
+            let status = response.status();
+            if status.is_redirection() {
+                let location = response
+                    .headers()
+                    .get("location")
+                    .and_then(|location| location.to_str().ok())
+                    .ok_or_else(|| format!("HTTP {}", status))?;
+                current = resolve(&current, location)
+                    .ok_or_else(|| "올바르지 않은 리다이렉트 주소".to_string())?;
+                continue;
+            }
+            if !status.is_success() {
+                return Err(format!("HTTP {}", status));
+            }
+            let is_html = response
+                .headers()
+                .get("content-type")
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.to_ascii_lowercase().starts_with("text/html"));
+            if !is_html {
+                return Err("HTML 문서가 아님".to_string());
+            }
+            let mut body = Vec::new();
+            response
+                .body_mut()
+                .as_reader()
+                .take(MAX_BODY_BYTES)
+                .read_to_end(&mut body)
+                .map_err(|error| error.to_string())?;
+            let mut preview = parse_html(&current, &String::from_utf8_lossy(&body));
+            preview.url = url.to_string();
+            return Ok(preview);
+        }
+        Err("리다이렉트가 너무 많음".to_string())
+    }
+    /// 주소들의 저장된 링크 미리보기를 주소 순서대로 반환하는 메서드이다.
+    ///
+    /// 저장되지 않았거나 오래된 미리보기는 별도의 스레드에서 읽어와 저장하므로 다음 조회부터 반환된다.
+    /// 읽어오지 못한 주소의 미리보기는 반환하지 않는다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get(&self, text: &str) -> Vec<LinkPreview> {
+        let urls = extract_urls(text);
+        if !self.enabled || urls.is_empty() {
+            return Vec::new();
+        }
+        let mut conn = db::get_read_conn();
+        let placeholders = vec!["?"; urls.len()].join(", ");
+        let rows: Vec<PreviewRow> = conn
+            .exec(
+                format!(
+                    r"select url, title, description, image,
+                    fetched_at < now() - interval {} day
+                    from link_preview where url in ({})",
+                    REFRESH_AFTER_DAYS, placeholders
+                ),
+                urls.clone(),
+            )
+            .unwrap();
+        let stale: Vec<String> = urls
+            .iter()
+            .filter(|url| {
+                rows.iter()
+                    .find(|row| &row.0 == *url)
+                    .is_none_or(|row| row.4)
+            })
+            .cloned()
+            .collect();
+        self.refresh(stale);
+        urls.iter()
+            .filter_map(|url| rows.iter().find(|row| &row.0 == url))
+            .map(|(url, title, description, image, _)| LinkPreview {
+                url: url.clone(),
+                title: title.clone(),
+                description: description.clone(),
+                image: image.clone(),
+            })
+            .filter(|preview| !preview.is_empty())
+            .collect()
+    }
+    /// 포스트 내용에 포함된 주소들 중 저장되지 않았거나 오래된 주소의 링크 미리보기를 별도의 스레드에서 읽어와 저장하는 메서드이다.
+    ///
+    /// 포스트를 저장한 뒤 호출하며, 링크 미리보기를 사용하지 않는 경우 아무 작업도 하지 않는다.
+    /// # 예제
+    /// ```ignore
+    /// link_previews.prefetch(&post.data);
+    /// ```
+    pub fn prefetch(&self, text: &str) {
+        let urls = extract_urls(text);
+        if !self.enabled || urls.is_empty() {
+            return;
+        }
+        let link_previews = self.clone();
+        rt::spawn(async move {
+            let result = web::block(move || -> Result<Vec<String>> {
+                let mut conn = db::get_read_conn();
+                let placeholders = vec!["?"; urls.len()].join(", ");
+                let fresh: Vec<String> = conn.exec(
+                    format!(
+                        r"select url from link_preview
+                        where url in ({}) and fetched_at >= now() - interval {} day",
+                        placeholders, REFRESH_AFTER_DAYS
+                    ),
+                    urls.clone(),
+                )?;
+                Ok(urls
+                    .into_iter()
+                    .filter(|url| !fresh.contains(url))
+                    .collect())
+            })
+            .await;
+            match result {
+                Ok(Ok(stale)) => link_previews.refresh(stale),
+                Ok(Err(error)) => {
+                    tracing::error!(%error, "저장된 링크 미리보기를 확인하지 못했습니다.")
+                }
+                Err(_) => tracing::error!("저장된 링크 미리보기를 확인하지 못했습니다."),
+            }
+        });
+    }
+    /// 주소들의 링크 미리보기를 별도의 스레드에서 읽어와 저장하는 메서드이다.
+    ///
+    /// 읽어오지 못한 주소도 다시 요청하지 않도록 내용이 비어있는 미리보기로 저장한다.
+    fn refresh(&self, urls: Vec<String>) {
+        if urls.is_empty() {
+            return;
+        }
+        let link_previews = self.clone();
+        rt::spawn(async move {
+            let result = web::block(move || -> Result<()> {
+                for url in urls {
+                    let preview = link_previews.fetch(&url).unwrap_or_else(|error| {
+                        tracing::info!(
+                            url = url.as_str(),
+                            error = error.as_str(),
+                            "링크 미리보기를 읽어오지 못했습니다."
+                        );
+                        LinkPreview {
+                            url: url.clone(),
+                            title: None,
+                            description: None,
+                            image: None,
+                        }
+                    });
+                    save(&preview)?;
+                }
+                Ok(())
+            })
+            .await;
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => {
+                    tracing::error!(%error, "링크 미리보기를 저장하지 못했습니다.")
+                }
+                Err(_) => tracing::error!("링크 미리보기를 읽어오지 못했습니다."),
+            }
+        });
+    }
+}
+
+/// 링크 미리보기를 저장하는 메서드이다. 이미 저장된 주소인 경우 새로 읽어온 내용으로 바꾼다.
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn save(preview: &LinkPreview) -> Result<()> {
+    let mut conn = db::get_conn();
+    conn.exec_drop(
+        r"insert into link_preview (url, title, description, image)
+        values (:url, :title, :description, :image)
+        on duplicate key update title = values(title), description = values(description),
+        image = values(image), fetched_at = current_timestamp",
+        params! {
+            "url" => &preview.url,
+            "title" => &preview.title,
+            "description" => &preview.description,
+            "image" => &preview.image,
+        },
+    )
+}
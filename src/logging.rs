@@ -0,0 +1,116 @@
+//! # 로그 기록 관련 동작을 정의하는 모듈
+//!
+//! `logging`은 코드뮤니티 서버의 로그를 `tracing`을 통해 일정한 형태로 기록하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 모든 요청은 처리가 끝난 뒤 메서드(`method`), 경로(`path`, `route`), 상태 코드(`status`),
+//! 처리 시간(`latency_ms`), 클라이언트의 IP 주소(`remote_ip`)와 User-Agent(`user_agent`),
+//! 인증된 사용자의 ID(`user_id`)와 함께 한 줄의 접근 로그로 기록된다.
+//! 요청 처리 중 기록된 로그에는 해당 요청의 ID(`request_id`)가 함께 기록된다.
+//! `LOG_FORMAT`을 `json`으로 지정하면 로그 수집기가 읽기 쉽도록 JSON 형태로 기록한다.
+
+use crate::reporting;
+use actix_web::dev::ServiceResponse;
+use actix_web::http::StatusCode;
+use actix_web::HttpMessage;
+use std::str::FromStr;
+use std::time::Instant;
+use tracing_subscriber::fmt;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::EnvFilter;
+
+/// 로그를 기록하는 형태이다.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// 사람이 읽기 쉬운 한 줄 형태
+    #[default]
+    Text,
+    /// 한 줄에 하나의 JSON 객체를 기록하는 형태
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    /// 설정 값을 로그 형태로 변환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::logging::LogFormat;
+    /// assert_eq!("json".parse(), Ok(LogFormat::Json));
+    /// assert!("xml".parse::<LogFormat>().is_err());
+    /// ```
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 요청을 보낸 인증된 사용자이다.
+///
+/// 인증에 성공한 요청의 확장 데이터에 저장되어 요청 로그에 사용자의 ID를 남길 때 사용된다.
+#[derive(Clone)]
+pub struct RequestUser(pub String);
+
+/// 로그 기록을 시작하는 메서드이다.
+///
+/// `level`은 `info`와 같은 로그 수준이나 `code_mmunity_server=debug,actix_web=warn`과 같은
+/// 모듈별 수준을 지정할 수 있으며, 올바르지 않은 경우 `info`를 사용한다.
+/// 오류 보고가 켜져있는 경우 기록된 로그는 `reporting::event_filter()`에 따라 함께 보고된다.
+/// 서버 시작 시 한 번만 호출해야 한다.
+pub fn init(level: &str, format: LogFormat) {
+    let filter = EnvFilter::try_new(level).unwrap_or_else(|_| EnvFilter::new("info"));
+    let output = match format {
+        LogFormat::Text => fmt::layer().boxed(),
+        LogFormat::Json => fmt::layer().json().flatten_event(true).boxed(),
+    };
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(output)
+        .with(sentry::integrations::tracing::layer().event_filter(reporting::event_filter))
+        .init();
+}
+
+/// 처리가 끝난 요청의 접근 로그를 한 줄로 기록하는 메서드이다.
+///
+/// 메서드, 경로, 상태 코드, 처리 시간, 클라이언트의 IP 주소와 User-Agent, 인증된 사용자의 ID가 기록된다.
+/// 서버 오류(5xx)는 `error`, 클라이언트 오류(4xx)와 준비되지 않은 상태(`503 Service Unavailable`)는 `warn`,
+/// 나머지는 `info` 수준으로 기록된다.
+pub fn log_request<B>(res: &ServiceResponse<B>, started: Instant) {
+    let req = res.request();
+    let route = req
+        .match_pattern()
+        .unwrap_or_else(|| req.path().to_string());
+    let method = req.method().as_str();
+    let path = req.path();
+    let status = res.status().as_u16();
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let connection_info = req.connection_info();
+    let remote_ip = connection_info.realip_remote_addr();
+    let user_agent = req
+        .headers()
+        .get("User-Agent")
+        .and_then(|value| value.to_str().ok());
+    let user_id = req
+        .extensions()
+        .get::<RequestUser>()
+        .map(|user| user.0.clone());
+    let user_id = user_id.as_deref();
+    macro_rules! access_log {
+        ($level:ident) => {
+            tracing::$level!(
+                method, path, route, status, latency_ms, remote_ip, user_agent, user_id, "access"
+            )
+        };
+    }
+    if res.status().is_server_error() && res.status() != StatusCode::SERVICE_UNAVAILABLE {
+        access_log!(error);
+    } else if res.status().is_client_error() || res.status().is_server_error() {
+        access_log!(warn);
+    } else {
+        access_log!(info);
+    }
+}
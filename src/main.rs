@@ -1,42 +1,622 @@
 use actix_cors::Cors;
-use actix_web::{App, HttpServer};
+use actix_files::Files;
+use actix_web::dev::{Service, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::http::KeepAlive;
+use actix_web::{web, App, HttpServer, ResponseError};
+use code_mmunity_server::activity;
+use code_mmunity_server::admin;
+use code_mmunity_server::analytics::Analytics;
+use code_mmunity_server::announcement;
+use code_mmunity_server::attachment;
+use code_mmunity_server::avatar;
+use code_mmunity_server::block;
+use code_mmunity_server::bookmark;
+use code_mmunity_server::cache::Cache;
+use code_mmunity_server::cleanup;
 use code_mmunity_server::comment;
-use code_mmunity_server::likes;
-use code_mmunity_server::post;
+use code_mmunity_server::config::{Config, DbDriver};
+use code_mmunity_server::db;
+use code_mmunity_server::email::Mailer;
+use code_mmunity_server::error::ApiError;
+use code_mmunity_server::events::{self, PostEvents};
+use code_mmunity_server::export;
+use code_mmunity_server::follow::{self, ImportThrottle};
+use code_mmunity_server::formatter::{self, Formatters};
+use code_mmunity_server::geo::RegionStats;
+use code_mmunity_server::graphql;
+use code_mmunity_server::health;
+use code_mmunity_server::idempotency::{self, Idempotency};
+use code_mmunity_server::integration;
+use code_mmunity_server::language::{self, LanguageStats};
+use code_mmunity_server::leaderboard::{self, Leaderboard};
+use code_mmunity_server::likes::{self, LikeThrottle};
+use code_mmunity_server::link_preview::LinkPreviews;
+use code_mmunity_server::logging;
+use code_mmunity_server::maintenance::Maintenance;
+use code_mmunity_server::message;
+use code_mmunity_server::migrate;
+use code_mmunity_server::moderation;
+use code_mmunity_server::outbox::Outbox;
+use code_mmunity_server::password;
+use code_mmunity_server::post::{self, Post};
+use code_mmunity_server::ranking;
+use code_mmunity_server::rate_limit::RateLimiter;
+use code_mmunity_server::reindex::Reindex;
+use code_mmunity_server::report;
+use code_mmunity_server::reporting;
+use code_mmunity_server::repository::Repositories;
+use code_mmunity_server::request_id;
+use code_mmunity_server::revision;
+use code_mmunity_server::sandbox::{self, Sandbox};
+use code_mmunity_server::scheduler::{Schedule, Scheduler};
+use code_mmunity_server::search;
+use code_mmunity_server::search_index::{self, SearchIndex};
+use code_mmunity_server::seed;
+use code_mmunity_server::session::{self, Session};
+use code_mmunity_server::shadow_ban::ShadowBans;
+use code_mmunity_server::shortlink;
+use code_mmunity_server::sitemap::{self, Sitemap};
+use code_mmunity_server::storage::Storage;
+use code_mmunity_server::subscription;
+use code_mmunity_server::summary;
+use code_mmunity_server::tag;
+use code_mmunity_server::tls;
+use code_mmunity_server::trending::{self, Trending};
+use code_mmunity_server::usage;
 use code_mmunity_server::user;
+use code_mmunity_server::user_names::UserNames;
+use code_mmunity_server::v1;
+use code_mmunity_server::verification;
+use code_mmunity_server::webhook;
+use code_mmunity_server::word_filter::WordFilter;
+use futures_util::future::{ready, Either};
+use futures_util::FutureExt;
 use std::env;
 use std::net::Ipv4Addr;
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::time::{Duration, Instant};
+use tracing::Instrument;
 
 /// 서버의 시작점이다.
 ///
-/// `APP_PORT` 환경변수를 지정하면 포트 번호 변경이 가능하다.
+/// `APP_PORT` 환경변수나 설정 파일의 `port`를 지정하면 포트 번호 변경이 가능하다.
 /// 포트 번호를 지정하지 않을 시 포트번호는 8080번으로 지정되어있다.
 /// `addr`을 통해 IP주소를 직접 전달하거나 LOCALHOST등으로 설정이 가능하다.
+/// `LISTEN_SOCKET`을 지정하면 TCP 포트 대신 해당 경로의 Unix 도메인 소켓으로 요청을 받는다.
+/// 서버 설정은 시작 시 한 번만 확인하며, 문제가 있는 경우 원인을 출력하고 바로 종료된다.
+/// 로그는 설정된 수준과 형태(`LOG_LEVEL`, `LOG_FORMAT`)로 기록된다.
+/// `migrate` 인자와 함께 실행하면 DB 스키마 마이그레이션만 적용한 뒤 종료된다.
+/// `seed [시드 값]` 인자와 함께 실행하면 데모 데이터를 생성한 뒤 종료된다.
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     let addr = Ipv4Addr::UNSPECIFIED;
-    let port = match env::var("APP_PORT") {
-        Ok(value) => value.parse().expect("APP_PORT가 숫자가 아닙니다."),
-        Err(_) => 8080,
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("서버 설정이 올바르지 않습니다: {}", message);
+            process::exit(1);
+        }
     };
-    println!("{}번 포트에서 서버가 작동됩니다.", port);
-    HttpServer::new(|| {
-        let cors = Cors::permissive();
+    logging::init(&config.log_level, config.log_format);
+    let _reporting = reporting::init(&config);
+    // DB 서버에 접속할 수 없어 발생한 패닉은 503으로 응답하고 db 모듈에서 따로 기록하므로 다시 출력하지 않는다.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if !info.payload().is::<db::Unavailable>() {
+            hook(info);
+        }
+    }));
+    db::init(config.db.clone());
+    language::init(config.languages.clone());
+    summary::init(config.summary_length);
+    let migrate_only = env::args().nth(1).as_deref() == Some("migrate");
+    if migrate_only || config.db.migrate_on_start {
+        match migrate::run() {
+            Ok(versions) if versions.is_empty() => tracing::info!("DB 스키마가 최신 상태입니다."),
+            Ok(versions) => tracing::info!("{:?}번 마이그레이션이 적용되었습니다.", versions),
+            Err(error) => {
+                tracing::error!("DB 스키마 마이그레이션에 실패했습니다: {}", error);
+                process::exit(1);
+            }
+        }
+        if migrate_only {
+            return Ok(());
+        }
+    }
+    if env::args().nth(1).as_deref() == Some("seed") {
+        let value = env::args()
+            .nth(2)
+            .map(|value| value.parse())
+            .unwrap_or(Ok(seed::DEFAULT_SEED));
+        let Ok(value) = value else {
+            tracing::error!("시드 값은 0 이상의 정수여야 합니다.");
+            process::exit(1);
+        };
+        match seed::run(value) {
+            Ok(summary) => tracing::info!(
+                "사용자 {}명, 포스트 {}개, 댓글 {}개, 공감 {}개가 생성되었습니다.",
+                summary.users,
+                summary.posts,
+                summary.comments,
+                summary.likes
+            ),
+            Err(error) => {
+                tracing::error!("데모 데이터 생성에 실패했습니다: {}", error);
+                process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    if config.db.auto_create {
+        match migrate::bootstrap() {
+            Ok(versions) if versions.is_empty() => {}
+            Ok(versions) => tracing::info!("{:?}번 마이그레이션이 적용되었습니다.", versions),
+            Err(error) => {
+                tracing::error!("DB 테이블 생성에 실패했습니다: {}", error);
+                process::exit(1);
+            }
+        }
+    }
+    let port = config.port;
+    let tls = match config.tls.as_ref().map(tls::server_config).transpose() {
+        Ok(tls) => tls,
+        Err(message) => {
+            tracing::error!("HTTPS 설정이 올바르지 않습니다: {}", message);
+            process::exit(1);
+        }
+    };
+    let cache = match Cache::new(config.cache.as_ref()) {
+        Ok(cache) => cache,
+        Err(message) => {
+            tracing::error!("캐시 설정이 올바르지 않습니다: {}", message);
+            process::exit(1);
+        }
+    };
+    let listen_socket = config.listen_socket.clone();
+    let runtime = config.server.clone();
+    match &listen_socket {
+        Some(path) => tracing::info!("{}에서 서버가 작동됩니다.", path.display()),
+        None if tls.is_some() => tracing::info!("{}번 포트에서 HTTPS 서버가 작동됩니다.", port),
+        None => tracing::info!("{}번 포트에서 서버가 작동됩니다.", port),
+    }
+    let repositories = match Repositories::open(&config.db) {
+        Ok(repositories) => repositories,
+        Err(message) => {
+            tracing::error!("저장소를 열 수 없습니다: {}", message);
+            process::exit(1);
+        }
+    };
+    let use_mysql = config.db.driver == DbDriver::MySql;
+    let config = web::Data::new(config);
+    let region_stats = web::Data::new(RegionStats::from_env());
+    let post_events = web::Data::new(PostEvents::new());
+    let storage = web::Data::new(Storage::from_env());
+    let leaderboard = web::Data::new(Leaderboard::default());
+    let language_stats = web::Data::new(LanguageStats::default());
+    let analytics = web::Data::new(Analytics::default());
+    let import_throttle = web::Data::new(ImportThrottle::default());
+    let maintenance = web::Data::new(Maintenance::default());
+    let mut outbox = Outbox::from_env();
+    if let Some(mailer) = Mailer::from_env() {
+        outbox.register(Box::new(mailer));
+    }
+    let outbox = web::Data::new(outbox);
+    let ranker = web::Data::from(ranking::from_env());
+    let reindex = web::Data::new(Reindex::default());
+    let rate_limiter = web::Data::new(RateLimiter::from_env());
+    let like_throttle = web::Data::new(LikeThrottle::from_env());
+    let idempotency_keys = web::Data::new(Idempotency::new());
+    let word_filter = web::Data::new(WordFilter::from_env());
+    let shadow_bans = web::Data::new(ShadowBans::new());
+    let sitemap = web::Data::new(Sitemap::default());
+    let moderator = web::Data::from(moderation::from_env());
+    let formatters = web::Data::new(Formatters::from_env());
+    let sandbox = web::Data::new(Sandbox::from_env());
+    // 링크 미리보기는 MySQL의 `link_preview` 테이블에 저장한다.
+    let link_previews = web::Data::new(if use_mysql {
+        LinkPreviews::from_env()
+    } else {
+        LinkPreviews::disabled()
+    });
+    // 검색 색인은 MySQL에 저장된 포스트를 기준으로 갱신한다.
+    let search_index = web::Data::new(if use_mysql {
+        SearchIndex::from_env()
+    } else {
+        SearchIndex::disabled()
+    });
+    if use_mysql {
+        match word_filter.load() {
+            Ok(count) => tracing::info!(count, "금칙어를 읽어왔습니다."),
+            Err(error) => tracing::error!(%error, "금칙어를 읽어오지 못했습니다."),
+        }
+        match shadow_bans.load() {
+            Ok(count) => tracing::info!(count, "섀도 밴된 사용자 목록을 읽어왔습니다."),
+            Err(error) => tracing::error!(%error, "섀도 밴된 사용자 목록을 읽어오지 못했습니다."),
+        }
+        match sitemap.regenerate() {
+            Ok(count) => tracing::info!(count, "사이트맵을 만들었습니다."),
+            Err(error) => tracing::error!(%error, "사이트맵을 만들지 못했습니다."),
+        }
+    }
+    let cache = web::Data::new(cache);
+    let post_repository = web::Data::from(repositories.posts);
+    let user_repository = web::Data::from(repositories.users);
+    let comment_repository = web::Data::from(repositories.comments);
+    let user_names = web::Data::from(UserNames::shared());
+    let graphql_schema = web::Data::new(graphql::schema(
+        cache.clone(),
+        post_events.clone(),
+        like_throttle.clone(),
+        ranker.clone(),
+        outbox.clone(),
+        word_filter.clone(),
+        moderator.clone(),
+        user_repository.clone(),
+        shadow_bans.clone(),
+        search_index.clone(),
+    ));
+    let mut scheduler = Scheduler::default();
+    {
+        let outbox = outbox.clone();
+        scheduler.add(
+            "outbox-replay",
+            Schedule::Every(Duration::from_secs(60)),
+            move || outbox.replay(),
+        );
+    }
+    {
+        let search_index = search_index.clone();
+        scheduler.add(
+            "publish-scheduled-posts",
+            Schedule::Every(Duration::from_secs(60)),
+            move || Post::publish_scheduled(&search_index),
+        );
+    }
+    scheduler.add(
+        "purge-deleted-posts",
+        Schedule::Every(Duration::from_secs(3600)),
+        || {
+            if let Err(error) = Post::purge_deleted() {
+                tracing::error!(%error, "삭제된 포스트를 제거하지 못했습니다.");
+            }
+        },
+    );
+    scheduler.add(
+        "purge-expired-sessions",
+        Schedule::Every(Duration::from_secs(3600)),
+        || {
+            if let Err(error) = Session::purge_expired() {
+                tracing::error!(%error, "만료된 세션을 지우지 못했습니다.");
+            }
+        },
+    );
+    scheduler.add(
+        "purge-expired-password-resets",
+        Schedule::Every(Duration::from_secs(3600)),
+        || {
+            if let Err(error) = password::PasswordAccount::purge_expired_resets() {
+                tracing::error!(%error, "만료된 비밀번호 재설정 토큰을 지우지 못했습니다.");
+            }
+        },
+    );
+    {
+        let sitemap = sitemap.clone();
+        scheduler.add(
+            "regenerate-sitemap",
+            Schedule::Every(sitemap::interval()),
+            move || {
+                if let Err(error) = sitemap.regenerate() {
+                    tracing::error!(%error, "사이트맵을 만들지 못했습니다.");
+                }
+            },
+        );
+    }
+    scheduler.add(
+        "orphan-cleanup",
+        Schedule::Every(Duration::from_secs(3600)),
+        || match cleanup::run() {
+            Ok(removed) => {
+                for removed in removed {
+                    tracing::info!(
+                        table = removed.table,
+                        column = removed.column,
+                        rows = removed.rows,
+                        "부모 데이터가 없는 행을 지웠습니다."
+                    );
+                }
+            }
+            Err(error) => tracing::error!(%error, "고아 데이터를 정리하지 못했습니다."),
+        },
+    );
+    scheduler.add(
+        "webhook-delivery",
+        Schedule::Every(Duration::from_secs(10)),
+        webhook::deliver_pending,
+    );
+    scheduler.add(
+        "trending-refresh",
+        Schedule::Every(Trending::refresh_interval()),
+        Trending::refresh,
+    );
+    if let Some(hour) = Maintenance::scheduled_hour() {
+        let maintenance = maintenance.clone();
+        scheduler.add("db-maintenance", Schedule::DailyAt(hour), move || {
+            maintenance.run()
+        });
+    }
+    // SQLite를 사용하는 경우 예약 작업들이 사용하는 MySQL 서버가 없으므로 시작하지 않는다.
+    if use_mysql {
+        scheduler.start();
+    }
+    let mut server = HttpServer::new(move || {
+        let cors = if config.cors_origins.is_empty() {
+            Cors::permissive()
+        } else {
+            config
+                .cors_origins
+                .iter()
+                .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+                .allow_any_method()
+                .allow_any_header()
+                .supports_credentials()
+                .expose_headers([request_id::HEADER, idempotency::REPLAYED_HEADER])
+        };
+        let stats = region_stats.clone();
+        let max_json_bytes = config.max_json_bytes;
+        let limiter = rate_limiter.clone();
         App::new()
+            .app_data(config.clone())
+            .app_data(region_stats.clone())
+            .app_data(post_events.clone())
+            .app_data(storage.clone())
+            .app_data(leaderboard.clone())
+            .app_data(language_stats.clone())
+            .app_data(analytics.clone())
+            .app_data(import_throttle.clone())
+            .app_data(maintenance.clone())
+            .app_data(outbox.clone())
+            .app_data(ranker.clone())
+            .app_data(reindex.clone())
+            .app_data(like_throttle.clone())
+            .app_data(idempotency_keys.clone())
+            .app_data(word_filter.clone())
+            .app_data(shadow_bans.clone())
+            .app_data(sitemap.clone())
+            .app_data(moderator.clone())
+            .app_data(formatters.clone())
+            .app_data(sandbox.clone())
+            .app_data(link_previews.clone())
+            .app_data(search_index.clone())
+            .app_data(cache.clone())
+            .app_data(user_names.clone())
+            .app_data(post_repository.clone())
+            .app_data(user_repository.clone())
+            .app_data(comment_repository.clone())
+            .app_data(graphql_schema.clone())
+            .app_data(
+                web::JsonConfig::default()
+                    .limit(max_json_bytes)
+                    .error_handler(move |error, _| {
+                        ApiError::from_json_error(error, max_json_bytes).into()
+                    }),
+            )
+            .app_data(
+                web::QueryConfig::default()
+                    .error_handler(|error, _| ApiError::BadRequest(error.to_string()).into()),
+            )
+            .app_data(
+                web::PathConfig::default()
+                    .error_handler(|error, _| ApiError::BadRequest(error.to_string()).into()),
+            )
+            .wrap_fn(|req, srv| {
+                // DB 서버에 접속할 수 없어 발생한 패닉은 워커를 멈추지 않고 503 응답으로 바꾼다.
+                let request = req.request().clone();
+                let response = AssertUnwindSafe(srv.call(req)).catch_unwind();
+                async move {
+                    match response.await {
+                        Ok(response) => response,
+                        Err(payload) => match payload.downcast::<db::Unavailable>() {
+                            Ok(unavailable) => Ok(ServiceResponse::new(
+                                request,
+                                ApiError::DbUnavailable(unavailable.retry_after).error_response(),
+                            )),
+                            Err(payload) => panic::resume_unwind(payload),
+                        },
+                    }
+                }
+            })
+            .wrap_fn(move |req, srv| {
+                stats.record(&req);
+                srv.call(req)
+            })
+            .wrap_fn(move |req, srv| match limiter.check(&req) {
+                Some(wait) => {
+                    let response = req.error_response(ApiError::TooManyRequests(wait));
+                    Either::Left(ready(Ok(response.map_into_right_body())))
+                }
+                None => {
+                    let response = srv.call(req);
+                    Either::Right(
+                        async move { response.await.map(ServiceResponse::map_into_left_body) },
+                    )
+                }
+            })
+            .wrap_fn(|req, srv| {
+                let started = Instant::now();
+                let id = request_id::resolve(
+                    req.headers()
+                        .get(request_id::HEADER)
+                        .and_then(|value| value.to_str().ok()),
+                );
+                let span = tracing::info_span!("request", request_id = id.as_str());
+                let response =
+                    request_id::sync_scope(id.clone(), || span.in_scope(|| srv.call(req)));
+                request_id::scope(
+                    id.clone(),
+                    async move {
+                        let mut response = response.await?;
+                        logging::log_request(&response, started);
+                        if let Ok(value) = HeaderValue::from_str(&id) {
+                            response
+                                .headers_mut()
+                                .insert(HeaderName::from_static("x-request-id"), value);
+                        }
+                        Ok(response)
+                    }
+                    .instrument(span),
+                )
+            })
             .wrap(cors)
+            .service(health::ready_api)
             .service(user::new_user_api)
             .service(post::get_posts_api)
+            .service(trending::get_trending_api)
+            .service(post::count_posts_api)
+            .service(search_index::search_posts_api)
             .service(post::get_post_api)
+            .service(events::post_stream_api)
+            .service(shortlink::create_shortlink_api)
+            .service(shortlink::share_post_api)
+            .service(shortlink::redirect_shortlink_api)
+            .service(sitemap::get_sitemap_api)
             .service(user::get_user_api)
-            .service(user::update_user_api)
             .service(user::delete_user_api)
+            .service(post::update_post_api)
+            .service(post::accept_answer_api)
+            .service(post::accept_comment_api)
+            .service(post::unaccept_answer_api)
+            .service(revision::get_revisions_api)
+            .service(revision::get_revision_diff_api)
             .service(post::delete_post_api)
+            .service(post::restore_post_api)
             .service(likes::modify_likes_api)
             .service(post::insert_post_api)
+            .service(integration::create_bot_post_api)
             .service(comment::get_comment_api)
+            .service(comment::count_comments_api)
             .service(comment::insert_comment_api)
-    })
-    .bind((addr, port))?
-    .run()
-    .await
+            .service(comment::update_comment_api)
+            .service(comment::delete_comment_api)
+            .service(report::report_post_api)
+            .service(report::report_comment_api)
+            .service(graphql::graphql_api)
+            .service(user::update_profile_api)
+            .service(user::replace_profile_api)
+            .service(user::deactivate_user_api)
+            .service(user::reactivate_user_api)
+            .service(session::create_session_api)
+            .service(session::refresh_session_api)
+            .service(session::delete_session_api)
+            .service(password::register_api)
+            .service(password::login_api)
+            .service(password::forgot_api)
+            .service(password::reset_api)
+            .service(verification::verify_email_api)
+            .service(verification::resend_verification_api)
+            .service(user::update_privacy_api)
+            .service(user::get_notifications_api)
+            .service(user::update_notifications_api)
+            .service(likes::get_user_likes_api)
+            .service(activity::get_user_activity_api)
+            .service(likes::get_post_likes_api)
+            .service(likes::get_post_likers_api)
+            .service(bookmark::add_bookmark_api)
+            .service(bookmark::remove_bookmark_api)
+            .service(bookmark::get_bookmarks_api)
+            .service(leaderboard::get_leaderboard_api)
+            .service(export::export_user_api)
+            .service(follow::follow_user_api)
+            .service(follow::unfollow_user_api)
+            .service(block::block_user_api)
+            .service(block::unblock_user_api)
+            .service(message::send_message_api)
+            .service(message::get_conversations_api)
+            .service(message::count_unread_api)
+            .service(message::get_messages_api)
+            .service(follow::get_feed_api)
+            .service(subscription::get_personalized_feed_api)
+            .service(subscription::get_subscriptions_api)
+            .service(subscription::subscribe_api)
+            .service(subscription::unsubscribe_api)
+            .service(search::suggest_api)
+            .service(tag::get_tags_api)
+            .service(tag::get_trending_tags_api)
+            .service(language::get_languages_api)
+            .service(language::get_language_stats_api)
+            .service(formatter::format_code_api)
+            .service(sandbox::run_post_api)
+            .service(follow::export_following_api)
+            .service(follow::import_following_api)
+            .service(avatar::upload_avatar_api)
+            .service(attachment::upload_attachment_api)
+            .service(usage::get_usage_api)
+            .service(announcement::get_announcements_api)
+            .service(admin::ban_user_api)
+            .service(admin::unban_user_api)
+            .service(admin::get_shadow_bans_api)
+            .service(admin::shadow_ban_user_api)
+            .service(admin::unshadow_ban_user_api)
+            .service(admin::get_region_stats_api)
+            .service(admin::get_database_stats_api)
+            .service(admin::get_analytics_api)
+            .service(admin::export_backup_api)
+            .service(admin::start_reindex_api)
+            .service(admin::get_reindex_status_api)
+            .service(admin::import_posts_api)
+            .service(admin::restore_backup_api)
+            .service(admin::register_webhook_api)
+            .service(admin::get_webhooks_api)
+            .service(admin::delete_webhook_api)
+            .service(admin::get_webhook_deliveries_api)
+            .service(admin::create_announcement_api)
+            .service(admin::get_all_announcements_api)
+            .service(admin::delete_announcement_api)
+            .service(admin::get_word_filter_api)
+            .service(admin::add_blocked_word_api)
+            .service(admin::remove_blocked_word_api)
+            .service(admin::get_flagged_content_api)
+            .service(admin::set_post_verdict_api)
+            .service(admin::set_comment_verdict_api)
+            .service(admin::get_comment_queue_api)
+            .service(admin::get_moderation_queue_api)
+            .service(admin::resolve_queued_post_api)
+            .service(admin::resolve_queued_comment_api)
+            .configure(v1::configure)
+            .configure(|cfg| {
+                if Storage::is_local() {
+                    cfg.service(Files::new("/uploads", Storage::local_dir()));
+                }
+            })
+    });
+    if let Some(workers) = runtime.workers {
+        server = server.workers(workers);
+    }
+    if let Some(keep_alive) = runtime.keep_alive {
+        server = server.keep_alive(if keep_alive.is_zero() {
+            KeepAlive::Disabled
+        } else {
+            KeepAlive::Timeout(keep_alive)
+        });
+    }
+    if let Some(timeout) = runtime.client_timeout {
+        server = server.client_request_timeout(timeout);
+    }
+    if let Some(max_connections) = runtime.max_connections {
+        server = server.max_connections(max_connections);
+    }
+    let server = match (tls, listen_socket) {
+        // `Config::load()`에서 걸러지지만, 한쪽 설정이 조용히 무시되지 않도록 여기서도 거부한다.
+        (Some(_), Some(_)) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "LISTEN_SOCKET과 TLS_CERT/TLS_KEY는 함께 설정할 수 없습니다.",
+            ))
+        }
+        (Some(tls), None) => server.bind_rustls((addr, port), tls)?,
+        (None, Some(path)) => server.bind_uds(path)?,
+        (None, None) => server.bind((addr, port))?,
+    };
+    server.run().await
 }
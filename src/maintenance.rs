@@ -0,0 +1,149 @@
+//! # DB 관리 작업 관련 동작을 정의하는 모듈
+//!
+//! `maintenance`는 코드뮤니티에서 사용하는 DB 테이블의 통계를 갱신하고,
+//! 테이블 크기와 인덱스 상태를 집계하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! `DB_MAINTENANCE_HOUR` 환경변수가 지정된 경우 매일 해당 시각(UTC)에 `scheduler`를 통해 실행되며,
+//! 마지막 실행 결과는 관리자 API를 통해 확인할 수 있다.
+
+use crate::db;
+use mysql::prelude::*;
+use serde::Serialize;
+use std::env;
+use std::sync::Mutex;
+
+/// 테이블의 여유 공간이 전체 크기에서 이 비율을 넘으면 단편화된 것으로 본다.
+const FRAGMENTATION_RATIO: f64 = 0.2;
+
+/// 테이블 하나의 크기 정보이다.
+#[derive(Serialize, Clone)]
+pub struct TableStats {
+    /// 테이블 이름이다.
+    pub table: String,
+    /// 테이블에 저장된 행의 수(추정치)이다.
+    pub rows: u64,
+    /// 데이터가 차지하는 용량(바이트)이다.
+    pub data_bytes: u64,
+    /// 인덱스가 차지하는 용량(바이트)이다.
+    pub index_bytes: u64,
+    /// 할당되었지만 사용되지 않는 용량(바이트)이다.
+    pub free_bytes: u64,
+    /// 여유 공간이 많아 `OPTIMIZE`가 필요한지 여부이다.
+    pub fragmented: bool,
+}
+
+/// DB 관리 작업의 실행 결과이다.
+#[derive(Serialize, Clone)]
+pub struct MaintenanceReport {
+    /// 작업이 끝난 시각이다.
+    pub finished_at: String,
+    /// `ANALYZE`를 수행한 테이블들이다.
+    pub analyzed: Vec<String>,
+    /// `OPTIMIZE`를 수행한 테이블들이다.
+    pub optimized: Vec<String>,
+    /// 작업이 끝난 뒤의 테이블 크기 정보이다.
+    pub tables: Vec<TableStats>,
+    /// 통계가 없어 `ANALYZE`가 필요한 인덱스들이다. `테이블.인덱스` 형식으로 표시된다.
+    pub stale_indexes: Vec<String>,
+}
+
+/// DB 관리 작업을 실행하고 마지막 실행 결과를 저장하는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 작업 스레드와 관리자 API가 공유하여 사용한다.
+#[derive(Default)]
+pub struct Maintenance {
+    last_report: Mutex<Option<MaintenanceReport>>,
+}
+
+impl Maintenance {
+    /// DB 관리 작업을 실행할 시각(UTC 기준 0~23시)을 반환하는 메서드이다.
+    ///
+    /// `DB_MAINTENANCE_HOUR` 환경변수가 지정되지 않았거나 올바른 값이 아닌 경우 `None`을 반환하며,
+    /// 이 경우 DB 관리 작업은 실행되지 않는다.
+    pub fn scheduled_hour() -> Option<u64> {
+        env::var("DB_MAINTENANCE_HOUR")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .filter(|hour| *hour < 24)
+    }
+    /// 마지막 실행 결과를 반환하는 메서드이다. 아직 실행된 적이 없는 경우 `None`을 반환한다.
+    pub fn last_report(&self) -> Option<MaintenanceReport> {
+        self.last_report.lock().unwrap().clone()
+    }
+    /// DB 관리 작업을 실행하는 메서드이다.
+    ///
+    /// 모든 테이블에 `ANALYZE`를 수행하고, `DB_MAINTENANCE_OPTIMIZE`가 `true`인 경우
+    /// 단편화된 테이블에 `OPTIMIZE`도 수행한다. 실행 결과는 `last_report()`로 확인할 수 있다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn run(&self) {
+        let mut conn = db::get_conn();
+        let tables: Vec<String> = conn
+            .query(
+                r"select table_name from information_schema.tables
+                where table_schema = database() and table_type = 'BASE TABLE'
+                order by table_name",
+            )
+            .unwrap();
+        for table in &tables {
+            conn.query_drop(format!("analyze table `{}`", table))
+                .unwrap();
+        }
+        let mut optimized = Vec::new();
+        if env::var("DB_MAINTENANCE_OPTIMIZE").as_deref() == Ok("true") {
+            for stats in Self::table_stats(&mut conn) {
+                if stats.fragmented {
+                    conn.query_drop(format!("optimize table `{}`", stats.table))
+                        .unwrap();
+                    optimized.push(stats.table);
+                }
+            }
+        }
+        let stale_indexes = conn
+            .query_map(
+                r"select distinct table_name, index_name from information_schema.statistics
+                where table_schema = database() and cardinality is null
+                order by table_name, index_name",
+                |(table, index): (String, String)| format!("{}.{}", table, index),
+            )
+            .unwrap();
+        let report = MaintenanceReport {
+            finished_at: conn
+                .query_first("select date_format(now(), '%Y-%m-%d %H:%i:%s')")
+                .unwrap()
+                .unwrap_or_default(),
+            analyzed: tables,
+            optimized,
+            tables: Self::table_stats(&mut conn),
+            stale_indexes,
+        };
+        *self.last_report.lock().unwrap() = Some(report);
+    }
+    /// 모든 테이블의 크기 정보를 반환하는 메서드이다.
+    fn table_stats(conn: &mut mysql::PooledConn) -> Vec<TableStats> {
+        conn.query_map(
+            r"select table_name, coalesce(table_rows, 0), coalesce(data_length, 0),
+            coalesce(index_length, 0), coalesce(data_free, 0)
+            from information_schema.tables
+            where table_schema = database() and table_type = 'BASE TABLE'
+            order by table_name",
+            |(table, rows, data_bytes, index_bytes, free_bytes): (String, u64, u64, u64, u64)| {
+                TableStats {
+                    fragmented: free_bytes as f64
+                        > (data_bytes + index_bytes) as f64 * FRAGMENTATION_RATIO,
+                    table,
+                    rows,
+                    data_bytes,
+                    index_bytes,
+                    free_bytes,
+                }
+            },
+        )
+        .unwrap()
+    }
+}
@@ -0,0 +1,361 @@
+//! # 메시지 관련 동작을 정의하는 모듈
+//!
+//! `message`는 코드뮤니티에서 두 사용자가 서로 메시지를 주고받기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 두 사용자 사이의 메시지는 하나의 대화(`conversation`)로 묶이며, 처음 메시지를 보낼 때 대화가 만들어진다.
+//! 대화의 메시지를 조회하면 상대방이 보낸 메시지를 읽은 것으로 표시하고, 대화 목록에서 읽지 않은 메시지 수를 확인할 수 있다.
+//! 메시지를 받은 사용자에게는 알림 설정에 따라 메일로 알린다.
+
+use crate::auth::Identity;
+use crate::db;
+use crate::email;
+use crate::error::ApiError;
+use crate::outbox::Outbox;
+use crate::pagination::{Count, Cursor, CursorPage, Pagination};
+use crate::repository::UserRepository;
+use crate::response;
+use crate::sanitize;
+use crate::user::User;
+use crate::validation::{is_user_id, FieldErrors, MAX_MESSAGE_CHARS};
+use actix_web::web::Json;
+use actix_web::{get, post, web, HttpResponse};
+use chrono::{DateTime, Utc};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+
+/// 두 사용자가 주고받은 메시지 하나이다.
+#[derive(Serialize)]
+pub struct Message {
+    /// 메시지의 고유 ID이다.
+    pub message_id: u64,
+    /// 메시지가 속한 대화의 고유 ID이다.
+    pub conversation_id: u64,
+    /// 메시지를 보낸 사용자의 고유 ID이다.
+    pub sender_id: String,
+    /// 메시지의 내용이다.
+    #[serde(with = "sanitize::escaped")]
+    pub data: String,
+    /// 메시지를 보낸 날짜 및 시간이다.
+    pub create_at: DateTime<Utc>,
+    /// 받은 사용자가 메시지를 읽은 날짜 및 시간이다. 읽지 않은 경우 `None`이다.
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+/// 요청한 사용자가 참여한 대화 하나이다.
+#[derive(Serialize)]
+pub struct Conversation {
+    /// 대화의 고유 ID이다.
+    pub conversation_id: u64,
+    /// 대화 상대의 고유 ID이다.
+    pub user_id: String,
+    /// 대화 상대의 이름이다.
+    pub user_name: String,
+    /// 대화에 마지막으로 메시지가 오간 날짜 및 시간이다.
+    pub updated_at: DateTime<Utc>,
+    /// 상대방이 보낸 메시지 중 읽지 않은 메시지 수이다.
+    pub unread: u64,
+}
+
+/// JSON 을 통해 보낼 메시지를 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct MessageRequest {
+    /// 메시지를 받을 사용자의 고유 ID이다.
+    pub recipient_id: String,
+    /// 메시지의 내용이다.
+    pub data: String,
+}
+
+impl MessageRequest {
+    /// 보낼 메시지의 내용이 조건에 맞는지 확인하는 메서드이다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::message::MessageRequest;
+    /// let request = MessageRequest { recipient_id: "u2".to_string(), data: " ".to_string() };
+    /// assert!(request.validate().is_err());
+    /// let request = MessageRequest { recipient_id: "u2".to_string(), data: "안녕하세요".to_string() };
+    /// assert!(request.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), ApiError> {
+        let mut errors = FieldErrors::default();
+        errors.check(
+            "recipient_id",
+            is_user_id(&self.recipient_id),
+            "사용자 ID 형식이 올바르지 않습니다.",
+        );
+        errors.check_data(&self.data, MAX_MESSAGE_CHARS);
+        errors.into_result()
+    }
+}
+
+impl Message {
+    /// `sender_id` 사용자가 `recipient_id` 사용자에게 메시지를 보내고, 보낸 메시지를 반환하는 메서드이다.
+    ///
+    /// 두 사용자 사이의 대화가 없는 경우 새로 만든다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn send(sender_id: &str, recipient_id: &str, data: String) -> Result<Self> {
+        let (user_a, user_b) = if sender_id < recipient_id {
+            (sender_id, recipient_id)
+        } else {
+            (recipient_id, sender_id)
+        };
+        db::transaction(|tx| {
+            tx.exec_drop(
+                r"insert into conversation(user_a, user_b) values(:user_a, :user_b)
+                on duplicate key update updated_at = now()",
+                params! {
+                    "user_a" => user_a,
+                    "user_b" => user_b,
+                },
+            )?;
+            let conversation_id: u64 = tx
+                .exec_first(
+                    "select conversation_id from conversation where user_a = :user_a and user_b = :user_b",
+                    params! {
+                        "user_a" => user_a,
+                        "user_b" => user_b,
+                    },
+                )?
+                .unwrap_or_default();
+            tx.exec_drop(
+                r"insert into message(conversation_id, sender_id, data)
+                values(:conversation_id, :sender_id, :data)",
+                params! {
+                    "conversation_id" => conversation_id,
+                    "sender_id" => sender_id,
+                    "data" => &data,
+                },
+            )?;
+            Ok(Self {
+                message_id: tx.last_insert_id().unwrap_or_default(),
+                conversation_id,
+                sender_id: sender_id.to_string(),
+                data,
+                create_at: Utc::now(),
+                read_at: None,
+            })
+        })
+    }
+    /// 대화의 메시지들을 커서 방식으로 최신순으로 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_after(conversation_id: u64, cursor: &Cursor) -> Vec<Self> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select message_id, conversation_id, sender_id, data,
+            date_format(create_at, '%Y-%m-%d %H:%i:%s'), date_format(read_at, '%Y-%m-%d %H:%i:%s')
+            from message where conversation_id = :conversation_id
+            and (:after is null or message_id < :after)
+            order by message_id desc limit :limit",
+            params! {
+                "conversation_id" => conversation_id,
+                "after" => cursor.after,
+                "limit" => cursor.limit(),
+            },
+            |(message_id, conversation_id, sender_id, data, create_at, read_at): (
+                u64,
+                u64,
+                String,
+                String,
+                String,
+                Option<String>,
+            )| Self {
+                message_id,
+                conversation_id,
+                sender_id,
+                data,
+                create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+                read_at: read_at.as_deref().and_then(db::parse_datetime),
+            },
+        )
+        .unwrap()
+    }
+    /// 대화에서 상대방이 보낸 메시지들을 `reader_id` 사용자가 읽은 것으로 표시하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn mark_read(conversation_id: u64, reader_id: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"update message set read_at = now()
+            where conversation_id = :conversation_id and sender_id <> :reader_id and read_at is null",
+            params! {
+                "conversation_id" => conversation_id,
+                "reader_id" => reader_id,
+            },
+        )
+    }
+    /// 사용자가 참여한 모든 대화에서 읽지 않은 메시지 수를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn count_unread(user_id: &str) -> u64 {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            r"select count(*) from message join conversation on message.conversation_id = conversation.conversation_id
+            where (conversation.user_a = :user_id or conversation.user_b = :user_id)
+            and message.sender_id <> :user_id and message.read_at is null",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+        .unwrap_or_default()
+    }
+}
+
+impl Conversation {
+    /// 사용자가 참여한 대화들을 마지막으로 메시지가 오간 순서로 반환하는 메서드이다.
+    ///
+    /// `page`를 통해 반환할 페이지를 지정할 수 있다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_list(user_id: &str, page: &Pagination) -> Vec<Self> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select conversation_id, if(user_a = :user_id, user_b, user_a),
+            date_format(updated_at, '%Y-%m-%d %H:%i:%s'),
+            (select count(*) from message where message.conversation_id = conversation.conversation_id
+                and message.sender_id <> :user_id and message.read_at is null)
+            from conversation where user_a = :user_id or user_b = :user_id
+            order by updated_at desc, conversation_id desc
+            limit :limit offset :offset",
+            params! {
+                "user_id" => user_id,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(conversation_id, other_id, updated_at, unread): (u64, String, String, u64)| Self {
+                conversation_id,
+                user_name: User::get_user_name(&other_id).unwrap_or_default(),
+                user_id: other_id,
+                updated_at: db::parse_datetime(&updated_at).unwrap_or_default(),
+                unread,
+            },
+        )
+        .unwrap()
+    }
+    /// 대화에 참여한 두 사용자의 고유 ID를 반환하는 메서드이다. 존재하지 않는 대화의 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_participants(conversation_id: u64) -> Option<(String, String)> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            "select user_a, user_b from conversation where conversation_id = :conversation_id",
+            params! {
+                "conversation_id" => conversation_id,
+            },
+        )
+        .unwrap()
+    }
+}
+
+#[post("/api/messages")]
+pub async fn send_message_api(
+    identity: Identity,
+    request: Json<MessageRequest>,
+    outbox: web::Data<Outbox>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let mut request = request.into_inner();
+    sanitize::clean(&mut request.data);
+    request.validate()?;
+    if request.recipient_id == identity.user_id {
+        return Err(ApiError::BadRequest(
+            "자기 자신에게는 메시지를 보낼 수 없습니다.".to_string(),
+        ));
+    }
+    if User::is_restricted(&identity.user_id)? {
+        return Err(ApiError::Forbidden);
+    }
+    if users.get(&request.recipient_id).is_none() || users.is_deactivated(&request.recipient_id) {
+        return Err(ApiError::NotFound(
+            "요청한 recipient_id는 존재하지 않는 사용자입니다.".to_string(),
+        ));
+    }
+    if users
+        .get_blocked(&request.recipient_id)
+        .contains(&identity.user_id)
+    {
+        return Err(ApiError::Forbidden);
+    }
+    let message = Message::send(&identity.user_id, &request.recipient_id, request.data)?;
+    email::notify_message(
+        outbox,
+        message.conversation_id,
+        request.recipient_id,
+        message.sender_id.clone(),
+    );
+    Ok(response::created(
+        format!("/api/conversations/{}/messages", message.conversation_id),
+        message,
+    ))
+}
+
+#[get("/api/conversations")]
+pub async fn get_conversations_api(
+    identity: Identity,
+    page: web::Query<Pagination>,
+) -> HttpResponse {
+    response::json(Conversation::get_list(&identity.user_id, &page))
+}
+
+#[get("/api/conversations/unread")]
+pub async fn count_unread_api(identity: Identity) -> HttpResponse {
+    response::json(Count {
+        count: Message::count_unread(&identity.user_id),
+    })
+}
+
+#[get("/api/conversations/{conversation_id}/messages")]
+pub async fn get_messages_api(
+    identity: Identity,
+    conversation_id: web::Path<u64>,
+    cursor: web::Query<Cursor>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let Some((user_a, user_b)) = Conversation::get_participants(*conversation_id) else {
+        return Err(ApiError::NotFound(
+            "요청한 conversation_id는 존재하지 않는 대화입니다.".to_string(),
+        ));
+    };
+    if identity.user_id != user_a && identity.user_id != user_b {
+        return Err(ApiError::Forbidden);
+    }
+    let messages = Message::get_after(*conversation_id, &cursor);
+    Message::mark_read(*conversation_id, &identity.user_id)?;
+    Ok(response::json(CursorPage::new(
+        messages,
+        &cursor,
+        |message| message.message_id,
+    )))
+}
@@ -0,0 +1,269 @@
+//! # DB 스키마 마이그레이션 관련 동작을 정의하는 모듈
+//!
+//! `migrate`는 새로 배포된 코드뮤니티 서버가 스스로 DB 스키마를 만들고 최신 상태로 유지할 수 있도록
+//! 실행 파일에 포함된 SQL 마이그레이션들을 순서대로 적용하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 마이그레이션 파일은 `migrations/V<버전>__<이름>.sql` 형태로 저장하며, 적용된 버전은
+//! `schema_migrations` 테이블에 기록되어 같은 마이그레이션이 두 번 적용되지 않는다.
+//! 한 번 배포된 마이그레이션 파일은 수정하지 않고 새 버전의 파일을 추가해야 한다.
+//!
+//! 개발 환경에서는 `DB_AUTO_CREATE`를 지정하여 서버가 시작될 때 모든 마이그레이션을 적용할 수도 있다.
+
+use crate::db;
+use mysql::prelude::*;
+use mysql::*;
+
+/// 실행 파일에 포함된 마이그레이션 하나이다.
+pub struct Migration {
+    /// 마이그레이션의 버전이다. 낮은 버전부터 순서대로 적용된다.
+    pub version: u32,
+    /// 마이그레이션의 이름이다.
+    pub name: &'static str,
+    /// 마이그레이션에서 실행할 SQL문들이다.
+    pub sql: &'static str,
+}
+
+/// 실행 파일에 포함된 모든 마이그레이션이다. 버전 순서대로 나열되어야 한다.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sql: include_str!("../migrations/V1__initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "webhooks",
+        sql: include_str!("../migrations/V2__webhooks.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "post_bot_flag",
+        sql: include_str!("../migrations/V3__post_bot_flag.sql"),
+    },
+    Migration {
+        version: 4,
+        name: "user_notifications",
+        sql: include_str!("../migrations/V4__user_notifications.sql"),
+    },
+    Migration {
+        version: 5,
+        name: "word_filter",
+        sql: include_str!("../migrations/V5__word_filter.sql"),
+    },
+    Migration {
+        version: 6,
+        name: "content_moderation",
+        sql: include_str!("../migrations/V6__content_moderation.sql"),
+    },
+    Migration {
+        version: 7,
+        name: "link_preview",
+        sql: include_str!("../migrations/V7__link_preview.sql"),
+    },
+    Migration {
+        version: 8,
+        name: "post_attachment",
+        sql: include_str!("../migrations/V8__post_attachment.sql"),
+    },
+    Migration {
+        version: 9,
+        name: "search_prefix_index",
+        sql: include_str!("../migrations/V9__search_prefix_index.sql"),
+    },
+    Migration {
+        version: 10,
+        name: "user_deactivation",
+        sql: include_str!("../migrations/V10__user_deactivation.sql"),
+    },
+    Migration {
+        version: 11,
+        name: "blocked",
+        sql: include_str!("../migrations/V11__blocked.sql"),
+    },
+    Migration {
+        version: 12,
+        name: "direct_messages",
+        sql: include_str!("../migrations/V12__direct_messages.sql"),
+    },
+    Migration {
+        version: 13,
+        name: "post_visibility",
+        sql: include_str!("../migrations/V13__post_visibility.sql"),
+    },
+    Migration {
+        version: 14,
+        name: "announcements",
+        sql: include_str!("../migrations/V14__announcements.sql"),
+    },
+    Migration {
+        version: 15,
+        name: "analytics",
+        sql: include_str!("../migrations/V15__analytics.sql"),
+    },
+    Migration {
+        version: 16,
+        name: "comment_reports",
+        sql: include_str!("../migrations/V16__comment_reports.sql"),
+    },
+    Migration {
+        version: 17,
+        name: "post_reports",
+        sql: include_str!("../migrations/V17__post_reports.sql"),
+    },
+    Migration {
+        version: 18,
+        name: "shadow_ban",
+        sql: include_str!("../migrations/V18__shadow_ban.sql"),
+    },
+    Migration {
+        version: 19,
+        name: "sessions",
+        sql: include_str!("../migrations/V19__sessions.sql"),
+    },
+    Migration {
+        version: 20,
+        name: "password_accounts",
+        sql: include_str!("../migrations/V20__password_accounts.sql"),
+    },
+    Migration {
+        version: 21,
+        name: "email_verification",
+        sql: include_str!("../migrations/V21__email_verification.sql"),
+    },
+    Migration {
+        version: 22,
+        name: "password_reset",
+        sql: include_str!("../migrations/V22__password_reset.sql"),
+    },
+    Migration {
+        version: 23,
+        name: "unique_email",
+        sql: include_str!("../migrations/V23__unique_email.sql"),
+    },
+    Migration {
+        version: 24,
+        name: "token_version",
+        sql: include_str!("../migrations/V24__token_version.sql"),
+    },
+];
+
+/// 마이그레이션 파일의 내용을 SQL문 단위로 나누는 메서드이다.
+///
+/// 세미콜론을 기준으로 나누며, 비어있는 문장과 `--`로 시작하는 주석 줄은 제외된다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::migrate::statements;
+/// let sql = "-- 테이블 생성\ncreate table a (id int);\n\ncreate table b (id int);\n";
+/// assert_eq!(statements(sql), vec!["create table a (id int)", "create table b (id int)"]);
+/// ```
+pub fn statements(sql: &str) -> Vec<String> {
+    sql.split(';')
+        .map(|statement| {
+            statement
+                .lines()
+                .filter(|line| !line.trim_start().starts_with("--"))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string()
+        })
+        .filter(|statement| !statement.is_empty())
+        .collect()
+}
+
+/// 아직 적용되지 않은 마이그레이션들을 적용하는 메서드이다.
+///
+/// 적용한 마이그레이션의 버전들을 반환하며, 모두 적용되어 있는 경우 빈 목록을 반환한다.
+/// 여러 SQL문으로 이루어진 마이그레이션은 SQL문 하나가 적용될 때마다 진행 상황이 `schema_migration_progress`에
+/// 기록되므로, 적용 도중 문제가 발생한 경우 원인을 해결한 뒤 다시 실행하면 실패한 SQL문부터 이어서 적용된다.
+/// # 예제
+/// ```ignore
+/// for version in migrate::run().unwrap() {
+///     println!("{}번 마이그레이션이 적용되었습니다.", version);
+/// }
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn run() -> Result<Vec<u32>> {
+    let mut conn = db::get_conn();
+    conn.query_drop(
+        r"create table if not exists schema_migrations (
+            version int unsigned not null primary key,
+            name varchar(255) not null,
+            applied_at datetime not null default current_timestamp
+        )",
+    )?;
+    // MySQL에서 DDL은 트랜잭션으로 묶이지 않으므로 SQL문 단위로 적용된 개수를 기록한다.
+    conn.query_drop(
+        r"create table if not exists schema_migration_progress (
+            version int unsigned not null primary key,
+            applied_statements int unsigned not null
+        )",
+    )?;
+    let applied: Vec<u32> = conn.query("select version from schema_migrations")?;
+    let mut versions = Vec::new();
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| !applied.contains(&migration.version))
+    {
+        let done: Option<usize> = conn.exec_first(
+            "select applied_statements from schema_migration_progress where version = :version",
+            params! { "version" => migration.version },
+        )?;
+        for (index, statement) in statements(migration.sql)
+            .into_iter()
+            .enumerate()
+            .skip(done.unwrap_or(0))
+        {
+            conn.query_drop(statement)?;
+            conn.exec_drop(
+                r"insert into schema_migration_progress(version, applied_statements)
+                values(:version, :count)
+                on duplicate key update applied_statements = :count",
+                params! {
+                    "version" => migration.version,
+                    "count" => index + 1,
+                },
+            )?;
+        }
+        conn.exec_drop(
+            "insert into schema_migrations(version, name) values(:version, :name)",
+            params! {
+                "version" => migration.version,
+                "name" => migration.name,
+            },
+        )?;
+        conn.exec_drop(
+            "delete from schema_migration_progress where version = :version",
+            params! { "version" => migration.version },
+        )?;
+        versions.push(migration.version);
+    }
+    Ok(versions)
+}
+
+/// `DB_AUTO_CREATE`가 지정된 경우 서버가 시작될 때 DB를 준비하는 메서드이다.
+///
+/// 테이블을 만드는 SQL문만 골라 적용하면 이후의 `alter table` 마이그레이션이 빠지므로
+/// [`run`]으로 모든 마이그레이션을 적용하고 버전을 기록한다. 적용한 마이그레이션의 버전들을 반환한다.
+/// # 예제
+/// ```ignore
+/// for version in migrate::bootstrap().unwrap() {
+///     println!("{}번 마이그레이션이 적용되었습니다.", version);
+/// }
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn bootstrap() -> Result<Vec<u32>> {
+    run()
+}
@@ -0,0 +1,250 @@
+//! # 외부 콘텐츠 검토 관련 동작을 정의하는 모듈
+//!
+//! `moderation`은 새로 작성되거나 수정된 포스트와 댓글의 내용을 외부 검토 서비스에 보내고,
+//! 그 판정에 따라 콘텐츠를 보류(`pending`)하거나 거부(`rejected`)하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 검토 방식은 `Moderator` 트레이트로 추상화되어 있으며, `MODERATION_URL` 환경변수가 지정된 경우
+//! 해당 주소로 검토를 요청하는 `RemoteModerator`를, 그렇지 않은 경우 모든 콘텐츠를 허용하는 `AllowAll`을 사용한다.
+//! 검토는 요청 처리가 늦어지지 않도록 콘텐츠를 저장한 뒤 별도의 스레드에서 수행되며,
+//! 판정이 `approved`가 아닌 포스트와 댓글은 목록과 조회 결과에서 제외된다.
+//! 검토 서비스에 접근할 수 없는 경우 콘텐츠는 그대로 공개된다.
+
+use crate::cache::Cache;
+use crate::db;
+use crate::search_index::SearchIndex;
+use crate::word_filter::ContentKind;
+use actix_web::{rt, web};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 검토 서비스의 응답을 기다리는 시간이다.
+const REVIEW_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 검토 서비스의 판정이다.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    /// 공개해도 되는 콘텐츠
+    Approved,
+    /// 사람의 검토가 필요하여 공개를 보류한 콘텐츠
+    Pending,
+    /// 공개할 수 없는 콘텐츠
+    Rejected,
+}
+
+impl Verdict {
+    /// DB에 저장되는 판정 이름을 반환하는 메서드이다.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Approved => "approved",
+            Self::Pending => "pending",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+/// 콘텐츠를 검토하는 방식이 구현해야 하는 트레이트이다.
+pub trait Moderator: Send + Sync {
+    /// 검토 방식의 이름이다.
+    fn name(&self) -> &'static str;
+    /// 외부 서비스에 검토를 요청하는지 여부이다. `false`인 경우 판정을 DB에 저장하지 않는다.
+    fn is_remote(&self) -> bool {
+        true
+    }
+    /// 콘텐츠의 내용을 검토한 판정을 반환하는 메서드이다. 검토할 수 없는 경우 그 이유를 반환한다.
+    fn review(&self, kind: ContentKind, text: &str) -> std::result::Result<Verdict, String>;
+}
+
+/// 모든 콘텐츠를 허용하는 기본 방식이다.
+pub struct AllowAll;
+
+impl Moderator for AllowAll {
+    fn name(&self) -> &'static str {
+        "allow_all"
+    }
+    fn is_remote(&self) -> bool {
+        false
+    }
+    fn review(&self, _kind: ContentKind, _text: &str) -> std::result::Result<Verdict, String> {
+        Ok(Verdict::Approved)
+    }
+}
+
+/// 검토 서비스에 보내는 요청 본문이다.
+#[derive(Serialize)]
+struct ReviewRequest<'a> {
+    /// 콘텐츠의 종류(`post` 또는 `comment`)이다.
+    content_type: &'static str,
+    /// 검토할 내용이다.
+    text: &'a str,
+}
+
+/// 검토 서비스가 응답하는 본문이다.
+#[derive(Deserialize)]
+struct ReviewResponse {
+    /// 판정이다.
+    verdict: Verdict,
+}
+
+/// 외부 검토 서비스에 HTTP로 검토를 요청하는 방식이다.
+///
+/// `{"content_type": "post", "text": "..."}`를 JSON으로 보내고 `{"verdict": "approved"}`와 같은 응답을 받는다.
+/// API 키가 주어진 경우 `Authorization: Bearer <키>` 헤더에 담아 보낸다.
+pub struct RemoteModerator {
+    /// 검토 서비스의 주소이다.
+    url: String,
+    /// 검토 서비스의 API 키이다.
+    api_key: Option<String>,
+    /// 요청에 사용할 HTTP 클라이언트이다.
+    agent: ureq::Agent,
+}
+
+impl RemoteModerator {
+    /// 검토 서비스의 주소와 API 키로 생성하는 메서드이다.
+    pub fn new(url: String, api_key: Option<String>) -> Self {
+        Self {
+            url,
+            api_key,
+            agent: ureq::Agent::config_builder()
+                .timeout_global(Some(REVIEW_TIMEOUT))
+                .build()
+                .into(),
+        }
+    }
+}
+
+impl Moderator for RemoteModerator {
+    fn name(&self) -> &'static str {
+        "remote"
+    }
+    fn review(&self, kind: ContentKind, text: &str) -> std::result::Result<Verdict, String> {
+        let payload = serde_json::to_string(&ReviewRequest {
+            content_type: kind.as_str(),
+            text,
+        })
+        .map_err(|error| error.to_string())?;
+        let mut request = self
+            .agent
+            .post(&self.url)
+            .header("Content-Type", "application/json");
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+        let body = request
+            .send(&payload)
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|error| error.to_string())?;
+        serde_json::from_str::<ReviewResponse>(&body)
+            .map(|response| response.verdict)
+            .map_err(|error| error.to_string())
+    }
+}
+
+/// 환경변수에 지정된 검토 방식을 반환하는 메서드이다.
+///
+/// `MODERATION_URL`이 지정된 경우 해당 주소로 검토를 요청하며, `MODERATION_API_KEY`로 API 키를 지정할 수 있다.
+/// 지정되지 않은 경우 모든 콘텐츠를 허용한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::moderation;
+/// std::env::remove_var("MODERATION_URL");
+/// assert_eq!(moderation::from_env().name(), "allow_all");
+/// ```
+pub fn from_env() -> Arc<dyn Moderator> {
+    match env::var("MODERATION_URL") {
+        Ok(url) if !url.is_empty() => Arc::new(RemoteModerator::new(
+            url,
+            env::var("MODERATION_API_KEY")
+                .ok()
+                .filter(|api_key| !api_key.is_empty()),
+        )),
+        _ => Arc::new(AllowAll),
+    }
+}
+
+/// 콘텐츠를 별도의 스레드에서 검토하고 판정을 저장하는 메서드이다.
+///
+/// 외부 서비스에 검토를 요청하지 않는 방식인 경우 아무 작업도 하지 않는다.
+/// # 예제
+/// ```ignore
+/// moderation::submit(moderator, cache, search_index, ContentKind::Post, post.post_id, post.data.clone());
+/// ```
+pub fn submit(
+    moderator: web::Data<dyn Moderator>,
+    cache: web::Data<Cache>,
+    search_index: web::Data<SearchIndex>,
+    kind: ContentKind,
+    content_id: u64,
+    text: String,
+) {
+    if !moderator.is_remote() {
+        return;
+    }
+    rt::spawn(async move {
+        let result = web::block(move || -> Result<()> {
+            match moderator.review(kind, &text) {
+                Ok(verdict) => {
+                    set_verdict(kind, content_id, verdict)?;
+                    if kind == ContentKind::Post {
+                        cache.invalidate_post(content_id);
+                    }
+                }
+                Err(error) => tracing::warn!(
+                    content_id,
+                    error = error.as_str(),
+                    "콘텐츠를 검토하지 못해 그대로 공개합니다."
+                ),
+            }
+            Ok(())
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {
+                if kind == ContentKind::Post {
+                    search_index.sync(content_id);
+                }
+            }
+            Ok(Err(error)) => {
+                tracing::error!(%error, content_id, "검토 결과를 저장하지 못했습니다.")
+            }
+            Err(_) => tracing::error!(content_id, "콘텐츠를 검토하지 못했습니다."),
+        }
+    });
+}
+
+/// 콘텐츠의 판정을 저장하는 메서드이다.
+///
+/// 포스트의 경우 판정이 `approved`가 아니면 게시되지 않은 상태로 바꾸고,
+/// `approved`이면 예약 게시 시각이 지난 경우 다시 게시한다.
+/// 게시 여부가 바뀌므로 호출한 쪽에서 `SearchIndex::sync`로 검색 색인에 반영해야 한다.
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn set_verdict(kind: ContentKind, content_id: u64, verdict: Verdict) -> Result<()> {
+    let mut conn = db::get_conn();
+    let statement = match kind {
+        ContentKind::Post => {
+            r"update post set moderation = :verdict,
+            published = (:verdict = 'approved' and (publish_at is null or publish_at <= now()))
+            where post_id = :content_id"
+        }
+        ContentKind::Comment => {
+            "update comment set moderation = :verdict where comment_id = :content_id"
+        }
+    };
+    conn.exec_drop(
+        statement,
+        params! {
+            "verdict" => verdict.as_str(),
+            "content_id" => content_id,
+        },
+    )
+}
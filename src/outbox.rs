@@ -0,0 +1,202 @@
+//! # 외부 전송 관련 동작을 정의하는 모듈
+//!
+//! `outbox`는 코드뮤니티에서 메일, 푸시 알림, 웹훅과 같이 외부 서비스로 보내는 전송을
+//! 한 곳에서 처리하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 전송 대상에 접속할 수 없어 전송에 실패한 경우 전송 내용을 로컬 디스크(`OUTBOX_DIR`)에 저장해두고,
+//! `scheduler`를 통해 주기적으로 다시 전송한다. 저장된 내용은 서버가 다시 시작되어도 유지된다.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// 전송 내용을 저장하는 기본 폴더이다.
+pub const DEFAULT_OUTBOX_DIR: &str = "./outbox";
+/// 다시 전송을 시도하는 최대 횟수이다. 이 횟수를 넘으면 `failed` 폴더로 옮겨진다.
+const MAX_ATTEMPTS: u32 = 20;
+/// 처음 다시 전송하기까지 기다리는 시간(초)이다.
+const BASE_BACKOFF_SECONDS: u64 = 30;
+/// 다시 전송하기까지 기다리는 최대 시간(초)이다.
+const MAX_BACKOFF_SECONDS: u64 = 60 * 60;
+
+/// 외부 서비스로 전송을 수행하는 객체가 구현해야 하는 트레이트이다.
+///
+/// 메일, 푸시 알림, 웹훅 등 전송 방식마다 하나씩 구현한 뒤 `Outbox::register()`로 등록한다.
+pub trait Deliver: Send + Sync {
+    /// 전송 방식의 이름이다. (예: `email`, `fcm`, `webhook`)
+    fn channel(&self) -> &'static str;
+    /// `target`에게 `payload`를 전송하는 메서드이다.
+    ///
+    /// 전송에 실패한 경우 실패 사유를 `Err`로 반환하며, 이 경우 나중에 다시 전송된다.
+    fn deliver(&self, target: &str, payload: &str) -> Result<(), String>;
+}
+
+/// 전송하지 못해 디스크에 저장된 전송 내용이다.
+#[derive(Serialize, Deserialize)]
+pub struct PendingDelivery {
+    /// 전송 방식의 이름이다.
+    pub channel: String,
+    /// 전송 대상(메일 주소, 기기 토큰, 웹훅 주소 등)이다.
+    pub target: String,
+    /// 전송할 내용이다.
+    pub payload: String,
+    /// 지금까지 전송을 시도한 횟수이다.
+    pub attempts: u32,
+    /// 다음으로 전송을 시도할 시각(유닉스 시간, 초)이다.
+    pub next_attempt_at: u64,
+    /// 마지막으로 전송에 실패한 사유이다.
+    pub last_error: String,
+}
+
+/// 현재 유닉스 시간(초)을 반환하는 메서드이다.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0)
+}
+
+/// 외부 전송을 처리하는 객체이다.
+///
+/// 서버 시작 시 `from_env()`로 생성하고 전송 방식을 등록한 뒤 모든 워커가 공유하여 사용한다.
+pub struct Outbox {
+    dir: PathBuf,
+    deliverers: HashMap<&'static str, Box<dyn Deliver>>,
+}
+
+impl Outbox {
+    /// 환경변수를 통해 객체를 생성하는 메서드이다.
+    ///
+    /// 전송 내용은 `OUTBOX_DIR` 환경변수로 지정한 폴더(기본값 `./outbox`)에 저장된다.
+    ///
+    /// # Panics
+    ///
+    /// `OUTBOX_DIR` 폴더를 만들 수 없는 경우 패닉이 발생한다.
+    pub fn from_env() -> Self {
+        Self::new(env::var("OUTBOX_DIR").unwrap_or_else(|_| DEFAULT_OUTBOX_DIR.to_string()))
+    }
+    /// 전송 내용을 `dir` 폴더에 저장하는 객체를 생성하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// `dir` 폴더를 만들 수 없는 경우 패닉이 발생한다.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        fs::create_dir_all(dir.join("failed")).expect("OUTBOX_DIR 폴더를 만들 수 없음");
+        Self {
+            dir,
+            deliverers: HashMap::new(),
+        }
+    }
+    /// 전송 방식을 등록하는 메서드이다.
+    pub fn register(&mut self, deliverer: Box<dyn Deliver>) {
+        self.deliverers.insert(deliverer.channel(), deliverer);
+    }
+    /// 전송에 실패한 횟수에 따라 다시 전송하기까지 기다릴 시간을 반환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::outbox::Outbox;
+    /// use std::time::Duration;
+    /// assert_eq!(Outbox::backoff(1), Duration::from_secs(30));
+    /// assert_eq!(Outbox::backoff(3), Duration::from_secs(120));
+    /// assert_eq!(Outbox::backoff(30), Duration::from_secs(3600));
+    /// ```
+    pub fn backoff(attempts: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempts.saturating_sub(1));
+        Duration::from_secs(
+            BASE_BACKOFF_SECONDS
+                .saturating_mul(factor)
+                .min(MAX_BACKOFF_SECONDS),
+        )
+    }
+    /// `channel` 방식으로 `target`에게 `payload`를 전송하는 메서드이다.
+    ///
+    /// 전송에 실패하거나 등록되지 않은 전송 방식인 경우 전송 내용을 디스크에 저장하고
+    /// 나중에 `replay()`를 통해 다시 전송한다.
+    /// # 예제
+    /// ```ignore
+    /// outbox.send("webhook", "https://example.com/hook", "{\"event\":\"post_created\"}");
+    /// ```
+    pub fn send(&self, channel: &str, target: &str, payload: &str) {
+        let result = match self.deliverers.get(channel) {
+            Some(deliverer) => deliverer.deliver(target, payload),
+            None => Err("등록되지 않은 전송 방식입니다.".to_string()),
+        };
+        if let Err(error) = result {
+            let pending = PendingDelivery {
+                channel: channel.to_string(),
+                target: target.to_string(),
+                payload: payload.to_string(),
+                attempts: 1,
+                next_attempt_at: now() + Self::backoff(1).as_secs(),
+                last_error: error,
+            };
+            let path = self.dir.join(format!("{}-{}.json", now(), Uuid::new_v4()));
+            if let Err(error) = Self::write(&path, &pending) {
+                tracing::error!(%error, channel, "전송 내용을 저장하지 못했습니다.");
+            }
+        }
+    }
+    /// 디스크에 저장된 전송 내용 중 다시 전송할 시각이 된 것들을 전송하는 메서드이다.
+    ///
+    /// 전송에 성공한 내용은 디스크에서 제거되고, 최대 시도 횟수를 넘긴 내용은 `failed` 폴더로 옮겨진다.
+    /// 등록되지 않은 전송 방식의 내용은 해당 방식이 등록될 때까지 그대로 남겨둔다.
+    pub fn replay(&self) {
+        let mut paths: Vec<PathBuf> = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect(),
+            Err(_) => return,
+        };
+        paths.sort();
+        for path in paths {
+            let mut pending: PendingDelivery = match fs::read_to_string(&path)
+                .ok()
+                .and_then(|data| serde_json::from_str(&data).ok())
+            {
+                Some(pending) => pending,
+                None => continue,
+            };
+            let deliverer = match self.deliverers.get(pending.channel.as_str()) {
+                Some(deliverer) => deliverer,
+                None => continue,
+            };
+            if pending.next_attempt_at > now() {
+                continue;
+            }
+            match deliverer.deliver(&pending.target, &pending.payload) {
+                Ok(_) => {
+                    let _ = fs::remove_file(&path);
+                }
+                Err(error) => {
+                    pending.attempts += 1;
+                    pending.last_error = error;
+                    pending.next_attempt_at = now() + Self::backoff(pending.attempts).as_secs();
+                    let target = if pending.attempts >= MAX_ATTEMPTS {
+                        self.dir.join("failed").join(path.file_name().unwrap())
+                    } else {
+                        path.clone()
+                    };
+                    if Self::write(&target, &pending).is_ok() && target != path {
+                        let _ = fs::remove_file(&path);
+                    }
+                }
+            }
+        }
+    }
+    /// 전송 내용을 파일에 저장하는 메서드이다.
+    ///
+    /// 저장 도중 서버가 종료되어도 파일이 손상되지 않도록 임시 파일에 먼저 쓴 뒤 이름을 바꾼다.
+    fn write(path: &Path, pending: &PendingDelivery) -> io::Result<()> {
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, serde_json::to_vec(pending)?)?;
+        fs::rename(temp, path)
+    }
+}
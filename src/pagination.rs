@@ -0,0 +1,168 @@
+//! # 페이지 나누기 관련 동작을 정의하는 모듈
+//!
+//! `pagination`은 코드뮤니티에서 목록을 반환하는 API가 공통으로 사용하는
+//! 페이지 관련 요소들로 이루어져 있다.
+//!
+//! 페이지 번호(`page`, `per_page`) 방식 외에 포스트가 많을 때도 느려지지 않는 커서(keyset) 방식(`after`, `limit`)을
+//! 함께 제공한다. 커서 방식은 응답을 `{ "items": [...], "next_cursor": ... }` 형태로 감싸서 반환하며,
+//! 다음 페이지는 `next_cursor`를 `after`에 넣어 요청하면 된다.
+
+use serde::{Deserialize, Serialize};
+
+/// 한 페이지에 포함되는 항목 수의 기본값이다.
+const DEFAULT_PER_PAGE: u64 = 20;
+/// 한 페이지에 포함될 수 있는 항목 수의 최대값이다.
+const MAX_PER_PAGE: u64 = 100;
+
+/// 쿼리 스트링을 통해 페이지 정보를 받을 때 필요한 구조체이다.
+///
+/// `page`는 1부터 시작하며 생략하면 첫 페이지를, `per_page`를 생략하면 20개의 항목을 반환한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::pagination::Pagination;
+/// let page = Pagination { page: Some(3), per_page: Some(10) };
+/// assert_eq!(page.limit(), 10);
+/// assert_eq!(page.offset(), 20);
+/// ```
+#[derive(Deserialize)]
+pub struct Pagination {
+    /// 요청한 페이지 번호이다.
+    pub page: Option<u64>,
+    /// 한 페이지에 포함될 항목 수이다.
+    pub per_page: Option<u64>,
+}
+
+impl Pagination {
+    /// SQL의 `limit`에 사용할 값을 반환하는 메서드이다.
+    pub fn limit(&self) -> u64 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+    /// SQL의 `offset`에 사용할 값을 반환하는 메서드이다.
+    pub fn offset(&self) -> u64 {
+        (self.number() - 1) * self.limit()
+    }
+    /// 1부터 시작하는 페이지 번호를 반환하는 메서드이다.
+    pub fn number(&self) -> u64 {
+        self.page.unwrap_or(1).max(1)
+    }
+}
+
+/// 페이지 번호 방식으로 요청한 목록을 전체 항목 수와 함께 반환할 때 사용하는 구조체이다.
+///
+/// `/api/v1` 아래의 목록 API에서 사용한다.
+#[derive(Serialize)]
+pub struct Page<T> {
+    /// 요청한 페이지의 항목들이다.
+    pub items: Vec<T>,
+    /// 조건에 맞는 전체 항목 수이다.
+    pub total: u64,
+    /// 요청한 페이지 번호이다.
+    pub page: u64,
+    /// 한 페이지에 포함될 항목 수이다.
+    pub per_page: u64,
+    /// 다음 페이지 번호이다. 마지막 페이지인 경우 `None`이다.
+    pub next: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// 요청한 페이지의 항목들과 전체 항목 수로 응답을 만드는 메서드이다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::pagination::{Page, Pagination};
+    /// let page = Page::new(vec!["a", "b"], 5, &Pagination { page: Some(2), per_page: Some(2) });
+    /// assert_eq!((page.page, page.per_page, page.next), (2, 2, Some(3)));
+    /// let page = Page::new(vec!["e"], 5, &Pagination { page: Some(3), per_page: Some(2) });
+    /// assert_eq!(page.next, None);
+    /// ```
+    pub fn new(items: Vec<T>, total: u64, page: &Pagination) -> Self {
+        let next = (page.offset() + page.limit() < total).then(|| page.number() + 1);
+        Self {
+            items,
+            total,
+            page: page.number(),
+            per_page: page.limit(),
+            next,
+        }
+    }
+}
+
+/// 쿼리 스트링을 통해 커서 방식의 페이지 정보를 받을 때 필요한 구조체이다.
+///
+/// `after`에는 이전 페이지의 `next_cursor`를 넣으며, 생략하면 첫 페이지를 반환한다.
+/// `after`와 `limit` 중 하나라도 지정된 경우 커서 방식으로 요청한 것으로 본다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::pagination::Cursor;
+/// let cursor = Cursor { after: Some(120), limit: Some(500) };
+/// assert!(cursor.is_requested());
+/// assert_eq!(cursor.limit(), 100);
+/// assert!(!Cursor { after: None, limit: None }.is_requested());
+/// ```
+#[derive(Deserialize)]
+pub struct Cursor {
+    /// 이 ID보다 작은 항목부터 반환한다.
+    pub after: Option<u64>,
+    /// 한 페이지에 포함될 항목 수이다.
+    pub limit: Option<u64>,
+}
+
+impl Cursor {
+    /// 커서 방식으로 요청했는지 확인하는 메서드이다.
+    pub fn is_requested(&self) -> bool {
+        self.after.is_some() || self.limit.is_some()
+    }
+    /// SQL의 `limit`에 사용할 값을 반환하는 메서드이다.
+    pub fn limit(&self) -> u64 {
+        self.limit
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+}
+
+/// 목록 대신 항목 수만 반환할 때 사용하는 구조체이다.
+#[derive(Serialize)]
+pub struct Count {
+    /// 조건에 맞는 항목 수이다.
+    pub count: u64,
+}
+
+/// 커서 방식으로 요청한 목록을 반환할 때 사용하는 구조체이다.
+#[derive(Serialize)]
+pub struct CursorPage<T> {
+    /// 요청한 페이지의 항목들이다.
+    pub items: Vec<T>,
+    /// 다음 페이지를 요청할 때 `after`에 넣을 값이다. 마지막 페이지인 경우 `None`이다.
+    pub next_cursor: Option<u64>,
+}
+
+impl<T> CursorPage<T> {
+    /// 요청한 페이지의 항목들로 응답을 만드는 메서드이다.
+    ///
+    /// 항목 수가 `limit`보다 적으면 마지막 페이지로 보고, 그렇지 않으면 마지막 항목의 ID(`id`)를 `next_cursor`로 사용한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::pagination::{Cursor, CursorPage};
+    /// let cursor = Cursor { after: None, limit: Some(2) };
+    /// let page = CursorPage::new(vec![9, 7], &cursor, |id| *id);
+    /// assert_eq!(page.next_cursor, Some(7));
+    /// let page = CursorPage::new(vec![5], &cursor, |id| *id);
+    /// assert_eq!(page.next_cursor, None);
+    /// ```
+    pub fn new(items: Vec<T>, cursor: &Cursor, id: impl Fn(&T) -> u64) -> Self {
+        let next_cursor = if items.len() as u64 >= cursor.limit() {
+            items.last().map(id)
+        } else {
+            None
+        };
+        Self { items, next_cursor }
+    }
+    /// 커서는 그대로 두고 항목들만 `f`로 변환하는 메서드이다.
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> CursorPage<U> {
+        CursorPage {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
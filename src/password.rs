@@ -0,0 +1,498 @@
+//! # 비밀번호 계정 관련 동작을 정의하는 모듈
+//!
+//! `password`는 외부 인증 서비스를 사용하지 않으려는 사용자가 이메일과 비밀번호로 가입하고 로그인하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 비밀번호는 argon2로 만든 해시만 `user` 테이블에 저장된다. 로그인에 성공하면 `session` 모듈의 세션 쿠키와 함께,
+//! `AUTH_SECRET`이 지정된 경우 모바일 앱에서 사용할 수 있는 인증 토큰을 발급한다.
+//! 가입한 사용자는 `verification` 모듈의 이메일 주소 인증을 마쳐야 콘텐츠를 작성할 수 있다.
+//! 비밀번호를 `LOGIN_MAX_FAILURES`번(기본값 5번) 연속으로 틀리면 계정이 `LOGIN_LOCKOUT_SECONDS`초(기본값 900초)동안 잠긴다.
+//! 비밀번호를 잊었거나 계정이 잠긴 사용자는 메일로 받은 재설정 토큰으로 새 비밀번호를 설정할 수 있다.
+//! 재설정 토큰은 `RESET_TOKEN_TTL_SECONDS`초동안 한 번만 사용할 수 있으며, DB에는 SHA-256 해시만 저장된다.
+
+use crate::auth::Identity;
+use crate::db;
+use crate::email;
+use crate::error::ApiError;
+use crate::outbox::Outbox;
+use crate::response;
+use crate::session::{self, Session};
+use crate::user::User;
+use crate::validation::FieldErrors;
+use crate::verification;
+use actix_web::web::Json;
+use actix_web::{post, web, HttpResponse};
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use chrono::{DateTime, Utc};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::sync::OnceLock;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// 계정이 잠기기까지 허용되는 기본 연속 로그인 실패 횟수이다.
+const DEFAULT_MAX_FAILURES: u32 = 5;
+/// 계정이 잠기는 기본 시간(초)이다.
+const DEFAULT_LOCKOUT_SECONDS: u64 = 900;
+/// 비밀번호 계정의 사용자 고유 ID 앞에 붙는 문자열이다.
+pub const USER_ID_PREFIX: &str = "password|";
+/// 비밀번호 재설정 토큰을 사용할 수 있는 시간(초)이다.
+pub const RESET_TOKEN_TTL_SECONDS: i64 = 60 * 60;
+/// 가입하지 않은 이메일 주소로 로그인을 시도했을 때 비교할 해시이다.
+static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+
+/// JSON 을 통해 가입 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct RegisterRequest {
+    /// 로그인에 사용할 이메일 주소이다. 알림 메일도 이 주소로 보내진다.
+    pub email: String,
+    /// 로그인에 사용할 비밀번호이다.
+    pub password: String,
+    /// 사용자의 표시 이름이다.
+    pub user_name: String,
+}
+
+impl RegisterRequest {
+    /// 가입 요청의 항목들이 조건에 맞는지 확인하는 메서드이다.
+    ///
+    /// 이메일 주소 형식이 올바르지 않거나, 비밀번호의 길이가 맞지 않거나, 이름이 비어있거나 너무 긴 경우
+    /// 항목별 이유를 담은 `ApiError::InvalidFields`를 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::password::RegisterRequest;
+    /// let request = RegisterRequest {
+    ///     email: "sun30812@example.com".to_string(),
+    ///     password: "correct horse battery staple".to_string(),
+    ///     user_name: "sun30812".to_string(),
+    /// };
+    /// assert!(request.validate().is_ok());
+    /// let request = RegisterRequest { password: "short".to_string(), ..request };
+    /// assert!(request.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), ApiError> {
+        let mut errors = FieldErrors::default();
+        errors.check_email(&self.email);
+        errors.check_password(&self.password);
+        errors.check_user_name(&self.user_name);
+        errors.into_result()
+    }
+}
+
+/// JSON 을 통해 로그인 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    /// 가입할 때 사용한 이메일 주소이다.
+    pub email: String,
+    /// 비밀번호이다.
+    pub password: String,
+}
+
+/// JSON 을 통해 비밀번호 재설정 메일 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct ForgotRequest {
+    /// 가입할 때 사용한 이메일 주소이다.
+    pub email: String,
+}
+
+/// JSON 을 통해 비밀번호 재설정 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct ResetRequest {
+    /// 메일로 받은 재설정 토큰이다.
+    pub token: String,
+    /// 새로 사용할 비밀번호이다.
+    pub password: String,
+}
+
+/// 로그인에 성공했을 때의 응답 본문이다.
+#[derive(Serialize)]
+pub struct LoginResponse {
+    /// 로그인한 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 세션과 인증 토큰이 만료되는 날짜 및 시간이다.
+    pub expires_at: DateTime<Utc>,
+    /// `Authorization` 헤더에 담아 사용할 인증 토큰이다. `AUTH_SECRET`이 지정되지 않은 경우 `null`이다.
+    pub token: Option<String>,
+}
+
+/// 로그인을 시도한 결과이다.
+enum Outcome {
+    /// 로그인에 성공했으며, 사용자의 고유 ID를 담고 있다.
+    Success(String),
+    /// 이메일 주소나 비밀번호가 올바르지 않다.
+    Invalid,
+    /// 계정이 잠겨있으며, 잠금이 풀릴 때까지 남은 시간(초)을 담고 있다.
+    Locked(u64),
+}
+
+/// 계정이 잠기기까지 허용되는 연속 로그인 실패 횟수를 반환하는 메서드이다.
+///
+/// `LOGIN_MAX_FAILURES` 환경변수가 주어지지 않았거나 올바르지 않은 경우 5를 반환한다.
+fn max_failures() -> u32 {
+    env::var("LOGIN_MAX_FAILURES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|failures| *failures > 0)
+        .unwrap_or(DEFAULT_MAX_FAILURES)
+}
+
+/// 계정이 잠기는 시간(초)을 반환하는 메서드이다.
+///
+/// `LOGIN_LOCKOUT_SECONDS` 환경변수가 주어지지 않았거나 올바르지 않은 경우 900을 반환한다.
+fn lockout_seconds() -> u64 {
+    env::var("LOGIN_LOCKOUT_SECONDS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_LOCKOUT_SECONDS)
+}
+
+/// 비밀번호의 argon2 해시를 PHC 문자열 형식으로 반환하는 메서드이다.
+///
+/// 해시마다 새로 만든 솔트를 사용하므로 같은 비밀번호라도 매번 다른 값이 반환된다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::password::{hash_password, verify_password};
+/// let hash = hash_password("correct horse battery staple");
+/// assert!(hash.starts_with("$argon2id$"));
+/// assert_ne!(hash, hash_password("correct horse battery staple"));
+/// assert!(verify_password("correct horse battery staple", &hash));
+/// assert!(!verify_password("wrong password", &hash));
+/// ```
+pub fn hash_password(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("기본 설정의 argon2는 모든 길이의 비밀번호를 해시할 수 있음")
+        .to_string()
+}
+
+/// 비밀번호가 저장된 argon2 해시와 일치하는지 확인하는 메서드이다.
+///
+/// 해시의 형식이 올바르지 않은 경우 `false`를 반환한다.
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash).is_ok_and(|hash| {
+        Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok()
+    })
+}
+
+/// 가입하지 않은 이메일 주소로 로그인을 시도했을 때 비교할 해시를 반환하는 메서드이다.
+///
+/// 가입한 계정과 같은 시간이 걸리도록 비밀번호를 확인하여, 응답 시간으로 가입 여부를 알아낼 수 없게 한다.
+fn dummy_hash() -> &'static str {
+    DUMMY_HASH.get_or_init(|| hash_password("code_mmunity dummy password"))
+}
+
+/// 이메일과 비밀번호로 가입한 계정을 처리하는 객체이다.
+pub struct PasswordAccount;
+
+impl PasswordAccount {
+    /// 이메일과 비밀번호로 사용자를 등록하고, 새로 만든 사용자의 고유 ID를 반환하는 메서드이다.
+    ///
+    /// `verified`가 `false`인 경우 이메일 주소를 인증하기 전까지 콘텐츠를 작성할 수 없다.
+    /// 다른 사용자가 이미 사용하고 있는 이메일 주소인 경우 `None`을 반환한다.
+    /// 비밀번호를 해시하는 데 시간이 걸리므로 `web::block` 안에서 호출해야 한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn register(request: &RegisterRequest, verified: bool) -> Result<Option<String>> {
+        let password_hash = hash_password(&request.password);
+        db::transaction(|tx| {
+            let exists: Option<String> = tx.exec_first(
+                "select user_id from user where email = :email for update",
+                params! {
+                    "email" => request.email.as_str(),
+                },
+            )?;
+            if exists.is_some() {
+                return Ok(None);
+            }
+            let user_id = format!("{}{}", USER_ID_PREFIX, Uuid::new_v4().simple());
+            let inserted = tx.exec_drop(
+                r"insert into user(user_id, user_name, email, password_hash, verified)
+                values(:user_id, :user_name, :email, :password_hash, :verified)",
+                params! {
+                    "user_id" => user_id.as_str(),
+                    "user_name" => request.user_name.as_str(),
+                    "email" => request.email.as_str(),
+                    "password_hash" => password_hash,
+                    "verified" => verified,
+                },
+            );
+            match inserted {
+                Ok(_) => Ok(Some(user_id)),
+                // 같은 주소로 동시에 가입한 경우 이메일 주소의 고유 인덱스에 의해 중복 키 오류(1062)가 발생한다.
+                Err(Error::MySqlError(ref error)) if error.code == 1062 => Ok(None),
+                Err(error) => Err(error),
+            }
+        })
+    }
+    /// 이메일과 비밀번호를 확인하고 로그인 실패 횟수를 기록하는 메서드이다.
+    ///
+    /// 같은 계정에 대한 로그인 시도는 한 번에 하나씩 처리되므로, 동시에 여러 번 시도하더라도 실패 횟수가 빠짐없이 기록된다.
+    /// 가입하지 않은 이메일 주소인 경우에도 `dummy_hash()`와 비밀번호를 비교하므로 응답 시간이 같다.
+    /// 비밀번호를 확인하는 데 시간이 걸리므로 `web::block` 안에서 호출해야 한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    fn login(request: &LoginRequest) -> Result<Outcome> {
+        db::transaction(|tx| {
+            let account: Option<(String, String, u32, i64)> = tx.exec_first(
+                r"select user_id, password_hash, failed_logins,
+                coalesce(timestampdiff(second, now(), locked_until), 0)
+                from user
+                where email = :email and password_hash is not null and deactivated_at is null
+                for update",
+                params! {
+                    "email" => request.email.as_str(),
+                },
+            )?;
+            let Some((user_id, password_hash, failed_logins, locked_seconds)) = account else {
+                verify_password(&request.password, dummy_hash());
+                return Ok(Outcome::Invalid);
+            };
+            if locked_seconds > 0 {
+                return Ok(Outcome::Locked(locked_seconds as u64));
+            }
+            if verify_password(&request.password, &password_hash) {
+                tx.exec_drop(
+                    "update user set failed_logins = 0, locked_until = null where user_id = :user_id",
+                    params! {
+                        "user_id" => user_id.as_str(),
+                    },
+                )?;
+                return Ok(Outcome::Success(user_id));
+            }
+            if failed_logins + 1 >= max_failures() {
+                let lockout = lockout_seconds();
+                tx.exec_drop(
+                    r"update user set failed_logins = 0,
+                    locked_until = now() + interval :lockout second
+                    where user_id = :user_id",
+                    params! {
+                        "lockout" => lockout,
+                        "user_id" => user_id.as_str(),
+                    },
+                )?;
+                return Ok(Outcome::Locked(lockout));
+            }
+            tx.exec_drop(
+                "update user set failed_logins = failed_logins + 1 where user_id = :user_id",
+                params! {
+                    "user_id" => user_id.as_str(),
+                },
+            )?;
+            Ok(Outcome::Invalid)
+        })
+    }
+    /// `email`로 가입한 계정의 비밀번호 재설정 토큰을 만들어 반환하는 메서드이다.
+    ///
+    /// 이전에 만든 재설정 토큰은 더 이상 사용할 수 없게 된다.
+    /// 해당 이메일 주소로 가입한 비밀번호 계정이 없거나 탈퇴한 계정인 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn issue_reset(email: &str) -> Result<Option<String>> {
+        db::transaction(|tx| {
+            let user_id: Option<String> = tx.exec_first(
+                r"select user_id from user
+                where email = :email and password_hash is not null and deactivated_at is null",
+                params! {
+                    "email" => email,
+                },
+            )?;
+            let Some(user_id) = user_id else {
+                return Ok(None);
+            };
+            tx.exec_drop(
+                "delete from password_reset where user_id = :user_id",
+                params! {
+                    "user_id" => user_id.as_str(),
+                },
+            )?;
+            let token = session::new_token();
+            tx.exec_drop(
+                r"insert into password_reset(token_hash, user_id, expires_at)
+                values(:token_hash, :user_id, now() + interval :ttl second)",
+                params! {
+                    "token_hash" => session::token_hash(&token),
+                    "user_id" => user_id.as_str(),
+                    "ttl" => RESET_TOKEN_TTL_SECONDS,
+                },
+            )?;
+            Ok(Some(token))
+        })
+    }
+    /// 재설정 토큰을 확인하여 비밀번호를 바꾸는 메서드이다.
+    ///
+    /// 비밀번호를 바꾸면 재설정 토큰을 지우고 계정의 잠금을 풀며, 다른 기기에 남아있는 세션도 모두 지운다.
+    /// 토큰 버전도 올리므로 이전에 발급된 인증 토큰은 더 이상 사용할 수 없다.
+    /// 메일로 받은 토큰을 사용했으므로 이메일 주소도 인증된 것으로 처리한다.
+    /// 토큰이 없거나 만료된 경우, 또는 이미 사용한 경우 `false`를 반환한다.
+    /// 비밀번호를 해시하는 데 시간이 걸리므로 `web::block` 안에서 호출해야 한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn reset(request: &ResetRequest) -> Result<bool> {
+        let password_hash = hash_password(&request.password);
+        db::transaction(|tx| {
+            let user_id: Option<String> = tx.exec_first(
+                r"select user_id from password_reset
+                where token_hash = :token_hash and expires_at > now() for update",
+                params! {
+                    "token_hash" => session::token_hash(&request.token),
+                },
+            )?;
+            let Some(user_id) = user_id else {
+                return Ok(false);
+            };
+            tx.exec_drop(
+                "delete from password_reset where user_id = :user_id",
+                params! {
+                    "user_id" => user_id.as_str(),
+                },
+            )?;
+            tx.exec_drop(
+                r"update user set password_hash = :password_hash, failed_logins = 0, locked_until = null,
+                verified = true, token_version = token_version + 1
+                where user_id = :user_id",
+                params! {
+                    "password_hash" => password_hash.as_str(),
+                    "user_id" => user_id.as_str(),
+                },
+            )?;
+            tx.exec_drop(
+                "delete from session where user_id = :user_id",
+                params! {
+                    "user_id" => user_id.as_str(),
+                },
+            )?;
+            Ok(true)
+        })
+    }
+    /// 만료된 비밀번호 재설정 토큰들을 지우는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn purge_expired_resets() -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.query_drop("delete from password_reset where expires_at <= now()")
+    }
+}
+
+#[post("/api/auth/register")]
+pub async fn register_api(
+    request: Json<RegisterRequest>,
+    outbox: web::Data<Outbox>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    request.validate()?;
+    let verified = !verification::is_enabled();
+    let request = request.into_inner();
+    let (address, user_name) = (request.email.clone(), request.user_name.clone());
+    let registered = web::block(move || PasswordAccount::register(&request, verified))
+        .await
+        .map_err(|error| ApiError::Unavailable(error.to_string()))??;
+    match registered {
+        Some(user_id) => {
+            if !verified {
+                email::send_verification(outbox, user_id.clone(), address);
+            }
+            Ok(response::created(
+                format!("/api/users/{}", user_id),
+                serde_json::json!({
+                    "user_id": user_id,
+                    "user_name": user_name,
+                    "verified": verified,
+                }),
+            ))
+        }
+        None => Err(ApiError::Conflict(
+            "이미 가입된 이메일 주소입니다.".to_string(),
+        )),
+    }
+}
+
+#[post("/api/auth/login")]
+pub async fn login_api(request: Json<LoginRequest>) -> std::result::Result<HttpResponse, ApiError> {
+    let request = request.into_inner();
+    let outcome = web::block(move || PasswordAccount::login(&request))
+        .await
+        .map_err(|error| ApiError::Unavailable(error.to_string()))??;
+    match outcome {
+        Outcome::Success(user_id) => {
+            let (token, session) = Session::create(&user_id)?;
+            let body = LoginResponse {
+                token: Identity::issue_token(
+                    &user_id,
+                    User::token_version(&user_id),
+                    session.expires_at.timestamp() as u64,
+                ),
+                user_id: session.user_id,
+                expires_at: session.expires_at,
+            };
+            Ok(HttpResponse::Ok()
+                .cookie(session::cookie(token))
+                .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+                .json(body))
+        }
+        Outcome::Invalid => Err(ApiError::Unauthorized),
+        Outcome::Locked(seconds) => Err(ApiError::TooManyRequests(Duration::from_secs(seconds))),
+    }
+}
+
+#[post("/api/auth/forgot")]
+pub async fn forgot_api(
+    request: Json<ForgotRequest>,
+    outbox: web::Data<Outbox>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if env::var("SMTP_URL").map_or(true, |url| url.is_empty()) {
+        return Err(ApiError::Unavailable(
+            "비밀번호 재설정 메일을 보낼 수 없습니다.".to_string(),
+        ));
+    }
+    if let Some(token) = PasswordAccount::issue_reset(&request.email)? {
+        email::send_password_reset(outbox, request.email.clone(), token);
+    }
+    Ok(HttpResponse::Accepted().finish())
+}
+
+#[post("/api/auth/reset")]
+pub async fn reset_api(request: Json<ResetRequest>) -> std::result::Result<HttpResponse, ApiError> {
+    let mut errors = FieldErrors::default();
+    errors.check_password(&request.password);
+    errors.into_result()?;
+    let request = request.into_inner();
+    let reset = web::block(move || PasswordAccount::reset(&request))
+        .await
+        .map_err(|error| ApiError::Unavailable(error.to_string()))??;
+    if !reset {
+        return Err(ApiError::BadRequest(
+            "재설정 토큰이 올바르지 않거나 만료되었습니다.".to_string(),
+        ));
+    }
+    Ok(response::no_content())
+}
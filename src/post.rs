@@ -6,39 +6,203 @@
 //! `post`를 통해 포스트 목록 요청을 받을 수 있고, 포스트를 받았을 때 처리 방식도
 //! 이곳에서 수행한다.
 
-use crate::user::User;
+use crate::attachment::Attachment;
+use crate::auth::Identity;
+use crate::block::BlockList;
+use crate::cache::Cache;
+use crate::conditional;
+use crate::db;
+use crate::email;
+use crate::error::ApiError;
+use crate::events::PostCounts;
+use crate::fields::FieldSet;
+use crate::highlight::{self, RenderFormat};
+use crate::idempotency::{self, Idempotency};
+use crate::link_preview::{LinkPreview, LinkPreviews};
+use crate::moderation::{self, Moderator};
+use crate::outbox::Outbox;
+use crate::pagination::{Count, Cursor, CursorPage, Pagination};
+use crate::ranking::Ranker;
+use crate::repository::{CommentRepository, PostRepository, UserRepository};
+use crate::response;
+use crate::sanitize;
+use crate::search_index::SearchIndex;
+use crate::shadow_ban::ShadowBans;
+use crate::summary::{self, BodyMode};
+use crate::tag::{Tag, MAX_TAGS};
+use crate::usage::Usage;
+use crate::user::{User, REPUTATION_PER_ACCEPTED_ANSWER};
+use crate::validation::{is_datetime, FieldErrors, MAX_POST_CHARS};
+use crate::webhook::{self, WebhookEvent};
+use crate::word_filter::{ContentKind, WordFilter};
 use actix_web::web::Json;
-use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use actix_web::{delete, get, patch, post, web, HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
 use mysql::prelude::*;
 use mysql::*;
 use serde::{Deserialize, Serialize};
-use std::env;
-use std::path::Path;
+use std::str::FromStr;
+
+/// 포스트 목록(`get_posts`)을 가져오는 질의문이다.
+///
+/// 가장 자주 실행되는 질의이므로 상수로 두어 항상 같은 문자열로 실행되게 한다.
+/// 연결마다 질의문 문자열을 기준으로 준비된 질의(prepared statement)를 캐시하므로 매번 다시 해석되지 않는다.
+pub const FEED_QUERY: &str = r"select post_id, user_id, title, language, data, likes, report_count,
+date_format(create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+date_format(updated_at, '%Y-%m-%d %H:%i:%s')
+from post where published and visibility = 'public' and deleted_at is null order by post_id desc
+limit :limit offset :offset";
+
+/// 삭제된 포스트를 복구할 수 있는 기간(일)이다. 이 기간이 지나면 DB에서 완전히 제거된다.
+const PURGE_AFTER_DAYS: u32 = 30;
+
+/// 포스트의 종류이다.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PostType {
+    /// 코드 조각을 공유하는 포스트
+    #[default]
+    Snippet,
+    /// 질문 포스트로, 작성자가 댓글 하나를 채택된 답변으로 지정할 수 있다.
+    Question,
+    /// 특정 주제에 대해 의견을 나누는 토론 포스트
+    Discussion,
+}
+
+impl PostType {
+    /// DB에 저장되는 값을 반환하는 메서드이다.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Snippet => "snippet",
+            Self::Question => "question",
+            Self::Discussion => "discussion",
+        }
+    }
+}
+
+impl FromStr for PostType {
+    type Err = ();
+
+    /// DB에 저장된 값을 포스트 종류로 변환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::post::PostType;
+    /// assert_eq!("question".parse(), Ok(PostType::Question));
+    /// assert_eq!("discussion".parse(), Ok(PostType::Discussion));
+    /// assert!("unknown".parse::<PostType>().is_err());
+    /// ```
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value {
+            "snippet" => Ok(Self::Snippet),
+            "question" => Ok(Self::Question),
+            "discussion" => Ok(Self::Discussion),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 포스트의 공개 범위이다.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    /// 홈 피드, 검색 등 모든 목록에 나타나는 포스트
+    #[default]
+    Public,
+    /// 목록에는 나타나지 않고 주소를 아는 사용자만 조회할 수 있는 포스트
+    Unlisted,
+    /// 작성자만 조회할 수 있는 포스트
+    Private,
+}
+
+impl Visibility {
+    /// DB에 저장되는 값을 반환하는 메서드이다.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Unlisted => "unlisted",
+            Self::Private => "private",
+        }
+    }
+}
+
+impl FromStr for Visibility {
+    type Err = ();
+
+    /// DB에 저장된 값을 공개 범위로 변환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::post::Visibility;
+    /// assert_eq!("unlisted".parse(), Ok(Visibility::Unlisted));
+    /// assert!("friends".parse::<Visibility>().is_err());
+    /// ```
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value {
+            "public" => Ok(Self::Public),
+            "unlisted" => Ok(Self::Unlisted),
+            "private" => Ok(Self::Private),
+            _ => Err(()),
+        }
+    }
+}
 
 /// 코드뮤니티에 쓰이는 포스트 객체이다.
 ///
 /// 실제로 새 포스트를 생성하려면 생성자인 `new()`를 대신 사용해야한다.  
 /// 만일 DB에서 포스트를 받아오는 경우 `from_db()`를 사용하면 된다.
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct Post {
     /// 포스트의 고유 ID 이다. DB에서 auto_increment에 의해 값이 자동으로 증가한다.
     pub post_id: u64,
     /// 포스트를 작성한 유저의 실제 구분 ID이다.
     pub user_id: String,
     /// 포스트의 제목이다.
+    #[serde(with = "sanitize::escaped")]
     pub title: String,
     /// 포스트를 작성한 유저의 이름이다.
     pub user_name: String,
     /// 포스트에 작성된 프로그래밍 언어 종류이다.
     pub language: String,
     /// 포스트 내용이다.
+    #[serde(with = "sanitize::escaped")]
     pub data: String,
     /// 포스트의 공감 수 이다.
     pub likes: u64,
     /// 포스트가 신고당한 횟수이다.
     pub report_count: u64,
-    /// 포스트가 생성된 날짜이다.
-    pub create_at: String,
+    /// 포스트가 생성된 날짜이다. RFC 3339 형식으로 직렬화된다.
+    pub create_at: DateTime<Utc>,
+    /// 포스트의 종류이다.
+    #[serde(default)]
+    pub post_type: PostType,
+    /// 채택된 답변 댓글의 고유 ID이다. 질문 포스트가 아니거나 채택된 답변이 없는 경우 `None`이다.
+    #[serde(default)]
+    pub accepted_comment_id: Option<u64>,
+    /// 포스트가 마지막으로 수정된 날짜 및 시간이다. 수정된 적이 없는 경우 `None`이다.
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// 포스트에 붙은 태그들이다.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 예약 게시 시각이다. 지정된 경우 해당 시각까지 다른 사용자에게 보이지 않는다.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<String>,
+    /// 포스트가 수정된 횟수이다. 수정을 요청할 때 함께 보내야 한다.
+    #[serde(default)]
+    pub version: u64,
+    /// 연동 API를 통해 봇이 작성한 포스트인지 여부이다. 단일 포스트를 조회할 때만 채워진다.
+    #[serde(default)]
+    pub bot: bool,
+    /// 포스트의 공개 범위이다. 단일 포스트를 조회할 때만 채워지며, 목록에는 공개 포스트만 나타난다.
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// 포스트에 첨부된 파일들이다. 단일 포스트를 조회할 때만 채워진다.
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    /// 포스트 목록에서 내용이 요약되어 잘렸는지 여부이다.
+    #[serde(default)]
+    pub is_truncated: bool,
 }
 
 impl Post {
@@ -50,7 +214,7 @@ impl Post {
     ///
     /// # 예제
     /// 생성자를 통해 포스트 생성하는 예제
-    /// ```
+    /// ```no_run
     /// use code_mmunity_server::post::Post;
     /// let new_post = Post::new(
     ///    "unique_id_for_user".to_string(),
@@ -65,27 +229,48 @@ impl Post {
             user_id: user_id.clone(),
             title,
             language,
-            user_name: User::get_user(user_id).expect("Unknown User").user_name,
+            user_name: User::get_user_name(&user_id).expect("Unknown User"),
             data,
             likes: 0,
             report_count: 0,
-            create_at: "2022-10-11 21:29:30".to_string(),
+            create_at: Utc::now(),
+            post_type: PostType::Snippet,
+            accepted_comment_id: None,
+            updated_at: None,
+            tags: Vec::new(),
+            publish_at: None,
+            version: 0,
+            bot: false,
+            visibility: Visibility::Public,
+            attachments: Vec::new(),
+            is_truncated: false,
         }
     }
     /// DB에서 포스트를 가져올 때 사용하는 메서드이다.
     ///
     /// DB에 존재하는 포스트를 가져올 때 사용되므로 `Post`테이블에 존재하는 모든 속성을 인자로 사용한다.
-    /// `user_name`의 경우 `User`테이블을 통해 가져오도록 처리한다. DB에서 받아온 각 속성을 받아서 처리하는 메서드이기 때문에
+    /// `user_name`의 경우 `User`테이블을, `tags`의 경우 `post_tag`테이블을 통해 가져오도록 처리한다. DB에서 받아온 각 속성을 받아서 처리하는 메서드이기 때문에
     /// 해당 메서드를 사용하므로써 DB에 접속되지 않는다.
     ///
     /// # 예제
     /// Sql 쿼리를 통해 단일 포스트를 가져오는 예제
-    /// ```
+    /// ```no_run
+    /// # use code_mmunity_server::db;
+    /// # use code_mmunity_server::post::Post;
+    /// # use mysql::prelude::*;
+    /// # use mysql::*;
+    /// # let post_id = 1;
+    /// # let mut conn = db::get_read_conn();
     /// let result = conn
-    /// .query_first(format!("select * from post where post_id={}", post_id))
+    /// .exec_first(
+    ///     r"select post_id, user_id, title, language, data, likes, report_count,
+    ///     date_format(create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+    ///     date_format(updated_at, '%Y-%m-%d %H:%i:%s') from post where post_id = :post_id",
+    ///     params! { "post_id" => post_id },
+    /// )
     /// .unwrap()
     /// .map(
-    ///     |(post_id, user_id, title, language, data, likes, report_count, create_at)| {
+    ///     |(post_id, user_id, title, language, data, likes, report_count, create_at, post_type, accepted_comment_id, updated_at)| {
     ///          Post::from_db(
     ///              post_id,
     ///              user_id,
@@ -95,6 +280,9 @@ impl Post {
     ///              likes,
     ///              report_count,
     ///              create_at,
+    ///              post_type,
+    ///              accepted_comment_id,
+    ///              updated_at,
     ///          )
     ///      },
     ///  );
@@ -103,6 +291,7 @@ impl Post {
     /// # Panics
     ///
     /// `Post`의 `user_id`가 유효한 사용자 고유 ID가 아닌 경우 패닉이 발생한다.
+    #[allow(clippy::too_many_arguments)]
     pub fn from_db(
         post_id: u64,
         user_id: String,
@@ -112,26 +301,43 @@ impl Post {
         likes: u64,
         report_count: u64,
         create_at: String,
+        post_type: String,
+        accepted_comment_id: Option<u64>,
+        updated_at: Option<String>,
     ) -> Self {
         Self {
             post_id,
             user_id: user_id.clone(),
             title,
             language,
-            user_name: User::get_user(user_id).expect("Unknown User").user_name,
+            user_name: User::get_user_name(&user_id).expect("Unknown User"),
             data,
             likes,
             report_count,
-            create_at,
+            create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+            post_type: post_type.parse().unwrap_or_default(),
+            accepted_comment_id,
+            updated_at: updated_at.as_deref().and_then(db::parse_datetime),
+            tags: Tag::get_names(post_id),
+            publish_at: None,
+            version: 0,
+            bot: false,
+            visibility: Visibility::Public,
+            attachments: Vec::new(),
+            is_truncated: false,
         }
     }
-    /// DB에 존재하는 모든 포스트를 반환하는 메서드이다.
+    /// DB에 존재하는 포스트를 최신순으로 반환하는 메서드이다.
     ///
-    /// DB에 모든 포스트를 요청하는 질의문을 수행 후 반환된 값 들을 `Vec<Post>`형태로 반환한다.
+    /// DB에 포스트를 요청하는 질의문을 수행 후 반환된 값 들을 `Vec<Post>`형태로 반환한다.
+    /// `page`를 통해 반환할 페이지를 지정할 수 있다.
     /// # 예제
     /// 포스트들을 최신순으로 가져오는 예시
-    /// ```
-    /// let posts = Post::get_posts(PostOrder::Recent);
+    /// ```no_run
+    /// # use code_mmunity_server::pagination::Pagination;
+    /// # use code_mmunity_server::post::Post;
+    /// let page = Pagination { page: Some(1), per_page: Some(20) };
+    /// let posts = Post::get_posts(&page);
     /// for post in &posts {
     ///     println!("요청한 포스트의 제목은 {}이며, 작성자는 {} 입니다.", post.title, post.user_name);
     /// }
@@ -143,43 +349,280 @@ impl Post {
     /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
-    pub fn get_posts() -> Vec<Self> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
-        conn
-        .query_map(
-            "select post_id, user_id, title, language, substr(data, 1, 35), likes, report_count, create_at from post order by post_id desc",
-            |(post_id, user_id, title, language, data, likes, report_count, create_at)| Post::from_db(post_id, user_id, title, language, data, likes, report_count, create_at)
+    pub fn get_posts(page: &Pagination) -> Vec<Self> {
+        let mut conn = db::get_read_conn();
+        conn.exec_map(
+            FEED_QUERY,
+            params! {
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(
+                post_id,
+                user_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+            )| {
+                Post::from_db(
+                    post_id,
+                    user_id,
+                    title,
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at,
+                    post_type,
+                    accepted_comment_id,
+                    updated_at,
+                )
+            },
+        )
+        .unwrap()
+    }
+    /// DB에 존재하는 포스트를 커서 방식으로 최신순으로 반환하는 메서드이다.
+    ///
+    /// `cursor.after`보다 작은 ID의 포스트부터 반환하므로, 포스트가 많아도 앞 페이지를 건너뛰는 비용이 들지 않는다.
+    /// `tag`를 지정한 경우 해당 태그가 붙은 포스트만 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_posts_after(tag: Option<&str>, cursor: &Cursor) -> Vec<Self> {
+        let mut conn = db::get_read_conn();
+        conn.exec_map(
+            r"select post_id, user_id, title, language, data, likes, report_count,
+            date_format(create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+            date_format(updated_at, '%Y-%m-%d %H:%i:%s')
+            from post where published and visibility = 'public' and deleted_at is null
+            and (:after is null or post_id < :after)
+            and (:tag is null or exists (select 1 from post_tag join tag on post_tag.tag_id = tag.tag_id
+                where post_tag.post_id = post.post_id and tag.name = :tag))
+            order by post_id desc limit :limit",
+            params! {
+                "after" => cursor.after,
+                "tag" => tag,
+                "limit" => cursor.limit(),
+            },
+            |(
+                post_id,
+                user_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+            )| {
+                Post::from_db(
+                    post_id,
+                    user_id,
+                    title,
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at,
+                    post_type,
+                    accepted_comment_id,
+                    updated_at,
+                )
+            },
+        )
+        .unwrap()
+    }
+    /// 게시된 포스트의 수를 반환하는 메서드이다.
+    ///
+    /// `tag`를 지정한 경우 해당 태그가 붙은 포스트만 센다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn count(tag: Option<&str>) -> u64 {
+        let mut conn = db::get_read_conn();
+        conn.exec_first(
+            r"select count(*) from post where published and visibility = 'public' and deleted_at is null
+            and (:tag is null or exists (select 1 from post_tag join tag on post_tag.tag_id = tag.tag_id
+                where post_tag.post_id = post.post_id and tag.name = :tag))",
+            params! {
+                "tag" => tag,
+            },
+        )
+        .unwrap()
+        .unwrap_or_default()
+    }
+    /// 사용자가 작성한 모든 포스트의 고유 ID들을 반환하는 메서드이다.
+    ///
+    /// 게시되지 않았거나 삭제된 포스트도 포함된다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_ids_by_user(user_id: &str) -> Vec<u64> {
+        let mut conn = db::get_read_conn();
+        conn.exec(
+            "select post_id from post where user_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+    }
+    /// 사용자가 공감한 포스트들을 반환하는 메서드이다.
+    ///
+    /// `react` 테이블에 기록된 사용자의 공감 내역을 통해 포스트들을 최신순으로 가져온다.
+    /// `page`를 통해 반환할 페이지를 지정할 수 있다.
+    /// # 예제
+    /// ```ignore
+    /// let page = Pagination { page: Some(1), per_page: Some(20) };
+    /// for post in Post::get_liked_posts("unique_id_for_user", &page) {
+    ///     println!("공감한 포스트의 제목은 {} 입니다.", post.title);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_liked_posts(user_id: &str, page: &Pagination) -> Vec<Self> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select post.post_id, post.user_id, title, language, data, likes, report_count,
+            date_format(post.create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+            date_format(post.updated_at, '%Y-%m-%d %H:%i:%s')
+            from react join post on react.post_id = post.post_id
+            where react.user_id = :user_id and react.react_kind = 'like' and post.deleted_at is null
+            and (post.visibility <> 'private' or post.user_id = :user_id)
+            order by post.post_id desc
+            limit :limit offset :offset",
+            params! {
+                "user_id" => user_id,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(
+                post_id,
+                user_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+            )| {
+                Post::from_db(
+                    post_id,
+                    user_id,
+                    title,
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at,
+                    post_type,
+                    accepted_comment_id,
+                    updated_at,
+                )
+            },
+        )
+        .unwrap()
+    }
+    /// 사용자에게 맞춘 포스트들을 반환하는 메서드이다.
+    ///
+    /// 팔로우한 사용자가 작성했거나 구독한 언어로 작성된 포스트들 중에서 점수가 높은 순으로 가져온다.
+    /// 점수는 팔로우한 사용자의 포스트인 경우 2점, 구독한 언어의 포스트인 경우 1점에 공감 수의 로그 값을 더한 뒤
+    /// 작성된 지 오래될수록 낮아지도록 계산한다. 사용자 자신이 작성한 포스트는 제외된다.
+    /// # 예제
+    /// ```ignore
+    /// let page = Pagination { page: Some(1), per_page: Some(20) };
+    /// for post in Post::get_personalized("unique_id_for_user", &page) {
+    ///     println!("추천 포스트의 제목은 {} 입니다.", post.title);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_personalized(user_id: &str, page: &Pagination) -> Vec<Self> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select post_id, user_id, title, language, data, likes, report_count, create_at, post_type, accepted_comment_id,
+            updated_at
+            from (
+                select post.post_id, post.user_id, title, post.language, data, likes, report_count,
+                date_format(post.create_at, '%Y-%m-%d %H:%i:%s') as create_at, post_type, accepted_comment_id,
+                date_format(post.updated_at, '%Y-%m-%d %H:%i:%s') as updated_at,
+                (2 * (follow.followee_id is not null) + (language_subscription.language is not null) + ln(1 + likes))
+                    / pow(timestampdiff(hour, post.create_at, now()) + 2, 1.5) as score
+                from post
+                left join follow on follow.follower_id = :user_id and follow.followee_id = post.user_id
+                left join language_subscription on language_subscription.user_id = :user_id
+                    and language_subscription.language = lower(post.language)
+                where post.published and post.visibility = 'public' and post.deleted_at is null
+                    and post.user_id <> :user_id
+                    and (follow.followee_id is not null or language_subscription.language is not null)
+            ) as ranked
+            order by score desc, post_id desc
+            limit :limit offset :offset",
+            params! {
+                "user_id" => user_id,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(
+                post_id,
+                user_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+            )| {
+                Post::from_db(
+                    post_id,
+                    user_id,
+                    title,
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at,
+                    post_type,
+                    accepted_comment_id,
+                    updated_at,
+                )
+            },
         )
         .unwrap()
     }
@@ -187,8 +630,14 @@ impl Post {
     ///
     /// 찾고자 하는 포스트가 존재하는 경우와 그렇지 않은 경우의 예외 처리를 할 수 있도록
     /// `Option<Post>`로 값을 반환한다.
+    /// `post_id`는 질의문에 직접 넣지 않고 매개변수로 전달하므로 SQL문으로 해석되지 않는다.
+    /// MySQL은 숫자와 비교하는 문자열의 앞부분만 숫자로 바꾸므로, `post_id`가 숫자가 아닌 경우 DB를 조회하지 않고 `None`을 반환한다.
+    /// 공개 범위와 관계없이 반환하므로, 작성자가 아닌 사용자에게 보여줄 때는 `is_visible_to()`로 확인해야 한다.
     /// # 예제
-    /// ```
+    /// ```no_run
+    /// # use actix_web::web;
+    /// # use code_mmunity_server::post::Post;
+    /// # let post_id = web::Path::from("1".to_string());
     /// let post = Post::get_post(post_id);
     /// match post {
     ///     Some(result) => println!("요청한 포스트의 제목은 {}이며, 작성자는 {} 입니다.", result.title, result.user_name),
@@ -202,43 +651,36 @@ impl Post {
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
     pub fn get_post(post_id: web::Path<String>) -> Option<Self> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
+        let post_id: u64 = post_id.parse().ok()?;
+        let mut conn = db::get_read_conn();
+        let post: Option<Self> = conn
+            .exec_first(
+                r"select post_id, user_id, title, language, data, likes, report_count,
+                date_format(create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+                date_format(updated_at, '%Y-%m-%d %H:%i:%s'), version
+                from post where post_id = :post_id and deleted_at is null",
+                params! {
+                    "post_id" => post_id,
+                },
             )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
-        conn.query_first(format!("select * from post where post_id={}", post_id))
             .unwrap()
             .map(
-                |(post_id, user_id, title, language, data, likes, report_count, create_at)| {
-                    Post::from_db(
+                |(
+                    post_id,
+                    user_id,
+                    title,
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at,
+                    post_type,
+                    accepted_comment_id,
+                    updated_at,
+                    version,
+                )| Post {
+                    version,
+                    ..Post::from_db(
                         post_id,
                         user_id,
                         title,
@@ -247,83 +689,79 @@ impl Post {
                         likes,
                         report_count,
                         create_at,
+                        post_type,
+                        accepted_comment_id,
+                        updated_at,
                     )
                 },
-            )
+            );
+        post.map(|post| {
+            let (bot, visibility): (bool, String) = conn
+                .exec_first(
+                    "select bot, visibility from post where post_id = :post_id",
+                    params! {
+                        "post_id" => post.post_id,
+                    },
+                )
+                .unwrap()
+                .unwrap_or_default();
+            Post {
+                bot,
+                visibility: visibility.parse().unwrap_or_default(),
+                attachments: Attachment::list(post.post_id),
+                ..post
+            }
+        })
     }
-    /// 포스트 객체를 DB에 삽입하는 메서드이다.
+    /// `viewer_id` 사용자가 포스트를 조회할 수 있는지 확인하는 메서드이다.
     ///
-    /// Sql명령이 정상적으로 작동되지 않은 경우에 예외 처리를 할 수 있도록
-    /// `Result<()>`로 값을 반환한다.
+    /// 비공개 포스트는 작성자만 조회할 수 있으며, 공개 및 목록 비공개 포스트는 누구나 조회할 수 있다.
     /// # 예제
     /// ```
-    /// use code_mmunity_server::post::Post;
-    /// let new_post = Post::new(
-    ///    "unique_id_for_user".to_string(),
-    ///    "Post Title".to_string(),
-    ///    "rust".to_string(),
-    ///    "Rust is awsome".to_string(),
-    /// );
-    /// new_post.insert_post().expect("Sql작업 중 문제가 발생하였습니다.")
+    /// use code_mmunity_server::post::{Post, Visibility};
+    /// let post: Post = serde_json::from_value(serde_json::json!({
+    ///     "post_id": 1, "user_id": "u1", "title": "Hello", "user_name": "sun30812", "language": "rust",
+    ///     "data": "fn main() {}", "likes": 0, "report_count": 0, "create_at": "2022-01-01T00:00:00Z",
+    ///     "visibility": "private"
+    /// })).unwrap();
+    /// assert!(post.is_visible_to(Some("u1")));
+    /// assert!(!post.is_visible_to(Some("u2")));
+    /// assert!(!post.is_visible_to(None));
+    /// let post = Post { visibility: Visibility::Unlisted, ..post };
+    /// assert!(post.is_visible_to(None));
     /// ```
+    pub fn is_visible_to(&self, viewer_id: Option<&str>) -> bool {
+        self.visibility != Visibility::Private || viewer_id == Some(self.user_id.as_str())
+    }
+    /// 포스트의 조회 수를 1 증가시키는 메서드이다.
+    ///
+    /// 조회 수는 인기 포스트 점수를 계산할 때 사용된다.
+    ///
     /// # Panics
     ///
     /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
     /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
-    pub fn insert_db(self) -> Result<()> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
+    pub fn record_view(post_id: u64) {
+        let mut conn = db::get_conn();
         conn.exec_drop(
-            r"insert into post(user_id, title, language, data, likes, report_count)
-        values(:user_id, :title, :language, :data, :likes, :report_count)",
+            "update post set views = views + 1 where post_id = :post_id",
             params! {
-                "user_id" => self.user_id,
-                "title" => self.title,
-                "language" => self.language,
-                "data" => self.data,
-                "likes" => self.likes,
-                "report_count" => self.report_count,
+                "post_id" => post_id,
             },
         )
+        .unwrap();
     }
-    /// 포스트 객체를 DB에서 제거하는 메서드이다.
+    /// 포스트 객체를 DB에 삽입하는 메서드이다.
     ///
+    /// 포스트와 태그는 하나의 트랜잭션으로 함께 저장된다.
+    /// 작성 시각은 DB의 현재 시각으로 저장되며, DB에서 부여된 `post_id`와 `create_at`이 채워진
+    /// 포스트 객체를 반환한다.
     /// Sql명령이 정상적으로 작동되지 않은 경우에 예외 처리를 할 수 있도록
-    /// `Result<()>`로 값을 반환한다.
+    /// `Result<Post>`로 값을 반환한다.
     /// # 예제
-    /// ```
+    /// ```no_run
     /// use code_mmunity_server::post::Post;
     /// let new_post = Post::new(
     ///    "unique_id_for_user".to_string(),
@@ -331,8 +769,8 @@ impl Post {
     ///    "rust".to_string(),
     ///    "Rust is awsome".to_string(),
     /// );
-    /// let trash_post_request = DeletePostRequest { user_id: "unique_user_id".to_string(), post_id: "unique_post_id".to_string() };
-    /// Post::delete_post(trash_post_request).expect("작업 중 문제가 발생하였습니다.")
+    /// let post = new_post.insert_db().expect("Sql작업 중 문제가 발생하였습니다.");
+    /// println!("{}번 포스트가 {}에 작성되었습니다.", post.post_id, post.create_at);
     /// ```
     /// # Panics
     ///
@@ -340,109 +778,1023 @@ impl Post {
     /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
-    pub fn delete_post(request: web::Query<DeletePostRequest>) -> Result<()> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
+    pub fn insert_db(mut self) -> Result<Self> {
+        db::transaction(|tx| {
+            tx.exec_drop(
+                r"insert into post(user_id, title, language, data, likes, report_count, post_type, visibility, publish_at, published, bot, create_at)
+            values(:user_id, :title, :language, :data, :likes, :report_count, :post_type, :visibility, :publish_at,
+            :publish_at is null or :publish_at <= now(), :bot, now())",
+                params! {
+                    "user_id" => &self.user_id,
+                    "title" => &self.title,
+                    "language" => &self.language,
+                    "data" => &self.data,
+                    "likes" => self.likes,
+                    "report_count" => self.report_count,
+                    "post_type" => self.post_type.as_str(),
+                    "visibility" => self.visibility.as_str(),
+                    "publish_at" => &self.publish_at,
+                    "bot" => self.bot,
+                },
+            )?;
+            if let Some(post_id) = tx.last_insert_id() {
+                Tag::attach(tx, post_id, &self.tags)?;
+                self.post_id = post_id;
+                let create_at: Option<String> = tx.exec_first(
+                    "select date_format(create_at, '%Y-%m-%d %H:%i:%s') from post where post_id = :post_id",
+                    params! {
+                        "post_id" => post_id,
+                    },
+                )?;
+                if let Some(create_at) = create_at.as_deref().and_then(db::parse_datetime) {
+                    self.create_at = create_at;
                 }
             }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
+            Ok(self)
+        })
+    }
+    /// 포스트를 삭제된 상태로 바꾸는 메서드이다.
+    ///
+    /// 삭제된 포스트는 조회되지 않으며, 30일 안에 `restore_post()`로 복구할 수 있다.
+    /// 30일이 지난 포스트는 `purge_deleted()`를 통해 DB에서 완전히 제거된다.
+    /// Sql명령이 정상적으로 작동되지 않은 경우에 예외 처리를 할 수 있도록
+    /// `Result<()>`로 값을 반환한다.
+    /// # 예제
+    /// ```no_run
+    /// use actix_web::web;
+    /// use code_mmunity_server::post::{DeletePostRequest, Post};
+    /// let trash_post_request = DeletePostRequest { post_id: "unique_post_id".to_string() };
+    /// Post::delete_post(web::Query(trash_post_request)).expect("작업 중 문제가 발생하였습니다.")
+    /// ```
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn delete_post(request: web::Query<DeletePostRequest>) -> Result<()> {
+        let mut conn = db::get_conn();
         conn.exec_drop(
-            "delete from post where user_id = :user_id and post_id = :post_id",
+            "update post set deleted_at = now() where post_id = :post_id and deleted_at is null",
             params! {
-                "user_id" => request.user_id.clone(),
                 "post_id" => request.post_id.clone(),
             },
         )
     }
+    /// 삭제된 포스트를 작성한 사용자의 고유 ID를 반환하는 메서드이다.
+    ///
+    /// 존재하지 않거나 삭제되지 않은 포스트의 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_deleted_owner(post_id: u64) -> Option<String> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            "select user_id from post where post_id = :post_id and deleted_at is not null",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()
+    }
+    /// 삭제된 포스트를 복구하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn restore_post(post_id: u64) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "update post set deleted_at = null where post_id = :post_id",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+    }
+    /// 삭제된 지 30일이 지난 포스트들을 DB에서 완전히 제거하는 메서드이다.
+    ///
+    /// 포스트에 달린 공감, 댓글, 북마크, 태그, 수정 기록, 단축 링크도 함께 제거된다.
+    /// `scheduler`를 통해 주기적으로 호출된다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn purge_deleted() -> Result<()> {
+        let mut conn = db::get_conn();
+        let mut tx = conn.start_transaction(TxOpts::default())?;
+        for table in [
+            "react",
+            "comment",
+            "bookmark",
+            "post_tag",
+            "post_revision",
+            "shortlink",
+            "attachment",
+        ] {
+            tx.exec_drop(
+                format!(
+                    r"delete from {} where post_id in
+                    (select post_id from post where deleted_at < now() - interval :days day)",
+                    table
+                ),
+                params! {
+                    "days" => PURGE_AFTER_DAYS,
+                },
+            )?;
+        }
+        tx.exec_drop(
+            "delete from post where deleted_at < now() - interval :days day",
+            params! {
+                "days" => PURGE_AFTER_DAYS,
+            },
+        )?;
+        tx.commit()
+    }
+    /// 포스트의 공감 수와 댓글 수를 반환하는 메서드이다.
+    ///
+    /// 존재하지 않는 포스트의 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_counts(post_id: u64) -> Option<PostCounts> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            r"select likes, (select count(*) from comment where comment.post_id = post.post_id)
+            from post where post_id = :post_id and deleted_at is null",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()
+        .map(|(likes, comments)| PostCounts {
+            post_id,
+            likes,
+            comments,
+        })
+    }
+    /// 포스트를 작성한 사용자의 고유 ID를 반환하는 메서드이다.
+    ///
+    /// 포스트를 수정하거나 삭제하기 전에 요청한 사용자가 작성자인지 확인할 때 사용한다.
+    /// 존재하지 않는 포스트의 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_owner(post_id: &str) -> Option<String> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            "select user_id from post where post_id = :post_id and deleted_at is null",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()
+    }
+    /// 포스트가 게시되었는지 확인하는 메서드이다.
+    ///
+    /// 예약 게시 시각이 지나지 않은 포스트나 존재하지 않는 포스트의 경우 `false`를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn is_published(post_id: u64) -> bool {
+        let mut conn = db::get_conn();
+        conn.exec_first::<bool, _, _>(
+            "select published from post where post_id = :post_id",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()
+        .unwrap_or(false)
+    }
+    /// 예약 게시 시각이 지난 포스트들을 게시하는 메서드이다.
+    ///
+    /// 게시된 포스트의 작성 시각은 예약 게시 시각으로 바뀌며, 게시된 포스트들은 검색 색인에도 반영된다.
+    /// `scheduler`를 통해 주기적으로 호출된다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn publish_scheduled(search_index: &SearchIndex) {
+        let published = db::transaction(|tx| {
+            let post_ids: Vec<u64> = tx.query(
+                r"select post_id from post
+                where not published and publish_at <= now() and moderation = 'approved'
+                for update",
+            )?;
+            for post_id in &post_ids {
+                tx.exec_drop(
+                    "update post set published = true, create_at = publish_at where post_id = :post_id",
+                    params! {
+                        "post_id" => post_id,
+                    },
+                )?;
+            }
+            Ok(post_ids)
+        })
+        .unwrap();
+        for post_id in published {
+            search_index.sync_now(post_id);
+        }
+    }
+    /// 포스트의 종류를 반환하는 메서드이다.
+    ///
+    /// 존재하지 않는 포스트의 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_post_type(post_id: u64) -> Option<PostType> {
+        let mut conn = db::get_conn();
+        conn.exec_first::<String, _, _>(
+            "select post_type from post where post_id = :post_id and deleted_at is null",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()
+        .map(|post_type| post_type.parse().unwrap_or_default())
+    }
+    /// 포스트의 공개 범위를 반환하는 메서드이다.
+    ///
+    /// 존재하지 않는 포스트의 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_visibility(post_id: u64) -> Option<Visibility> {
+        let mut conn = db::get_conn();
+        conn.exec_first::<String, _, _>(
+            "select visibility from post where post_id = :post_id",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()
+        .map(|visibility| visibility.parse().unwrap_or_default())
+    }
+    /// 질문 포스트의 채택된 답변을 지정하거나 취소하는 메서드이다.
+    ///
+    /// `comment_id`가 `None`인 경우 채택을 취소한다. 답변을 작성한 사용자의 평판 점수도 함께 변경되며,
+    /// 질문 작성자가 자신의 댓글을 채택한 경우에는 평판 점수가 변하지 않는다.
+    /// 댓글이 해당 포스트에 달린 댓글인지는 호출하는 쪽에서 확인해야 한다.
+    /// # 예제
+    /// ```ignore
+    /// Post::set_accepted_answer(1, Some(3)).expect("Sql작업 중 문제가 발생하였습니다.");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn set_accepted_answer(post_id: u64, comment_id: Option<u64>) -> Result<()> {
+        let mut conn = db::get_conn();
+        let mut tx = conn.start_transaction(TxOpts::default())?;
+        let previous: Option<Option<u64>> = tx.exec_first(
+            "select accepted_comment_id from post where post_id = :post_id for update",
+            params! {
+                "post_id" => post_id,
+            },
+        )?;
+        let previous = previous.flatten();
+        if previous == comment_id {
+            return tx.commit();
+        }
+        let changes = [
+            (previous, -REPUTATION_PER_ACCEPTED_ANSWER),
+            (comment_id, REPUTATION_PER_ACCEPTED_ANSWER),
+        ];
+        for (answer_id, delta) in changes {
+            if let Some(answer_id) = answer_id {
+                tx.exec_drop(
+                    r"update user join comment on user.user_id = comment.user_id join post on comment.post_id = post.post_id
+                    set user.reputation = user.reputation + :delta
+                    where comment.comment_id = :comment_id and comment.user_id <> post.user_id",
+                    params! {
+                        "delta" => delta,
+                        "comment_id" => answer_id,
+                    },
+                )?;
+            }
+        }
+        tx.exec_drop(
+            "update post set accepted_comment_id = :comment_id where post_id = :post_id",
+            params! {
+                "comment_id" => comment_id,
+                "post_id" => post_id,
+            },
+        )?;
+        tx.commit()
+    }
+    /// 포스트의 제목, 언어, 내용, 공개 범위를 수정하는 메서드이다.
+    ///
+    /// `request`에서 값이 주어진 항목만 수정된다.
+    /// 수정하기 전의 제목, 언어, 내용은 `post_revision` 테이블에 수정 이력으로 저장된다.
+    /// `request`의 `version`이 DB에 저장된 값과 다른 경우 다른 곳에서 먼저 수정된 것이므로
+    /// 수정하지 않고 `false`를 반환한다.
+    /// Sql명령이 정상적으로 작동되지 않은 경우에 예외 처리를 할 수 있도록
+    /// `Result<bool>`로 값을 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// let request = UpdatePostRequest {
+    ///     title: Some("New Title".to_string()),
+    ///     language: None,
+    ///     data: None,
+    ///     visibility: None,
+    ///     version: 0,
+    /// };
+    /// if !Post::update_post("1", request).expect("Sql작업 중 문제가 발생하였습니다.") {
+    ///     println!("다른 곳에서 먼저 수정된 포스트입니다.");
+    /// }
+    /// ```
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn update_post(post_id: &str, request: UpdatePostRequest) -> Result<bool> {
+        let mut conn = db::get_conn();
+        let mut tx = conn.start_transaction(TxOpts::default())?;
+        let version: Option<u64> = tx.exec_first(
+            "select version from post where post_id = :post_id for update",
+            params! {
+                "post_id" => post_id,
+            },
+        )?;
+        if version != Some(request.version) {
+            return Ok(false);
+        }
+        tx.exec_drop(
+            r"insert into post_revision(post_id, revision, title, language, data)
+            select post_id, (select coalesce(max(revision), 0) + 1 from post_revision where post_id = :post_id),
+            title, language, data
+            from post where post_id = :post_id",
+            params! {
+                "post_id" => post_id,
+            },
+        )?;
+        tx.exec_drop(
+            r"update post
+            set title = coalesce(:title, title), language = coalesce(:language, language), data = coalesce(:data, data),
+            visibility = coalesce(:visibility, visibility), version = version + 1, updated_at = now()
+            where post_id = :post_id",
+            params! {
+                "title" => request.title,
+                "language" => request.language,
+                "data" => request.data,
+                "visibility" => request.visibility.map(|visibility| visibility.as_str()),
+                "post_id" => post_id,
+            },
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
 }
 
 /// JSON 을 통해 새로 등록해야 할 포스트를 받을 때 필요한 구조체이다.
 #[derive(Deserialize, Serialize)]
 pub struct PostRequest {
-    user_id: String,
-    title: String,
-    language: String,
-    data: String,
+    /// 포스트를 작성한 사용자의 고유 ID이다. 인증된 사용자로 채워지며 요청 본문으로는 지정할 수 없다.
+    #[serde(skip_deserializing)]
+    pub user_id: String,
+    /// 포스트의 제목이다.
+    pub title: String,
+    /// 포스트에 작성된 프로그래밍 언어 종류이다.
+    pub language: String,
+    /// 포스트 내용이다.
+    pub data: String,
+    /// 포스트의 종류이다. 주어지지 않은 경우 코드 조각 포스트가 된다.
+    #[serde(default)]
+    pub post_type: PostType,
+    /// 포스트에 붙일 태그들이다.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 포스트의 공개 범위이다. 주어지지 않은 경우 공개 포스트가 된다.
+    #[serde(default)]
+    pub visibility: Visibility,
+    /// 예약 게시 시각(`YYYY-MM-DD HH:MM:SS`)이다. 주어지지 않은 경우 바로 게시된다.
+    #[serde(default)]
+    pub publish_at: Option<String>,
+    /// 연동 API를 통해 봇이 작성한 포스트인지 여부이다. 요청 본문으로는 지정할 수 없다.
+    #[serde(skip)]
+    pub bot: bool,
+}
+
+impl PostRequest {
+    /// 요청받은 포스트의 입력값이 조건에 맞는지 확인하고, 정규화된 태그들을 반환하는 메서드이다.
+    ///
+    /// 제목(1~120자), 내용, 언어, 사용자 ID, 태그, 예약 게시 시각 중 조건에 맞지 않는 항목이 있는 경우
+    /// 항목별 이유를 담은 `ApiError::InvalidFields`를 반환한다.
+    /// 저장소마다 같은 규칙으로 포스트를 검증하도록 공통으로 사용한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::post::{PostRequest, PostType, Visibility};
+    /// let request = PostRequest {
+    ///     user_id: "u1".to_string(),
+    ///     title: "Hello".to_string(),
+    ///     language: "rust".to_string(),
+    ///     data: "fn main() {}".to_string(),
+    ///     post_type: PostType::Snippet,
+    ///     tags: vec!["Rust".to_string()],
+    ///     visibility: Visibility::Public,
+    ///     publish_at: None,
+    ///     bot: false,
+    /// };
+    /// assert_eq!(request.validate().unwrap(), vec!["rust"]);
+    /// let request = PostRequest { title: " ".to_string(), language: "cobol".to_string(), ..request };
+    /// let details = request.validate().unwrap_err().details().unwrap();
+    /// assert!(details.get("title").is_some());
+    /// assert!(details.get("language").is_some());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<Vec<String>, ApiError> {
+        let mut errors = FieldErrors::default();
+        errors.check_user_id(&self.user_id);
+        errors.check_title(&self.title);
+        errors.check_data(&self.data, MAX_POST_CHARS);
+        errors.check_language(&self.language);
+        let tags = Tag::normalize_all(&self.tags);
+        errors.check(
+            "tags",
+            tags.is_some(),
+            format!("태그는 공백 없이 최대 {}개까지 붙일 수 있습니다.", MAX_TAGS),
+        );
+        errors.check(
+            "publish_at",
+            self.publish_at.as_deref().is_none_or(is_datetime),
+            "publish_at은 YYYY-MM-DD HH:MM:SS 형식이어야 합니다.",
+        );
+        errors.into_result()?;
+        Ok(tags.unwrap_or_default())
+    }
+    /// 요청받은 포스트를 검증한 뒤 DB에 등록하고, 등록된 포스트를 반환하는 메서드이다.
+    ///
+    /// REST API와 GraphQL API에서 같은 규칙으로 포스트를 작성하도록 공통으로 사용한다.
+    /// 등록된 포스트는 `post_created` 이벤트를 구독한 웹훅으로 알린다.
+    /// 이용 정지되었거나 비활성화된 사용자이거나 입력값이 조건에 맞지 않는 경우 그 이유를 `ApiError`로 반환한다.
+    pub fn submit(&self) -> std::result::Result<Post, ApiError> {
+        if User::is_restricted(&self.user_id)? {
+            return Err(ApiError::Forbidden);
+        }
+        let tags = self.validate()?;
+        if !Usage::can_post(&self.user_id, (self.title.len() + self.data.len()) as u64) {
+            return Err(ApiError::PayloadTooLarge);
+        }
+        let mut new_post = Post::new(
+            self.user_id.clone(),
+            self.title.clone(),
+            self.language.clone(),
+            self.data.clone(),
+        );
+        new_post.post_type = self.post_type;
+        new_post.tags = tags;
+        new_post.visibility = self.visibility;
+        new_post.publish_at = self.publish_at.clone();
+        new_post.bot = self.bot;
+        let post = new_post.insert_db()?;
+        webhook::emit(WebhookEvent::PostCreated, &post);
+        Ok(post)
+    }
+}
+
+/// JSON 을 통해 포스트의 수정할 내용을 받을 때 필요한 구조체이다.
+///
+/// 값이 주어지지 않은 항목은 수정되지 않는다.
+#[derive(Deserialize)]
+pub struct UpdatePostRequest {
+    /// 포스트의 새 제목이다.
+    pub title: Option<String>,
+    /// 포스트의 새 프로그래밍 언어 종류이다.
+    pub language: Option<String>,
+    /// 포스트의 새 내용이다.
+    pub data: Option<String>,
+    /// 포스트의 새 공개 범위이다.
+    #[serde(default)]
+    pub visibility: Option<Visibility>,
+    /// 수정을 시작할 때 받은 포스트의 `version`이다.
+    pub version: u64,
+}
+
+impl UpdatePostRequest {
+    /// 수정할 항목들이 포스트를 작성할 때와 같은 조건에 맞는지 확인하는 메서드이다.
+    ///
+    /// 조건에 맞지 않는 항목이 있는 경우 항목별 이유를 담은 `ApiError::InvalidFields`를 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::post::UpdatePostRequest;
+    /// let request = UpdatePostRequest {
+    ///     title: None,
+    ///     language: Some("rust".to_string()),
+    ///     data: None,
+    ///     visibility: None,
+    ///     version: 0,
+    /// };
+    /// assert!(request.validate().is_ok());
+    /// let request = UpdatePostRequest { title: Some("".to_string()), ..request };
+    /// assert!(request.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), ApiError> {
+        let mut errors = FieldErrors::default();
+        if let Some(title) = &self.title {
+            errors.check_title(title);
+        }
+        if let Some(data) = &self.data {
+            errors.check_data(data, MAX_POST_CHARS);
+        }
+        if let Some(language) = &self.language {
+            errors.check_language(language);
+        }
+        errors.into_result()
+    }
+}
+/// JSON 을 통해 채택할 답변을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct AcceptAnswerRequest {
+    /// 채택할 댓글의 고유 ID이다.
+    pub comment_id: u64,
 }
 /// JSON 을 통해 삭제해야 할 포스트를 받을 때 필요한 구조체이다.
 #[derive(Deserialize)]
 pub struct DeletePostRequest {
-    /// 포스트를 작성한 유저의 실제 구분 ID이다.
-    pub user_id: String,
     /// 포스트의 고유 ID이다.
     pub post_id: String,
 }
 
+/// 쿼리 스트링을 통해 홈 피드를 요청한 사용자를 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct HomeFeedRequest {
+    /// 피드를 요청한 사용자의 고유 ID이다. 개인화 순위를 사용할 때만 쓰인다.
+    pub viewer_id: Option<String>,
+    /// 지정한 경우 해당 태그가 붙은 포스트만 최신순으로 반환한다.
+    pub tag: Option<String>,
+    /// 포스트 내용을 보내는 방식이다. 지정하지 않은 경우 `summary`이다.
+    #[serde(default)]
+    pub body: BodyMode,
+    /// 쉼표로 구분된 항목 이름들이다. 지정한 경우 각 포스트에서 해당 항목들만 반환한다.
+    pub fields: Option<String>,
+}
+
 #[get("/api/posts")]
-pub async fn get_posts_api() -> impl Responder {
-    println!("GET /api/posts");
-    let results = Post::get_posts();
-    HttpResponse::Ok()
-        .insert_header(("Content-Type", "application/json;charset=utf-8"))
-        .json(results)
+// 피드 조회에 필요한 쿼리 스트링, 순위 알고리즘, 캐시, 차단 목록을 각각의 추출자로 받는다.
+#[allow(clippy::too_many_arguments)]
+pub async fn get_posts_api(
+    req: HttpRequest,
+    request: web::Query<HomeFeedRequest>,
+    page: web::Query<Pagination>,
+    ranker: web::Data<dyn Ranker>,
+    cursor: web::Query<Cursor>,
+    cache: web::Data<Cache>,
+    users: web::Data<dyn UserRepository>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let fields = request
+        .fields
+        .as_deref()
+        .map(FieldSet::parse)
+        .transpose()
+        .map_err(|message| ApiError::InvalidFields([("fields", message)].into()))?;
+    let viewer_id = Identity::from_header(&req);
+    let blocked = BlockList::load(users.get_ref(), viewer_id.as_deref())
+        .extend(shadow_bans.hidden_from(viewer_id.as_deref()));
+    if cursor.is_requested() {
+        let posts = match request.tag.as_deref().map(Tag::normalize) {
+            Some(None) => Vec::new(),
+            tag => Post::get_posts_after(tag.flatten().as_deref(), &cursor),
+        };
+        let mut page = CursorPage::new(summary::apply(posts, request.body), &cursor, |post| {
+            post.post_id
+        });
+        blocked.retain(&mut page.items, |post| &post.user_id);
+        let mut builder = HttpResponse::Ok();
+        builder.insert_header(("Content-Type", response::JSON_CONTENT_TYPE));
+        return Ok(match fields {
+            Some(fields) => builder.json(page.map(|post| fields.pick(&post))),
+            None => builder.json(page),
+        });
+    }
+    // 차단한 사용자가 있는 경우 작성자를 확인할 수 있도록 포스트 전체를 조회한 뒤 항목을 고른다.
+    if let Some(fields) = fields.as_ref().filter(|_| blocked.is_empty()) {
+        let results = match request.tag.as_deref().map(Tag::normalize) {
+            Some(Some(tag)) => fields.get_posts(Some(&tag), &page, request.body),
+            Some(None) => Vec::new(),
+            None => ranker.rank_fields(request.viewer_id.as_deref(), &page, fields, request.body),
+        };
+        return Ok(response::json(results));
+    }
+    let mut results = match request.tag.as_deref().map(Tag::normalize) {
+        Some(Some(tag)) => cache.feed(None, Some(&tag), &page, || Tag::get_posts(&tag, &page)),
+        Some(None) => Vec::new(),
+        None => cache.feed(request.viewer_id.as_deref(), None, &page, || {
+            ranker.rank(request.viewer_id.as_deref(), &page)
+        }),
+    };
+    blocked.retain(&mut results, |post| &post.user_id);
+    let results = summary::apply(results, request.body);
+    let mut builder = HttpResponse::Ok();
+    builder.insert_header(("Content-Type", response::JSON_CONTENT_TYPE));
+    Ok(match fields {
+        Some(fields) => builder.json(
+            results
+                .iter()
+                .map(|post| fields.pick(post))
+                .collect::<Vec<_>>(),
+        ),
+        None => builder.json(results),
+    })
+}
+
+/// 쿼리 스트링을 통해 포스트 수를 셀 조건을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct PostCountRequest {
+    /// 지정한 경우 해당 태그가 붙은 포스트만 센다.
+    pub tag: Option<String>,
+}
+
+#[get("/api/posts/count")]
+pub async fn count_posts_api(request: web::Query<PostCountRequest>) -> impl Responder {
+    let count = match request.tag.as_deref().map(Tag::normalize) {
+        Some(None) => 0,
+        tag => Post::count(tag.flatten().as_deref()),
+    };
+    response::json(Count { count })
+}
+
+/// 쿼리 스트링을 통해 포스트 조회 옵션을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct GetPostRequest {
+    /// `html`로 지정한 경우 포스트 내용을 구문 강조한 HTML을 `html` 항목에 함께 담아 반환한다.
+    pub render: Option<RenderFormat>,
+}
+
+/// 단일 포스트를 조회한 결과를 반환할 때 사용하는 구조체이다.
+#[derive(Serialize)]
+struct PostDetail {
+    /// 조회한 포스트이다.
+    #[serde(flatten)]
+    post: Post,
+    /// 포스트 내용을 구문 강조한 HTML이다. `render=html`로 요청한 경우에만 포함된다.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    html: Option<String>,
+    /// 포스트 내용에 포함된 주소들의 링크 미리보기이다.
+    link_previews: Vec<LinkPreview>,
 }
 
 #[get("/api/posts/{post_id}")]
-pub async fn get_post_api(post_id: web::Path<String>) -> impl Responder {
-    println!("GET /api/posts with ID");
-    let result = Post::get_post(post_id);
-    match result {
-        Some(result) => HttpResponse::Ok()
-            .insert_header(("Content-Type", "application/json;charset=utf-8"))
-            .json(result),
-        None => HttpResponse::NotFound()
-            .insert_header(("Content-Type", "application/text;charset=utf-8"))
-            .body("요청한 post_id는 존재하지 않는 포스트 입니다."),
+pub async fn get_post_api(
+    req: HttpRequest,
+    identity: Option<Identity>,
+    post_id: web::Path<String>,
+    request: web::Query<GetPostRequest>,
+    cache: web::Data<Cache>,
+    link_previews: web::Data<LinkPreviews>,
+    posts: web::Data<dyn PostRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let post = cache
+        .post(&post_id, || {
+            posts
+                .get(&post_id)
+                .filter(|post| posts.is_published(post.post_id) && post.is_visible_to(None))
+        })
+        .or_else(|| {
+            posts.get(&post_id).filter(|post| {
+                identity
+                    .as_ref()
+                    .is_some_and(|identity| identity.can_modify(&post.user_id))
+            })
+        })
+        .ok_or_else(post_not_found)?;
+    posts.record_view(post.post_id);
+    let modified = post.updated_at.unwrap_or(post.create_at);
+    if conditional::is_not_modified(&req, modified) {
+        return Ok(HttpResponse::NotModified()
+            .insert_header(conditional::last_modified(modified))
+            .finish());
     }
+    let html = match request.render {
+        Some(RenderFormat::Html) => Some(highlight::to_html(&post.data, &post.language)),
+        None => None,
+    };
+    let link_previews = link_previews.get(&post.data);
+    Ok(HttpResponse::Ok()
+        .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+        .insert_header(conditional::last_modified(modified))
+        .json(PostDetail {
+            post,
+            html,
+            link_previews,
+        }))
 }
 
 #[post("/api/posts")]
-pub async fn insert_post_api(request: Json<PostRequest>) -> impl Responder {
-    println!("POST /api/posts");
-    let new_post = Post::new(
-        request.user_id.clone(),
-        request.title.clone(),
-        request.language.clone(),
-        request.data.clone(),
+// 작성 요청의 검증, 검토, 링크 미리보기, 검색 색인에 필요한 공유 상태를 각각의 추출자로 받는다.
+#[allow(clippy::too_many_arguments)]
+pub async fn insert_post_api(
+    req: HttpRequest,
+    identity: Identity,
+    request: Json<PostRequest>,
+    idempotency: web::Data<Idempotency>,
+    cache: web::Data<Cache>,
+    word_filter: web::Data<WordFilter>,
+    moderator: web::Data<dyn Moderator>,
+    link_previews: web::Data<LinkPreviews>,
+    search_index: web::Data<SearchIndex>,
+    posts: web::Data<dyn PostRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let key = idempotency::key(&req, &identity.user_id)?;
+    if let Some(replayed) = idempotency.begin(key.as_deref())? {
+        return Ok(replayed);
+    }
+    let request = PostRequest {
+        user_id: identity.user_id,
+        ..request.into_inner()
+    };
+    let result = insert_post(
+        request,
+        cache,
+        &word_filter,
+        moderator,
+        &link_previews,
+        search_index,
+        posts.get_ref(),
+    )
+    .map(|post| (format!("/api/posts/{}", post.post_id), post));
+    idempotency.finish(key.as_deref(), &result);
+    let (location, post) = result?;
+    Ok(response::created(location, post))
+}
+
+/// 포스트를 작성하고 작성된 포스트를 반환하는 메서드이다.
+fn insert_post(
+    mut request: PostRequest,
+    cache: web::Data<Cache>,
+    word_filter: &WordFilter,
+    moderator: web::Data<dyn Moderator>,
+    link_previews: &LinkPreviews,
+    search_index: web::Data<SearchIndex>,
+    posts: &dyn PostRepository,
+) -> std::result::Result<Post, ApiError> {
+    sanitize::clean(&mut request.title);
+    sanitize::clean(&mut request.data);
+    let flagged =
+        word_filter.screen(&mut [("title", &mut request.title), ("data", &mut request.data)])?;
+    let post = posts.create(&request)?;
+    WordFilter::flag(ContentKind::Post, post.post_id, &flagged);
+    cache.invalidate_post(post.post_id);
+    link_previews.prefetch(&request.data);
+    search_index.sync(post.post_id);
+    moderation::submit(
+        moderator,
+        cache,
+        search_index,
+        ContentKind::Post,
+        post.post_id,
+        format!("{}\n\n{}", request.title, request.data),
     );
-    match new_post.insert_db() {
-        Ok(_) => HttpResponse::Created(),
-        Err(_) => HttpResponse::InternalServerError(),
+    Ok(post)
+}
+
+#[patch("/api/posts/{post_id}")]
+// 작성 요청과 같이 검증, 검토, 링크 미리보기, 검색 색인에 필요한 공유 상태를 모두 받는다.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_post_api(
+    identity: Identity,
+    post_id: web::Path<String>,
+    request: Json<UpdatePostRequest>,
+    cache: web::Data<Cache>,
+    word_filter: web::Data<WordFilter>,
+    moderator: web::Data<dyn Moderator>,
+    link_previews: web::Data<LinkPreviews>,
+    search_index: web::Data<SearchIndex>,
+    posts: web::Data<dyn PostRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let mut request = request.into_inner();
+    request.title.iter_mut().for_each(sanitize::clean);
+    request.data.iter_mut().for_each(sanitize::clean);
+    request.validate()?;
+    let owner = posts.get_owner(&post_id).ok_or_else(post_not_found)?;
+    if !identity.can_modify(&owner) {
+        return Err(ApiError::Forbidden);
+    }
+    let mut fields = Vec::new();
+    if let Some(title) = request.title.as_mut() {
+        fields.push(("title", title));
+    }
+    if let Some(data) = request.data.as_mut() {
+        fields.push(("data", data));
+    }
+    let flagged = word_filter.screen(&mut fields)?;
+    let text = [request.title.as_deref(), request.data.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if !posts.update(&post_id, request)? {
+        return Err(ApiError::Conflict(
+            "다른 곳에서 먼저 수정된 포스트입니다.".to_string(),
+        ));
+    }
+    cache.invalidate_post(&*post_id);
+    link_previews.prefetch(&text);
+    if let Ok(post_id) = post_id.parse() {
+        search_index.sync(post_id);
+        WordFilter::flag(ContentKind::Post, post_id, &flagged);
+        if !text.is_empty() {
+            moderation::submit(
+                moderator,
+                cache,
+                search_index,
+                ContentKind::Post,
+                post_id,
+                text,
+            );
+        }
     }
+    Ok(HttpResponse::Ok().finish())
 }
 
 #[delete("/api/posts")]
-pub async fn delete_post_api(request: web::Query<DeletePostRequest>) -> impl Responder {
-    println!("DELETE /api/posts");
-    match Post::delete_post(request) {
-        Ok(_) => HttpResponse::Created(),
-        Err(_) => HttpResponse::InternalServerError(),
+pub async fn delete_post_api(
+    identity: Identity,
+    request: web::Query<DeletePostRequest>,
+    cache: web::Data<Cache>,
+    outbox: web::Data<Outbox>,
+    search_index: web::Data<SearchIndex>,
+    posts: web::Data<dyn PostRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let owner = posts
+        .get_owner(&request.post_id)
+        .ok_or_else(post_not_found)?;
+    if !identity.can_modify(&owner) {
+        return Err(ApiError::Forbidden);
+    }
+    let post_id = request.post_id.clone();
+    posts.delete(&post_id)?;
+    if let Ok(post_id) = post_id.parse() {
+        search_index.sync(post_id);
+        if owner != identity.user_id {
+            email::notify_post_removed(outbox, post_id);
+        }
+    }
+    cache.invalidate_post(post_id);
+    Ok(response::no_content())
+}
+
+#[post("/api/posts/{post_id}/restore")]
+pub async fn restore_post_api(
+    identity: Identity,
+    post_id: web::Path<u64>,
+    cache: web::Data<Cache>,
+    search_index: web::Data<SearchIndex>,
+    posts: web::Data<dyn PostRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let owner = posts.get_deleted_owner(*post_id).ok_or_else(|| {
+        ApiError::NotFound("요청한 post_id는 삭제된 포스트가 아닙니다.".to_string())
+    })?;
+    if !identity.can_modify(&owner) {
+        return Err(ApiError::Forbidden);
+    }
+    posts.restore(*post_id)?;
+    cache.invalidate_post(*post_id);
+    search_index.sync(*post_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[post("/api/posts/{post_id}/answer")]
+pub async fn accept_answer_api(
+    identity: Identity,
+    post_id: web::Path<u64>,
+    request: Json<AcceptAnswerRequest>,
+    cache: web::Data<Cache>,
+    posts: web::Data<dyn PostRepository>,
+    comments: web::Data<dyn CommentRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    accept_answer(
+        &identity,
+        *post_id,
+        request.comment_id,
+        &cache,
+        posts.get_ref(),
+        comments.get_ref(),
+    )
+}
+
+#[post("/api/posts/{post_id}/accept/{comment_id}")]
+pub async fn accept_comment_api(
+    identity: Identity,
+    path: web::Path<(u64, u64)>,
+    cache: web::Data<Cache>,
+    posts: web::Data<dyn PostRepository>,
+    comments: web::Data<dyn CommentRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let (post_id, comment_id) = path.into_inner();
+    accept_answer(
+        &identity,
+        post_id,
+        comment_id,
+        &cache,
+        posts.get_ref(),
+        comments.get_ref(),
+    )
+}
+
+/// 질문 포스트의 작성자가 요청한 경우 `comment_id` 댓글을 채택된 답변으로 지정하는 메서드이다.
+fn accept_answer(
+    identity: &Identity,
+    post_id: u64,
+    comment_id: u64,
+    cache: &Cache,
+    posts: &dyn PostRepository,
+    comments: &dyn CommentRepository,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let owner = posts
+        .get_owner(&post_id.to_string())
+        .ok_or_else(post_not_found)?;
+    if owner != identity.user_id {
+        return Err(ApiError::Forbidden);
+    }
+    if posts.get_post_type(post_id) != Some(PostType::Question) {
+        return Err(ApiError::BadRequest(
+            "질문 포스트에서만 답변을 채택할 수 있습니다.".to_string(),
+        ));
+    }
+    if comments.get_post_id(comment_id) != Some(post_id) {
+        return Err(ApiError::BadRequest(
+            "해당 포스트에 달린 댓글만 채택할 수 있습니다.".to_string(),
+        ));
+    }
+    posts.set_accepted_answer(post_id, Some(comment_id))?;
+    cache.invalidate_post(post_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[delete("/api/posts/{post_id}/answer")]
+pub async fn unaccept_answer_api(
+    identity: Identity,
+    post_id: web::Path<u64>,
+    cache: web::Data<Cache>,
+    posts: web::Data<dyn PostRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let owner = posts
+        .get_owner(&post_id.to_string())
+        .ok_or_else(post_not_found)?;
+    if owner != identity.user_id {
+        return Err(ApiError::Forbidden);
+    }
+    posts.set_accepted_answer(*post_id, None)?;
+    cache.invalidate_post(*post_id);
+    Ok(response::no_content())
+}
+
+/// 포스트가 존재하지 않을 때 반환하는 오류이다.
+pub(crate) fn post_not_found() -> ApiError {
+    ApiError::NotFound("요청한 post_id는 존재하지 않는 포스트 입니다.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_post_rejects_hostile_post_id() {
+        for hostile in [
+            "0 or 1=1",
+            "0 union select * from user",
+            "1; drop table post",
+            "' or ''='",
+        ] {
+            assert!(Post::get_post(web::Path::from(hostile.to_string())).is_none());
+        }
     }
 }
@@ -0,0 +1,122 @@
+//! # 홈 피드 순위 관련 동작을 정의하는 모듈
+//!
+//! `ranking`은 코드뮤니티의 홈 피드(`GET /api/posts`)에 포스트를 어떤 순서로 보여줄지 정하는
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 순위를 정하는 방식은 `Ranker` 트레이트로 추상화되어 있으며, `FEED_RANKER` 환경변수로 선택한다.
+//! 새로운 방식을 실험할 때는 `Ranker`를 구현한 뒤 `from_env()`에 추가하면 되고, 핸들러는 수정할 필요가 없다.
+
+use crate::fields::FieldSet;
+use crate::pagination::Pagination;
+use crate::post::Post;
+use crate::summary::{self, BodyMode};
+use crate::trending::Trending;
+use serde_json::{Map, Value};
+use std::env;
+use std::sync::Arc;
+
+/// 홈 피드에 보여줄 포스트의 순위를 정하는 방식이 구현해야 하는 트레이트이다.
+pub trait Ranker: Send + Sync {
+    /// 순위 방식의 이름이다. `FEED_RANKER` 환경변수에 지정하는 값과 같다.
+    fn name(&self) -> &'static str;
+    /// 순위에 따라 정렬된 포스트들을 반환하는 메서드이다.
+    ///
+    /// `viewer_id`에는 피드를 요청한 사용자의 고유 ID가 들어가며, 알 수 없는 경우 `None`이다.
+    fn rank(&self, viewer_id: Option<&str>, page: &Pagination) -> Vec<Post>;
+    /// 순위에 따라 정렬된 포스트들에서 `fields`에 지정된 항목만 골라 반환하는 메서드이다.
+    ///
+    /// 기본 구현은 `rank()`의 결과에서 항목을 고르므로, 필요한 열만 조회할 수 있는 방식은 이를 재정의하면 된다.
+    fn rank_fields(
+        &self,
+        viewer_id: Option<&str>,
+        page: &Pagination,
+        fields: &FieldSet,
+        body: BodyMode,
+    ) -> Vec<Map<String, Value>> {
+        summary::apply(self.rank(viewer_id, page), body)
+            .iter()
+            .map(|post| fields.pick(post))
+            .collect()
+    }
+}
+
+/// 최신 포스트를 먼저 보여주는 방식이다.
+pub struct Chronological;
+
+impl Ranker for Chronological {
+    fn name(&self) -> &'static str {
+        "chronological"
+    }
+    fn rank(&self, _viewer_id: Option<&str>, page: &Pagination) -> Vec<Post> {
+        Post::get_posts(page)
+    }
+    fn rank_fields(
+        &self,
+        _viewer_id: Option<&str>,
+        page: &Pagination,
+        fields: &FieldSet,
+        body: BodyMode,
+    ) -> Vec<Map<String, Value>> {
+        fields.get_posts(None, page, body)
+    }
+}
+
+/// 공감, 댓글, 조회 수를 시간에 따라 줄어들도록 계산한 점수가 높은 포스트를 먼저 보여주는 방식이다.
+///
+/// 점수는 `trending` 모듈에서 주기적으로 계산된다.
+pub struct EngagementWeighted;
+
+impl Ranker for EngagementWeighted {
+    fn name(&self) -> &'static str {
+        "engagement"
+    }
+    fn rank(&self, _viewer_id: Option<&str>, page: &Pagination) -> Vec<Post> {
+        Trending::get_posts(page)
+    }
+}
+
+/// 팔로우한 사용자와 구독한 언어의 포스트를 먼저 보여주는 방식이다.
+///
+/// 요청한 사용자를 알 수 없거나 보여줄 포스트가 없는 경우 최신순으로 보여준다.
+pub struct Personalized;
+
+impl Ranker for Personalized {
+    fn name(&self) -> &'static str {
+        "personalized"
+    }
+    fn rank(&self, viewer_id: Option<&str>, page: &Pagination) -> Vec<Post> {
+        match viewer_id {
+            Some(viewer_id) => {
+                let posts = Post::get_personalized(viewer_id, page);
+                if posts.is_empty() && page.offset() == 0 {
+                    Chronological.rank(None, page)
+                } else {
+                    posts
+                }
+            }
+            None => Chronological.rank(None, page),
+        }
+    }
+}
+
+/// `FEED_RANKER` 환경변수에 지정된 순위 방식을 반환하는 메서드이다.
+///
+/// `chronological`(기본값), `engagement`, `personalized` 중 하나를 지정할 수 있다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::ranking;
+/// std::env::set_var("FEED_RANKER", "engagement");
+/// assert_eq!(ranking::from_env().name(), "engagement");
+/// ```
+///
+/// # Panics
+///
+/// `FEED_RANKER`가 올바른 값이 아닌 경우 패닉이 발생한다.
+pub fn from_env() -> Arc<dyn Ranker> {
+    match env::var("FEED_RANKER").as_deref() {
+        Ok("chronological") | Err(_) => Arc::new(Chronological),
+        Ok("engagement") => Arc::new(EngagementWeighted),
+        Ok("personalized") => Arc::new(Personalized),
+        Ok(_) => panic!("FEED_RANKER가 올바른 형식이 아님"),
+    }
+}
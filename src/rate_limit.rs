@@ -0,0 +1,215 @@
+//! # 요청 수 제한 관련 동작을 정의하는 모듈
+//!
+//! `rate_limit`은 공감이나 포스트 작성과 같은 요청이 짧은 시간에 지나치게 많이 들어오는 것을 막기 위해
+//! IP 주소별, 사용자별 요청 수를 제한하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 제한은 메모리에 저장되는 토큰 버킷 방식으로 동작하며, 제한을 초과한 요청에는
+//! `429 Too Many Requests`와 함께 다시 요청할 수 있을 때까지의 시간(`Retry-After`)을 응답한다.
+//! 여러 인스턴스로 서버를 운영하는 경우 제한은 인스턴스마다 따로 적용된다.
+
+use crate::auth::Identity;
+use actix_web::dev::ServiceRequest;
+use actix_web::http::Method;
+use std::collections::HashMap;
+use std::env;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// IP 주소별 요청 수 제한의 기본값이다.
+const DEFAULT_PER_IP: &str = "60/min";
+/// 사용자별 쓰기 요청 수 제한의 기본값이다.
+const DEFAULT_WRITES_PER_USER: &str = "5/min";
+/// 저장된 버킷 수가 이 값을 넘으면 가득 찬 버킷들을 정리한다.
+const PRUNE_THRESHOLD: usize = 10_000;
+/// 사용자별 제한이 적용되는 요청의 메서드와 경로이다.
+const WRITE_ROUTES: &[(Method, &str)] =
+    &[(Method::POST, "/api/posts"), (Method::PATCH, "/api/likes")];
+
+/// 일정 시간 동안 허용되는 요청 수이다.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// 허용되는 요청 수이다.
+    pub requests: u32,
+    /// 요청 수를 세는 기간이다.
+    pub period: Duration,
+}
+
+impl FromStr for RateLimit {
+    type Err = ();
+
+    /// `<요청 수>/<기간>` 형식의 문자열을 요청 수 제한으로 변환하는 메서드이다.
+    ///
+    /// 기간은 `sec`, `min`, `hour` 중 하나이다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::rate_limit::RateLimit;
+    /// use std::time::Duration;
+    /// let limit: RateLimit = "60/min".parse().unwrap();
+    /// assert_eq!(limit.requests, 60);
+    /// assert_eq!(limit.period, Duration::from_secs(60));
+    /// assert!("0/min".parse::<RateLimit>().is_err());
+    /// assert!("60/day".parse::<RateLimit>().is_err());
+    /// ```
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (requests, period) = value.trim().split_once('/').ok_or(())?;
+        let requests = requests.trim().parse().map_err(|_| ())?;
+        let period = match period.trim() {
+            "sec" => Duration::from_secs(1),
+            "min" => Duration::from_secs(60),
+            "hour" => Duration::from_secs(3600),
+            _ => return Err(()),
+        };
+        if requests == 0 {
+            return Err(());
+        }
+        Ok(Self { requests, period })
+    }
+}
+
+/// 요청 수를 세는 토큰 버킷이다.
+struct Bucket {
+    /// 남은 토큰 수이다.
+    tokens: f64,
+    /// 토큰 수를 마지막으로 계산한 시각이다.
+    updated: Instant,
+}
+
+impl Bucket {
+    /// 경과한 시간만큼 토큰을 채운 뒤 하나를 사용하는 메서드이다.
+    ///
+    /// 토큰이 없는 경우 다음 토큰이 채워질 때까지의 시간을 반환한다.
+    fn take(&mut self, limit: &RateLimit, now: Instant) -> Option<Duration> {
+        let capacity = f64::from(limit.requests);
+        let rate = capacity / limit.period.as_secs_f64();
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(capacity);
+        self.updated = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+/// IP 주소별, 사용자별 요청 수를 제한하는 객체이다.
+///
+/// 서버 시작 시 `from_env()`를 통해 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+pub struct RateLimiter {
+    /// 모든 요청에 적용되는 IP 주소별 제한이다. `None`인 경우 제한하지 않는다.
+    per_ip: Option<RateLimit>,
+    /// 포스트 작성과 공감 요청에 적용되는 사용자별 제한이다. `None`인 경우 제한하지 않는다.
+    writes_per_user: Option<RateLimit>,
+    /// 제한 대상별 토큰 버킷이다.
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+/// 환경변수에서 요청 수 제한을 읽는 메서드이다.
+///
+/// `off`인 경우 `None`을 반환한다.
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - 환경변수가 올바른 형식이 아닌 경우
+fn limit_from_env(name: &str, default: &str) -> Option<RateLimit> {
+    let value = env::var(name).unwrap_or_else(|_| default.to_string());
+    if value == "off" {
+        return None;
+    }
+    Some(
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("{}가 올바른 형식이 아님: {}", name, value)),
+    )
+}
+
+impl RateLimiter {
+    /// 환경변수를 통해 요청 수 제한 객체를 생성하는 메서드이다.
+    ///
+    /// - `RATE_LIMIT_PER_IP`: IP 주소별 제한 (기본값 `60/min`)
+    /// - `RATE_LIMIT_WRITES_PER_USER`: 포스트 작성과 공감 요청의 사용자별 제한 (기본값 `5/min`)
+    ///
+    /// 각 값을 `off`로 지정하면 해당 제한을 사용하지 않는다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - 환경변수가 `<요청 수>/<sec|min|hour>` 또는 `off` 형식이 아닌 경우
+    pub fn from_env() -> Self {
+        Self {
+            per_ip: limit_from_env("RATE_LIMIT_PER_IP", DEFAULT_PER_IP),
+            writes_per_user: limit_from_env("RATE_LIMIT_WRITES_PER_USER", DEFAULT_WRITES_PER_USER),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+    /// `key`에 대한 요청을 하나 허용할 수 있는지 확인하는 메서드이다.
+    ///
+    /// 허용할 수 있는 경우 `None`을, 그렇지 않은 경우 기다려야 하는 시간을 반환한다.
+    fn acquire(&self, key: String, limit: &RateLimit) -> Option<Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let now = Instant::now();
+        if buckets.len() > PRUNE_THRESHOLD {
+            let longest = [self.per_ip, self.writes_per_user]
+                .iter()
+                .flatten()
+                .map(|limit| limit.period)
+                .max()
+                .unwrap_or_default();
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated) < longest);
+        }
+        buckets
+            .entry(key)
+            .or_insert(Bucket {
+                tokens: f64::from(limit.requests),
+                updated: now,
+            })
+            .take(limit, now)
+    }
+    /// 요청을 처리해도 되는지 확인하는 메서드이다.
+    ///
+    /// 제한을 초과한 경우 다시 요청할 수 있을 때까지의 시간을 반환한다.
+    /// 사용자는 인증 토큰의 사용자 ID, 쿼리 스트링의 `user_id`, IP 주소 순으로 구분한다.
+    pub fn check(&self, req: &ServiceRequest) -> Option<Duration> {
+        let ip = match req.connection_info().realip_remote_addr() {
+            Some(addr) => addr
+                .parse::<SocketAddr>()
+                .map(|addr| addr.ip().to_string())
+                .unwrap_or_else(|_| addr.to_string()),
+            None => "unknown".to_string(),
+        };
+        if let Some(limit) = &self.per_ip {
+            if let Some(wait) = self.acquire(format!("ip:{}", ip), limit) {
+                return Some(wait);
+            }
+        }
+        let limit = self.writes_per_user.as_ref()?;
+        let is_write = WRITE_ROUTES
+            .iter()
+            .any(|(method, path)| req.method() == method && req.path() == *path);
+        if !is_write {
+            return None;
+        }
+        let user = req
+            .headers()
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(Identity::verify_token)
+            .or_else(|| query_user_id(req.query_string()).map(|user_id| user_id.to_string()))
+            .unwrap_or(ip);
+        self.acquire(format!("user:{}", user), limit)
+    }
+}
+
+/// 쿼리 스트링에서 `user_id`의 값을 찾는 메서드이다.
+fn query_user_id(query: &str) -> Option<&str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "user_id")
+        .map(|(_, value)| value)
+}
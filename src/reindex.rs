@@ -0,0 +1,111 @@
+//! # 색인 및 캐시 재구성 관련 동작을 정의하는 모듈
+//!
+//! `reindex`는 코드뮤니티에서 대량으로 데이터를 가져오거나 DB 구조를 변경한 뒤
+//! 인기 포스트 점수, DB 통계, 메모리에 저장된 캐시 등을 한 번에 다시 만들기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 재구성은 별도의 스레드에서 단계별로 실행되며, 진행 상황은 관리자 API를 통해 확인할 수 있다.
+
+use serde::Serialize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 재구성 작업의 상태이다.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReindexState {
+    /// 한 번도 실행되지 않은 상태
+    #[default]
+    Idle,
+    /// 실행 중인 상태
+    Running,
+    /// 모든 단계가 끝난 상태
+    Done,
+    /// 실행 중 문제가 발생하여 중단된 상태
+    Failed,
+}
+
+/// 재구성 작업의 진행 상황이다.
+#[derive(Serialize, Clone, Default)]
+pub struct ReindexStatus {
+    /// 작업의 상태이다.
+    pub state: ReindexState,
+    /// 전체 단계 수이다.
+    pub total_steps: usize,
+    /// 끝난 단계 수이다.
+    pub completed_steps: usize,
+    /// 현재 실행 중이거나 문제가 발생한 단계의 이름이다.
+    pub current_step: Option<String>,
+    /// 작업이 시작된 시각(유닉스 시간, 초)이다.
+    pub started_at: Option<u64>,
+    /// 작업이 끝난 시각(유닉스 시간, 초)이다.
+    pub finished_at: Option<u64>,
+}
+
+/// 재구성 작업의 한 단계이다.
+pub type Step = (&'static str, Box<dyn FnOnce() + Send>);
+
+/// 현재 유닉스 시간(초)을 반환하는 메서드이다.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0)
+}
+
+/// 재구성 작업을 실행하고 진행 상황을 저장하는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+#[derive(Default)]
+pub struct Reindex {
+    status: Arc<Mutex<ReindexStatus>>,
+}
+
+impl Reindex {
+    /// 현재 진행 상황을 반환하는 메서드이다.
+    pub fn status(&self) -> ReindexStatus {
+        self.status.lock().unwrap().clone()
+    }
+    /// `steps`를 순서대로 실행하는 재구성 작업을 시작하는 메서드이다.
+    ///
+    /// 이미 실행 중인 작업이 있는 경우 새로 시작하지 않고 `false`를 반환한다.
+    /// 한 단계에서 문제가 발생하면 이후 단계는 실행되지 않는다.
+    /// # 예제
+    /// ```ignore
+    /// let started = reindex.start(vec![("trending", Box::new(Trending::refresh))]);
+    /// ```
+    pub fn start(&self, steps: Vec<Step>) -> bool {
+        {
+            let mut status = self.status.lock().unwrap();
+            if status.state == ReindexState::Running {
+                return false;
+            }
+            *status = ReindexStatus {
+                state: ReindexState::Running,
+                total_steps: steps.len(),
+                started_at: Some(now()),
+                ..Default::default()
+            };
+        }
+        let status = self.status.clone();
+        thread::spawn(move || {
+            for (name, step) in steps {
+                status.lock().unwrap().current_step = Some(name.to_string());
+                if panic::catch_unwind(AssertUnwindSafe(step)).is_err() {
+                    let mut status = status.lock().unwrap();
+                    status.state = ReindexState::Failed;
+                    status.finished_at = Some(now());
+                    return;
+                }
+                status.lock().unwrap().completed_steps += 1;
+            }
+            let mut status = status.lock().unwrap();
+            status.state = ReindexState::Done;
+            status.current_step = None;
+            status.finished_at = Some(now());
+        });
+        true
+    }
+}
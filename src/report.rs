@@ -0,0 +1,580 @@
+//! # 신고 관련 동작을 정의하는 모듈
+//!
+//! `report`는 사용자가 스팸이나 욕설 등이 담긴 포스트와 댓글을 신고하고, 관리자가 신고된 댓글을
+//! 검토 대기열에서 확인하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 한 사용자는 같은 포스트나 댓글을 한 번만 신고할 수 있으며, 신고가 접수될 때마다 대상의 `report_count`가 증가한다.
+//! 신고를 악용하지 못하도록 한 사용자가 한 시간 동안 접수할 수 있는 신고 수는 `REPORT_LIMIT_PER_HOUR`개(기본값 10개)로 제한된다.
+//! 신고된 댓글은 검토 판정이 `pending`인 댓글과 함께 관리자의 검토 대기열에 포함되고,
+//! 관리자가 판정을 내리면 신고 기록이 지워져 대기열에서 빠진다.
+//!
+//! 통합 검토 대기열(`GET /api/admin/moderation/queue`)에는 신고가 `MODERATION_QUEUE_MIN_REPORTS`개(기본값 3개) 이상 쌓였거나,
+//! 스팸으로 신고되었거나, 판정이 `pending`이거나, 금칙어가 발견된 포스트와 댓글이 함께 포함된다.
+
+use crate::auth::Identity;
+use crate::db;
+use crate::error::ApiError;
+use crate::moderation::Verdict;
+use crate::pagination::Pagination;
+use crate::post::post_not_found;
+use crate::sanitize;
+use crate::word_filter::ContentKind;
+use actix_web::{post, web, HttpResponse};
+use chrono::{DateTime, Utc};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::time::Duration;
+
+/// 신고 상세 내용의 최대 길이이다.
+pub const MAX_DETAIL_CHARS: usize = 500;
+/// 한 사용자가 한 시간 동안 접수할 수 있는 기본 신고 수이다.
+const DEFAULT_REPORTS_PER_HOUR: u64 = 10;
+/// 통합 검토 대기열에 포함되기 위한 기본 신고 수이다.
+const DEFAULT_QUEUE_MIN_REPORTS: u64 = 3;
+
+/// 신고 사유이다.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    /// 광고나 도배
+    Spam,
+    /// 욕설이나 괴롭힘
+    Abuse,
+    /// 포스트와 관계없는 내용
+    OffTopic,
+    /// 그 외의 사유로, 상세 내용을 함께 적어야 한다.
+    Other,
+}
+
+impl ReportReason {
+    /// DB에 저장되는 신고 사유의 이름을 반환하는 메서드이다.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Spam => "spam",
+            Self::Abuse => "abuse",
+            Self::OffTopic => "off_topic",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// JSON 을 통해 신고 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct ReportRequest {
+    /// 신고 사유이다.
+    pub reason: ReportReason,
+    /// 신고 상세 내용이다. 사유가 `other`인 경우 반드시 적어야 한다.
+    pub detail: Option<String>,
+}
+
+impl ReportRequest {
+    /// 신고 요청이 조건에 맞는지 확인하는 메서드이다.
+    ///
+    /// 상세 내용이 500자를 넘거나, 사유가 `other`인데 상세 내용이 비어있는 경우 `ApiError::Validation`을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::report::{ReportReason, ReportRequest};
+    /// let request = ReportRequest { reason: ReportReason::Spam, detail: None };
+    /// assert!(request.validate().is_ok());
+    /// let request = ReportRequest { reason: ReportReason::Other, detail: Some(" ".to_string()) };
+    /// assert!(request.validate().is_err());
+    /// let request = ReportRequest { reason: ReportReason::Other, detail: Some("개인정보 노출".to_string()) };
+    /// assert!(request.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), ApiError> {
+        let detail = self.detail.as_deref().map(str::trim).unwrap_or_default();
+        if detail.chars().count() > MAX_DETAIL_CHARS {
+            return Err(ApiError::Validation(format!(
+                "신고 상세 내용은 {}자를 넘을 수 없습니다.",
+                MAX_DETAIL_CHARS
+            )));
+        }
+        if self.reason == ReportReason::Other && detail.is_empty() {
+            return Err(ApiError::Validation(
+                "신고 사유가 other인 경우 상세 내용을 적어야 합니다.".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// 관리자의 검토 대기열에 포함된 댓글이다.
+#[derive(Serialize)]
+pub struct ReportedComment {
+    /// 댓글의 고유 ID이다.
+    pub comment_id: u64,
+    /// 댓글이 달린 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 댓글을 작성한 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 댓글의 내용이다.
+    #[serde(with = "sanitize::escaped")]
+    pub data: String,
+    /// 검토 서비스의 판정(`approved`, `pending`, `rejected`)이다.
+    pub moderation: String,
+    /// 접수된 신고 수이다.
+    pub report_count: u64,
+    /// 접수된 신고 사유들이다.
+    pub reasons: Vec<String>,
+    /// 댓글 작성 날짜 및 시간이다.
+    pub create_at: DateTime<Utc>,
+}
+
+/// 통합 검토 대기열에 포함된 포스트나 댓글이다.
+#[derive(Serialize)]
+pub struct QueueItem {
+    /// 콘텐츠의 종류(`post`, `comment`)이다.
+    pub content_type: String,
+    /// 포스트나 댓글의 고유 ID이다.
+    pub content_id: u64,
+    /// 포스트의 고유 ID이다. 댓글인 경우 댓글이 달린 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 작성한 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 포스트인 경우 제목, 댓글인 경우 내용이다.
+    #[serde(with = "sanitize::escaped")]
+    pub text: String,
+    /// 검토 서비스의 판정(`approved`, `pending`, `rejected`)이다.
+    pub moderation: String,
+    /// 접수된 신고 수이다.
+    pub report_count: u64,
+    /// 접수된 신고 사유들이다.
+    pub reasons: Vec<String>,
+    /// 발견된 금칙어들이다.
+    pub flagged_words: Vec<String>,
+    /// 스팸으로 신고되었는지 여부이다.
+    pub spam_suspect: bool,
+    /// 작성 날짜 및 시간이다.
+    pub create_at: DateTime<Utc>,
+}
+
+/// 관리자가 검토 대기열의 콘텐츠에 내리는 처리이다.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueAction {
+    /// 문제가 없으므로 공개한다.
+    Approve,
+    /// 공개할 수 없으므로 숨긴다.
+    Remove,
+}
+
+impl QueueAction {
+    /// 처리에 해당하는 검토 판정을 반환하는 메서드이다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::moderation::Verdict;
+    /// use code_mmunity_server::report::QueueAction;
+    /// assert_eq!(QueueAction::Approve.verdict().as_str(), Verdict::Approved.as_str());
+    /// assert_eq!(QueueAction::Remove.verdict().as_str(), Verdict::Rejected.as_str());
+    /// ```
+    pub fn verdict(&self) -> Verdict {
+        match self {
+            Self::Approve => Verdict::Approved,
+            Self::Remove => Verdict::Rejected,
+        }
+    }
+}
+
+/// JSON 을 통해 검토 대기열의 콘텐츠 처리 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct QueueActionRequest {
+    /// 처리(`approve`, `remove`)이다.
+    pub action: QueueAction,
+}
+
+/// 신고를 처리하는 객체이다.
+pub struct Report;
+
+/// 신고를 접수하려 한 결과이다.
+enum Outcome {
+    /// 신고가 접수되었다.
+    Reported,
+    /// 신고할 대상이 존재하지 않는다.
+    Missing,
+    /// 이미 신고한 대상이다.
+    Duplicate,
+    /// 신고 수 제한을 넘었으며, 다시 신고할 수 있을 때까지 기다려야 하는 시간(초)을 담고 있다.
+    Limited(u64),
+}
+
+/// 신고 대상 종류별로 대상을 저장하는 테이블, 고유 ID 열, 신고를 저장하는 테이블과
+/// 신고한 사용자가 대상을 볼 수 있는지 확인하는 조건이다.
+fn target(kind: ContentKind) -> (&'static str, &'static str, &'static str, &'static str) {
+    match kind {
+        ContentKind::Post => (
+            "post",
+            "post_id",
+            "post_report",
+            "published and deleted_at is null and (visibility <> 'private' or user_id = :user_id)",
+        ),
+        ContentKind::Comment => (
+            "comment",
+            "comment_id",
+            "comment_report",
+            "post_id in (select post_id from post where deleted_at is null and (visibility <> 'private' or user_id = :user_id))",
+        ),
+    }
+}
+
+/// 한 사용자가 한 시간 동안 접수할 수 있는 신고 수를 반환하는 메서드이다.
+///
+/// `REPORT_LIMIT_PER_HOUR` 환경변수가 주어지지 않았거나 올바르지 않은 경우 10을 반환한다.
+fn reports_per_hour() -> u64 {
+    env::var("REPORT_LIMIT_PER_HOUR")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_REPORTS_PER_HOUR)
+}
+
+/// 통합 검토 대기열에 포함되기 위한 신고 수를 반환하는 메서드이다.
+///
+/// `MODERATION_QUEUE_MIN_REPORTS` 환경변수가 주어지지 않았거나 올바르지 않은 경우 3을 반환한다.
+fn queue_min_reports() -> u64 {
+    env::var("MODERATION_QUEUE_MIN_REPORTS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_QUEUE_MIN_REPORTS)
+}
+
+/// DB에서 읽은 검토 대기열 항목이다.
+///
+/// 종류, 콘텐츠 ID, 포스트 ID, 작성자, 내용, 판정, 신고 수, 신고 사유들, 금칙어들, 작성 시각 순이다.
+type QueueRow = (
+    String,
+    u64,
+    u64,
+    String,
+    String,
+    String,
+    u64,
+    Option<String>,
+    Option<String>,
+    String,
+);
+
+/// 쉼표로 구분된 값들을 중복 없이 나누는 메서드이다.
+fn split_values(values: Option<String>) -> Vec<String> {
+    let mut values: Vec<String> = values
+        .map(|values| values.split(',').map(str::to_string).collect())
+        .unwrap_or_default();
+    values.sort();
+    values.dedup();
+    values
+}
+
+impl Report {
+    /// 사용자가 포스트나 댓글을 신고하는 메서드이다.
+    ///
+    /// 신고할 대상이 존재하지 않는 경우 `ApiError::NotFound`를, 이미 신고한 대상인 경우 `ApiError::Conflict`를,
+    /// 최근 한 시간 동안 접수한 신고 수가 제한을 넘은 경우 `ApiError::TooManyRequests`를 반환한다.
+    /// 신고 기록과 대상의 신고 수는 하나의 트랜잭션으로 함께 변경된다.
+    /// # 예제
+    /// ```ignore
+    /// let request = ReportRequest { reason: ReportReason::Spam, detail: None };
+    /// match Report::submit(ContentKind::Comment, "unique_id_for_user", 1, &request) {
+    ///     Ok(()) => println!("신고가 접수되었습니다."),
+    ///     Err(error) => println!("신고에 실패했습니다: {}", error),
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn submit(
+        kind: ContentKind,
+        user_id: &str,
+        content_id: u64,
+        request: &ReportRequest,
+    ) -> std::result::Result<(), ApiError> {
+        let (table, id_column, report_table, visible) = target(kind);
+        let detail = request
+            .detail
+            .as_deref()
+            .map(str::trim)
+            .filter(|detail| !detail.is_empty());
+        let outcome = db::transaction(|tx| {
+            let exists: Option<u64> = tx.exec_first(
+                format!(
+                    "select {1} from {0} where {1} = :content_id and {2} for update",
+                    table, id_column, visible
+                ),
+                params! {
+                    "content_id" => content_id,
+                    "user_id" => user_id,
+                },
+            )?;
+            if exists.is_none() {
+                return Ok(Outcome::Missing);
+            }
+            let (recent, wait): (u64, i64) = tx
+                .exec_first(
+                    r"select count(*), coalesce(timestampdiff(second, now(), min(create_at) + interval 1 hour), 0)
+                    from (
+                        select create_at from post_report where user_id = :user_id and create_at > now() - interval 1 hour
+                        union all
+                        select create_at from comment_report where user_id = :user_id and create_at > now() - interval 1 hour
+                    ) recent",
+                    params! {
+                        "user_id" => user_id,
+                    },
+                )?
+                .unwrap_or_default();
+            if recent >= reports_per_hour() {
+                return Ok(Outcome::Limited(wait.max(1) as u64));
+            }
+            tx.exec_drop(
+                format!(
+                    r"insert ignore into {}({}, user_id, reason, detail)
+                    values(:content_id, :user_id, :reason, :detail)",
+                    report_table, id_column
+                ),
+                params! {
+                    "content_id" => content_id,
+                    "user_id" => user_id,
+                    "reason" => request.reason.as_str(),
+                    "detail" => detail,
+                },
+            )?;
+            if tx.affected_rows() == 0 {
+                return Ok(Outcome::Duplicate);
+            }
+            tx.exec_drop(
+                format!(
+                    "update {0} set report_count = report_count + 1 where {1} = :content_id",
+                    table, id_column
+                ),
+                params! {
+                    "content_id" => content_id,
+                },
+            )?;
+            Ok(Outcome::Reported)
+        })?;
+        match outcome {
+            Outcome::Reported => Ok(()),
+            Outcome::Missing => Err(match kind {
+                ContentKind::Post => post_not_found(),
+                ContentKind::Comment => {
+                    ApiError::NotFound("요청한 comment_id는 존재하지 않는 댓글 입니다.".to_string())
+                }
+            }),
+            Outcome::Duplicate => Err(ApiError::Conflict(format!(
+                "이미 신고한 {}입니다.",
+                match kind {
+                    ContentKind::Post => "포스트",
+                    ContentKind::Comment => "댓글",
+                }
+            ))),
+            Outcome::Limited(wait) => Err(ApiError::TooManyRequests(Duration::from_secs(wait))),
+        }
+    }
+    /// 포스트나 댓글의 신고 기록과 금칙어 발견 기록을 지우고 신고 수를 0으로 되돌리는 메서드이다.
+    ///
+    /// 관리자가 신고된 대상에 판정을 내린 뒤 검토 대기열에서 빼기 위해 사용한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn resolve(kind: ContentKind, content_id: u64) -> Result<()> {
+        let (table, id_column, report_table, _) = target(kind);
+        db::transaction(|tx| {
+            tx.exec_drop(
+                format!(
+                    "delete from {} where {} = :content_id",
+                    report_table, id_column
+                ),
+                params! {
+                    "content_id" => content_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from flagged_content
+                where content_type = :content_type and content_id = :content_id",
+                params! {
+                    "content_type" => kind.as_str(),
+                    "content_id" => content_id,
+                },
+            )?;
+            tx.exec_drop(
+                format!(
+                    "update {} set report_count = 0 where {} = :content_id",
+                    table, id_column
+                ),
+                params! {
+                    "content_id" => content_id,
+                },
+            )
+        })
+    }
+    /// 통합 검토 대기열에 포함된 포스트와 댓글들을 반환하는 메서드이다.
+    ///
+    /// 신고 수가 많은 순, 최신순으로 반환한다. 삭제된 포스트와 그 댓글은 포함되지 않는다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn queue(page: &Pagination) -> Vec<QueueItem> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select 'post' as content_type, post_id as content_id, post_id, user_id,
+            title as text, moderation, report_count,
+            (select group_concat(distinct reason order by reason) from post_report
+            where post_report.post_id = post.post_id) as reasons,
+            (select group_concat(words) from flagged_content
+            where content_type = 'post' and content_id = post.post_id) as words,
+            date_format(create_at, '%Y-%m-%d %H:%i:%s') as created
+            from post where deleted_at is null and (report_count >= :min_reports
+            or moderation = 'pending'
+            or exists(select 1 from post_report
+            where post_report.post_id = post.post_id and reason = 'spam')
+            or exists(select 1 from flagged_content
+            where content_type = 'post' and content_id = post.post_id))
+            union all
+            select 'comment', comment_id, post_id, user_id, data, moderation, report_count,
+            (select group_concat(distinct reason order by reason) from comment_report
+            where comment_report.comment_id = comment.comment_id),
+            (select group_concat(words) from flagged_content
+            where content_type = 'comment' and content_id = comment.comment_id),
+            date_format(create_at, '%Y-%m-%d %H:%i:%s')
+            from comment where post_id in (select post_id from post where deleted_at is null)
+            and (report_count >= :min_reports
+            or moderation = 'pending'
+            or exists(select 1 from comment_report
+            where comment_report.comment_id = comment.comment_id and reason = 'spam')
+            or exists(select 1 from flagged_content
+            where content_type = 'comment' and content_id = comment.comment_id))
+            order by report_count desc, created desc
+            limit :limit offset :offset",
+            params! {
+                "min_reports" => queue_min_reports(),
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(
+                content_type,
+                content_id,
+                post_id,
+                user_id,
+                text,
+                moderation,
+                report_count,
+                reasons,
+                words,
+                create_at,
+            ): QueueRow| {
+                let reasons = split_values(reasons);
+                QueueItem {
+                    content_type,
+                    content_id,
+                    post_id,
+                    user_id,
+                    text,
+                    moderation,
+                    report_count,
+                    spam_suspect: reasons
+                        .iter()
+                        .any(|reason| reason == ReportReason::Spam.as_str()),
+                    reasons,
+                    flagged_words: split_values(words),
+                    create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+                }
+            },
+        )
+        .unwrap()
+    }
+    /// 검토 대기열에 포함된 댓글들을 반환하는 메서드이다.
+    ///
+    /// 신고되었거나 검토 판정이 `pending`인 댓글을 신고 수가 많은 순, 최신순으로 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn comment_queue(page: &Pagination) -> Vec<ReportedComment> {
+        let mut conn = db::get_conn();
+        conn
+            .exec_map(
+                r"select comment_id, post_id, user_id, data, moderation, report_count,
+            (select group_concat(distinct reason order by reason) from comment_report
+            where comment_report.comment_id = comment.comment_id),
+            date_format(create_at, '%Y-%m-%d %H:%i:%s')
+            from comment where report_count > 0 or moderation = 'pending'
+            order by report_count desc, comment_id desc
+            limit :limit offset :offset",
+                params! {
+                    "limit" => page.limit(),
+                    "offset" => page.offset(),
+                },
+                |(
+                    comment_id,
+                    post_id,
+                    user_id,
+                    data,
+                    moderation,
+                    report_count,
+                    reasons,
+                    create_at,
+                ): (
+                    u64,
+                    u64,
+                    String,
+                    String,
+                    String,
+                    u64,
+                    Option<String>,
+                    String,
+                )| ReportedComment {
+                    comment_id,
+                    post_id,
+                    user_id,
+                    data,
+                    moderation,
+                    report_count,
+                    reasons: reasons
+                        .map(|reasons| reasons.split(',').map(str::to_string).collect())
+                        .unwrap_or_default(),
+                    create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+                },
+            )
+            .unwrap()
+    }
+}
+
+#[post("/api/posts/{post_id}/report")]
+pub async fn report_post_api(
+    identity: Identity,
+    post_id: web::Path<u64>,
+    request: web::Json<ReportRequest>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    request.validate()?;
+    Report::submit(ContentKind::Post, &identity.user_id, *post_id, &request)?;
+    Ok(HttpResponse::Created().finish())
+}
+
+#[post("/api/comments/{comment_id}/report")]
+pub async fn report_comment_api(
+    identity: Identity,
+    comment_id: web::Path<u64>,
+    request: web::Json<ReportRequest>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    request.validate()?;
+    Report::submit(
+        ContentKind::Comment,
+        &identity.user_id,
+        *comment_id,
+        &request,
+    )?;
+    Ok(HttpResponse::Created().finish())
+}
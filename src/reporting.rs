@@ -0,0 +1,51 @@
+//! # 오류 보고 관련 동작을 정의하는 모듈
+//!
+//! `reporting`은 운영 중 발생한 패닉과 서버 오류(5xx)가 컨테이너 로그에 묻히지 않도록
+//! Sentry 호환 서버로 보내기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! `SENTRY_DSN`이 지정된 경우에만 동작하며, 서버 오류는 접근 로그에 기록된 요청 정보(메서드, 경로,
+//! 상태 코드, 요청 ID 등)와 함께 보내진다. 요청 처리 중 기록된 다른 로그는 이동 경로(breadcrumb)로 함께 보내진다.
+
+use crate::config::Config;
+use sentry::integrations::tracing::EventFilter;
+use sentry::ClientInitGuard;
+use std::borrow::Cow;
+use tracing::{Level, Metadata};
+
+/// 접근 로그를 기록하는 모듈의 경로이다.
+const ACCESS_LOG_TARGET: &str = "code_mmunity_server::logging";
+
+/// 오류 보고를 시작하는 메서드이다.
+///
+/// `SENTRY_DSN`이 지정되지 않은 경우 아무 작업도 하지 않고 `None`을 반환한다.
+/// 반환된 값이 해제될 때 보내지 못한 오류를 모두 보내므로 서버가 종료될 때까지 가지고 있어야 한다.
+/// # 예제
+/// ```ignore
+/// let _reporting = reporting::init(&config);
+/// ```
+pub fn init(config: &Config) -> Option<ClientInitGuard> {
+    let dsn = config.sentry_dsn.as_ref()?;
+    let guard = sentry::init((
+        dsn.as_str(),
+        sentry::ClientOptions {
+            release: sentry::release_name!(),
+            environment: config.sentry_environment.clone().map(Cow::Owned),
+            ..Default::default()
+        },
+    ));
+    if !guard.is_enabled() {
+        tracing::warn!("SENTRY_DSN이 올바르지 않아 오류를 보고하지 않습니다.");
+    }
+    Some(guard)
+}
+
+/// 로그를 Sentry에 어떻게 보낼지 정하는 메서드이다.
+///
+/// 서버 오류를 기록한 접근 로그는 오류로 보내고, `info` 이상의 다른 로그는 이동 경로로 보낸다.
+pub fn event_filter(metadata: &Metadata) -> EventFilter {
+    match *metadata.level() {
+        Level::ERROR if metadata.target() == ACCESS_LOG_TARGET => EventFilter::Event,
+        Level::ERROR | Level::WARN | Level::INFO => EventFilter::Breadcrumb,
+        _ => EventFilter::Ignore,
+    }
+}
@@ -0,0 +1,299 @@
+//! # 저장소 추상화 관련 동작을 정의하는 모듈
+//!
+//! `repository`는 HTTP 핸들러가 포스트, 사용자, 댓글을 어떤 DB에 저장하는지 알지 않아도 되도록
+//! 저장소가 제공해야 하는 동작을 트레이트로 정의한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 핸들러는 `web::Data<dyn PostRepository>`와 같이 트레이트 객체를 앱 상태로 받아 사용하며,
+//! 다른 DB를 지원하거나 핸들러를 테스트할 때는 각 트레이트를 구현한 뒤 `Repositories`로 등록하면 된다.
+//! 기본 구현인 `MySqlRepository`는 각 모듈에 정의된 MySQL 질의 메서드를 그대로 사용하며,
+//! 로컬 개발용으로 `sqlite::SqliteRepository`를, 핸들러 테스트용으로 `testing::MemoryRepository`(`testing` 기능을 켠 경우)를 사용할 수 있다.
+
+use crate::block::Block;
+use crate::comment::{Comment, CommentRequest, CommentSort};
+use crate::config::{DbConfig, DbDriver};
+use crate::email::NotificationSettings;
+use crate::error::ApiError;
+use crate::pagination::Cursor;
+use crate::post::{DeletePostRequest, Post, PostRequest, PostType, UpdatePostRequest};
+use crate::sqlite::SqliteRepository;
+use crate::user::{
+    UpdateNotificationsRequest, UpdateProfileRequest, User, EMAIL_IN_USE, USER_EXISTS,
+};
+use crate::webhook::{self, WebhookEvent};
+use actix_web::web;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 포스트 저장소가 구현해야 하는 트레이트이다.
+pub trait PostRepository: Send + Sync {
+    /// `post_id`의 포스트를 반환한다. 존재하지 않거나 삭제된 포스트의 경우 `None`이다.
+    fn get(&self, post_id: &str) -> Option<Post>;
+    /// 포스트가 게시되었는지 확인한다. 예약 게시 시각이 지나지 않은 경우 `false`이다.
+    fn is_published(&self, post_id: u64) -> bool;
+    /// 포스트의 조회 수를 1 증가시킨다.
+    fn record_view(&self, post_id: u64);
+    /// 포스트 작성자의 고유 ID를 반환한다. 존재하지 않거나 삭제된 포스트의 경우 `None`이다.
+    fn get_owner(&self, post_id: &str) -> Option<String>;
+    /// 삭제된 포스트 작성자의 고유 ID를 반환한다. 삭제된 포스트가 아닌 경우 `None`이다.
+    fn get_deleted_owner(&self, post_id: u64) -> Option<String>;
+    /// 포스트의 종류를 반환한다. 존재하지 않는 포스트의 경우 `None`이다.
+    fn get_post_type(&self, post_id: u64) -> Option<PostType>;
+    /// 요청받은 포스트를 검증한 뒤 등록하고, 등록된 포스트를 반환한다.
+    fn create(&self, request: &PostRequest) -> Result<Post, ApiError>;
+    /// 포스트를 수정한다. 다른 곳에서 먼저 수정된 경우 `false`를 반환한다.
+    fn update(&self, post_id: &str, request: UpdatePostRequest) -> Result<bool, ApiError>;
+    /// 포스트를 삭제된 상태로 바꾼다.
+    fn delete(&self, post_id: &str) -> Result<(), ApiError>;
+    /// 삭제된 포스트를 복구한다.
+    fn restore(&self, post_id: u64) -> Result<(), ApiError>;
+    /// 질문 포스트의 채택된 답변을 지정하거나(`Some`) 취소한다(`None`).
+    fn set_accepted_answer(&self, post_id: u64, comment_id: Option<u64>) -> Result<(), ApiError>;
+}
+
+/// 사용자 저장소가 구현해야 하는 트레이트이다.
+pub trait UserRepository: Send + Sync {
+    /// `user_id`의 사용자를 반환한다. 존재하지 않는 사용자의 경우 `None`이다.
+    fn get(&self, user_id: &str) -> Option<User>;
+    /// 새 사용자를 등록한다. 이미 존재하는 `user_id`인 경우 `ApiError::Conflict`를 반환한다.
+    fn create(&self, user: User) -> Result<(), ApiError>;
+    /// 사용자가 운영자인지 확인한다. 존재하지 않는 사용자의 경우 `false`이다.
+    fn is_moderator(&self, user_id: &str) -> bool;
+    /// 사용자가 계정을 비활성화한 상태인지 확인한다. 존재하지 않는 사용자의 경우 `false`이다.
+    fn is_deactivated(&self, user_id: &str) -> bool;
+    /// 사용자의 인증 토큰 버전을 반환한다. 존재하지 않는 사용자의 경우 0이다.
+    fn token_version(&self, user_id: &str) -> u32;
+    /// 사용자의 계정을 비활성화한다. 작성한 콘텐츠는 남지만 작성자 이름은 `DEACTIVATED_USER_NAME`으로 표시된다.
+    fn deactivate(&self, user_id: &str) -> Result<(), ApiError>;
+    /// 비활성화한 지 `REACTIVATION_DAYS`일이 지나지 않은 계정을 다시 활성화한다. 활성화하지 못한 경우 `false`를 반환한다.
+    fn reactivate(&self, user_id: &str) -> Result<bool, ApiError>;
+    /// `blocker_id` 사용자가 `blocked_id` 사용자를 차단한다. 차단할 사용자가 존재하지 않는 경우 `false`를 반환한다.
+    fn block(&self, blocker_id: &str, blocked_id: &str) -> Result<bool, ApiError>;
+    /// `blocker_id` 사용자가 `blocked_id` 사용자의 차단을 해제한다.
+    fn unblock(&self, blocker_id: &str, blocked_id: &str) -> Result<(), ApiError>;
+    /// 사용자가 차단한 사용자들의 고유 ID를 반환한다.
+    fn get_blocked(&self, user_id: &str) -> Vec<String>;
+    /// 사용자의 프로필을 변경한다.
+    fn update_profile(&self, user_id: &str, request: UpdateProfileRequest) -> Result<(), ApiError>;
+    /// 공감한 포스트 목록의 공개 여부를 변경한다.
+    fn set_likes_public(&self, user_id: &str, likes_public: bool) -> Result<(), ApiError>;
+    /// 메일 알림 설정을 반환한다. 존재하지 않는 사용자의 경우 `None`이다.
+    fn get_notification_settings(&self, user_id: &str) -> Option<NotificationSettings>;
+    /// 메일 알림 설정을 변경한다. 메일 주소가 바뀌어 다시 인증해야 하는 경우 `true`를 반환한다.
+    fn update_notification_settings(
+        &self,
+        user_id: &str,
+        request: UpdateNotificationsRequest,
+    ) -> Result<bool, ApiError>;
+    /// 사용자와 사용자가 작성한 콘텐츠를 삭제하고, 저장소에서 지워야 하는 파일들의 경로를 반환한다.
+    fn delete(&self, user: User) -> Result<Vec<String>, ApiError>;
+}
+
+/// 댓글 저장소가 구현해야 하는 트레이트이다.
+pub trait CommentRepository: Send + Sync {
+    /// 포스트에 달린 댓글들을 `sort`에 따라 반환한다. 채택된 답변이 가장 먼저 온다.
+    fn get_by_post(&self, post_id: u32, sort: CommentSort) -> Vec<Comment>;
+    /// 포스트에 달린 댓글들을 커서 방식으로 `sort`에 따라 반환한다. 채택된 답변도 같은 순서로 정렬된다.
+    fn get_page_by_post(&self, post_id: u32, cursor: &Cursor, sort: CommentSort) -> Vec<Comment>;
+    /// 포스트에 달린 댓글의 수를 반환한다.
+    fn count_by_post(&self, post_id: u32) -> u64;
+    /// 댓글 작성자의 고유 ID를 반환한다. 존재하지 않는 댓글의 경우 `None`이다.
+    fn get_owner(&self, comment_id: u64) -> Option<String>;
+    /// 댓글이 달린 포스트의 고유 ID를 반환한다. 존재하지 않는 댓글의 경우 `None`이다.
+    fn get_post_id(&self, comment_id: u64) -> Option<u64>;
+    /// 요청받은 댓글을 검증한 뒤 등록하고, 등록된 댓글을 반환한다.
+    fn create(&self, request: &CommentRequest) -> Result<Comment, ApiError>;
+    /// 댓글을 수정한다. 다른 곳에서 먼저 수정된 경우 `false`를 반환한다.
+    fn update(&self, comment_id: u64, data: String, version: u64) -> Result<bool, ApiError>;
+    /// 댓글을 삭제하고 댓글이 달려있던 포스트의 고유 ID를 반환한다. 존재하지 않는 댓글의 경우 `None`이다.
+    fn delete(&self, comment_id: u64) -> Result<Option<u32>, ApiError>;
+}
+
+/// 앱 상태로 등록할 저장소들이다.
+///
+/// 서버 시작 시 생성한 뒤 각 저장소를 `web::Data::from()`으로 감싸 등록한다.
+/// # 예제
+/// ```ignore
+/// let repositories = Repositories::open(&config.db).unwrap();
+/// App::new().app_data(web::Data::from(repositories.posts.clone()));
+/// ```
+#[derive(Clone)]
+pub struct Repositories {
+    /// 포스트 저장소이다.
+    pub posts: Arc<dyn PostRepository>,
+    /// 사용자 저장소이다.
+    pub users: Arc<dyn UserRepository>,
+    /// 댓글 저장소이다.
+    pub comments: Arc<dyn CommentRepository>,
+}
+
+impl Repositories {
+    /// MySQL을 사용하는 저장소들을 생성하는 메서드이다.
+    pub fn mysql() -> Self {
+        let repository = Arc::new(MySqlRepository);
+        Self {
+            posts: repository.clone(),
+            users: repository.clone(),
+            comments: repository,
+        }
+    }
+    /// SQLite 파일을 사용하는 저장소들을 생성하는 메서드이다.
+    ///
+    /// 파일을 열 수 없는 경우 그 이유를 반환한다.
+    pub fn sqlite(path: &Path) -> Result<Self, String> {
+        let repository = Arc::new(
+            SqliteRepository::open(path)
+                .map_err(|error| format!("{}을 열 수 없음: {}", path.display(), error))?,
+        );
+        Ok(Self {
+            posts: repository.clone(),
+            users: repository.clone(),
+            comments: repository,
+        })
+    }
+    /// DB 설정에 지정된 종류의 저장소들을 생성하는 메서드이다.
+    pub fn open(config: &DbConfig) -> Result<Self, String> {
+        match &config.driver {
+            DbDriver::MySql => Ok(Self::mysql()),
+            DbDriver::Sqlite(path) => Self::sqlite(path),
+        }
+    }
+}
+
+/// MySQL을 사용하는 기본 저장소이다.
+///
+/// 각 모듈에 정의된 MySQL 질의 메서드를 그대로 호출한다.
+pub struct MySqlRepository;
+
+impl PostRepository for MySqlRepository {
+    fn get(&self, post_id: &str) -> Option<Post> {
+        Post::get_post(web::Path::from(post_id.to_string()))
+    }
+    fn is_published(&self, post_id: u64) -> bool {
+        Post::is_published(post_id)
+    }
+    fn record_view(&self, post_id: u64) {
+        Post::record_view(post_id)
+    }
+    fn get_owner(&self, post_id: &str) -> Option<String> {
+        Post::get_owner(post_id)
+    }
+    fn get_deleted_owner(&self, post_id: u64) -> Option<String> {
+        Post::get_deleted_owner(post_id)
+    }
+    fn get_post_type(&self, post_id: u64) -> Option<PostType> {
+        Post::get_post_type(post_id)
+    }
+    fn create(&self, request: &PostRequest) -> Result<Post, ApiError> {
+        request.submit()
+    }
+    fn update(&self, post_id: &str, request: UpdatePostRequest) -> Result<bool, ApiError> {
+        Ok(Post::update_post(post_id, request)?)
+    }
+    fn delete(&self, post_id: &str) -> Result<(), ApiError> {
+        Ok(Post::delete_post(web::Query(DeletePostRequest {
+            post_id: post_id.to_string(),
+        }))?)
+    }
+    fn restore(&self, post_id: u64) -> Result<(), ApiError> {
+        Ok(Post::restore_post(post_id)?)
+    }
+    fn set_accepted_answer(&self, post_id: u64, comment_id: Option<u64>) -> Result<(), ApiError> {
+        Ok(Post::set_accepted_answer(post_id, comment_id)?)
+    }
+}
+
+impl UserRepository for MySqlRepository {
+    fn get(&self, user_id: &str) -> Option<User> {
+        User::get_user(user_id.to_string())
+    }
+    fn create(&self, user: User) -> Result<(), ApiError> {
+        let registered = serde_json::json!({
+            "user_id": user.user_id,
+            "user_name": user.user_name,
+        });
+        match User::new_user(web::Query(user)) {
+            Ok(_) => {}
+            Err(mysql::Error::MySqlError(ref error)) if error.code == 1062 => {
+                return Err(ApiError::Conflict(USER_EXISTS.to_string()))
+            }
+            Err(error) => return Err(error.into()),
+        }
+        webhook::emit(WebhookEvent::UserRegistered, &registered);
+        Ok(())
+    }
+    fn is_moderator(&self, user_id: &str) -> bool {
+        User::is_moderator(user_id)
+    }
+    fn is_deactivated(&self, user_id: &str) -> bool {
+        User::is_deactivated(user_id)
+    }
+    fn token_version(&self, user_id: &str) -> u32 {
+        User::token_version(user_id)
+    }
+    fn deactivate(&self, user_id: &str) -> Result<(), ApiError> {
+        Ok(User::deactivate(user_id)?)
+    }
+    fn reactivate(&self, user_id: &str) -> Result<bool, ApiError> {
+        Ok(User::reactivate(user_id)?)
+    }
+    fn block(&self, blocker_id: &str, blocked_id: &str) -> Result<bool, ApiError> {
+        Ok(Block::block(blocker_id, blocked_id)?)
+    }
+    fn unblock(&self, blocker_id: &str, blocked_id: &str) -> Result<(), ApiError> {
+        Ok(Block::unblock(blocker_id, blocked_id)?)
+    }
+    fn get_blocked(&self, user_id: &str) -> Vec<String> {
+        Block::get_blocked(user_id)
+    }
+    fn update_profile(&self, user_id: &str, request: UpdateProfileRequest) -> Result<(), ApiError> {
+        Ok(User::update_profile(user_id, request)?)
+    }
+    fn set_likes_public(&self, user_id: &str, likes_public: bool) -> Result<(), ApiError> {
+        Ok(User::set_likes_public(user_id, likes_public)?)
+    }
+    fn get_notification_settings(&self, user_id: &str) -> Option<NotificationSettings> {
+        User::get_notification_settings(user_id)
+    }
+    fn update_notification_settings(
+        &self,
+        user_id: &str,
+        request: UpdateNotificationsRequest,
+    ) -> Result<bool, ApiError> {
+        match User::update_notification_settings(user_id, request) {
+            Err(mysql::Error::MySqlError(ref error)) if error.code == 1062 => {
+                Err(ApiError::Conflict(EMAIL_IN_USE.to_string()))
+            }
+            result => Ok(result?),
+        }
+    }
+    fn delete(&self, user: User) -> Result<Vec<String>, ApiError> {
+        Ok(User::delete_user(web::Query(user))?)
+    }
+}
+
+impl CommentRepository for MySqlRepository {
+    fn get_by_post(&self, post_id: u32, sort: CommentSort) -> Vec<Comment> {
+        Comment::get(post_id, sort)
+    }
+    fn get_page_by_post(&self, post_id: u32, cursor: &Cursor, sort: CommentSort) -> Vec<Comment> {
+        Comment::get_after(post_id, cursor, sort)
+    }
+    fn count_by_post(&self, post_id: u32) -> u64 {
+        Comment::count(post_id)
+    }
+    fn get_owner(&self, comment_id: u64) -> Option<String> {
+        Comment::get_owner(comment_id)
+    }
+    fn get_post_id(&self, comment_id: u64) -> Option<u64> {
+        Comment::get_post_id(comment_id)
+    }
+    fn create(&self, request: &CommentRequest) -> Result<Comment, ApiError> {
+        request.submit()
+    }
+    fn update(&self, comment_id: u64, data: String, version: u64) -> Result<bool, ApiError> {
+        Ok(Comment::update_comment(comment_id, data, version)?)
+    }
+    fn delete(&self, comment_id: u64) -> Result<Option<u32>, ApiError> {
+        Ok(Comment::delete_comment(comment_id)?)
+    }
+}
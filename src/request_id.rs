@@ -0,0 +1,65 @@
+//! # 요청 ID 관련 동작을 정의하는 모듈
+//!
+//! `request_id`는 클라이언트가 보고한 문제를 서버 로그와 연결할 수 있도록 모든 요청에
+//! 고유한 ID를 붙이기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 요청에 `X-Request-Id` 헤더가 있으면 그 값을 그대로 사용하고, 없으면 새로 만든다.
+//! 요청 ID는 응답의 `X-Request-Id` 헤더, 요청 처리 중 기록된 모든 로그, 오류 응답의 본문에 포함된다.
+
+use std::future::Future;
+use uuid::Uuid;
+
+/// 요청 ID를 주고받는 헤더 이름이다.
+pub const HEADER: &str = "X-Request-Id";
+/// 클라이언트가 보낸 요청 ID의 최대 길이이다.
+const MAX_LENGTH: usize = 128;
+
+tokio::task_local! {
+    /// 현재 처리 중인 요청의 ID이다.
+    static CURRENT: String;
+}
+
+/// 요청 ID를 정하는 메서드이다.
+///
+/// 클라이언트가 보낸 값이 128자 이하이고 영문자, 숫자, `-`, `_`, `.`, `:`로만 이루어진 경우
+/// 그 값을 사용하며, 아닌 경우 새로운 UUID를 만든다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::request_id;
+/// assert_eq!(request_id::resolve(Some("client-42")), "client-42");
+/// assert_eq!(request_id::resolve(Some("bad id\n")).len(), 36);
+/// assert_eq!(request_id::resolve(None).len(), 36);
+/// ```
+pub fn resolve(header: Option<&str>) -> String {
+    match header {
+        Some(value)
+            if !value.is_empty()
+                && value.len() <= MAX_LENGTH
+                && value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_.:".contains(c)) =>
+        {
+            value.to_string()
+        }
+        _ => Uuid::new_v4().to_string(),
+    }
+}
+
+/// `future`를 처리하는 동안 `current()`가 `request_id`를 반환하도록 하는 메서드이다.
+pub async fn scope<F: Future>(request_id: String, future: F) -> F::Output {
+    CURRENT.scope(request_id, future).await
+}
+
+/// `f`를 실행하는 동안 `current()`가 `request_id`를 반환하도록 하는 메서드이다.
+///
+/// 요청을 처리하는 비동기 작업이 만들어지기 전에 동기적으로 실행되는 부분에 사용한다.
+pub fn sync_scope<R>(request_id: String, f: impl FnOnce() -> R) -> R {
+    CURRENT.sync_scope(request_id, f)
+}
+
+/// 현재 처리 중인 요청의 ID를 반환하는 메서드이다.
+///
+/// 요청을 처리하는 중이 아닌 경우 `None`을 반환한다.
+pub fn current() -> Option<String> {
+    CURRENT.try_with(Clone::clone).ok()
+}
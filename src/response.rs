@@ -0,0 +1,73 @@
+//! # API 응답 관련 동작을 정의하는 모듈
+//!
+//! `response`는 각 모듈의 API가 같은 상태 코드와 `Content-Type`으로 응답하도록 하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! - 조회나 수정에 성공한 경우 `200 OK`와 JSON 본문으로 응답한다.
+//! - 새로운 항목을 만든 경우 `201 Created`와 만든 항목의 JSON 본문, 항목의 주소를 담은 `Location` 헤더로 응답한다.
+//! - 삭제에 성공한 경우와 같이 돌려줄 본문이 없는 경우 `204 No Content`로 응답한다.
+//! - 사람이 읽을 메세지로 응답하는 경우 `text/plain` 형식을 사용한다.
+//!
+//! 오류 응답은 `error` 모듈의 `ApiError`가 JSON 형식으로 만든다.
+
+use actix_web::http::StatusCode;
+use actix_web::HttpResponse;
+use serde::Serialize;
+
+/// JSON 본문의 `Content-Type` 헤더 값이다.
+pub const JSON_CONTENT_TYPE: &str = "application/json;charset=utf-8";
+/// 텍스트 본문의 `Content-Type` 헤더 값이다.
+pub const TEXT_CONTENT_TYPE: &str = "text/plain;charset=utf-8";
+
+/// 주어진 값을 JSON 본문으로 담은 `200 OK` 응답을 반환하는 메서드이다.
+/// # 예제
+/// ```
+/// use actix_web::http::StatusCode;
+/// use code_mmunity_server::response;
+/// let response = response::json(vec!["rust", "python"]);
+/// assert_eq!(response.status(), StatusCode::OK);
+/// assert_eq!(response.headers().get("Content-Type").unwrap(), response::JSON_CONTENT_TYPE);
+/// ```
+pub fn json<T: Serialize>(body: T) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(("Content-Type", JSON_CONTENT_TYPE))
+        .json(body)
+}
+
+/// 새로 만든 항목을 JSON 본문으로 담고, 항목의 주소를 `Location` 헤더로 담은 `201 Created` 응답을 반환하는 메서드이다.
+/// # 예제
+/// ```
+/// use actix_web::http::StatusCode;
+/// use code_mmunity_server::response;
+/// let response = response::created("/api/posts/1".to_string(), serde_json::json!({"post_id": 1}));
+/// assert_eq!(response.status(), StatusCode::CREATED);
+/// assert_eq!(response.headers().get("Location").unwrap(), "/api/posts/1");
+/// ```
+pub fn created<T: Serialize>(location: String, body: T) -> HttpResponse {
+    HttpResponse::Created()
+        .insert_header(("Content-Type", JSON_CONTENT_TYPE))
+        .insert_header(("Location", location))
+        .json(body)
+}
+
+/// 본문이 없는 `204 No Content` 응답을 반환하는 메서드이다.
+pub fn no_content() -> HttpResponse {
+    HttpResponse::NoContent().finish()
+}
+
+/// 주어진 메세지를 텍스트 본문으로 담은 응답을 반환하는 메서드이다.
+///
+/// 성공 응답에만 사용하며, 오류 응답은 `ApiError`를 반환하여 만든다.
+/// # 예제
+/// ```
+/// use actix_web::http::StatusCode;
+/// use code_mmunity_server::response;
+/// let response = response::text(StatusCode::OK, "pong");
+/// assert_eq!(response.status(), StatusCode::OK);
+/// assert_eq!(response.headers().get("Content-Type").unwrap(), response::TEXT_CONTENT_TYPE);
+/// ```
+pub fn text(status: StatusCode, message: impl Into<String>) -> HttpResponse {
+    HttpResponse::build(status)
+        .insert_header(("Content-Type", TEXT_CONTENT_TYPE))
+        .body(message.into())
+}
@@ -0,0 +1,227 @@
+//! # 포스트 수정 이력 관련 동작을 정의하는 모듈
+//!
+//! `revision`은 코드뮤니티에서 포스트가 수정될 때 저장된 이전 내용을 확인하고,
+//! 두 시점의 코드를 비교하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 수정 이력의 번호는 1부터 시작하며, 가장 마지막 번호의 다음 번호는 현재 포스트의 내용을 가리킨다.
+
+use crate::db;
+use crate::error::ApiError;
+use crate::post;
+use crate::response;
+use actix_web::{get, web, HttpResponse};
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+
+/// 수정 이력을 처리하는 객체이다.
+pub struct Revision;
+
+/// 포스트가 수정되기 전에 저장된 내용이다.
+#[derive(Serialize)]
+pub struct RevisionEntry {
+    /// 수정 이력 번호이다.
+    pub revision: u32,
+    /// 수정되기 전의 제목이다.
+    pub title: String,
+    /// 수정되기 전의 프로그래밍 언어 종류이다.
+    pub language: String,
+    /// 수정되기 전의 내용이다.
+    pub data: String,
+    /// 수정된 시각이다.
+    pub create_at: String,
+}
+
+/// 비교 결과에서 한 줄이 어떻게 바뀌었는지 나타낸다.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    /// 바뀌지 않은 줄
+    Equal,
+    /// 새로 추가된 줄
+    Insert,
+    /// 삭제된 줄
+    Delete,
+}
+
+/// 비교 결과의 한 줄이다.
+#[derive(Serialize, Debug)]
+pub struct DiffLine {
+    /// 줄이 어떻게 바뀌었는지 나타낸다.
+    pub kind: DiffKind,
+    /// 이전 내용에서의 줄 번호이다. 추가된 줄인 경우 `None`이다.
+    pub old_line: Option<usize>,
+    /// 이후 내용에서의 줄 번호이다. 삭제된 줄인 경우 `None`이다.
+    pub new_line: Option<usize>,
+    /// 줄의 내용이다. 줄바꿈 문자는 포함되지 않는다.
+    pub text: String,
+}
+
+/// 두 수정 이력의 코드를 비교한 결과이다.
+#[derive(Serialize)]
+pub struct RevisionDiff {
+    /// 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 비교 기준이 되는 수정 이력 번호이다.
+    pub from: u32,
+    /// 비교 대상이 되는 수정 이력 번호이다.
+    pub to: u32,
+    /// 줄 단위로 비교한 결과이다.
+    pub lines: Vec<DiffLine>,
+}
+
+/// 두 문자열을 줄 단위로 비교하는 메서드이다.
+///
+/// # 예제
+/// ```
+/// use code_mmunity_server::revision::{diff_lines, DiffKind};
+/// let lines = diff_lines("fn main() {\n}\n", "fn main() {\n    println!();\n}\n");
+/// assert_eq!(lines.len(), 3);
+/// assert_eq!(lines[1].kind, DiffKind::Insert);
+/// assert_eq!(lines[1].new_line, Some(2));
+/// ```
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            kind: match change.tag() {
+                ChangeTag::Equal => DiffKind::Equal,
+                ChangeTag::Insert => DiffKind::Insert,
+                ChangeTag::Delete => DiffKind::Delete,
+            },
+            old_line: change.old_index().map(|index| index + 1),
+            new_line: change.new_index().map(|index| index + 1),
+            text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+        })
+        .collect()
+}
+
+impl Revision {
+    /// 포스트의 수정 이력들을 오래된 순으로 반환하는 메서드이다.
+    ///
+    /// 존재하지 않거나 비공개 포스트인 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// if let Some(revisions) = Revision::get_revisions(1) {
+    ///     for revision in revisions {
+    ///         println!("{}번째 수정 전 제목은 {} 입니다.", revision.revision, revision.title);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_revisions(post_id: u64) -> Option<Vec<RevisionEntry>> {
+        let mut conn = db::get_conn();
+        conn.exec_first::<u64, _, _>(
+            "select post_id from post where post_id = :post_id and deleted_at is null and visibility <> 'private'",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()?;
+        Some(
+            conn.exec_map(
+                r"select revision, title, language, data, date_format(create_at, '%Y-%m-%d %H:%i:%s')
+                from post_revision where post_id = :post_id order by revision",
+                params! {
+                    "post_id" => post_id,
+                },
+                |(revision, title, language, data, create_at)| RevisionEntry {
+                    revision,
+                    title,
+                    language,
+                    data,
+                    create_at,
+                },
+            )
+            .unwrap(),
+        )
+    }
+    /// 포스트의 특정 수정 이력에 저장된 코드를 반환하는 메서드이다.
+    ///
+    /// `revision`이 마지막 수정 이력 번호의 다음 번호인 경우 현재 포스트의 코드를 반환한다.
+    /// 존재하지 않거나 비공개인 포스트이거나 존재하지 않는 수정 이력인 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_data(post_id: u64, revision: u32) -> Option<String> {
+        let mut conn = db::get_conn();
+        let (data, latest): (String, u32) = conn
+            .exec_first(
+                r"select data, (select coalesce(max(revision), 0) from post_revision where post_id = :post_id)
+                from post where post_id = :post_id and deleted_at is null and visibility <> 'private'",
+                params! {
+                    "post_id" => post_id,
+                },
+            )
+            .unwrap()?;
+        if revision == latest + 1 {
+            return Some(data);
+        }
+        conn.exec_first(
+            "select data from post_revision where post_id = :post_id and revision = :revision",
+            params! {
+                "post_id" => post_id,
+                "revision" => revision,
+            },
+        )
+        .unwrap()
+    }
+    /// 포스트의 두 수정 이력의 코드를 줄 단위로 비교하는 메서드이다.
+    ///
+    /// 둘 중 하나라도 존재하지 않는 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// if let Some(diff) = Revision::diff(1, 1, 2) {
+    ///     for line in diff.lines {
+    ///         println!("{:?} {}", line.kind, line.text);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn diff(post_id: u64, from: u32, to: u32) -> Option<RevisionDiff> {
+        let old = Self::get_data(post_id, from)?;
+        let new = Self::get_data(post_id, to)?;
+        Some(RevisionDiff {
+            post_id,
+            from,
+            to,
+            lines: diff_lines(&old, &new),
+        })
+    }
+}
+
+#[get("/api/posts/{post_id}/revisions")]
+pub async fn get_revisions_api(
+    post_id: web::Path<u64>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let revisions = Revision::get_revisions(*post_id).ok_or_else(post::post_not_found)?;
+    Ok(response::json(revisions))
+}
+
+#[get("/api/posts/{post_id}/revisions/{from}/diff/{to}")]
+pub async fn get_revision_diff_api(
+    path: web::Path<(u64, u32, u32)>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let (post_id, from, to) = path.into_inner();
+    let diff = Revision::diff(post_id, from, to).ok_or_else(|| {
+        ApiError::NotFound("요청한 포스트 또는 수정 이력이 존재하지 않습니다.".to_string())
+    })?;
+    Ok(response::json(diff))
+}
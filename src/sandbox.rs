@@ -0,0 +1,247 @@
+//! # 코드 실행 관련 동작을 정의하는 모듈
+//!
+//! `sandbox`는 포스트의 코드 조각을 외부 실행 샌드박스(Piston API 호환)에 보내 실행하고
+//! 표준 출력과 표준 오류 출력을 돌려주기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 서버는 코드를 직접 실행하지 않으며, `SANDBOX_URL` 환경변수가 지정된 경우에만
+//! `POST /api/posts/{post_id}/run`을 사용할 수 있다.
+//! 실행 시간과 코드, 출력의 크기는 서버에서 제한한 뒤 샌드박스에 전달된다.
+
+use crate::error::ApiError;
+use crate::post::post_not_found;
+use crate::repository::PostRepository;
+use crate::response;
+use actix_web::{post, web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::time::Duration;
+
+/// 실행 단계(컴파일, 실행)마다 허용되는 기본 시간(밀리초)이다.
+const DEFAULT_TIMEOUT_MS: u64 = 3000;
+/// 실행할 수 있는 코드의 기본 최대 크기(바이트)이다.
+const DEFAULT_MAX_CODE_BYTES: usize = 64 * 1024;
+/// 표준 출력과 표준 오류 출력 각각의 최대 크기(바이트)이다. 넘는 부분은 잘린다.
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+/// 샌드박스의 응답을 기다릴 때 실행 제한 시간에 더하는 여유 시간이다.
+const RESPONSE_MARGIN: Duration = Duration::from_secs(5);
+
+/// 샌드박스에 보내는 파일이다.
+#[derive(Serialize)]
+struct SandboxFile<'a> {
+    /// 코드이다.
+    content: &'a str,
+}
+
+/// 샌드박스에 보내는 실행 요청 본문이다.
+#[derive(Serialize)]
+struct SandboxRequest<'a> {
+    /// 프로그래밍 언어 이름이다.
+    language: &'a str,
+    /// 언어의 버전이다. 항상 샌드박스에 설치된 최신 버전(`*`)을 사용한다.
+    version: &'static str,
+    /// 실행할 파일들이다.
+    files: [SandboxFile<'a>; 1],
+    /// 컴파일에 허용되는 시간(밀리초)이다.
+    compile_timeout: u64,
+    /// 실행에 허용되는 시간(밀리초)이다.
+    run_timeout: u64,
+}
+
+/// 실행 단계(컴파일 또는 실행)의 결과이다.
+#[derive(Deserialize, Serialize, Default)]
+pub struct Stage {
+    /// 표준 출력이다.
+    #[serde(default)]
+    pub stdout: String,
+    /// 표준 오류 출력이다.
+    #[serde(default)]
+    pub stderr: String,
+    /// 종료 코드이다. 시그널로 종료된 경우 `None`이다.
+    pub code: Option<i32>,
+    /// 프로세스를 종료시킨 시그널(예: 제한 시간을 넘긴 경우 `SIGKILL`)이다.
+    pub signal: Option<String>,
+}
+
+/// 코드 실행 결과이다.
+#[derive(Deserialize, Serialize)]
+pub struct RunResult {
+    /// 실행한 프로그래밍 언어 이름이다.
+    pub language: String,
+    /// 실행한 언어의 버전이다.
+    pub version: String,
+    /// 컴파일 단계의 결과이다. 컴파일하지 않는 언어인 경우 `None`이다.
+    #[serde(default)]
+    pub compile: Option<Stage>,
+    /// 실행 단계의 결과이다.
+    pub run: Stage,
+}
+
+/// 샌드박스가 오류를 응답할 때의 본문이다.
+#[derive(Deserialize)]
+struct SandboxError {
+    /// 오류 메세지이다.
+    message: String,
+}
+
+/// 외부 실행 샌드박스의 설정이다.
+///
+/// 서버 시작 시 `from_env()`로 한 번만 생성한 뒤 앱 상태로 등록한다.
+pub struct Sandbox {
+    /// 실행 요청을 보낼 주소이다. 지정되지 않은 경우 코드를 실행할 수 없다.
+    url: Option<String>,
+    /// 샌드박스의 API 키이다.
+    api_key: Option<String>,
+    /// 실행 단계마다 허용되는 시간이다.
+    timeout: Duration,
+    /// 실행할 수 있는 코드의 최대 크기(바이트)이다.
+    max_code_bytes: usize,
+    /// 요청에 사용할 HTTP 클라이언트이다.
+    agent: ureq::Agent,
+}
+
+/// 문자열을 `max_bytes`바이트 이하로 자르는 메서드이다. 글자 중간에서 자르지 않는다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::sandbox::truncate;
+/// let mut text = "가나다".to_string();
+/// truncate(&mut text, 4);
+/// assert_eq!(text, "가");
+/// ```
+pub fn truncate(text: &mut String, max_bytes: usize) {
+    if text.len() <= max_bytes {
+        return;
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+}
+
+impl Stage {
+    /// 출력을 `MAX_OUTPUT_BYTES`바이트 이하로 자르는 메서드이다.
+    fn limit_output(&mut self) {
+        truncate(&mut self.stdout, MAX_OUTPUT_BYTES);
+        truncate(&mut self.stderr, MAX_OUTPUT_BYTES);
+    }
+}
+
+impl Sandbox {
+    /// 환경변수를 통해 샌드박스 설정을 생성하는 메서드이다.
+    ///
+    /// `SANDBOX_URL`로 실행 요청을 보낼 주소(예: `https://emkc.org/api/v2/piston/execute`)를,
+    /// `SANDBOX_API_KEY`로 `Authorization` 헤더에 담을 API 키를 지정한다.
+    /// `SANDBOX_TIMEOUT_MS`로 실행 단계마다 허용되는 시간을, `SANDBOX_MAX_CODE_BYTES`로 코드의 최대 크기를 바꿀 수 있다.
+    pub fn from_env() -> Self {
+        let timeout = Duration::from_millis(
+            env::var("SANDBOX_TIMEOUT_MS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .filter(|&value| value > 0)
+                .unwrap_or(DEFAULT_TIMEOUT_MS),
+        );
+        Self {
+            url: env::var("SANDBOX_URL").ok().filter(|url| !url.is_empty()),
+            api_key: env::var("SANDBOX_API_KEY")
+                .ok()
+                .filter(|api_key| !api_key.is_empty()),
+            timeout,
+            max_code_bytes: env::var("SANDBOX_MAX_CODE_BYTES")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_MAX_CODE_BYTES),
+            // 컴파일과 실행 단계가 모두 제한 시간을 채우더라도 응답을 받을 수 있도록 기다린다.
+            agent: ureq::Agent::config_builder()
+                .timeout_global(Some(timeout * 2 + RESPONSE_MARGIN))
+                .http_status_as_error(false)
+                .build()
+                .into(),
+        }
+    }
+    /// 코드를 실행할 수 있는지 확인하는 메서드이다.
+    pub fn is_enabled(&self) -> bool {
+        self.url.is_some()
+    }
+    /// 코드를 샌드박스에서 실행한 결과를 반환하는 메서드이다.
+    ///
+    /// 코드가 너무 크면 `RequestTooLarge`를, 샌드박스가 언어를 지원하지 않는 등 요청을 거부하면 `Validation`을,
+    /// 샌드박스에 접근할 수 없으면 `Unavailable`을 반환한다.
+    pub fn run(&self, language: &str, code: &str) -> Result<RunResult, ApiError> {
+        let url = self.url.as_ref().ok_or_else(|| {
+            ApiError::Unavailable("코드 실행 기능이 설정되지 않았습니다.".to_string())
+        })?;
+        if code.len() > self.max_code_bytes {
+            return Err(ApiError::RequestTooLarge(self.max_code_bytes));
+        }
+        let timeout = self.timeout.as_millis() as u64;
+        let payload = serde_json::to_string(&SandboxRequest {
+            language,
+            version: "*",
+            files: [SandboxFile { content: code }],
+            compile_timeout: timeout,
+            run_timeout: timeout,
+        })
+        .expect("실행 요청을 직렬화할 수 없음");
+        let mut request = self
+            .agent
+            .post(url)
+            .header("Content-Type", "application/json");
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", api_key);
+        }
+        let unavailable = |error: String| {
+            tracing::warn!(
+                error = error.as_str(),
+                "코드 실행 샌드박스에 접근할 수 없습니다."
+            );
+            ApiError::Unavailable(
+                "코드를 실행할 수 없습니다. 잠시 후 다시 시도해주세요.".to_string(),
+            )
+        };
+        let mut response = request
+            .send(&payload)
+            .map_err(|error| unavailable(error.to_string()))?;
+        let status = response.status();
+        let body = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|error| unavailable(error.to_string()))?;
+        if status.is_client_error() {
+            let message = serde_json::from_str::<SandboxError>(&body)
+                .map(|error| error.message)
+                .unwrap_or(body);
+            return Err(ApiError::Validation(message));
+        }
+        if !status.is_success() {
+            return Err(unavailable(format!("HTTP {}", status)));
+        }
+        let mut result: RunResult =
+            serde_json::from_str(&body).map_err(|error| unavailable(error.to_string()))?;
+        result.run.limit_output();
+        if let Some(compile) = result.compile.as_mut() {
+            compile.limit_output();
+        }
+        Ok(result)
+    }
+}
+
+#[post("/api/posts/{post_id}/run")]
+pub async fn run_post_api(
+    post_id: web::Path<String>,
+    sandbox: web::Data<Sandbox>,
+    posts: web::Data<dyn PostRepository>,
+) -> Result<HttpResponse, ApiError> {
+    if !sandbox.is_enabled() {
+        return Err(ApiError::Unavailable(
+            "코드 실행 기능이 설정되지 않았습니다.".to_string(),
+        ));
+    }
+    let post = posts
+        .get(&post_id)
+        .filter(|post| posts.is_published(post.post_id) && post.is_visible_to(None))
+        .ok_or_else(post_not_found)?;
+    let result = web::block(move || sandbox.run(&post.language, &post.data))
+        .await
+        .map_err(|error| ApiError::Unavailable(error.to_string()))??;
+    Ok(response::json(result))
+}
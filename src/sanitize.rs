@@ -0,0 +1,198 @@
+//! # 포스트와 댓글 내용의 XSS 방지 관련 동작을 정의하는 모듈
+//!
+//! `sanitize`는 포스트와 댓글의 내용을 그대로 HTML에 넣는 웹 클라이언트가 저장형 XSS에 노출되지 않도록
+//! 내용을 정리하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 처리 방식은 `CONTENT_SANITIZE` 환경변수로 배포마다 정할 수 있다.
+//! - `strip`(기본값): 저장하기 전에 `<script>`, `<iframe>`, `<object>`, `<embed>` 요소를 내용과 함께 제거한다.
+//! - `escape`: 저장된 내용은 그대로 두고, 응답할 때 포스트의 제목과 내용, 댓글 내용의 HTML 특수 문자를 이스케이프한다.
+//!   HTML 코드 조각을 잃지 않아야 하는 경우 사용한다.
+//! - `none`: 아무 처리도 하지 않는다.
+
+use serde::{Deserialize, Deserializer, Serializer};
+use std::borrow::Cow;
+use std::env;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// `strip` 방식에서 제거하는 요소들이다.
+const STRIPPED_TAGS: &[&str] = &["script", "iframe", "object", "embed"];
+
+/// 서버 시작 후 처음 사용할 때 정해지는 처리 방식이다.
+static MODE: OnceLock<SanitizeMode> = OnceLock::new();
+
+/// 포스트와 댓글 내용의 처리 방식이다.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum SanitizeMode {
+    /// 아무 처리도 하지 않는다.
+    None,
+    /// 저장하기 전에 위험한 요소를 제거한다.
+    #[default]
+    Strip,
+    /// 응답할 때 HTML 특수 문자를 이스케이프한다.
+    Escape,
+}
+
+impl FromStr for SanitizeMode {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "none" => Ok(Self::None),
+            "strip" => Ok(Self::Strip),
+            "escape" => Ok(Self::Escape),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 현재 처리 방식을 반환하는 메서드이다.
+///
+/// 처음 호출될 때 `CONTENT_SANITIZE` 환경변수를 읽으며, 주어지지 않았거나 올바르지 않은 경우 `strip`이다.
+pub fn mode() -> SanitizeMode {
+    *MODE.get_or_init(|| {
+        env::var("CONTENT_SANITIZE")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default()
+    })
+}
+
+/// `<`부터 시작하는 문자열(소문자)이 `tag` 요소의 여는 태그인지 확인하는 메서드이다.
+fn is_tag_start(rest: &str, tag: &str) -> bool {
+    if !rest.starts_with(tag) {
+        return false;
+    }
+    match rest[tag.len()..].chars().next() {
+        None => true,
+        Some(c) => c.is_ascii_whitespace() || c == '>' || c == '/',
+    }
+}
+
+/// `from`부터 처음 나오는 `>`의 다음 위치를 반환하는 메서드이다. 없는 경우 문자열의 끝을 반환한다.
+fn tag_end(text: &str, from: usize) -> usize {
+    text[from..]
+        .find('>')
+        .map_or(text.len(), |offset| from + offset + 1)
+}
+
+/// 문자열에서 `<script>`, `<iframe>`, `<object>`, `<embed>` 요소를 내용과 함께 제거한 문자열을 반환하는 메서드이다.
+///
+/// 닫는 태그가 없는 경우 여는 태그만 제거하며, 짝이 없는 닫는 태그도 제거한다. 태그 이름은 대소문자를 구분하지 않는다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::sanitize::strip_tags;
+/// assert_eq!(strip_tags("a<script>alert(1)</script>b"), "ab");
+/// assert_eq!(strip_tags("<IFRAME src=x>text</iframe>"), "");
+/// assert_eq!(strip_tags("<scripts> if a < b"), "<scripts> if a < b");
+/// ```
+pub fn strip_tags(text: &str) -> String {
+    // ASCII 문자만 소문자로 바꾸므로 바이트 위치가 원래 문자열과 같다.
+    let lower = text.to_ascii_lowercase();
+    let mut stripped = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut index = 0;
+    while let Some(offset) = lower[index..].find('<') {
+        let start = index + offset;
+        let rest = &lower[start + 1..];
+        let end = if let Some(tag) = STRIPPED_TAGS.iter().find(|tag| is_tag_start(rest, tag)) {
+            let closing = format!("</{}", tag);
+            match lower[start..].find(&closing) {
+                Some(close) => tag_end(&lower, start + close),
+                None => tag_end(&lower, start),
+            }
+        } else if let Some(rest) = rest.strip_prefix('/') {
+            if STRIPPED_TAGS.iter().any(|tag| is_tag_start(rest, tag)) {
+                tag_end(&lower, start)
+            } else {
+                index = start + 1;
+                continue;
+            }
+        } else {
+            index = start + 1;
+            continue;
+        };
+        stripped.push_str(&text[last..start]);
+        last = end;
+        index = end;
+    }
+    stripped.push_str(&text[last..]);
+    stripped
+}
+
+/// HTML 특수 문자(`&`, `<`, `>`, `"`, `'`)를 이스케이프한 문자열을 반환하는 메서드이다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::sanitize::escape;
+/// assert_eq!(escape("<b>\"a\" & 'b'</b>"), "&lt;b&gt;&quot;a&quot; &amp; &#39;b&#39;&lt;/b&gt;");
+/// ```
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `escape()`로 이스케이프한 문자열을 원래대로 되돌리는 메서드이다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::sanitize::{escape, unescape};
+/// let text = "if a < b && c > d { \"&lt;\" }";
+/// assert_eq!(unescape(&escape(text)), text);
+/// ```
+pub fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 저장할 내용을 처리 방식에 따라 정리하는 메서드이다. `strip`인 경우에만 내용이 바뀐다.
+pub fn clean(text: &mut String) {
+    if mode() == SanitizeMode::Strip {
+        *text = strip_tags(text);
+    }
+}
+
+/// 응답할 내용을 처리 방식에 따라 반환하는 메서드이다. `escape`인 경우에만 이스케이프된다.
+pub fn output(text: &str) -> Cow<'_, str> {
+    match mode() {
+        SanitizeMode::Escape => Cow::Owned(escape(text)),
+        _ => Cow::Borrowed(text),
+    }
+}
+
+/// 응답할 때 `output()`을 적용하는 serde 직렬화 모듈이다.
+///
+/// 캐시에 저장된 값을 다시 읽을 때 원래 내용이 되도록 역직렬화할 때는 이스케이프를 되돌린다.
+/// # 예제
+/// ```ignore
+/// #[serde(with = "sanitize::escaped")]
+/// pub data: String,
+/// ```
+pub mod escaped {
+    use super::*;
+
+    /// 처리 방식에 따라 이스케이프하여 직렬화하는 메서드이다.
+    pub fn serialize<S: Serializer>(text: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&output(text))
+    }
+
+    /// 처리 방식이 `escape`인 경우 이스케이프를 되돌려 역직렬화하는 메서드이다.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Ok(match mode() {
+            SanitizeMode::Escape => unescape(&text),
+            _ => text,
+        })
+    }
+}
@@ -0,0 +1,94 @@
+//! # 주기적인 작업 실행 관련 동작을 정의하는 모듈
+//!
+//! `scheduler`는 코드뮤니티 서버에서 요청과 관계없이 주기적으로 실행되어야 하는
+//! 작업(DB 관리 작업 등)을 등록하고 실행하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 각 작업은 별도의 스레드에서 실행되며, 작업 중 패닉이 발생하더라도 다음 실행 시각에 다시 실행된다.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 하루를 초 단위로 나타낸 값이다.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// 작업을 실행할 시각을 정하는 방식이다.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Schedule {
+    /// 지정한 간격마다 실행한다.
+    Every(Duration),
+    /// 매일 지정한 시각(UTC 기준, 0~23시)에 실행한다.
+    DailyAt(u64),
+}
+
+impl Schedule {
+    /// 현재 시각(유닉스 시간, 초)을 기준으로 다음 실행까지 기다려야 하는 시간을 반환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::scheduler::Schedule;
+    /// use std::time::Duration;
+    /// // 1970-01-01 01:00:00 UTC 기준으로 다음 03시까지는 2시간이 남는다.
+    /// assert_eq!(Schedule::DailyAt(3).next_delay(3600), Duration::from_secs(2 * 3600));
+    /// // 이미 지난 시각이면 다음 날 같은 시각에 실행한다.
+    /// assert_eq!(Schedule::DailyAt(0).next_delay(3600), Duration::from_secs(23 * 3600));
+    /// ```
+    pub fn next_delay(&self, now: u64) -> Duration {
+        match self {
+            Self::Every(interval) => *interval,
+            Self::DailyAt(hour) => {
+                let target = (hour % 24) * 3600;
+                let elapsed = now % SECONDS_PER_DAY;
+                Duration::from_secs(if elapsed < target {
+                    target - elapsed
+                } else {
+                    SECONDS_PER_DAY - elapsed + target
+                })
+            }
+        }
+    }
+}
+
+/// 실행할 작업이다.
+type Task = Box<dyn Fn() + Send + 'static>;
+
+/// 주기적인 작업들을 등록하고 실행하는 객체이다.
+///
+/// # 예제
+/// ```ignore
+/// let mut scheduler = Scheduler::default();
+/// scheduler.add("cleanup", Schedule::Every(Duration::from_secs(60)), || println!("정리 작업"));
+/// scheduler.start();
+/// ```
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<(String, Schedule, Task)>,
+}
+
+impl Scheduler {
+    /// 작업을 등록하는 메서드이다.
+    ///
+    /// `name`은 작업 실행 및 실패를 출력할 때 사용된다.
+    pub fn add<F>(&mut self, name: &str, schedule: Schedule, task: F)
+    where
+        F: Fn() + Send + 'static,
+    {
+        self.jobs.push((name.to_string(), schedule, Box::new(task)));
+    }
+    /// 등록된 작업들을 각각의 스레드에서 실행하기 시작하는 메서드이다.
+    pub fn start(self) {
+        for (name, schedule, task) in self.jobs {
+            thread::spawn(move || loop {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|time| time.as_secs())
+                    .unwrap_or(0);
+                thread::sleep(schedule.next_delay(now));
+                tracing::info!(job = name.as_str(), "작업 실행");
+                if panic::catch_unwind(AssertUnwindSafe(&task)).is_err() {
+                    tracing::error!(job = name.as_str(), "작업 실행 중 문제가 발생하였습니다.");
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! # 검색 관련 동작을 정의하는 모듈
+//!
+//! `search`는 클라이언트의 검색창에서 입력하는 동안 보여줄 검색어 자동 완성을 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 자동 완성은 입력한 검색어로 시작하는 포스트 제목, 태그, 사용자 이름을 종류별로 최대 `MAX_SUGGESTIONS`개씩 반환한다.
+//! 요청한 사용자가 차단한 사용자와 섀도 밴된 사용자, 그리고 그 사용자들이 작성한 포스트는 결과에서 제외된다.
+
+use crate::auth::Identity;
+use crate::block::BlockList;
+use crate::db;
+use crate::error::ApiError;
+use crate::repository::UserRepository;
+use crate::response;
+use crate::sanitize;
+use crate::shadow_ban::ShadowBans;
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+
+/// 종류별로 반환하는 자동 완성 결과의 최대 개수이다.
+pub const MAX_SUGGESTIONS: u64 = 5;
+/// 자동 완성에 사용할 수 있는 검색어의 최대 글자 수이다.
+const MAX_QUERY_CHARS: usize = 64;
+
+/// 쿼리 스트링을 통해 자동 완성할 검색어를 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct SuggestRequest {
+    /// 사용자가 입력 중인 검색어이다.
+    pub q: String,
+}
+
+/// 자동 완성 결과 중 포스트 하나이다.
+#[derive(Serialize)]
+pub struct PostSuggestion {
+    /// 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 포스트의 제목이다.
+    #[serde(with = "sanitize::escaped")]
+    pub title: String,
+    /// 포스트를 작성한 사용자의 고유 ID이다. 차단한 사용자의 포스트를 제외할 때 사용하며 응답에는 포함되지 않는다.
+    #[serde(skip)]
+    pub user_id: String,
+}
+
+/// 자동 완성 결과 중 사용자 한 명이다.
+#[derive(Serialize)]
+pub struct UserSuggestion {
+    /// 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 사용자의 이름이다.
+    pub user_name: String,
+}
+
+/// 검색어 자동 완성 결과이다.
+#[derive(Serialize, Default)]
+pub struct Suggestions {
+    /// 검색어로 시작하는 제목의 포스트들이다. 공감 수가 많은 순으로 정렬된다.
+    pub posts: Vec<PostSuggestion>,
+    /// 검색어로 시작하는 태그들이다.
+    pub tags: Vec<String>,
+    /// 검색어로 시작하는 이름의 사용자들이다.
+    pub users: Vec<UserSuggestion>,
+}
+
+/// `like` 패턴에서 특수한 의미를 가지는 문자를 이스케이프하여, 검색어로 시작하는 값을 찾는 패턴을 반환하는 메서드이다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::search::prefix_pattern;
+/// assert_eq!(prefix_pattern("rust"), "rust%");
+/// assert_eq!(prefix_pattern("100%_done\\"), "100\\%\\_done\\\\%");
+/// ```
+pub fn prefix_pattern(query: &str) -> String {
+    let mut pattern = String::with_capacity(query.len() + 1);
+    for c in query.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+impl Suggestions {
+    /// 검색어로 시작하는 포스트 제목, 태그, 사용자 이름을 찾는 메서드이다.
+    ///
+    /// 게시되지 않았거나 삭제된 포스트와 이용 정지되었거나 비활성화된 사용자는 제외된다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn find(query: &str) -> Result<Self> {
+        let mut conn = db::get_read_conn();
+        let pattern = prefix_pattern(query);
+        let posts = conn.exec_map(
+            r"select post_id, title, user_id from post
+            where title like :pattern and published and visibility = 'public' and deleted_at is null
+            order by likes desc, post_id desc limit :limit",
+            params! {
+                "pattern" => &pattern,
+                "limit" => MAX_SUGGESTIONS,
+            },
+            |(post_id, title, user_id)| PostSuggestion {
+                post_id,
+                title,
+                user_id,
+            },
+        )?;
+        let tags = conn.exec(
+            "select name from tag where name like :pattern order by name limit :limit",
+            params! {
+                "pattern" => prefix_pattern(query.trim_start_matches('#')).to_lowercase(),
+                "limit" => MAX_SUGGESTIONS,
+            },
+        )?;
+        let users = conn.exec_map(
+            r"select user_id, user_name from user
+            where user_name like :pattern and (banned_until is null or banned_until < now())
+            and deactivated_at is null
+            order by reputation desc, user_name limit :limit",
+            params! {
+                "pattern" => &pattern,
+                "limit" => MAX_SUGGESTIONS,
+            },
+            |(user_id, user_name)| UserSuggestion { user_id, user_name },
+        )?;
+        Ok(Self { posts, tags, users })
+    }
+}
+
+#[get("/api/search/suggest")]
+pub async fn suggest_api(
+    req: HttpRequest,
+    request: web::Query<SuggestRequest>,
+    users: web::Data<dyn UserRepository>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let query = request.q.trim();
+    if query.chars().count() > MAX_QUERY_CHARS {
+        return Err(ApiError::Validation(format!(
+            "검색어는 {}자를 넘을 수 없습니다.",
+            MAX_QUERY_CHARS
+        )));
+    }
+    let mut suggestions = if query.is_empty() {
+        Suggestions::default()
+    } else {
+        let query = query.to_string();
+        web::block(move || Suggestions::find(&query))
+            .await
+            .map_err(|error| ApiError::Unavailable(error.to_string()))??
+    };
+    let viewer_id = Identity::from_header(&req);
+    let hidden = BlockList::load(users.get_ref(), viewer_id.as_deref())
+        .extend(shadow_bans.hidden_from(viewer_id.as_deref()));
+    hidden.retain(&mut suggestions.posts, |post| &post.user_id);
+    hidden.retain(&mut suggestions.users, |user| &user.user_id);
+    Ok(response::json(suggestions))
+}
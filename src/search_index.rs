@@ -0,0 +1,465 @@
+//! # 검색 색인 연동 관련 동작을 정의하는 모듈
+//!
+//! `search_index`는 MySQL의 `like` 검색보다 관련도가 높은 결과를 위해 포스트를 외부 검색 엔진(Meilisearch)에
+//! 색인하고, 포스트 검색(`GET /api/posts/search`)을 처리하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! `MEILISEARCH_URL`이 지정된 경우 포스트가 작성, 수정, 삭제, 복구될 때마다 별도의 작업에서 색인을 갱신하며,
+//! 검색은 색인에서 찾은 포스트를 DB에서 읽어 반환한다. 지정되지 않았거나 검색 엔진에 요청할 수 없는 경우
+//! DB에서 제목과 내용에 검색어가 포함된 포스트를 최신순으로 찾는다.
+//!
+//! 색인이 DB와 달라진 경우 관리자 API의 재구성 작업(`POST /api/admin/maintenance/reindex`)으로 전체 색인을 다시 만들 수 있다.
+
+use crate::auth::Identity;
+use crate::block::BlockList;
+use crate::error::ApiError;
+use crate::pagination::Pagination;
+use crate::post::{Post, Visibility};
+use crate::repository::UserRepository;
+use crate::response;
+use crate::search::prefix_pattern;
+use crate::shadow_ban::ShadowBans;
+use crate::summary::{self, BodyMode};
+use crate::{db, sanitize};
+use actix_web::{get, rt, web, HttpRequest, HttpResponse};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::time::Duration;
+
+/// 검색 엔진에 보내는 요청의 기본 제한 시간(밀리초)이다.
+const DEFAULT_TIMEOUT_MS: u64 = 2000;
+/// 색인 이름의 기본값이다.
+const DEFAULT_INDEX: &str = "posts";
+/// 전체 색인을 다시 만들 때 한 번에 보내는 포스트 수이다.
+const REBUILD_BATCH: u64 = 100;
+/// 검색어의 최대 글자 수이다.
+const MAX_QUERY_CHARS: usize = 200;
+
+/// 검색 엔진에 저장하는 포스트 문서이다.
+#[derive(Serialize)]
+struct Document<'a> {
+    post_id: u64,
+    title: &'a str,
+    data: &'a str,
+    language: &'a str,
+    user_name: &'a str,
+    tags: &'a [String],
+    likes: u64,
+    create_at: i64,
+}
+
+impl<'a> From<&'a Post> for Document<'a> {
+    fn from(post: &'a Post) -> Self {
+        Self {
+            post_id: post.post_id,
+            title: &post.title,
+            data: &post.data,
+            language: &post.language,
+            user_name: &post.user_name,
+            tags: &post.tags,
+            likes: post.likes,
+            create_at: post.create_at.timestamp(),
+        }
+    }
+}
+
+/// 검색 엔진에 보내는 검색 요청이다.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchRequest<'a> {
+    q: &'a str,
+    limit: u64,
+    offset: u64,
+    attributes_to_retrieve: [&'static str; 1],
+}
+
+/// 검색 엔진이 반환한 검색 결과이다.
+#[derive(Deserialize)]
+struct SearchResponse {
+    hits: Vec<Hit>,
+}
+
+/// 검색 결과 중 포스트 하나이다.
+#[derive(Deserialize)]
+struct Hit {
+    post_id: u64,
+}
+
+/// Meilisearch 서버에 접속하기 위한 정보이다.
+#[derive(Clone)]
+struct Meilisearch {
+    url: String,
+    api_key: Option<String>,
+    index: String,
+    agent: ureq::Agent,
+}
+
+impl Meilisearch {
+    /// 색인의 API 주소를 반환하는 메서드이다.
+    fn endpoint(&self, path: &str) -> String {
+        format!(
+            "{}/indexes/{}{}",
+            self.url.trim_end_matches('/'),
+            self.index,
+            path
+        )
+    }
+    /// 검색 엔진에 `POST` 요청을 보내고 응답 본문을 반환하는 메서드이다.
+    fn post(&self, path: &str, body: &str) -> std::result::Result<String, String> {
+        let mut request = self
+            .agent
+            .post(self.endpoint(path))
+            .header("Content-Type", "application/json");
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+        request
+            .send(body)
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|error| error.to_string())
+    }
+    /// 검색 엔진에 `DELETE` 요청을 보내는 메서드이다.
+    fn delete(&self, path: &str) -> std::result::Result<(), String> {
+        let mut request = self.agent.delete(self.endpoint(path));
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+        request
+            .call()
+            .map(|_| ())
+            .map_err(|error| error.to_string())
+    }
+}
+
+/// 포스트를 검색 색인에 반영하고 검색하는 객체이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+#[derive(Clone)]
+pub struct SearchIndex {
+    backend: Option<Meilisearch>,
+}
+
+impl SearchIndex {
+    /// 검색 엔진을 사용하지 않는 객체를 생성하는 메서드이다. 검색은 항상 DB에서 수행한다.
+    pub fn disabled() -> Self {
+        Self { backend: None }
+    }
+    /// 환경변수를 통해 검색 엔진 설정을 읽는 메서드이다.
+    ///
+    /// `MEILISEARCH_URL`이 지정되지 않은 경우 검색 엔진을 사용하지 않는다.
+    /// `MEILISEARCH_API_KEY`, `MEILISEARCH_INDEX`(기본값 `posts`), `MEILISEARCH_TIMEOUT_MS`(기본값 2000)를 함께 지정할 수 있다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::search_index::SearchIndex;
+    /// std::env::remove_var("MEILISEARCH_URL");
+    /// assert!(!SearchIndex::from_env().is_enabled());
+    /// ```
+    pub fn from_env() -> Self {
+        let Some(url) = env::var("MEILISEARCH_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+        else {
+            return Self::disabled();
+        };
+        let timeout = env::var("MEILISEARCH_TIMEOUT_MS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .filter(|&value| value > 0)
+            .unwrap_or(DEFAULT_TIMEOUT_MS);
+        Self {
+            backend: Some(Meilisearch {
+                url,
+                api_key: env::var("MEILISEARCH_API_KEY")
+                    .ok()
+                    .filter(|api_key| !api_key.is_empty()),
+                index: env::var("MEILISEARCH_INDEX")
+                    .ok()
+                    .filter(|index| !index.is_empty())
+                    .unwrap_or_else(|| DEFAULT_INDEX.to_string()),
+                agent: ureq::Agent::config_builder()
+                    .timeout_global(Some(Duration::from_millis(timeout)))
+                    .build()
+                    .into(),
+            }),
+        }
+    }
+    /// 검색 엔진을 사용하는지 확인하는 메서드이다.
+    pub fn is_enabled(&self) -> bool {
+        self.backend.is_some()
+    }
+    /// 포스트의 현재 상태를 별도의 작업에서 검색 색인에 반영하는 메서드이다.
+    ///
+    /// 게시된 공개 포스트는 색인에 추가하거나 갱신하고, 삭제되었거나 게시되지 않았거나 공개 포스트가 아닌 포스트는 색인에서 제거한다.
+    /// 검색 엔진을 사용하지 않는 경우 아무 작업도 하지 않는다.
+    pub fn sync(&self, post_id: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        let index = self.clone();
+        rt::spawn(async move {
+            let result = web::block(move || index.apply(post_id)).await;
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => tracing::warn!(
+                    post_id,
+                    error = error.as_str(),
+                    "검색 색인을 갱신하지 못했습니다."
+                ),
+                Err(_) => tracing::error!(post_id, "검색 색인을 갱신하지 못했습니다."),
+            }
+        });
+    }
+    /// 포스트의 현재 상태를 현재 스레드에서 검색 색인에 반영하는 메서드이다.
+    ///
+    /// `scheduler`의 작업처럼 actix 런타임 밖에서 실행되는 경우에 사용하며, 반영 기준은 `sync`와 같다.
+    /// 검색 엔진을 사용하지 않는 경우 아무 작업도 하지 않는다.
+    pub fn sync_now(&self, post_id: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Err(error) = self.apply(post_id) {
+            tracing::warn!(
+                post_id,
+                error = error.as_str(),
+                "검색 색인을 갱신하지 못했습니다."
+            );
+        }
+    }
+    /// 포스트의 현재 상태를 검색 색인에 반영하는 메서드이다.
+    fn apply(&self, post_id: u64) -> std::result::Result<(), String> {
+        let post = Post::get_post(web::Path::from(post_id.to_string())).filter(|post| {
+            Post::is_published(post.post_id) && post.visibility == Visibility::Public
+        });
+        match post {
+            Some(post) => self.put(&[post]),
+            None => self.remove(post_id),
+        }
+    }
+    /// 사용자의 이름이 바뀌었을 때 사용자가 작성한 게시된 포스트들을 별도의 작업에서 검색 색인에 다시 반영하는 메서드이다.
+    ///
+    /// 색인의 문서에는 작성자의 이름이 함께 저장되어 있기 때문에 필요하다.
+    /// 검색 엔진을 사용하지 않는 경우 아무 작업도 하지 않는다.
+    pub fn sync_author(&self, user_id: &str) {
+        if !self.is_enabled() {
+            return;
+        }
+        let index = self.clone();
+        let user_id = user_id.to_string();
+        rt::spawn(async move {
+            let result = web::block(move || {
+                let posts: Vec<Post> = Post::get_ids_by_user(&user_id)
+                    .into_iter()
+                    .filter(|post_id| Post::is_published(*post_id))
+                    .filter_map(|post_id| Post::get_post(web::Path::from(post_id.to_string())))
+                    .filter(|post| post.visibility == Visibility::Public)
+                    .collect();
+                if posts.is_empty() {
+                    return Ok(());
+                }
+                index.put(&posts)
+            })
+            .await;
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => {
+                    tracing::warn!(error = error.as_str(), "검색 색인을 갱신하지 못했습니다.")
+                }
+                Err(_) => tracing::error!("검색 색인을 갱신하지 못했습니다."),
+            }
+        });
+    }
+    /// 포스트들을 검색 색인에 추가하거나 갱신하는 메서드이다.
+    fn put(&self, posts: &[Post]) -> std::result::Result<(), String> {
+        let Some(backend) = &self.backend else {
+            return Ok(());
+        };
+        let documents: Vec<Document> = posts.iter().map(Document::from).collect();
+        let body = serde_json::to_string(&documents).map_err(|error| error.to_string())?;
+        backend
+            .post("/documents?primaryKey=post_id", &body)
+            .map(|_| ())
+    }
+    /// 포스트를 검색 색인에서 제거하는 메서드이다.
+    fn remove(&self, post_id: u64) -> std::result::Result<(), String> {
+        let Some(backend) = &self.backend else {
+            return Ok(());
+        };
+        backend.delete(&format!("/documents/{}", post_id))
+    }
+    /// 검색 색인을 비운 뒤 게시된 모든 포스트로 다시 만드는 메서드이다.
+    ///
+    /// 재구성 작업의 한 단계로 실행되며, 검색 엔진을 사용하지 않는 경우 아무 작업도 하지 않는다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    /// - 검색 엔진에 요청할 수 없는 경우
+    pub fn rebuild(&self) {
+        let Some(backend) = &self.backend else {
+            return;
+        };
+        backend
+            .delete("/documents")
+            .expect("검색 색인을 비우지 못했습니다.");
+        for page in 1.. {
+            let posts = Post::get_posts(&Pagination {
+                page: Some(page),
+                per_page: Some(REBUILD_BATCH),
+            });
+            if posts.is_empty() {
+                break;
+            }
+            self.put(&posts)
+                .expect("검색 색인에 포스트를 추가하지 못했습니다.");
+        }
+    }
+    /// 검색 엔진에서 검색어와 관련된 포스트의 고유 ID들을 관련도 순으로 반환하는 메서드이다.
+    ///
+    /// 검색 엔진을 사용하지 않거나 요청에 실패한 경우 `None`을 반환한다.
+    fn search_ids(&self, query: &str, page: &Pagination) -> Option<Vec<u64>> {
+        let backend = self.backend.as_ref()?;
+        let body = serde_json::to_string(&SearchRequest {
+            q: query,
+            limit: page.limit(),
+            offset: page.offset(),
+            attributes_to_retrieve: ["post_id"],
+        })
+        .ok()?;
+        match backend.post("/search", &body).and_then(|body| {
+            serde_json::from_str::<SearchResponse>(&body).map_err(|error| error.to_string())
+        }) {
+            Ok(response) => Some(response.hits.into_iter().map(|hit| hit.post_id).collect()),
+            Err(error) => {
+                tracing::warn!(
+                    error = error.as_str(),
+                    "검색 엔진에 요청하지 못해 DB에서 검색합니다."
+                );
+                None
+            }
+        }
+    }
+    /// 검색어와 관련된 포스트들을 반환하는 메서드이다.
+    ///
+    /// 검색 엔진을 사용할 수 있으면 관련도 순으로, 그렇지 않으면 DB에서 찾아 최신순으로 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn search(&self, query: &str, page: &Pagination) -> Vec<Post> {
+        match self.search_ids(query, page) {
+            Some(post_ids) => post_ids
+                .into_iter()
+                .filter_map(|post_id| Post::get_post(web::Path::from(post_id.to_string())))
+                .filter(|post| {
+                    Post::is_published(post.post_id) && post.visibility == Visibility::Public
+                })
+                .collect(),
+            None => search_db(query, page),
+        }
+    }
+}
+
+/// DB에서 제목이나 내용에 검색어가 포함된 게시된 포스트들을 최신순으로 반환하는 메서드이다.
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn search_db(query: &str, page: &Pagination) -> Vec<Post> {
+    let mut conn = db::get_read_conn();
+    conn.exec_map(
+        r"select post_id, user_id, title, language, data, likes, report_count,
+        date_format(create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+        date_format(updated_at, '%Y-%m-%d %H:%i:%s')
+        from post where published and visibility = 'public' and deleted_at is null
+        and (title like :pattern or data like :pattern)
+        order by post_id desc limit :limit offset :offset",
+        params! {
+            "pattern" => format!("%{}", prefix_pattern(query)),
+            "limit" => page.limit(),
+            "offset" => page.offset(),
+        },
+        |(
+            post_id,
+            user_id,
+            title,
+            language,
+            data,
+            likes,
+            report_count,
+            create_at,
+            post_type,
+            accepted_comment_id,
+            updated_at,
+        )| {
+            Post::from_db(
+                post_id,
+                user_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+            )
+        },
+    )
+    .unwrap()
+}
+
+/// 쿼리 스트링을 통해 포스트 검색 조건을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct SearchPostsRequest {
+    /// 검색어이다.
+    pub q: String,
+    /// 포스트 내용을 보내는 방식이다. 지정하지 않은 경우 `summary`이다.
+    #[serde(default)]
+    pub body: BodyMode,
+}
+
+#[get("/api/posts/search")]
+pub async fn search_posts_api(
+    req: HttpRequest,
+    request: web::Query<SearchPostsRequest>,
+    page: web::Query<Pagination>,
+    index: web::Data<SearchIndex>,
+    users: web::Data<dyn UserRepository>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let SearchPostsRequest { q, body } = request.into_inner();
+    let mut query = q.trim().to_string();
+    sanitize::clean(&mut query);
+    if query.is_empty() {
+        return Err(ApiError::Validation(
+            "검색어를 입력해야 합니다.".to_string(),
+        ));
+    }
+    if query.chars().count() > MAX_QUERY_CHARS {
+        return Err(ApiError::Validation(format!(
+            "검색어는 {}자를 넘을 수 없습니다.",
+            MAX_QUERY_CHARS
+        )));
+    }
+    let viewer_id = Identity::from_header(&req);
+    let page = page.into_inner();
+    let index = index.into_inner();
+    let mut posts = web::block(move || index.search(&query, &page))
+        .await
+        .map_err(|error| ApiError::Unavailable(error.to_string()))?;
+    BlockList::load(users.get_ref(), viewer_id.as_deref())
+        .extend(shadow_bans.hidden_from(viewer_id.as_deref()))
+        .retain(&mut posts, |post| &post.user_id);
+    Ok(response::json(summary::apply(posts, body)))
+}
@@ -0,0 +1,245 @@
+//! # 데모 데이터 생성 관련 동작을 정의하는 모듈
+//!
+//! `seed`는 프론트엔드 개발자가 운영 DB의 덤프 없이도 실제와 비슷한 데이터로 개발할 수 있도록
+//! 사용자, 여러 언어의 포스트, 댓글, 공감 내역을 DB에 채워넣기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 같은 시드 값을 사용하면 항상 같은 데이터가 생성된다. 생성된 사용자의 ID는 모두 `seed_`로 시작하며,
+//! 다시 생성할 때는 이전에 생성된 데이터를 지운 뒤 새로 만든다.
+
+use crate::db;
+use crate::user::REPUTATION_PER_LIKE;
+use mysql::prelude::*;
+use mysql::*;
+
+/// 시드 값을 지정하지 않은 경우 사용하는 값이다.
+pub const DEFAULT_SEED: u64 = 42;
+/// 생성할 사용자 수이다.
+const USER_COUNT: u64 = 20;
+/// 생성할 포스트 수이다.
+const POST_COUNT: u64 = 100;
+/// 포스트 하나에 달릴 수 있는 최대 댓글 수이다.
+const MAX_COMMENTS_PER_POST: u64 = 5;
+/// 생성된 포스트들의 작성 시각이 분포할 기간(분)이다. 약 30일이다.
+const CREATE_SPAN_MINUTES: u64 = 60 * 24 * 30;
+
+/// 포스트에 사용할 언어와 코드 조각들이다.
+const SNIPPETS: &[(&str, &str)] = &[
+    ("rust", "fn main() {\n    println!(\"Hello, world!\");\n}"),
+    (
+        "python",
+        "def fib(n):\n    return n if n < 2 else fib(n - 1) + fib(n - 2)",
+    ),
+    (
+        "javascript",
+        "const sum = (xs) => xs.reduce((a, b) => a + b, 0);",
+    ),
+    ("go", "func main() {\n\tfmt.Println(\"hello\")\n}"),
+    (
+        "java",
+        "public static int max(int a, int b) {\n    return a > b ? a : b;\n}",
+    ),
+    ("dart", "void main() => print('Hello, Dart!');"),
+    ("c", "int square(int x) {\n    return x * x;\n}"),
+    ("kotlin", "fun greet(name: String) = \"Hello, $name\""),
+];
+/// 포스트 제목에 사용할 문구들이다.
+const TITLES: &[&str] = &[
+    "간단한 예제 공유합니다",
+    "이 코드 더 짧게 쓸 수 있을까요?",
+    "자주 쓰는 유틸리티 함수",
+    "처음 배울 때 헷갈렸던 부분",
+    "성능 개선 전후 비교",
+    "리뷰 부탁드립니다",
+];
+/// 댓글에 사용할 문구들이다.
+const COMMENTS: &[&str] = &[
+    "좋은 예제네요!",
+    "저는 이렇게 작성했는데 비교해보세요.",
+    "덕분에 해결했습니다. 감사합니다.",
+    "이 부분은 에러 처리가 필요할 것 같아요.",
+    "테스트 코드도 있으면 좋겠네요.",
+];
+/// 사용자 이름에 사용할 단어들이다.
+const NAMES: &[&str] = &[
+    "하늘", "바다", "코드", "별빛", "새벽", "구름", "숲속", "노을",
+];
+
+/// 시드 값으로 항상 같은 순서의 수를 만드는 난수 생성기이다.
+///
+/// 외부 라이브러리의 버전이 바뀌어도 같은 데이터가 생성되도록 SplitMix64 알고리즘을 직접 구현하였다.
+pub struct SeedRng {
+    state: u64,
+}
+
+impl SeedRng {
+    /// 난수 생성기의 생성자이다.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+    /// 다음 수를 반환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::seed::SeedRng;
+    /// let mut first = SeedRng::new(42);
+    /// let mut second = SeedRng::new(42);
+    /// assert_eq!(first.next_u64(), second.next_u64());
+    /// assert_ne!(first.next_u64(), SeedRng::new(7).next_u64());
+    /// ```
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    /// `0` 이상 `bound` 미만의 수를 반환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::seed::SeedRng;
+    /// let mut rng = SeedRng::new(1);
+    /// assert!((0..100).all(|_| rng.below(3) < 3));
+    /// ```
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+    /// 목록에서 원소 하나를 고르는 메서드이다.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+}
+
+/// 생성된 데이터의 개수이다.
+#[derive(Debug, Default)]
+pub struct SeedSummary {
+    /// 생성된 사용자 수이다.
+    pub users: u64,
+    /// 생성된 포스트 수이다.
+    pub posts: u64,
+    /// 생성된 댓글 수이다.
+    pub comments: u64,
+    /// 생성된 공감 수이다.
+    pub likes: u64,
+}
+
+/// 이전에 생성된 데모 데이터를 지우는 메서드이다.
+fn clear<Q: Queryable>(conn: &mut Q) -> Result<()> {
+    conn.query_drop(
+        r"delete from react
+        where user_id like 'seed\_%' or post_id in (select post_id from post where user_id like 'seed\_%')",
+    )?;
+    conn.query_drop(
+        r"delete from comment
+        where user_id like 'seed\_%' or post_id in (select post_id from post where user_id like 'seed\_%')",
+    )?;
+    conn.query_drop("delete from post where user_id like 'seed\\_%'")?;
+    conn.query_drop("delete from user where user_id like 'seed\\_%'")
+}
+
+/// 시드 값으로 데모 데이터를 생성하는 메서드이다.
+///
+/// 사용자 20명과 포스트 100개를 만들고, 각 포스트에 댓글과 공감을 무작위로 추가한다.
+/// 모든 작업은 하나의 트랜잭션에서 수행되므로 도중에 문제가 발생하면 아무것도 저장되지 않는다.
+/// # 예제
+/// ```ignore
+/// let summary = seed::run(42).unwrap();
+/// println!("포스트 {}개가 생성되었습니다.", summary.posts);
+/// ```
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn run(seed: u64) -> Result<SeedSummary> {
+    let mut rng = SeedRng::new(seed);
+    let mut conn = db::get_conn();
+    let mut tx = conn.start_transaction(TxOpts::default())?;
+    clear(&mut tx)?;
+    let mut summary = SeedSummary::default();
+    let users: Vec<String> = (1..=USER_COUNT)
+        .map(|index| format!("seed_user_{:02}", index))
+        .collect();
+    for user_id in &users {
+        let (language, _) = rng.pick(SNIPPETS);
+        tx.exec_drop(
+            r"insert into user(user_id, user_name, bio, preferred_language)
+            values(:user_id, :user_name, :bio, :preferred_language)",
+            params! {
+                "user_id" => user_id,
+                "user_name" => format!("{}{}", rng.pick(NAMES), rng.below(1000)),
+                "bio" => format!("{}을 주로 사용합니다.", language),
+                "preferred_language" => language,
+            },
+        )?;
+        summary.users += 1;
+    }
+    for _ in 0..POST_COUNT {
+        let author = rng.pick(&users);
+        let (language, data) = rng.pick(SNIPPETS);
+        let post_type = if rng.below(4) == 0 {
+            "question"
+        } else {
+            "snippet"
+        };
+        let minutes_ago = rng.below(CREATE_SPAN_MINUTES);
+        tx.exec_drop(
+            r"insert into post(user_id, title, language, data, likes, report_count, post_type, create_at)
+            values(:user_id, :title, :language, :data, 0, 0, :post_type, now() - interval :minutes minute)",
+            params! {
+                "user_id" => author,
+                "title" => format!("[{}] {}", language, rng.pick(TITLES)),
+                "language" => language,
+                "data" => data,
+                "post_type" => post_type,
+                "minutes" => minutes_ago,
+            },
+        )?;
+        let post_id = tx.last_insert_id().unwrap_or_default();
+        summary.posts += 1;
+        for _ in 0..rng.below(MAX_COMMENTS_PER_POST + 1) {
+            tx.exec_drop(
+                r"insert into comment(post_id, user_id, data, create_at)
+                values(:post_id, :user_id, :data, now() - interval :minutes minute)",
+                params! {
+                    "post_id" => post_id,
+                    "user_id" => rng.pick(&users),
+                    "data" => rng.pick(COMMENTS),
+                    "minutes" => rng.below(minutes_ago + 1),
+                },
+            )?;
+            summary.comments += 1;
+        }
+        let like_chance = 1 + rng.below(4);
+        for user_id in &users {
+            if user_id == author || rng.below(10) >= like_chance {
+                continue;
+            }
+            tx.exec_drop(
+                "insert into react(user_id, post_id, react_kind) values(:user_id, :post_id, 'like')",
+                params! {
+                    "user_id" => user_id,
+                    "post_id" => post_id,
+                },
+            )?;
+            summary.likes += 1;
+        }
+    }
+    tx.query_drop(
+        r"update post
+        set likes = (select count(*) from react where react.post_id = post.post_id and react_kind = 'like')
+        where user_id like 'seed\_%'",
+    )?;
+    tx.exec_drop(
+        r"update user
+        set reputation = :per_like * (select coalesce(sum(likes), 0) from post where post.user_id = user.user_id)
+        where user_id like 'seed\_%'",
+        params! {
+            "per_like" => REPUTATION_PER_LIKE,
+        },
+    )?;
+    tx.commit()?;
+    Ok(summary)
+}
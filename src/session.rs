@@ -0,0 +1,273 @@
+//! # 로그인 세션 관련 동작을 정의하는 모듈
+//!
+//! `session`은 웹 클라이언트가 인증 토큰을 직접 보관하지 않고 `HttpOnly` 쿠키로 로그인 상태를 유지하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 클라이언트가 `Authorization` 헤더의 인증 토큰으로 `POST /api/auth/session`을 요청하면 세션을 만들고
+//! 세션 토큰을 `SESSION_COOKIE` 쿠키로 보낸다. 이후 요청에서는 `auth` 모듈의 `Identity`가 헤더가 없는 경우 쿠키의 세션을 확인한다.
+//! 세션은 `SESSION_TTL_SECONDS`초(기본값 14일) 뒤에 만료되며, 만료되기 전에 `POST /api/auth/session/refresh`로
+//! 새 세션 토큰을 받아 만료 시각을 늘릴 수 있다. `DELETE /api/auth/session`으로 로그아웃하면 세션이 지워진다.
+//!
+//! DB에는 세션 토큰의 SHA-256 해시만 저장되므로 DB의 내용이 노출되어도 세션을 사용할 수 없다.
+
+use crate::auth::Identity;
+use crate::db;
+use crate::error::ApiError;
+use crate::response;
+use actix_web::cookie::{time, Cookie, SameSite};
+use actix_web::{delete, post, HttpRequest, HttpResponse, HttpResponseBuilder};
+use chrono::{DateTime, Utc};
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::env;
+use uuid::Uuid;
+
+/// 세션 토큰을 담는 쿠키의 이름이다.
+pub const SESSION_COOKIE: &str = "code_mmunity_session";
+/// 세션이 유지되는 기본 시간(초)이다.
+const DEFAULT_TTL_SECONDS: i64 = 14 * 24 * 60 * 60;
+
+/// 만들어진 세션의 정보이다.
+#[derive(Serialize)]
+pub struct Session {
+    /// 로그인한 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 세션이 만료되는 날짜 및 시간이다.
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 세션이 유지되는 시간(초)을 반환하는 메서드이다.
+///
+/// `SESSION_TTL_SECONDS` 환경변수가 주어지지 않았거나 올바르지 않은 경우 14일을 반환한다.
+fn ttl_seconds() -> i64 {
+    env::var("SESSION_TTL_SECONDS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|seconds| *seconds > 0)
+        .unwrap_or(DEFAULT_TTL_SECONDS)
+}
+
+/// 세션 쿠키에 `Secure` 속성을 붙일지 여부를 반환하는 메서드이다.
+///
+/// `SESSION_COOKIE_SECURE` 환경변수가 `false`인 경우에만 붙이지 않는다.
+fn secure_cookie() -> bool {
+    env::var("SESSION_COOKIE_SECURE").ok().as_deref() != Some("false")
+}
+
+/// 세션 토큰을 DB에 저장하는 SHA-256 해시(16진수 문자열)로 바꾸는 메서드이다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::session::token_hash;
+/// assert_eq!(
+///     token_hash("abc"),
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+/// );
+/// ```
+pub fn token_hash(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// 세션 토큰을 담은 쿠키를 반환하는 메서드이다.
+///
+/// 자바스크립트에서 읽을 수 없도록 `HttpOnly`를, 다른 사이트에서 보낸 요청에 담기지 않도록 `SameSite=Lax`를 지정한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::session::{cookie, SESSION_COOKIE};
+/// let cookie = cookie("token".to_string());
+/// assert_eq!(cookie.name(), SESSION_COOKIE);
+/// assert_eq!(cookie.http_only(), Some(true));
+/// assert_eq!(cookie.path(), Some("/"));
+/// ```
+pub fn cookie(token: String) -> Cookie<'static> {
+    Cookie::build(SESSION_COOKIE, token)
+        .path("/")
+        .http_only(true)
+        .secure(secure_cookie())
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::seconds(ttl_seconds()))
+        .finish()
+}
+
+/// 브라우저에 저장된 세션 쿠키를 지우는 쿠키를 반환하는 메서드이다.
+fn removal_cookie() -> Cookie<'static> {
+    let mut cookie = cookie(String::new());
+    cookie.make_removal();
+    cookie
+}
+
+/// 추측할 수 없는 새 토큰(32바이트 16진수 문자열)을 만드는 메서드이다.
+///
+/// 세션 토큰 외에 `password` 모듈의 비밀번호 재설정 토큰에도 사용된다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::session::new_token;
+/// let token = new_token();
+/// assert_eq!(token.len(), 64);
+/// assert_ne!(token, new_token());
+/// ```
+pub fn new_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+impl Session {
+    /// `user_id` 사용자의 세션을 만들고, 세션 토큰과 세션 정보를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn create(user_id: &str) -> Result<(String, Session)> {
+        let mut conn = db::get_conn();
+        Self::insert(&mut conn, user_id)
+    }
+    /// 세션을 저장하는 메서드이다.
+    fn insert<C: Queryable>(conn: &mut C, user_id: &str) -> Result<(String, Session)> {
+        let token = new_token();
+        let ttl = ttl_seconds();
+        conn.exec_drop(
+            r"insert into session(session_id, user_id, expires_at)
+            values(:session_id, :user_id, now() + interval :ttl second)",
+            params! {
+                "session_id" => token_hash(&token),
+                "user_id" => user_id,
+                "ttl" => ttl,
+            },
+        )?;
+        let session = Session {
+            user_id: user_id.to_string(),
+            expires_at: Utc::now() + chrono::Duration::seconds(ttl),
+        };
+        Ok((token, session))
+    }
+    /// 세션 토큰을 확인하여 만료되지 않은 세션의 사용자 고유 ID를 반환하는 메서드이다.
+    ///
+    /// 세션이 없거나 만료된 경우, 또는 세션을 확인할 수 없는 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn verify(token: &str) -> Option<String> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            "select user_id from session where session_id = :session_id and expires_at > now()",
+            params! {
+                "session_id" => token_hash(token),
+            },
+        )
+        .ok()
+        .flatten()
+    }
+    /// 요청의 세션 쿠키를 확인하여 세션의 사용자 고유 ID를 반환하는 메서드이다.
+    ///
+    /// 쿠키가 없는 경우 DB에 접속하지 않고 `None`을 반환한다.
+    pub fn from_cookie(req: &HttpRequest) -> Option<String> {
+        req.cookie(SESSION_COOKIE)
+            .filter(|cookie| !cookie.value().is_empty())
+            .and_then(|cookie| Self::verify(cookie.value()))
+    }
+    /// 만료되지 않은 세션을 지우고 같은 사용자의 새 세션을 만드는 메서드이다.
+    ///
+    /// 세션이 없거나 만료된 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn refresh(token: &str) -> Result<Option<(String, Session)>> {
+        db::transaction(|tx| {
+            let user_id: Option<String> = tx.exec_first(
+                r"select user_id from session
+                where session_id = :session_id and expires_at > now() for update",
+                params! {
+                    "session_id" => token_hash(token),
+                },
+            )?;
+            let Some(user_id) = user_id else {
+                return Ok(None);
+            };
+            tx.exec_drop(
+                "delete from session where session_id = :session_id",
+                params! {
+                    "session_id" => token_hash(token),
+                },
+            )?;
+            Self::insert(tx, &user_id).map(Some)
+        })
+    }
+    /// 세션을 지우는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn delete(token: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "delete from session where session_id = :session_id",
+            params! {
+                "session_id" => token_hash(token),
+            },
+        )
+    }
+    /// 만료된 세션들을 지우는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn purge_expired() -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.query_drop("delete from session where expires_at <= now()")
+    }
+}
+
+/// 세션 정보를 JSON 본문으로, 세션 토큰을 쿠키로 담은 응답을 반환하는 메서드이다.
+fn session_response(
+    mut builder: HttpResponseBuilder,
+    token: String,
+    session: Session,
+) -> HttpResponse {
+    builder
+        .cookie(cookie(token))
+        .insert_header(("Content-Type", response::JSON_CONTENT_TYPE))
+        .json(session)
+}
+
+#[post("/api/auth/session")]
+pub async fn create_session_api(identity: Identity) -> std::result::Result<HttpResponse, ApiError> {
+    let (token, session) = Session::create(&identity.user_id)?;
+    Ok(session_response(HttpResponse::Created(), token, session))
+}
+
+#[post("/api/auth/session/refresh")]
+pub async fn refresh_session_api(req: HttpRequest) -> std::result::Result<HttpResponse, ApiError> {
+    let token = req.cookie(SESSION_COOKIE).ok_or(ApiError::Unauthorized)?;
+    match Session::refresh(token.value())? {
+        Some((token, session)) => Ok(session_response(HttpResponse::Ok(), token, session)),
+        None => Err(ApiError::Unauthorized),
+    }
+}
+
+#[delete("/api/auth/session")]
+pub async fn delete_session_api(req: HttpRequest) -> std::result::Result<HttpResponse, ApiError> {
+    if let Some(token) = req.cookie(SESSION_COOKIE) {
+        Session::delete(token.value())?;
+    }
+    Ok(HttpResponse::NoContent().cookie(removal_cookie()).finish())
+}
@@ -0,0 +1,127 @@
+//! # 섀도 밴 관련 동작을 정의하는 모듈
+//!
+//! `shadow_ban`은 관리자가 스팸 계정 등을 본인이 알아차리지 못하게 제한하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 섀도 밴된 사용자도 평소처럼 포스트와 댓글을 작성하고 자신의 콘텐츠를 볼 수 있지만,
+//! 다른 사용자가 요청한 피드, 검색 결과, 댓글 목록에서는 제외된다.
+//! 섀도 밴된 사용자 목록은 서버 시작 시 DB에서 읽어 메모리에 두고, 요청마다 `block` 모듈의 `BlockList`에 더해 적용한다.
+//! 피드 캐시에는 제외하기 전의 결과가 저장되므로 섀도 밴을 바꿔도 캐시를 지울 필요가 없다.
+
+use crate::db;
+use mysql::prelude::*;
+use mysql::*;
+use std::collections::HashSet;
+use std::sync::RwLock;
+
+/// 섀도 밴된 사용자들의 목록이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 `load()`로 DB에서 목록을 읽어오고, 모든 워커가 공유하여 사용한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::shadow_ban::ShadowBans;
+/// let shadow_bans = ShadowBans::from(vec!["spammer".to_string()]);
+/// assert_eq!(shadow_bans.hidden_from(Some("u1")), vec!["spammer".to_string()]);
+/// assert_eq!(shadow_bans.hidden_from(None), vec!["spammer".to_string()]);
+/// assert!(shadow_bans.hidden_from(Some("spammer")).is_empty());
+/// ```
+#[derive(Default)]
+pub struct ShadowBans {
+    /// 섀도 밴된 사용자들의 고유 ID이다.
+    user_ids: RwLock<HashSet<String>>,
+}
+
+impl From<Vec<String>> for ShadowBans {
+    fn from(user_ids: Vec<String>) -> Self {
+        Self {
+            user_ids: RwLock::new(user_ids.into_iter().collect()),
+        }
+    }
+}
+
+impl ShadowBans {
+    /// 빈 목록을 생성하는 메서드이다.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// `viewer_id` 사용자가 요청한 목록에서 제외할 사용자들을 반환하는 메서드이다.
+    ///
+    /// 섀도 밴된 사용자 본인이 요청한 경우 자신은 제외 대상에 포함되지 않는다.
+    pub fn hidden_from(&self, viewer_id: Option<&str>) -> Vec<String> {
+        self.user_ids
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|user_id| Some(user_id.as_str()) != viewer_id)
+            .cloned()
+            .collect()
+    }
+    /// 섀도 밴된 사용자들의 고유 ID를 정렬하여 반환하는 메서드이다.
+    pub fn list(&self) -> Vec<String> {
+        let mut user_ids: Vec<String> = self.user_ids.read().unwrap().iter().cloned().collect();
+        user_ids.sort();
+        user_ids
+    }
+    /// DB에서 섀도 밴된 사용자 목록을 읽어 메모리의 목록을 바꾸고, 읽어온 사용자 수를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn load(&self) -> Result<usize> {
+        let mut conn = db::get_conn();
+        let user_ids: Vec<String> = conn.query("select user_id from user where shadow_banned")?;
+        let mut loaded = self.user_ids.write().unwrap();
+        *loaded = user_ids.into_iter().collect();
+        Ok(loaded.len())
+    }
+    /// 사용자를 섀도 밴하는 메서드이다. 존재하지 않는 사용자인 경우 `false`를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn add(&self, user_id: &str) -> Result<bool> {
+        let mut conn = db::get_conn();
+        let exists: Option<String> = conn.exec_first(
+            "select user_id from user where user_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )?;
+        if exists.is_none() {
+            return Ok(false);
+        }
+        conn.exec_drop(
+            "update user set shadow_banned = true where user_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )?;
+        self.user_ids.write().unwrap().insert(user_id.to_string());
+        Ok(true)
+    }
+    /// 사용자의 섀도 밴을 해제하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn remove(&self, user_id: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "update user set shadow_banned = false where user_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )?;
+        self.user_ids.write().unwrap().remove(user_id);
+        Ok(())
+    }
+}
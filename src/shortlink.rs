@@ -0,0 +1,210 @@
+//! # 포스트 공유용 짧은 주소 관련 동작을 정의하는 모듈
+//!
+//! `shortlink`는 코드뮤니티에서 포스트를 다른 곳에 공유할 때 사용할 짧은 주소를 만들고,
+//! 짧은 주소로 들어온 요청을 포스트로 안내하면서 클릭 수를 집계하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 짧은 주소는 브라우저에서는 포스트 주소로 이동하고, `Accept: application/json` 헤더를 보낸 경우
+//! 이동하지 않고 포스트의 고유 ID와 주소를 JSON으로 반환한다.
+
+use crate::db;
+use crate::error::ApiError;
+use crate::post;
+use crate::response;
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+use std::env;
+use uuid::Uuid;
+
+/// 짧은 주소 코드에 사용되는 문자들이다.
+const ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// 짧은 주소 코드의 길이이다.
+const CODE_LENGTH: usize = 7;
+
+/// 짧은 주소가 가리키는 포스트를 JSON으로 반환할 때 사용하는 구조체이다.
+#[derive(Serialize)]
+pub struct ResolvedLink {
+    /// 짧은 주소가 가리키는 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 짧은 주소가 안내하는 포스트 주소이다.
+    pub url: String,
+}
+
+/// 포스트의 짧은 주소 객체이다.
+#[derive(Serialize)]
+pub struct ShortLink {
+    /// 짧은 주소 코드이다. `/s/{code}`로 접근할 수 있다.
+    pub code: String,
+    /// 짧은 주소가 가리키는 포스트의 고유 ID이다.
+    pub post_id: u64,
+    /// 짧은 주소가 클릭된 횟수이다.
+    pub clicks: u64,
+}
+
+impl ShortLink {
+    /// 무작위 짧은 주소 코드를 생성하는 메서드이다.
+    fn generate_code() -> String {
+        let mut value = Uuid::new_v4().as_u128();
+        (0..CODE_LENGTH)
+            .map(|_| {
+                let index = (value % ALPHABET.len() as u128) as usize;
+                value /= ALPHABET.len() as u128;
+                ALPHABET[index] as char
+            })
+            .collect()
+    }
+    /// 포스트의 짧은 주소를 반환하는 메서드이다.
+    ///
+    /// 이미 짧은 주소가 있는 포스트는 기존 주소를 반환하고, 없는 경우 새로 만든다.
+    /// 존재하지 않거나 비공개 포스트인 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// if let Some(link) = ShortLink::get_or_create(1)? {
+    ///     println!("짧은 주소는 /s/{} 입니다.", link.code);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_or_create(post_id: u64) -> Result<Option<Self>> {
+        let mut conn = db::get_conn();
+        let exists: Option<u64> = conn.exec_first(
+            r"select post_id from post where post_id = :post_id and deleted_at is null
+            and visibility <> 'private'",
+            params! {
+                "post_id" => post_id,
+            },
+        )?;
+        if exists.is_none() {
+            return Ok(None);
+        }
+        let existing = conn.exec_first(
+            "select code, clicks from shortlink where post_id = :post_id",
+            params! {
+                "post_id" => post_id,
+            },
+        )?;
+        if let Some((code, clicks)) = existing {
+            return Ok(Some(Self {
+                code,
+                post_id,
+                clicks,
+            }));
+        }
+        let mut attempts = 0;
+        loop {
+            let code = Self::generate_code();
+            let result = conn.exec_drop(
+                "insert into shortlink(code, post_id, clicks) values(:code, :post_id, 0)",
+                params! {
+                    "code" => &code,
+                    "post_id" => post_id,
+                },
+            );
+            match result {
+                Ok(_) => {
+                    return Ok(Some(Self {
+                        code,
+                        post_id,
+                        clicks: 0,
+                    }))
+                }
+                // 코드가 중복된 경우(1062) 다른 코드로 다시 시도한다.
+                Err(Error::MySqlError(ref error)) if error.code == 1062 && attempts < 5 => {
+                    attempts += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+    /// 짧은 주소 코드가 가리키는 포스트의 고유 ID를 반환하면서 클릭 수를 1 증가시키는 메서드이다.
+    ///
+    /// 존재하지 않는 코드인 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn resolve(code: &str) -> Option<u64> {
+        let mut conn = db::get_conn();
+        let post_id = conn
+            .exec_first(
+                "select post_id from shortlink where code = :code",
+                params! {
+                    "code" => code,
+                },
+            )
+            .unwrap()?;
+        conn.exec_drop(
+            "update shortlink set clicks = clicks + 1 where code = :code",
+            params! {
+                "code" => code,
+            },
+        )
+        .unwrap();
+        Some(post_id)
+    }
+}
+
+/// 짧은 주소가 안내할 포스트 주소를 반환하는 메서드이다.
+///
+/// `SHARE_BASE_URL` 환경변수가 지정된 경우 `{SHARE_BASE_URL}/posts/{post_id}`로,
+/// 지정되지 않은 경우 포스트 API 주소로 안내한다.
+fn target_url(post_id: u64) -> String {
+    match env::var("SHARE_BASE_URL") {
+        Ok(base) if !base.is_empty() => format!("{}/posts/{}", base.trim_end_matches('/'), post_id),
+        _ => format!("/api/posts/{}", post_id),
+    }
+}
+
+#[post("/api/posts/{post_id}/shortlink")]
+pub async fn create_shortlink_api(
+    post_id: web::Path<u64>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    share(*post_id)
+}
+
+#[post("/api/posts/{post_id}/share")]
+pub async fn share_post_api(
+    post_id: web::Path<u64>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    share(post_id.into_inner())
+}
+
+/// 포스트의 짧은 주소를 만들어 응답으로 반환하는 메서드이다.
+fn share(post_id: u64) -> std::result::Result<HttpResponse, ApiError> {
+    let link = ShortLink::get_or_create(post_id)?.ok_or_else(post::post_not_found)?;
+    Ok(response::json(link))
+}
+
+#[get("/s/{code}")]
+pub async fn redirect_shortlink_api(
+    req: HttpRequest,
+    code: web::Path<String>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let post_id = ShortLink::resolve(&code)
+        .ok_or_else(|| ApiError::NotFound("요청한 짧은 주소는 존재하지 않습니다.".to_string()))?;
+    let url = target_url(post_id);
+    if wants_json(&req) {
+        return Ok(response::json(ResolvedLink { post_id, url }));
+    }
+    Ok(HttpResponse::Found()
+        .insert_header(("Location", url))
+        .finish())
+}
+
+/// 요청의 `Accept` 헤더가 JSON 응답을 요청하는지 확인하는 메서드이다.
+fn wants_json(req: &HttpRequest) -> bool {
+    req.headers()
+        .get("Accept")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("application/json"))
+}
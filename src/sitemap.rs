@@ -0,0 +1,184 @@
+//! # 사이트맵 관련 동작을 정의하는 모듈
+//!
+//! `sitemap`은 검색 엔진이 코드뮤니티의 공개 포스트를 수집할 수 있도록 `GET /sitemap.xml`을 제공하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 사이트맵에는 공개된 포스트의 주소(`{SHARE_BASE_URL}/posts/{post_id}/{slug}`)와 마지막으로 수정된 시각이 담긴다.
+//! 포스트가 많을 때 요청마다 DB를 조회하지 않도록 서버 시작 시와 `SITEMAP_INTERVAL_SECONDS`초(기본값 3600초)마다
+//! 예약 작업으로 다시 만들어 메모리에 저장해둔다. 포스트 주소에 사용할 `SHARE_BASE_URL`이 지정되지 않은 경우 만들지 않는다.
+
+use crate::db;
+use crate::error::ApiError;
+use actix_web::{get, web, HttpResponse};
+use mysql::prelude::*;
+use mysql::*;
+use std::env;
+use std::fmt::Write;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// 사이트맵 하나에 담을 수 있는 최대 주소 수이다.
+const MAX_URLS: u32 = 50_000;
+/// 슬러그의 최대 글자 수이다.
+const MAX_SLUG_CHARS: usize = 60;
+/// 사이트맵을 다시 만드는 기본 간격(초)이다.
+const DEFAULT_INTERVAL_SECONDS: u64 = 3600;
+
+/// 사이트맵을 다시 만드는 간격을 반환하는 메서드이다.
+///
+/// `SITEMAP_INTERVAL_SECONDS` 환경변수가 주어지지 않았거나 올바르지 않은 경우 3600초를 반환한다.
+pub fn interval() -> Duration {
+    Duration::from_secs(
+        env::var("SITEMAP_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECONDS),
+    )
+}
+
+/// 포스트 제목을 주소에 사용할 슬러그로 바꾸는 메서드이다.
+///
+/// 영문자는 소문자로 바꾸고, 글자와 숫자가 아닌 문자들은 `-` 하나로 합친다.
+/// 한글처럼 ASCII가 아닌 글자는 퍼센트 인코딩하며, 남는 글자가 없는 경우 `post`를 반환한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::sitemap::slug;
+/// assert_eq!(slug("Hello, Rust World!"), "hello-rust-world");
+/// assert_eq!(slug("  C++ & <Templates>  "), "c-templates");
+/// assert_eq!(slug("러스트"), "%EB%9F%AC%EC%8A%A4%ED%8A%B8");
+/// assert_eq!(slug("!!!"), "post");
+/// ```
+pub fn slug(title: &str) -> String {
+    let mut slug = String::new();
+    let mut chars = 0;
+    let mut separator = false;
+    for c in title.chars() {
+        if chars >= MAX_SLUG_CHARS {
+            break;
+        }
+        if !c.is_alphanumeric() {
+            separator = !slug.is_empty();
+            continue;
+        }
+        if separator {
+            slug.push('-');
+            separator = false;
+        }
+        if c.is_ascii() {
+            slug.push(c.to_ascii_lowercase());
+        } else {
+            for byte in c.to_string().bytes() {
+                write!(slug, "%{:02X}", byte).unwrap();
+            }
+        }
+        chars += 1;
+    }
+    if slug.is_empty() {
+        "post".to_string()
+    } else {
+        slug
+    }
+}
+
+/// XML 문서에 넣을 수 있도록 특수 문자를 바꾸는 메서드이다.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// 사이트맵 문서를 만드는 메서드이다.
+///
+/// `posts`에는 포스트의 고유 ID, 제목, 마지막으로 수정된 시각(W3C 형식)이 들어간다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::sitemap::render;
+/// let xml = render(
+///     "https://example.com/",
+///     &[(3, "Hello Rust".to_string(), "2024-01-02T03:04:05Z".to_string())],
+/// );
+/// assert!(xml.contains("<loc>https://example.com/posts/3/hello-rust</loc>"));
+/// assert!(xml.contains("<lastmod>2024-01-02T03:04:05Z</lastmod>"));
+/// ```
+pub fn render(base_url: &str, posts: &[(u64, String, String)]) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for (post_id, title, lastmod) in posts {
+        let loc = format!("{}/posts/{}/{}", base_url, post_id, slug(title));
+        write!(
+            xml,
+            "  <url>\n    <loc>{}</loc>\n    <lastmod>{}</lastmod>\n  </url>\n",
+            escape(&loc),
+            lastmod
+        )
+        .unwrap();
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// 메모리에 저장해둔 사이트맵이다.
+///
+/// 서버 시작 시 한 번만 생성한 뒤 `regenerate()`로 사이트맵을 만들고, 모든 워커가 공유하여 사용한다.
+#[derive(Default)]
+pub struct Sitemap {
+    /// 마지막으로 만든 사이트맵 문서이다. 아직 만들지 않은 경우 `None`이다.
+    xml: RwLock<Option<String>>,
+}
+
+impl Sitemap {
+    /// 마지막으로 만든 사이트맵 문서를 반환하는 메서드이다.
+    pub fn get(&self) -> Option<String> {
+        self.xml.read().unwrap().clone()
+    }
+    /// 공개된 포스트들로 사이트맵을 다시 만들고, 담긴 포스트 수를 반환하는 메서드이다.
+    ///
+    /// `SHARE_BASE_URL`이 지정되지 않은 경우 만들지 않고 `0`을 반환한다.
+    /// 최근에 작성된 포스트부터 최대 50,000개의 포스트가 담긴다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn regenerate(&self) -> Result<usize> {
+        let Some(base_url) = env::var("SHARE_BASE_URL")
+            .ok()
+            .filter(|base| !base.is_empty())
+        else {
+            return Ok(0);
+        };
+        let mut conn = db::get_read_conn();
+        let posts: Vec<(u64, String, String)> = conn.exec(
+            r"select post_id, title,
+            date_format(coalesce(updated_at, create_at), '%Y-%m-%dT%H:%i:%sZ')
+            from post where published and visibility = 'public' and deleted_at is null
+            and moderation = 'approved'
+            order by post_id desc limit :limit",
+            params! {
+                "limit" => MAX_URLS,
+            },
+        )?;
+        *self.xml.write().unwrap() = Some(render(&base_url, &posts));
+        Ok(posts.len())
+    }
+}
+
+#[get("/sitemap.xml")]
+pub async fn get_sitemap_api(
+    sitemap: web::Data<Sitemap>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let xml = sitemap
+        .get()
+        .ok_or_else(|| ApiError::NotFound("사이트맵이 만들어지지 않았습니다.".to_string()))?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/xml; charset=utf-8")
+        .body(xml))
+}
@@ -0,0 +1,723 @@
+//! # SQLite 저장소 관련 동작을 정의하는 모듈
+//!
+//! `sqlite`는 외부 DB 서버 없이 코드뮤니티 서버를 실행할 수 있도록 포스트, 사용자, 댓글을
+//! 하나의 SQLite 파일에 저장하는 `repository` 트레이트 구현으로 이루어져 있다.
+//!
+//! `DB_DRIVER=sqlite`로 지정하면 사용되며, 파일 경로는 `SQLITE_PATH`(기본값 `./code_mmunity.db`)로 지정한다.
+//! 필요한 테이블은 파일을 열 때 만들어지므로 따로 마이그레이션할 필요가 없다.
+//! 태그 검색, 공감, 팔로우와 같이 저장소 트레이트를 거치지 않는 기능은 여전히 MySQL이 필요하다.
+
+use crate::comment::{Comment, CommentRequest, CommentSort};
+use crate::db;
+use crate::email::NotificationSettings;
+use crate::error::ApiError;
+use crate::pagination::Cursor;
+use crate::post::{post_not_found, Post, PostRequest, PostType, UpdatePostRequest};
+use crate::repository::{CommentRepository, PostRepository, UserRepository};
+use crate::user::{
+    UpdateNotificationsRequest, UpdateProfileRequest, User, REACTIVATION_DAYS, USER_EXISTS,
+};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+/// 파일을 열 때 실행하는 스키마이다. MySQL 스키마 중 저장소 트레이트에서 사용하는 테이블과 열만 포함한다.
+const SCHEMA: &str = r"
+create table if not exists user (
+    user_id text not null primary key,
+    user_name text not null,
+    banned_until text null,
+    likes_public integer not null default 1,
+    bio text null,
+    avatar_url text null,
+    website text null,
+    preferred_language text null,
+    reputation integer not null default 0,
+    email text null,
+    notify_comments integer not null default 1,
+    notify_post_removed integer not null default 1,
+    notify_messages integer not null default 1,
+    is_moderator integer not null default 0,
+    deactivated_at text null
+);
+create table if not exists post (
+    post_id integer primary key autoincrement,
+    user_id text not null,
+    title text not null,
+    language text not null,
+    data text not null,
+    likes integer not null default 0,
+    report_count integer not null default 0,
+    views integer not null default 0,
+    post_type text not null default 'snippet',
+    visibility text not null default 'public',
+    accepted_comment_id integer null,
+    publish_at text null,
+    bot integer not null default 0,
+    version integer not null default 0,
+    create_at text not null default current_timestamp,
+    updated_at text null,
+    deleted_at text null
+);
+create index if not exists post_user_id on post (user_id);
+create table if not exists post_tag (
+    post_id integer not null,
+    name text not null,
+    primary key (post_id, name)
+);
+create table if not exists comment (
+    comment_id integer primary key autoincrement,
+    post_id integer not null,
+    user_id text not null,
+    data text not null,
+    version integer not null default 0,
+    create_at text not null default current_timestamp,
+    updated_at text null
+);
+create index if not exists comment_post_id on comment (post_id);
+create table if not exists blocked (
+    blocker_id text not null,
+    blocked_id text not null,
+    primary key (blocker_id, blocked_id)
+);
+";
+
+/// 포스트를 조회할 때 사용하는 열들이다. `post_from_row()`가 같은 순서로 읽는다.
+const POST_COLUMNS: &str = r"post.post_id, post.user_id,
+    case when user.deactivated_at is null then user.user_name else '비활성화된 사용자' end, post.title, post.language, post.data,
+    post.likes, post.report_count, post.create_at, post.post_type, post.accepted_comment_id, post.updated_at,
+    post.publish_at, post.version, post.bot, post.visibility";
+
+/// SQLite 파일에 데이터를 저장하는 저장소이다.
+///
+/// 하나의 연결을 모든 워커가 나누어 사용하므로 동시에 하나의 작업만 수행된다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::post::{PostRequest, PostType, Visibility};
+/// use code_mmunity_server::repository::{PostRepository, UserRepository};
+/// use code_mmunity_server::sqlite::SqliteRepository;
+/// let repository = SqliteRepository::open(":memory:").unwrap();
+/// let user = serde_json::from_value(serde_json::json!({"user_id": "u1", "user_name": "sun30812"})).unwrap();
+/// UserRepository::create(&repository, user).unwrap();
+/// let request = PostRequest {
+///     user_id: "u1".to_string(),
+///     title: "Hello".to_string(),
+///     language: "rust".to_string(),
+///     data: "fn main() {}".to_string(),
+///     post_type: PostType::Snippet,
+///     tags: vec!["Rust".to_string()],
+///     visibility: Visibility::Public,
+///     publish_at: None,
+///     bot: false,
+/// };
+/// let post = PostRepository::create(&repository, &request).unwrap();
+/// let found = PostRepository::get(&repository, &post.post_id.to_string()).unwrap();
+/// assert_eq!(found.user_name, "sun30812");
+/// assert_eq!(found.tags, vec!["rust"]);
+/// assert!(PostRepository::get(&repository, "1 or 1=1").is_none());
+/// ```
+pub struct SqliteRepository {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteRepository {
+    /// `path`의 SQLite 파일을 열고 필요한 테이블을 만드는 메서드이다.
+    ///
+    /// 파일이 없는 경우 새로 만들며, `:memory:`를 지정하면 메모리에만 저장한다.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+    /// 저장소의 연결을 반환하는 메서드이다.
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap()
+    }
+}
+
+/// 조회한 행을 포스트로 바꾸는 메서드이다. 태그는 `tags()`로 따로 채워야 한다.
+fn post_from_row(row: &Row) -> rusqlite::Result<Post> {
+    let create_at: String = row.get(8)?;
+    let post_type: String = row.get(9)?;
+    let updated_at: Option<String> = row.get(11)?;
+    let visibility: String = row.get(15)?;
+    Ok(Post {
+        post_id: row.get(0)?,
+        user_id: row.get(1)?,
+        user_name: row.get(2)?,
+        title: row.get(3)?,
+        language: row.get(4)?,
+        data: row.get(5)?,
+        likes: row.get(6)?,
+        report_count: row.get(7)?,
+        create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+        post_type: post_type.parse().unwrap_or_default(),
+        accepted_comment_id: row.get(10)?,
+        updated_at: updated_at.as_deref().and_then(db::parse_datetime),
+        tags: Vec::new(),
+        publish_at: row.get(12)?,
+        version: row.get(13)?,
+        bot: row.get(14)?,
+        visibility: visibility.parse().unwrap_or_default(),
+        attachments: Vec::new(),
+        is_truncated: false,
+    })
+}
+
+/// 포스트에 붙은 태그들을 이름순으로 반환하는 메서드이다.
+fn tags(conn: &Connection, post_id: u64) -> rusqlite::Result<Vec<String>> {
+    let mut statement =
+        conn.prepare_cached("select name from post_tag where post_id = ?1 order by name")?;
+    let names = statement.query_map([post_id], |row| row.get(0))?;
+    names.collect()
+}
+
+/// 사용자가 현재 이용 정지되었거나 계정을 비활성화한 상태인지 확인하는 메서드이다.
+fn is_restricted(conn: &Connection, user_id: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        r"select count(*) > 0 from user
+        where user_id = ?1 and (banned_until > datetime('now') or deactivated_at is not null)",
+        [user_id],
+        |row| row.get(0),
+    )
+}
+
+/// 사용자의 이름을 반환하는 메서드이다. 존재하지 않는 사용자의 경우 `None`을 반환한다.
+fn user_name(conn: &Connection, user_id: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "select user_name from user where user_id = ?1",
+        [user_id],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+impl PostRepository for SqliteRepository {
+    fn get(&self, post_id: &str) -> Option<Post> {
+        let post_id: u64 = post_id.parse().ok()?;
+        let conn = self.conn();
+        let mut post = conn
+            .query_row(
+                &format!(
+                    "select {} from post join user on user.user_id = post.user_id
+                    where post.post_id = ?1 and post.deleted_at is null",
+                    POST_COLUMNS
+                ),
+                [post_id],
+                post_from_row,
+            )
+            .optional()
+            .unwrap()?;
+        post.tags = tags(&conn, post.post_id).unwrap();
+        Some(post)
+    }
+    fn is_published(&self, post_id: u64) -> bool {
+        self.conn()
+            .query_row(
+                r"select count(*) > 0 from post
+                where post_id = ?1 and (publish_at is null or publish_at <= datetime('now'))",
+                [post_id],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+    fn record_view(&self, post_id: u64) {
+        self.conn()
+            .execute(
+                "update post set views = views + 1 where post_id = ?1",
+                [post_id],
+            )
+            .unwrap();
+    }
+    fn get_owner(&self, post_id: &str) -> Option<String> {
+        let post_id: u64 = post_id.parse().ok()?;
+        self.conn()
+            .query_row(
+                "select user_id from post where post_id = ?1 and deleted_at is null",
+                [post_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap()
+    }
+    fn get_deleted_owner(&self, post_id: u64) -> Option<String> {
+        self.conn()
+            .query_row(
+                "select user_id from post where post_id = ?1 and deleted_at is not null",
+                [post_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap()
+    }
+    fn get_post_type(&self, post_id: u64) -> Option<PostType> {
+        self.conn()
+            .query_row(
+                "select post_type from post where post_id = ?1",
+                [post_id],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .unwrap()
+            .and_then(|post_type| post_type.parse().ok())
+    }
+    fn create(&self, request: &PostRequest) -> Result<Post, ApiError> {
+        let mut conn = self.conn();
+        if is_restricted(&conn, &request.user_id)? {
+            return Err(ApiError::Forbidden);
+        }
+        let tags = request.validate()?;
+        if user_name(&conn, &request.user_id)?.is_none() {
+            return Err(ApiError::Validation(
+                "존재하지 않는 사용자입니다.".to_string(),
+            ));
+        }
+        let tx = conn.transaction()?;
+        tx.execute(
+            r"insert into post(user_id, title, language, data, post_type, visibility, publish_at, bot)
+            values(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                request.user_id,
+                request.title,
+                request.language,
+                request.data,
+                request.post_type.as_str(),
+                request.visibility.as_str(),
+                request.publish_at,
+                request.bot,
+            ],
+        )?;
+        let post_id = tx.last_insert_rowid() as u64;
+        for tag in &tags {
+            tx.execute(
+                "insert or ignore into post_tag(post_id, name) values(?1, ?2)",
+                params![post_id, tag],
+            )?;
+        }
+        let mut post = tx.query_row(
+            &format!(
+                "select {} from post join user on user.user_id = post.user_id where post.post_id = ?1",
+                POST_COLUMNS
+            ),
+            [post_id],
+            post_from_row,
+        )?;
+        tx.commit()?;
+        post.tags = tags;
+        Ok(post)
+    }
+    fn update(&self, post_id: &str, request: UpdatePostRequest) -> Result<bool, ApiError> {
+        Ok(self.conn().execute(
+            r"update post
+            set title = coalesce(?1, title), language = coalesce(?2, language), data = coalesce(?3, data),
+            visibility = coalesce(?4, visibility), version = version + 1, updated_at = datetime('now')
+            where post_id = ?5 and version = ?6 and deleted_at is null",
+            params![
+                request.title,
+                request.language,
+                request.data,
+                request.visibility.map(|visibility| visibility.as_str()),
+                post_id,
+                request.version
+            ],
+        )? > 0)
+    }
+    fn delete(&self, post_id: &str) -> Result<(), ApiError> {
+        self.conn().execute(
+            "update post set deleted_at = datetime('now') where post_id = ?1",
+            [post_id],
+        )?;
+        Ok(())
+    }
+    fn restore(&self, post_id: u64) -> Result<(), ApiError> {
+        self.conn().execute(
+            "update post set deleted_at = null where post_id = ?1",
+            [post_id],
+        )?;
+        Ok(())
+    }
+    fn set_accepted_answer(&self, post_id: u64, comment_id: Option<u64>) -> Result<(), ApiError> {
+        self.conn().execute(
+            "update post set accepted_comment_id = ?1 where post_id = ?2",
+            params![comment_id, post_id],
+        )?;
+        Ok(())
+    }
+}
+
+impl UserRepository for SqliteRepository {
+    fn get(&self, user_id: &str) -> Option<User> {
+        self.conn()
+            .query_row(
+                r"select user_id, user_name, coalesce(banned_until > datetime('now'), 0), banned_until,
+                bio, avatar_url, website, preferred_language, reputation,
+                (select count(*) from comment join post on post.accepted_comment_id = comment.comment_id
+                where comment.user_id = user.user_id)
+                from user where user_id = ?1",
+                [user_id],
+                |row| {
+                    let is_banned: bool = row.get(2)?;
+                    let banned_until: Option<String> = row.get(3)?;
+                    Ok(User {
+                        user_id: row.get(0)?,
+                        user_name: row.get(1)?,
+                        is_banned,
+                        banned_until: if is_banned { banned_until } else { None },
+                        bio: row.get(4)?,
+                        avatar_url: row.get(5)?,
+                        website: row.get(6)?,
+                        preferred_language: row.get(7)?,
+                        reputation: row.get(8)?,
+                        accepted_answers: row.get(9)?,
+                    })
+                },
+            )
+            .optional()
+            .unwrap()
+    }
+    fn create(&self, user: User) -> Result<(), ApiError> {
+        let conn = self.conn();
+        let exists = conn
+            .query_row(
+                "select 1 from user where user_id = ?1",
+                [&user.user_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if exists {
+            return Err(ApiError::Conflict(USER_EXISTS.to_string()));
+        }
+        conn.execute(
+            r"insert into user(user_id, user_name, bio, avatar_url, website, preferred_language)
+            values(?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                user.user_id,
+                user.user_name,
+                user.bio,
+                user.avatar_url,
+                user.website,
+                user.preferred_language
+            ],
+        )?;
+        Ok(())
+    }
+    fn is_moderator(&self, user_id: &str) -> bool {
+        self.conn()
+            .query_row(
+                "select is_moderator from user where user_id = ?1",
+                [user_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap()
+            .unwrap_or(false)
+    }
+    fn is_deactivated(&self, user_id: &str) -> bool {
+        self.conn()
+            .query_row(
+                "select deactivated_at is not null from user where user_id = ?1",
+                [user_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap()
+            .unwrap_or(false)
+    }
+    fn token_version(&self, _user_id: &str) -> u32 {
+        // 이메일과 비밀번호로 가입한 계정을 지원하지 않으므로 비밀번호 재설정으로 토큰 버전이 바뀌지 않는다.
+        0
+    }
+    fn deactivate(&self, user_id: &str) -> Result<(), ApiError> {
+        self.conn().execute(
+            r"update user set deactivated_at = datetime('now')
+            where user_id = ?1 and deactivated_at is null",
+            [user_id],
+        )?;
+        Ok(())
+    }
+    fn reactivate(&self, user_id: &str) -> Result<bool, ApiError> {
+        let rows = self.conn().execute(
+            r"update user set deactivated_at = null
+            where user_id = ?1 and deactivated_at > datetime('now', ?2)",
+            params![user_id, format!("-{} days", REACTIVATION_DAYS)],
+        )?;
+        Ok(rows > 0)
+    }
+    fn block(&self, blocker_id: &str, blocked_id: &str) -> Result<bool, ApiError> {
+        let conn = self.conn();
+        if user_name(&conn, blocked_id)?.is_none() {
+            return Ok(false);
+        }
+        conn.execute(
+            "insert or ignore into blocked(blocker_id, blocked_id) values(?1, ?2)",
+            [blocker_id, blocked_id],
+        )?;
+        Ok(true)
+    }
+    fn unblock(&self, blocker_id: &str, blocked_id: &str) -> Result<(), ApiError> {
+        self.conn().execute(
+            "delete from blocked where blocker_id = ?1 and blocked_id = ?2",
+            [blocker_id, blocked_id],
+        )?;
+        Ok(())
+    }
+    fn get_blocked(&self, user_id: &str) -> Vec<String> {
+        let conn = self.conn();
+        let mut statement = conn
+            .prepare_cached("select blocked_id from blocked where blocker_id = ?1")
+            .unwrap();
+        let blocked = statement
+            .query_map([user_id], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        blocked
+    }
+    fn update_profile(&self, user_id: &str, request: UpdateProfileRequest) -> Result<(), ApiError> {
+        self.conn().execute(
+            r"update user
+            set user_name = coalesce(?1, user_name), bio = coalesce(?2, bio),
+            avatar_url = coalesce(?3, avatar_url), website = coalesce(?4, website),
+            preferred_language = coalesce(?5, preferred_language)
+            where user_id = ?6",
+            params![
+                request.user_name,
+                request.bio,
+                request.avatar_url,
+                request.website,
+                request.preferred_language,
+                user_id
+            ],
+        )?;
+        Ok(())
+    }
+    fn set_likes_public(&self, user_id: &str, likes_public: bool) -> Result<(), ApiError> {
+        self.conn().execute(
+            "update user set likes_public = ?1 where user_id = ?2",
+            params![likes_public, user_id],
+        )?;
+        Ok(())
+    }
+    fn get_notification_settings(&self, user_id: &str) -> Option<NotificationSettings> {
+        self.conn()
+            .query_row(
+                r"select email, notify_comments, notify_post_removed, notify_messages
+                from user where user_id = ?1",
+                [user_id],
+                |row| {
+                    Ok(NotificationSettings {
+                        email: row.get(0)?,
+                        comments: row.get(1)?,
+                        post_removed: row.get(2)?,
+                        messages: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .unwrap()
+    }
+    fn update_notification_settings(
+        &self,
+        user_id: &str,
+        request: UpdateNotificationsRequest,
+    ) -> Result<bool, ApiError> {
+        self.conn().execute(
+            r"update user
+            set email = iif(?1 is null, email, nullif(?1, '')),
+            notify_comments = coalesce(?2, notify_comments),
+            notify_post_removed = coalesce(?3, notify_post_removed),
+            notify_messages = coalesce(?4, notify_messages)
+            where user_id = ?5",
+            params![
+                request.email,
+                request.comments,
+                request.post_removed,
+                request.messages,
+                user_id
+            ],
+        )?;
+        // SQLite 저장소는 이메일 주소 인증을 지원하지 않으므로 다시 인증할 필요가 없다.
+        Ok(false)
+    }
+    fn delete(&self, user: User) -> Result<Vec<String>, ApiError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute(
+            r"update post set accepted_comment_id = null
+            where accepted_comment_id in (select comment_id from comment where user_id = ?1)",
+            [&user.user_id],
+        )?;
+        tx.execute(
+            r"delete from comment
+            where user_id = ?1 or post_id in (select post_id from post where user_id = ?1)",
+            [&user.user_id],
+        )?;
+        tx.execute(
+            "delete from post_tag where post_id in (select post_id from post where user_id = ?1)",
+            [&user.user_id],
+        )?;
+        tx.execute("delete from post where user_id = ?1", [&user.user_id])?;
+        tx.execute(
+            "delete from blocked where blocker_id = ?1 or blocked_id = ?1",
+            [&user.user_id],
+        )?;
+        tx.execute("delete from user where user_id = ?1", [&user.user_id])?;
+        tx.commit()?;
+        // 파일 업로드를 지원하지 않으므로 지워야 하는 파일이 없다.
+        Ok(Vec::new())
+    }
+}
+
+impl CommentRepository for SqliteRepository {
+    fn get_by_post(&self, post_id: u32, sort: CommentSort) -> Vec<Comment> {
+        let conn = self.conn();
+        let mut statement = conn
+            .prepare_cached(&format!(
+                r"select comment.comment_id, comment.post_id, comment.user_id,
+                case when user.deactivated_at is null then user.user_name else '비활성화된 사용자' end, comment.data,
+                comment.create_at, comment.updated_at, comment.version,
+                comment.comment_id = coalesce((select accepted_comment_id from post where post.post_id = comment.post_id), 0) as is_accepted
+                from comment join user on user.user_id = comment.user_id
+                where comment.post_id = ?1 order by is_accepted desc, comment.create_at {0}, comment.comment_id {0}",
+                sort.direction()
+            ))
+            .unwrap();
+        let comments = statement
+            .query_map([post_id], comment_from_row)
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        comments
+    }
+    fn get_page_by_post(&self, post_id: u32, cursor: &Cursor, sort: CommentSort) -> Vec<Comment> {
+        let conn = self.conn();
+        let mut statement = conn
+            .prepare_cached(&format!(
+                r"select comment.comment_id, comment.post_id, comment.user_id,
+                case when user.deactivated_at is null then user.user_name else '비활성화된 사용자' end, comment.data,
+                comment.create_at, comment.updated_at, comment.version,
+                comment.comment_id = coalesce((select accepted_comment_id from post where post.post_id = comment.post_id), 0) as is_accepted
+                from comment join user on user.user_id = comment.user_id
+                where comment.post_id = ?1 and (?2 is null or comment.comment_id {} ?2)
+                order by comment.comment_id {} limit ?3",
+                sort.after_operator(),
+                sort.direction()
+            ))
+            .unwrap();
+        let comments = statement
+            .query_map(
+                params![post_id, cursor.after, cursor.limit()],
+                comment_from_row,
+            )
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        comments
+    }
+    fn count_by_post(&self, post_id: u32) -> u64 {
+        self.conn()
+            .query_row(
+                "select count(*) from comment where post_id = ?1",
+                [post_id],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+    fn get_owner(&self, comment_id: u64) -> Option<String> {
+        self.conn()
+            .query_row(
+                "select user_id from comment where comment_id = ?1",
+                [comment_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap()
+    }
+    fn get_post_id(&self, comment_id: u64) -> Option<u64> {
+        self.conn()
+            .query_row(
+                "select post_id from comment where comment_id = ?1",
+                [comment_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .unwrap()
+    }
+    fn create(&self, request: &CommentRequest) -> Result<Comment, ApiError> {
+        let conn = self.conn();
+        if is_restricted(&conn, &request.user_id)? {
+            return Err(ApiError::Forbidden);
+        }
+        request.validate()?;
+        if user_name(&conn, &request.user_id)?.is_none() {
+            return Err(ApiError::Validation(
+                "존재하지 않는 사용자입니다.".to_string(),
+            ));
+        }
+        let hidden: bool = conn.query_row(
+            "select count(*) > 0 from post where post_id = ?1 and visibility = 'private' and user_id <> ?2",
+            params![request.post_id, request.user_id],
+            |row| row.get(0),
+        )?;
+        if hidden {
+            return Err(post_not_found());
+        }
+        conn.execute(
+            "insert into comment(post_id, user_id, data) values(?1, ?2, ?3)",
+            params![request.post_id, request.user_id, request.data],
+        )?;
+        Ok(conn.query_row(
+            r"select comment.comment_id, comment.post_id, comment.user_id,
+                case when user.deactivated_at is null then user.user_name else '비활성화된 사용자' end, comment.data,
+            comment.create_at, comment.updated_at, comment.version, 0
+            from comment join user on user.user_id = comment.user_id where comment.comment_id = ?1",
+            [conn.last_insert_rowid()],
+            comment_from_row,
+        )?)
+    }
+    fn update(&self, comment_id: u64, data: String, version: u64) -> Result<bool, ApiError> {
+        Ok(self.conn().execute(
+            r"update comment set data = ?1, version = version + 1, updated_at = datetime('now')
+            where comment_id = ?2 and version = ?3",
+            params![data, comment_id, version],
+        )? > 0)
+    }
+    fn delete(&self, comment_id: u64) -> Result<Option<u32>, ApiError> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let post_id: Option<u32> = tx
+            .query_row(
+                "select post_id from comment where comment_id = ?1",
+                [comment_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        tx.execute(
+            "update post set accepted_comment_id = null where accepted_comment_id = ?1",
+            [comment_id],
+        )?;
+        tx.execute("delete from comment where comment_id = ?1", [comment_id])?;
+        tx.commit()?;
+        Ok(post_id)
+    }
+}
+
+/// 조회한 행을 댓글로 바꾸는 메서드이다.
+fn comment_from_row(row: &Row) -> rusqlite::Result<Comment> {
+    let create_at: String = row.get(5)?;
+    let updated_at: Option<String> = row.get(6)?;
+    Ok(Comment {
+        comment_id: row.get(0)?,
+        post_id: row.get(1)?,
+        user_id: row.get(2)?,
+        user_name: row.get(3)?,
+        data: row.get(4)?,
+        create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+        is_accepted: row.get(8)?,
+        updated_at: updated_at.as_deref().and_then(db::parse_datetime),
+        version: row.get(7)?,
+    })
+}
@@ -0,0 +1,137 @@
+//! # 파일 저장소 관련 동작을 정의하는 모듈
+//!
+//! `storage`는 코드뮤니티에서 사용자가 올린 파일(프로필 이미지 등)을 저장하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! `STORAGE_BACKEND` 환경변수를 통해 서버의 로컬 디스크(`local`) 또는 S3 호환 저장소(`s3`) 중
+//! 하나를 선택할 수 있으며, 핸들러는 어떤 저장소를 사용하는지 알 필요 없이 `Storage`를 통해 파일을 저장한다.
+
+use actix_web::web::Bytes;
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::{Attribute, Attributes, ObjectStore, PutOptions};
+use std::env;
+use std::fs;
+
+/// 로컬 디스크 저장소를 사용할 때 파일이 저장되는 기본 폴더이다.
+pub const DEFAULT_LOCAL_DIR: &str = "./uploads";
+
+/// 사용자가 올린 파일을 저장하는 저장소 객체이다.
+///
+/// 서버 시작 시 `from_env()`를 통해 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+pub struct Storage {
+    /// 실제 파일이 저장되는 저장소이다.
+    store: Box<dyn ObjectStore>,
+    /// 저장된 파일에 접근할 때 사용하는 주소의 앞부분이다.
+    public_url: String,
+    /// 저장소가 `Content-Type`과 같은 파일 속성을 지원하는지 여부이다.
+    supports_attributes: bool,
+}
+
+impl Storage {
+    /// 환경변수를 통해 저장소 객체를 생성하는 메서드이다.
+    ///
+    /// - `STORAGE_BACKEND`: `local`(기본값) 또는 `s3`
+    /// - `STORAGE_LOCAL_DIR`: 로컬 디스크 저장소의 폴더 경로 (기본값 `./uploads`)
+    /// - `STORAGE_S3_BUCKET`: S3 호환 저장소의 버킷 이름, 접속 정보는 `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`,
+    ///   `AWS_REGION`, `AWS_ENDPOINT` 환경변수를 사용한다.
+    /// - `STORAGE_PUBLIC_URL`: 저장된 파일의 주소 앞부분 (로컬 디스크 저장소의 기본값은 `/uploads`)
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - `STORAGE_BACKEND`가 올바른 값이 아닌 경우
+    /// - 로컬 디스크 저장소의 폴더를 만들 수 없는 경우
+    /// - S3 호환 저장소 사용 시 `STORAGE_S3_BUCKET` 또는 `STORAGE_PUBLIC_URL`이 설정되지 않은 경우
+    pub fn from_env() -> Self {
+        let backend = env::var("STORAGE_BACKEND").unwrap_or_else(|_| "local".to_string());
+        match backend.as_str() {
+            "local" => Self::local(
+                Self::local_dir(),
+                env::var("STORAGE_PUBLIC_URL").unwrap_or_else(|_| "/uploads".to_string()),
+            ),
+            "s3" => Self {
+                store: Box::new(
+                    AmazonS3Builder::from_env()
+                        .with_bucket_name(
+                            env::var("STORAGE_S3_BUCKET")
+                                .expect("STORAGE_S3_BUCKET가 설정되지 않음"),
+                        )
+                        .build()
+                        .expect("S3 저장소 설정이 올바르지 않음"),
+                ),
+                public_url: env::var("STORAGE_PUBLIC_URL")
+                    .expect("STORAGE_PUBLIC_URL가 설정되지 않음"),
+                supports_attributes: true,
+            },
+            _ => panic!("STORAGE_BACKEND가 올바른 형식이 아님"),
+        }
+    }
+    /// `dir` 폴더에 파일을 저장하는 로컬 디스크 저장소 객체를 생성하는 메서드이다.
+    ///
+    /// 저장된 파일의 주소는 `public_url`로 시작한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - 폴더를 만들 수 없는 경우
+    pub fn local(dir: impl AsRef<std::path::Path>, public_url: String) -> Self {
+        fs::create_dir_all(&dir).expect("STORAGE_LOCAL_DIR 폴더를 만들 수 없음");
+        Self {
+            store: Box::new(
+                LocalFileSystem::new_with_prefix(dir)
+                    .expect("STORAGE_LOCAL_DIR가 올바른 경로가 아님"),
+            ),
+            public_url,
+            supports_attributes: false,
+        }
+    }
+    /// 로컬 디스크 저장소를 사용하는 경우 파일이 저장되는 폴더를 반환하는 메서드이다.
+    pub fn local_dir() -> String {
+        env::var("STORAGE_LOCAL_DIR").unwrap_or_else(|_| DEFAULT_LOCAL_DIR.to_string())
+    }
+    /// 로컬 디스크 저장소를 사용하는지 여부를 반환하는 메서드이다.
+    ///
+    /// 로컬 디스크 저장소를 사용하는 경우 서버가 직접 `/uploads` 경로로 파일을 제공해야 한다.
+    pub fn is_local() -> bool {
+        env::var("STORAGE_BACKEND")
+            .map(|backend| backend == "local")
+            .unwrap_or(true)
+    }
+    /// 파일을 저장한 뒤 저장된 파일의 주소를 반환하는 메서드이다.
+    ///
+    /// `key`는 저장소 안에서의 파일 경로(예: `avatars/abc.png`)이다.
+    /// # 예제
+    /// ```ignore
+    /// let url = storage.put("avatars/abc.png", bytes, "image/png").await?;
+    /// ```
+    pub async fn put(
+        &self,
+        key: &str,
+        bytes: Bytes,
+        content_type: &str,
+    ) -> object_store::Result<String> {
+        let mut attributes = Attributes::new();
+        if self.supports_attributes {
+            attributes.insert(Attribute::ContentType, content_type.to_string().into());
+        }
+        self.store
+            .put_opts(
+                &Path::from(key),
+                bytes.into(),
+                PutOptions {
+                    attributes,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        Ok(format!("{}/{}", self.public_url.trim_end_matches('/'), key))
+    }
+    /// 저장소에서 파일을 지우는 메서드이다.
+    ///
+    /// `key`는 저장소 안에서의 파일 경로이며, 파일이 존재하지 않는 경우 `object_store::Error::NotFound`를 반환한다.
+    pub async fn delete(&self, key: &str) -> object_store::Result<()> {
+        self.store.delete(&Path::from(key)).await
+    }
+}
@@ -0,0 +1,152 @@
+//! # 프로그래밍 언어 구독 관련 동작을 정의하는 모듈
+//!
+//! `subscription`은 코드뮤니티에서 사용자가 관심 있는 프로그래밍 언어를 구독하고,
+//! 구독한 언어와 팔로우한 사용자의 포스트를 섞은 개인화 피드를 제공하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+
+use crate::auth::Identity;
+use crate::db;
+use crate::error::ApiError;
+use crate::pagination::Pagination;
+use crate::post::Post;
+use crate::response;
+use crate::summary::{self, BodyRequest};
+use actix_web::{delete, get, post, web, HttpResponse, Responder};
+use mysql::prelude::*;
+use mysql::*;
+
+/// 구독할 수 있는 언어 이름의 최대 길이이다.
+const MAX_LANGUAGE_LENGTH: usize = 30;
+
+/// 프로그래밍 언어 구독을 처리하는 객체이다.
+pub struct Subscription;
+
+impl Subscription {
+    /// 언어 이름을 저장되는 형태로 바꾸는 메서드이다.
+    ///
+    /// 앞뒤 공백을 제거하고 소문자로 바꾸며, 비어있거나 너무 긴 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::subscription::Subscription;
+    /// assert_eq!(Subscription::normalize(" Rust "), Some("rust".to_string()));
+    /// assert_eq!(Subscription::normalize("  "), None);
+    /// ```
+    pub fn normalize(language: &str) -> Option<String> {
+        let language = language.trim().to_lowercase();
+        if language.is_empty() || language.chars().count() > MAX_LANGUAGE_LENGTH {
+            return None;
+        }
+        Some(language)
+    }
+    /// 사용자가 구독한 언어 목록을 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_languages(user_id: &str) -> Vec<String> {
+        let mut conn = db::get_conn();
+        conn.exec(
+            "select language from language_subscription where user_id = :user_id order by language",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+    }
+    /// 사용자가 언어를 구독하도록 하는 메서드이다.
+    ///
+    /// 이미 구독 중인 언어인 경우 아무 작업도 하지 않는다.
+    /// # 예제
+    /// ```ignore
+    /// Subscription::subscribe("unique_id_for_user", "rust").expect("Sql작업 중 문제가 발생하였습니다.");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn subscribe(user_id: &str, language: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"insert ignore into language_subscription(user_id, language)
+            values(:user_id, :language)",
+            params! {
+                "user_id" => user_id,
+                "language" => language,
+            },
+        )
+    }
+    /// 사용자가 언어 구독을 취소하도록 하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn unsubscribe(user_id: &str, language: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "delete from language_subscription where user_id = :user_id and language = :language",
+            params! {
+                "user_id" => user_id,
+                "language" => language,
+            },
+        )
+    }
+}
+
+#[get("/api/users/{user_id}/subscriptions")]
+pub async fn get_subscriptions_api(user_id: web::Path<String>) -> impl Responder {
+    response::json(Subscription::get_languages(&user_id))
+}
+
+#[post("/api/users/{user_id}/subscriptions/{language}")]
+pub async fn subscribe_api(
+    identity: Identity,
+    path: web::Path<(String, String)>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let (user_id, language) = path.into_inner();
+    if identity.user_id != user_id {
+        return Err(ApiError::Forbidden);
+    }
+    let language = Subscription::normalize(&language).ok_or_else(invalid_language)?;
+    Subscription::subscribe(&user_id, &language)?;
+    Ok(HttpResponse::Created().finish())
+}
+
+#[delete("/api/users/{user_id}/subscriptions/{language}")]
+pub async fn unsubscribe_api(
+    identity: Identity,
+    path: web::Path<(String, String)>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let (user_id, language) = path.into_inner();
+    if identity.user_id != user_id {
+        return Err(ApiError::Forbidden);
+    }
+    let language = Subscription::normalize(&language).ok_or_else(invalid_language)?;
+    Subscription::unsubscribe(&user_id, &language)?;
+    Ok(response::no_content())
+}
+
+/// 언어 이름이 올바르지 않을 때 반환하는 오류이다.
+fn invalid_language() -> ApiError {
+    ApiError::Validation("올바르지 않은 언어 이름입니다.".to_string())
+}
+
+#[get("/api/feed/personalized")]
+pub async fn get_personalized_feed_api(
+    identity: Identity,
+    page: web::Query<Pagination>,
+    body: web::Query<BodyRequest>,
+) -> impl Responder {
+    response::json(summary::apply(
+        Post::get_personalized(&identity.user_id, &page),
+        body.body,
+    ))
+}
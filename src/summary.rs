@@ -0,0 +1,104 @@
+//! # 포스트 목록의 내용 요약 관련 동작을 정의하는 모듈
+//!
+//! `summary`는 홈 피드와 같은 포스트 목록을 반환할 때 포스트 내용을 앞부분만 보낼지,
+//! 전체를 보낼지 정하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 목록 API는 `?body=summary`(기본값) 또는 `?body=full`을 받으며, `summary`인 경우 내용을
+//! 설정 파일의 `summary_length`(환경변수 `SUMMARY_LENGTH`)자까지 잘라서 보낸다.
+//! 내용이 잘린 포스트는 `is_truncated`가 `true`이므로, 클라이언트는 이를 보고 단일 포스트를 다시 조회하면 된다.
+
+use crate::post::Post;
+use serde::Deserialize;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// 요약할 때 남기는 내용의 기본 글자 수이다.
+pub const DEFAULT_SUMMARY_LENGTH: usize = 35;
+
+/// 서버 시작 시 설정된 요약 글자 수이다.
+static SUMMARY_LENGTH: OnceLock<usize> = OnceLock::new();
+
+/// 포스트 목록에서 내용을 보내는 방식이다.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyMode {
+    /// 내용의 앞부분만 보낸다.
+    #[default]
+    Summary,
+    /// 내용 전체를 보낸다.
+    Full,
+}
+
+impl FromStr for BodyMode {
+    type Err = ();
+
+    /// 쿼리 스트링으로 받은 값을 내용을 보내는 방식으로 변환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::summary::BodyMode;
+    /// assert_eq!("full".parse(), Ok(BodyMode::Full));
+    /// assert!("short".parse::<BodyMode>().is_err());
+    /// ```
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "summary" => Ok(Self::Summary),
+            "full" => Ok(Self::Full),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 쿼리 스트링을 통해 포스트 목록의 내용을 보내는 방식을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct BodyRequest {
+    /// 내용을 보내는 방식이다. 지정하지 않은 경우 `summary`이다.
+    #[serde(default)]
+    pub body: BodyMode,
+}
+
+/// 서버 시작 시 설정된 요약 글자 수를 저장하는 메서드이다.
+///
+/// 이미 저장된 경우 아무 작업도 하지 않는다.
+pub fn init(length: usize) {
+    let _ = SUMMARY_LENGTH.set(length);
+}
+
+/// 요약할 때 남기는 내용의 글자 수를 반환하는 메서드이다.
+///
+/// `init()`으로 저장된 값이 없는 경우 `DEFAULT_SUMMARY_LENGTH`를 반환한다.
+pub fn length() -> usize {
+    *SUMMARY_LENGTH.get_or_init(|| DEFAULT_SUMMARY_LENGTH)
+}
+
+/// 문자열을 `max_chars`자 이하로 자르고, 잘렸는지 여부를 반환하는 메서드이다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::summary::truncate;
+/// let mut data = "fn main() { println!(\"안녕\"); }".to_string();
+/// assert!(truncate(&mut data, 24));
+/// assert_eq!(data, "fn main() { println!(\"안녕");
+/// assert!(!truncate(&mut data, 100));
+/// ```
+pub fn truncate(data: &mut String, max_chars: usize) -> bool {
+    match data.char_indices().nth(max_chars) {
+        Some((end, _)) => {
+            data.truncate(end);
+            true
+        }
+        None => false,
+    }
+}
+
+/// 포스트 목록의 내용을 방식에 맞게 바꿔서 반환하는 메서드이다.
+///
+/// `summary`인 경우 설정된 글자 수보다 긴 내용을 자르고 `is_truncated`를 `true`로 바꾼다.
+pub fn apply(mut posts: Vec<Post>, mode: BodyMode) -> Vec<Post> {
+    if mode == BodyMode::Summary {
+        let length = length();
+        for post in &mut posts {
+            post.is_truncated = truncate(&mut post.data, length);
+        }
+    }
+    posts
+}
@@ -0,0 +1,275 @@
+//! # 태그 관련 동작을 정의하는 모듈
+//!
+//! `tag`는 코드뮤니티에서 프로그래밍 언어 외에도 포스트를 주제별로 분류할 수 있도록
+//! 포스트에 태그를 붙이고 태그로 포스트를 찾기 위한 요소 및 메서드들로 이루어져 있다.
+
+use crate::db;
+use crate::pagination::Pagination;
+use crate::post::Post;
+use crate::response;
+use actix_web::{get, web, Responder};
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+
+/// 포스트 하나에 붙일 수 있는 최대 태그 수이다.
+pub const MAX_TAGS: usize = 10;
+/// 태그 이름의 최대 길이이다.
+const MAX_TAG_LENGTH: usize = 30;
+/// 인기 태그를 집계할 기간(일)이다.
+const TRENDING_DAYS: u32 = 7;
+
+/// 태그 이름과 해당 태그가 붙은 포스트 수이다.
+#[derive(Serialize)]
+pub struct TagCount {
+    /// 태그 이름이다.
+    pub name: String,
+    /// 태그가 붙은 포스트 수이다.
+    pub count: u64,
+}
+
+/// 태그를 처리하는 객체이다.
+pub struct Tag;
+
+impl Tag {
+    /// 태그 이름을 저장되는 형태로 바꾸는 메서드이다.
+    ///
+    /// 앞뒤 공백과 `#`을 제거하고 소문자로 바꾸며, 비어있거나 너무 길거나
+    /// 공백이 포함된 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::tag::Tag;
+    /// assert_eq!(Tag::normalize(" #Async "), Some("async".to_string()));
+    /// assert_eq!(Tag::normalize("two words"), None);
+    /// ```
+    pub fn normalize(name: &str) -> Option<String> {
+        let name = name.trim().trim_start_matches('#').to_lowercase();
+        if name.is_empty()
+            || name.chars().count() > MAX_TAG_LENGTH
+            || name.chars().any(char::is_whitespace)
+        {
+            return None;
+        }
+        Some(name)
+    }
+    /// 태그 목록을 저장되는 형태로 바꾸는 메서드이다.
+    ///
+    /// 중복된 태그는 하나만 남기며, 올바르지 않은 태그가 있거나 태그가 너무 많은 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::tag::Tag;
+    /// let tags = vec!["Rust".to_string(), "rust".to_string(), "async".to_string()];
+    /// assert_eq!(Tag::normalize_all(&tags), Some(vec!["rust".to_string(), "async".to_string()]));
+    /// ```
+    pub fn normalize_all(names: &[String]) -> Option<Vec<String>> {
+        let mut tags: Vec<String> = Vec::new();
+        for name in names {
+            let tag = Self::normalize(name)?;
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if tags.len() > MAX_TAGS {
+            return None;
+        }
+        Some(tags)
+    }
+    /// 포스트에 붙은 태그 이름들을 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_names(post_id: u64) -> Vec<String> {
+        let mut conn = db::get_conn();
+        conn.exec(
+            r"select tag.name from post_tag join tag on post_tag.tag_id = tag.tag_id
+            where post_tag.post_id = :post_id order by tag.name",
+            params! {
+                "post_id" => post_id,
+            },
+        )
+        .unwrap()
+    }
+    /// 포스트에 태그들을 붙이는 메서드이다.
+    ///
+    /// 처음 사용되는 태그는 `tag` 테이블에 새로 추가된다.
+    /// 포스트를 삽입하는 트랜잭션 안에서 호출할 수 있도록 연결을 인자로 받는다.
+    pub fn attach<Q: Queryable>(conn: &mut Q, post_id: u64, tags: &[String]) -> Result<()> {
+        for tag in tags {
+            conn.exec_drop(
+                "insert ignore into tag(name) values(:name)",
+                params! {
+                    "name" => tag,
+                },
+            )?;
+            conn.exec_drop(
+                r"insert ignore into post_tag(post_id, tag_id)
+                select :post_id, tag_id from tag where name = :name",
+                params! {
+                    "post_id" => post_id,
+                    "name" => tag,
+                },
+            )?;
+        }
+        Ok(())
+    }
+    /// 태그가 붙은 포스트들을 최신순으로 반환하는 메서드이다.
+    ///
+    /// `page`를 통해 반환할 페이지를 지정할 수 있다.
+    /// # 예제
+    /// ```ignore
+    /// let page = Pagination { page: Some(1), per_page: Some(20) };
+    /// for post in Tag::get_posts("async", &page) {
+    ///     println!("async 태그가 붙은 포스트의 제목은 {} 입니다.", post.title);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_posts(tag: &str, page: &Pagination) -> Vec<Post> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select post.post_id, post.user_id, title, language, data, likes, report_count,
+            date_format(post.create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+            date_format(post.updated_at, '%Y-%m-%d %H:%i:%s')
+            from post
+            join post_tag on post.post_id = post_tag.post_id
+            join tag on post_tag.tag_id = tag.tag_id
+            where tag.name = :tag and post.published and post.visibility = 'public' and post.deleted_at is null
+            order by post.post_id desc
+            limit :limit offset :offset",
+            params! {
+                "tag" => tag,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(
+                post_id,
+                user_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+            )| {
+                Post::from_db(
+                    post_id,
+                    user_id,
+                    title,
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at,
+                    post_type,
+                    accepted_comment_id,
+                    updated_at,
+                )
+            },
+        )
+        .unwrap()
+    }
+    /// 사용 중인 태그들을 포스트 수가 많은 순으로 반환하는 메서드이다.
+    ///
+    /// `page`를 통해 반환할 페이지를 지정할 수 있다.
+    /// # 예제
+    /// ```ignore
+    /// let page = Pagination { page: Some(1), per_page: Some(20) };
+    /// for tag in Tag::get_tags(&page) {
+    ///     println!("{} 태그는 {}개의 포스트에 붙어있습니다.", tag.name, tag.count);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_tags(page: &Pagination) -> Vec<TagCount> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select tag.name, count(*) as count
+            from tag
+            join post_tag on tag.tag_id = post_tag.tag_id
+            join post on post_tag.post_id = post.post_id
+            where post.published and post.visibility = 'public' and post.deleted_at is null
+            group by tag.tag_id, tag.name
+            order by count desc, tag.name
+            limit :limit offset :offset",
+            params! {
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(name, count)| TagCount { name, count },
+        )
+        .unwrap()
+    }
+    /// 사용 중인 태그(`get_tags`)의 수를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn count_tags() -> u64 {
+        let mut conn = db::get_conn();
+        conn.query_first(
+            r"select count(distinct post_tag.tag_id)
+            from post_tag join post on post_tag.post_id = post.post_id
+            where post.published and post.visibility = 'public' and post.deleted_at is null",
+        )
+        .unwrap()
+        .unwrap_or_default()
+    }
+    /// 최근 7일간 작성된 포스트에 많이 붙은 태그들을 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_trending(page: &Pagination) -> Vec<TagCount> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select tag.name, count(*) as count
+            from tag
+            join post_tag on tag.tag_id = post_tag.tag_id
+            join post on post_tag.post_id = post.post_id
+            where post.published and post.visibility = 'public' and post.deleted_at is null and post.create_at >= now() - interval :days day
+            group by tag.tag_id, tag.name
+            order by count desc, tag.name
+            limit :limit offset :offset",
+            params! {
+                "days" => TRENDING_DAYS,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(name, count)| TagCount { name, count },
+        )
+        .unwrap()
+    }
+}
+
+#[get("/api/tags")]
+pub async fn get_tags_api(page: web::Query<Pagination>) -> impl Responder {
+    response::json(Tag::get_tags(&page))
+}
+
+#[get("/api/tags/trending")]
+pub async fn get_trending_tags_api(page: web::Query<Pagination>) -> impl Responder {
+    response::json(Tag::get_trending(&page))
+}
@@ -0,0 +1,938 @@
+//! # 테스트 지원 관련 동작을 정의하는 모듈
+//!
+//! `testing`은 MySQL 서버 없이 `actix_web::test`로 포스트, 사용자, 댓글 API의 상태 코드와 JSON 형태,
+//! 작성자 확인 등을 테스트할 수 있도록 데이터를 메모리에 저장하는 저장소와 테스트용 앱을 만드는
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 테스트용 앱은 `repository` 트레이트를 사용하는 핸들러만 등록한다.
+//! 메일 알림, 웹훅과 같이 저장소를 거치지 않는 부가 기능은 테스트 중에 전송되지 않는다.
+//!
+//! 서버 바이너리에는 포함되지 않으며, 이 크레이트의 테스트를 빌드하거나 `testing` 기능을 켠 경우에만 사용할 수 있다.
+
+use crate::auth::Claims;
+use crate::block;
+use crate::cache::Cache;
+use crate::comment::{self, Comment, CommentRequest, CommentSort};
+use crate::email::NotificationSettings;
+use crate::error::ApiError;
+use crate::events::PostEvents;
+use crate::idempotency::Idempotency;
+use crate::link_preview::LinkPreviews;
+use crate::moderation::{AllowAll, Moderator};
+use crate::outbox::Outbox;
+use crate::pagination::Cursor;
+use crate::post::{self, Post, PostRequest, PostType, UpdatePostRequest};
+use crate::repository::{CommentRepository, PostRepository, Repositories, UserRepository};
+use crate::search_index::SearchIndex;
+use crate::shadow_ban::ShadowBans;
+use crate::storage::Storage;
+use crate::user::{
+    self, UpdateNotificationsRequest, UpdateProfileRequest, User, DEACTIVATED_USER_NAME,
+    REACTIVATION_DAYS, USER_EXISTS,
+};
+use crate::user_names::UserNames;
+use crate::word_filter::{FilterMode, WordFilter};
+use actix_web::body::BoxBody;
+use actix_web::dev::{ServiceFactory, ServiceRequest, ServiceResponse};
+use actix_web::{web, App};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// 테스트에서 인증 토큰을 서명할 때 사용하는 비밀 키이다.
+pub const AUTH_SECRET: &str = "code_mmunity_test_secret";
+/// 테스트용 앱의 JSON 요청 본문 최대 크기(바이트)이다.
+const MAX_JSON_BYTES: usize = 256 * 1024;
+
+/// 저장된 사용자와 사용자별 설정이다.
+struct StoredUser {
+    user: User,
+    likes_public: bool,
+    is_moderator: bool,
+    email: Option<String>,
+    notify_comments: bool,
+    notify_post_removed: bool,
+    notify_messages: bool,
+    deactivated_at: Option<String>,
+    blocked: BTreeSet<String>,
+}
+
+/// 저장된 포스트와 삭제 여부이다.
+struct StoredPost {
+    post: Post,
+    views: u64,
+    deleted: bool,
+}
+
+/// 메모리에 저장된 데이터이다.
+#[derive(Default)]
+struct Store {
+    users: BTreeMap<String, StoredUser>,
+    posts: BTreeMap<u64, StoredPost>,
+    comments: BTreeMap<u64, Comment>,
+    last_post_id: u64,
+    last_comment_id: u64,
+}
+
+impl Store {
+    /// 사용자가 현재 이용 정지 상태인지 확인하는 메서드이다.
+    fn is_banned(&self, user_id: &str) -> bool {
+        self.users
+            .get(user_id)
+            .and_then(|stored| stored.user.banned_until.as_deref())
+            .is_some_and(|until| until > now().as_str())
+    }
+    /// 사용자가 현재 이용 정지되었거나 계정을 비활성화한 상태인지 확인하는 메서드이다.
+    fn is_restricted(&self, user_id: &str) -> bool {
+        self.is_banned(user_id) || self.is_deactivated(user_id)
+    }
+    /// 사용자가 계정을 비활성화한 상태인지 확인하는 메서드이다.
+    fn is_deactivated(&self, user_id: &str) -> bool {
+        self.users
+            .get(user_id)
+            .is_some_and(|stored| stored.deactivated_at.is_some())
+    }
+    /// 콘텐츠에 표시할 작성자의 이름을 반환하는 메서드이다. 비활성화된 사용자인 경우 `DEACTIVATED_USER_NAME`이다.
+    fn author_name(&self, user_id: &str, user_name: &str) -> String {
+        if self.is_deactivated(user_id) {
+            DEACTIVATED_USER_NAME.to_string()
+        } else {
+            user_name.to_string()
+        }
+    }
+    /// 사용자의 이름을 반환하는 메서드이다. 존재하지 않는 사용자인 경우 검증 오류를 반환한다.
+    fn user_name(&self, user_id: &str) -> Result<String, ApiError> {
+        self.users
+            .get(user_id)
+            .map(|stored| stored.user.user_name.clone())
+            .ok_or_else(|| ApiError::Validation("존재하지 않는 사용자입니다.".to_string()))
+    }
+    /// 삭제되지 않은 포스트를 반환하는 메서드이다.
+    fn post(&self, post_id: &str) -> Option<&StoredPost> {
+        let post_id: u64 = post_id.parse().ok()?;
+        self.posts.get(&post_id).filter(|stored| !stored.deleted)
+    }
+}
+
+/// 현재 시각을 DB와 같은 `YYYY-MM-DD HH:MM:SS` 형식으로 반환하는 메서드이다.
+fn now() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// 데이터를 메모리에 저장하는 저장소이다.
+///
+/// MySQL 저장소와 같은 규칙으로 포스트와 댓글을 검증하며, 서버가 종료되면 데이터는 사라진다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::repository::UserRepository;
+/// use code_mmunity_server::testing::MemoryRepository;
+/// let repository = MemoryRepository::default();
+/// repository.add_user("u1", "sun30812");
+/// repository.set_moderator("u1", true);
+/// assert_eq!(repository.get("u1").unwrap().user_name, "sun30812");
+/// assert!(repository.is_moderator("u1"));
+/// assert!(repository.get("u2").is_none());
+/// ```
+#[derive(Default)]
+pub struct MemoryRepository {
+    store: Mutex<Store>,
+}
+
+impl MemoryRepository {
+    /// 저장된 데이터를 반환하는 메서드이다.
+    fn store(&self) -> MutexGuard<'_, Store> {
+        self.store.lock().unwrap()
+    }
+    /// 테스트에 사용할 사용자를 등록하는 메서드이다.
+    pub fn add_user(&self, user_id: &str, user_name: &str) {
+        let user = serde_json::from_value(serde_json::json!({
+            "user_id": user_id,
+            "user_name": user_name,
+        }))
+        .unwrap();
+        UserRepository::create(self, user).unwrap();
+    }
+    /// 사용자의 운영자 여부를 변경하는 메서드이다.
+    pub fn set_moderator(&self, user_id: &str, is_moderator: bool) {
+        if let Some(stored) = self.store().users.get_mut(user_id) {
+            stored.is_moderator = is_moderator;
+        }
+    }
+    /// 사용자를 `until`(`YYYY-MM-DD HH:MM:SS` 형식)까지 이용 정지시키는 메서드이다.
+    pub fn ban(&self, user_id: &str, until: &str) {
+        if let Some(stored) = self.store().users.get_mut(user_id) {
+            stored.user.banned_until = Some(until.to_string());
+        }
+    }
+    /// 사용자가 공감한 포스트 목록의 공개 여부를 반환하는 메서드이다. 존재하지 않는 사용자의 경우 `None`을 반환한다.
+    pub fn likes_public(&self, user_id: &str) -> Option<bool> {
+        self.store()
+            .users
+            .get(user_id)
+            .map(|stored| stored.likes_public)
+    }
+    /// 포스트의 조회 수를 반환하는 메서드이다. 존재하지 않는 포스트의 경우 `None`을 반환한다.
+    pub fn views(&self, post_id: u64) -> Option<u64> {
+        self.store().posts.get(&post_id).map(|stored| stored.views)
+    }
+}
+
+impl From<Arc<MemoryRepository>> for Repositories {
+    fn from(repository: Arc<MemoryRepository>) -> Self {
+        Self {
+            posts: repository.clone(),
+            users: repository.clone(),
+            comments: repository,
+        }
+    }
+}
+
+impl PostRepository for MemoryRepository {
+    fn get(&self, post_id: &str) -> Option<Post> {
+        let store = self.store();
+        store.post(post_id).map(|stored| Post {
+            user_name: store.author_name(&stored.post.user_id, &stored.post.user_name),
+            ..stored.post.clone()
+        })
+    }
+    fn is_published(&self, post_id: u64) -> bool {
+        self.store().posts.get(&post_id).is_some_and(|stored| {
+            match stored.post.publish_at.as_deref() {
+                Some(publish_at) => publish_at <= now().as_str(),
+                None => true,
+            }
+        })
+    }
+    fn record_view(&self, post_id: u64) {
+        if let Some(stored) = self.store().posts.get_mut(&post_id) {
+            stored.views += 1;
+        }
+    }
+    fn get_owner(&self, post_id: &str) -> Option<String> {
+        self.store()
+            .post(post_id)
+            .map(|stored| stored.post.user_id.clone())
+    }
+    fn get_deleted_owner(&self, post_id: u64) -> Option<String> {
+        self.store()
+            .posts
+            .get(&post_id)
+            .filter(|stored| stored.deleted)
+            .map(|stored| stored.post.user_id.clone())
+    }
+    fn get_post_type(&self, post_id: u64) -> Option<PostType> {
+        self.store()
+            .posts
+            .get(&post_id)
+            .map(|stored| stored.post.post_type)
+    }
+    fn create(&self, request: &PostRequest) -> Result<Post, ApiError> {
+        let mut store = self.store();
+        if store.is_restricted(&request.user_id) {
+            return Err(ApiError::Forbidden);
+        }
+        let tags = request.validate()?;
+        let user_name = store.user_name(&request.user_id)?;
+        store.last_post_id += 1;
+        let post = Post {
+            post_id: store.last_post_id,
+            user_id: request.user_id.clone(),
+            user_name,
+            title: request.title.clone(),
+            language: request.language.clone(),
+            data: request.data.clone(),
+            likes: 0,
+            report_count: 0,
+            create_at: Utc::now(),
+            post_type: request.post_type,
+            accepted_comment_id: None,
+            updated_at: None,
+            tags,
+            publish_at: request.publish_at.clone(),
+            version: 0,
+            bot: request.bot,
+            visibility: request.visibility,
+            attachments: Vec::new(),
+            is_truncated: false,
+        };
+        store.posts.insert(
+            post.post_id,
+            StoredPost {
+                post: post.clone(),
+                views: 0,
+                deleted: false,
+            },
+        );
+        Ok(post)
+    }
+    fn update(&self, post_id: &str, request: UpdatePostRequest) -> Result<bool, ApiError> {
+        let Ok(post_id) = post_id.parse::<u64>() else {
+            return Ok(false);
+        };
+        let mut store = self.store();
+        let Some(stored) = store
+            .posts
+            .get_mut(&post_id)
+            .filter(|stored| !stored.deleted && stored.post.version == request.version)
+        else {
+            return Ok(false);
+        };
+        let post = &mut stored.post;
+        if let Some(title) = request.title {
+            post.title = title;
+        }
+        if let Some(language) = request.language {
+            post.language = language;
+        }
+        if let Some(data) = request.data {
+            post.data = data;
+        }
+        if let Some(visibility) = request.visibility {
+            post.visibility = visibility;
+        }
+        post.version += 1;
+        post.updated_at = Some(Utc::now());
+        Ok(true)
+    }
+    fn delete(&self, post_id: &str) -> Result<(), ApiError> {
+        let Ok(post_id) = post_id.parse::<u64>() else {
+            return Ok(());
+        };
+        if let Some(stored) = self.store().posts.get_mut(&post_id) {
+            stored.deleted = true;
+        }
+        Ok(())
+    }
+    fn restore(&self, post_id: u64) -> Result<(), ApiError> {
+        if let Some(stored) = self.store().posts.get_mut(&post_id) {
+            stored.deleted = false;
+        }
+        Ok(())
+    }
+    fn set_accepted_answer(&self, post_id: u64, comment_id: Option<u64>) -> Result<(), ApiError> {
+        if let Some(stored) = self.store().posts.get_mut(&post_id) {
+            stored.post.accepted_comment_id = comment_id;
+        }
+        Ok(())
+    }
+}
+
+impl UserRepository for MemoryRepository {
+    fn get(&self, user_id: &str) -> Option<User> {
+        let store = self.store();
+        let stored = store.users.get(user_id)?;
+        let is_banned = store.is_banned(user_id);
+        let accepted_answers = store
+            .posts
+            .values()
+            .filter_map(|stored| stored.post.accepted_comment_id)
+            .filter(|comment_id| {
+                store
+                    .comments
+                    .get(comment_id)
+                    .is_some_and(|comment| comment.user_id == user_id)
+            })
+            .count() as u64;
+        Some(User {
+            is_banned,
+            banned_until: stored.user.banned_until.clone().filter(|_| is_banned),
+            accepted_answers,
+            ..stored.user.clone()
+        })
+    }
+    fn create(&self, user: User) -> Result<(), ApiError> {
+        let user = User {
+            is_banned: false,
+            banned_until: None,
+            reputation: 0,
+            accepted_answers: 0,
+            ..user
+        };
+        let mut store = self.store();
+        if store.users.contains_key(&user.user_id) {
+            return Err(ApiError::Conflict(USER_EXISTS.to_string()));
+        }
+        store.users.insert(
+            user.user_id.clone(),
+            StoredUser {
+                user,
+                likes_public: true,
+                is_moderator: false,
+                email: None,
+                notify_comments: true,
+                notify_post_removed: true,
+                notify_messages: true,
+                deactivated_at: None,
+                blocked: BTreeSet::new(),
+            },
+        );
+        Ok(())
+    }
+    fn is_moderator(&self, user_id: &str) -> bool {
+        self.store()
+            .users
+            .get(user_id)
+            .is_some_and(|stored| stored.is_moderator)
+    }
+    fn is_deactivated(&self, user_id: &str) -> bool {
+        self.store().is_deactivated(user_id)
+    }
+    fn token_version(&self, _user_id: &str) -> u32 {
+        0
+    }
+    fn deactivate(&self, user_id: &str) -> Result<(), ApiError> {
+        if let Some(stored) = self.store().users.get_mut(user_id) {
+            stored.deactivated_at.get_or_insert_with(now);
+        }
+        Ok(())
+    }
+    fn reactivate(&self, user_id: &str) -> Result<bool, ApiError> {
+        let deadline = (Utc::now() - Duration::days(REACTIVATION_DAYS.into()))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let mut store = self.store();
+        let Some(stored) = store.users.get_mut(user_id).filter(|stored| {
+            stored
+                .deactivated_at
+                .as_ref()
+                .is_some_and(|at| *at > deadline)
+        }) else {
+            return Ok(false);
+        };
+        stored.deactivated_at = None;
+        Ok(true)
+    }
+    fn block(&self, blocker_id: &str, blocked_id: &str) -> Result<bool, ApiError> {
+        let mut store = self.store();
+        if !store.users.contains_key(blocked_id) {
+            return Ok(false);
+        }
+        if let Some(stored) = store.users.get_mut(blocker_id) {
+            stored.blocked.insert(blocked_id.to_string());
+        }
+        Ok(true)
+    }
+    fn unblock(&self, blocker_id: &str, blocked_id: &str) -> Result<(), ApiError> {
+        if let Some(stored) = self.store().users.get_mut(blocker_id) {
+            stored.blocked.remove(blocked_id);
+        }
+        Ok(())
+    }
+    fn get_blocked(&self, user_id: &str) -> Vec<String> {
+        self.store()
+            .users
+            .get(user_id)
+            .map(|stored| stored.blocked.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+    fn update_profile(&self, user_id: &str, request: UpdateProfileRequest) -> Result<(), ApiError> {
+        if let Some(stored) = self.store().users.get_mut(user_id) {
+            let user = &mut stored.user;
+            if let Some(user_name) = request.user_name {
+                user.user_name = user_name;
+            }
+            user.bio = request.bio.or(user.bio.take());
+            user.avatar_url = request.avatar_url.or(user.avatar_url.take());
+            user.website = request.website.or(user.website.take());
+            user.preferred_language = request
+                .preferred_language
+                .or(user.preferred_language.take());
+        }
+        Ok(())
+    }
+    fn set_likes_public(&self, user_id: &str, likes_public: bool) -> Result<(), ApiError> {
+        if let Some(stored) = self.store().users.get_mut(user_id) {
+            stored.likes_public = likes_public;
+        }
+        Ok(())
+    }
+    fn get_notification_settings(&self, user_id: &str) -> Option<NotificationSettings> {
+        self.store()
+            .users
+            .get(user_id)
+            .map(|stored| NotificationSettings {
+                email: stored.email.clone(),
+                comments: stored.notify_comments,
+                post_removed: stored.notify_post_removed,
+                messages: stored.notify_messages,
+            })
+    }
+    fn update_notification_settings(
+        &self,
+        user_id: &str,
+        request: UpdateNotificationsRequest,
+    ) -> Result<bool, ApiError> {
+        if let Some(stored) = self.store().users.get_mut(user_id) {
+            if let Some(email) = request.email {
+                stored.email = Some(email).filter(|email| !email.is_empty());
+            }
+            if let Some(comments) = request.comments {
+                stored.notify_comments = comments;
+            }
+            if let Some(post_removed) = request.post_removed {
+                stored.notify_post_removed = post_removed;
+            }
+            if let Some(messages) = request.messages {
+                stored.notify_messages = messages;
+            }
+        }
+        Ok(false)
+    }
+    fn delete(&self, user: User) -> Result<Vec<String>, ApiError> {
+        let mut store = self.store();
+        let store = &mut *store;
+        store
+            .posts
+            .retain(|_, stored| stored.post.user_id != user.user_id);
+        let posts = &store.posts;
+        store.comments.retain(|_, comment| {
+            comment.user_id != user.user_id && posts.contains_key(&(comment.post_id as u64))
+        });
+        for stored in store.posts.values_mut() {
+            if stored
+                .post
+                .accepted_comment_id
+                .is_some_and(|comment_id| !store.comments.contains_key(&comment_id))
+            {
+                stored.post.accepted_comment_id = None;
+            }
+        }
+        store.users.remove(&user.user_id);
+        for stored in store.users.values_mut() {
+            stored.blocked.remove(&user.user_id);
+        }
+        Ok(Vec::new())
+    }
+}
+
+impl CommentRepository for MemoryRepository {
+    fn get_by_post(&self, post_id: u32, sort: CommentSort) -> Vec<Comment> {
+        let store = self.store();
+        let accepted = store
+            .posts
+            .get(&(post_id as u64))
+            .and_then(|stored| stored.post.accepted_comment_id);
+        let mut comments: Vec<Comment> = store
+            .comments
+            .values()
+            .filter(|comment| comment.post_id == post_id)
+            .map(|comment| Comment {
+                user_name: store.author_name(&comment.user_id, &comment.user_name),
+                is_accepted: Some(comment.comment_id) == accepted,
+                ..comment.clone()
+            })
+            .collect();
+        comments.sort_by(|a, b| {
+            let order = match sort {
+                CommentSort::Newest => b
+                    .create_at
+                    .cmp(&a.create_at)
+                    .then(b.comment_id.cmp(&a.comment_id)),
+                CommentSort::Oldest => a
+                    .create_at
+                    .cmp(&b.create_at)
+                    .then(a.comment_id.cmp(&b.comment_id)),
+            };
+            b.is_accepted.cmp(&a.is_accepted).then(order)
+        });
+        comments
+    }
+    fn get_page_by_post(&self, post_id: u32, cursor: &Cursor, sort: CommentSort) -> Vec<Comment> {
+        let mut comments: Vec<Comment> = self
+            .get_by_post(post_id, sort)
+            .into_iter()
+            .filter(|comment| {
+                cursor.after.is_none_or(|after| match sort {
+                    CommentSort::Newest => comment.comment_id < after,
+                    CommentSort::Oldest => comment.comment_id > after,
+                })
+            })
+            .collect();
+        comments.sort_by(|a, b| match sort {
+            CommentSort::Newest => b.comment_id.cmp(&a.comment_id),
+            CommentSort::Oldest => a.comment_id.cmp(&b.comment_id),
+        });
+        comments.truncate(cursor.limit() as usize);
+        comments
+    }
+    fn count_by_post(&self, post_id: u32) -> u64 {
+        self.store()
+            .comments
+            .values()
+            .filter(|comment| comment.post_id == post_id)
+            .count() as u64
+    }
+    fn get_owner(&self, comment_id: u64) -> Option<String> {
+        self.store()
+            .comments
+            .get(&comment_id)
+            .map(|comment| comment.user_id.clone())
+    }
+    fn get_post_id(&self, comment_id: u64) -> Option<u64> {
+        self.store()
+            .comments
+            .get(&comment_id)
+            .map(|comment| comment.post_id as u64)
+    }
+    fn create(&self, request: &CommentRequest) -> Result<Comment, ApiError> {
+        let mut store = self.store();
+        if store.is_restricted(&request.user_id) {
+            return Err(ApiError::Forbidden);
+        }
+        request.validate()?;
+        if store
+            .posts
+            .get(&(request.post_id as u64))
+            .is_some_and(|stored| !stored.post.is_visible_to(Some(request.user_id.as_str())))
+        {
+            return Err(post::post_not_found());
+        }
+        let user_name = store.user_name(&request.user_id)?;
+        store.last_comment_id += 1;
+        let comment = Comment {
+            comment_id: store.last_comment_id,
+            post_id: request.post_id,
+            user_id: request.user_id.clone(),
+            user_name,
+            data: request.data.clone(),
+            create_at: Utc::now(),
+            is_accepted: false,
+            updated_at: None,
+            version: 0,
+        };
+        store.comments.insert(comment.comment_id, comment.clone());
+        Ok(comment)
+    }
+    fn update(&self, comment_id: u64, data: String, version: u64) -> Result<bool, ApiError> {
+        let mut store = self.store();
+        let Some(comment) = store
+            .comments
+            .get_mut(&comment_id)
+            .filter(|comment| comment.version == version)
+        else {
+            return Ok(false);
+        };
+        comment.data = data;
+        comment.version += 1;
+        comment.updated_at = Some(Utc::now());
+        Ok(true)
+    }
+    fn delete(&self, comment_id: u64) -> Result<Option<u32>, ApiError> {
+        let mut store = self.store();
+        let Some(comment) = store.comments.remove(&comment_id) else {
+            return Ok(None);
+        };
+        for stored in store.posts.values_mut() {
+            if stored.post.accepted_comment_id == Some(comment_id) {
+                stored.post.accepted_comment_id = None;
+            }
+        }
+        Ok(Some(comment.post_id))
+    }
+}
+
+/// `user_id`의 사용자로 인증된 요청에 붙일 `Authorization` 헤더를 반환하는 메서드이다.
+///
+/// 토큰은 `AUTH_SECRET` 상수로 서명되며, 호출 시 `AUTH_SECRET` 환경변수를 같은 값으로 지정한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::auth::Identity;
+/// use code_mmunity_server::testing;
+/// let (name, value) = testing::bearer("u1");
+/// assert_eq!(name, "Authorization");
+/// let token = value.strip_prefix("Bearer ").unwrap();
+/// assert_eq!(Identity::verify_token(token), Some("u1".to_string()));
+/// ```
+pub fn bearer(user_id: &str) -> (&'static str, String) {
+    env::set_var("AUTH_SECRET", AUTH_SECRET);
+    let claims = Claims {
+        sub: user_id.to_string(),
+        exp: Utc::now().timestamp() as u64 + 3600,
+        ver: 0,
+    };
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(AUTH_SECRET.as_bytes()),
+    )
+    .unwrap();
+    ("Authorization", format!("Bearer {}", token))
+}
+
+/// 주어진 저장소들로 포스트, 사용자, 댓글 API를 처리하는 테스트용 앱을 만드는 메서드이다.
+///
+/// 캐시는 사용하지 않으며, 외부 전송 내용과 업로드한 파일은 임시 폴더에 저장된다.
+/// # 예제
+/// ```
+/// use actix_web::http::StatusCode;
+/// use actix_web::test;
+/// use code_mmunity_server::testing::{self, MemoryRepository};
+/// use serde_json::{json, Value};
+/// use std::sync::Arc;
+/// actix_web::rt::System::new().block_on(async {
+///     let repository = Arc::new(MemoryRepository::default());
+///     repository.add_user("u1", "sun30812");
+///     repository.add_user("u2", "guest");
+///     let app = test::init_service(testing::app(repository.clone().into())).await;
+///
+///     let request = test::TestRequest::post()
+///         .uri("/api/posts")
+///         .insert_header(testing::bearer("u1"))
+///         .set_json(json!({"title": "Hello", "language": "rust", "data": "fn main() {}"}))
+///         .to_request();
+///     let response = test::call_service(&app, request).await;
+///     assert_eq!(response.status(), StatusCode::CREATED);
+///     let post: Value = test::read_body_json(response).await;
+///     assert_eq!(post["user_name"], "sun30812");
+/// });
+/// ```
+pub fn app(
+    repositories: Repositories,
+) -> App<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse<BoxBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    App::new()
+        .app_data(web::Data::new(Cache::disabled()))
+        .app_data(web::Data::new(Idempotency::new()))
+        .app_data(web::Data::new(PostEvents::new()))
+        .app_data(web::Data::new(Outbox::new(
+            env::temp_dir().join("code_mmunity_test_outbox"),
+        )))
+        .app_data(web::Data::new(UserNames::from_env()))
+        .app_data(web::Data::new(WordFilter::new(FilterMode::Reject, &[])))
+        .app_data(web::Data::from(Arc::new(AllowAll) as Arc<dyn Moderator>))
+        .app_data(web::Data::new(LinkPreviews::disabled()))
+        .app_data(web::Data::new(SearchIndex::disabled()))
+        .app_data(web::Data::new(ShadowBans::new()))
+        .app_data(web::Data::new(Storage::local(
+            env::temp_dir().join("code_mmunity_test_uploads"),
+            "/uploads".to_string(),
+        )))
+        .app_data(web::Data::from(repositories.posts))
+        .app_data(web::Data::from(repositories.users))
+        .app_data(web::Data::from(repositories.comments))
+        .app_data(
+            web::JsonConfig::default()
+                .limit(MAX_JSON_BYTES)
+                .error_handler(|error, _| ApiError::from_json_error(error, MAX_JSON_BYTES).into()),
+        )
+        .app_data(
+            web::QueryConfig::default()
+                .error_handler(|error, _| ApiError::BadRequest(error.to_string()).into()),
+        )
+        .app_data(
+            web::PathConfig::default()
+                .error_handler(|error, _| ApiError::BadRequest(error.to_string()).into()),
+        )
+        .service(user::new_user_api)
+        .service(post::get_post_api)
+        .service(user::get_user_api)
+        .service(user::delete_user_api)
+        .service(post::update_post_api)
+        .service(post::accept_answer_api)
+        .service(post::accept_comment_api)
+        .service(post::unaccept_answer_api)
+        .service(post::delete_post_api)
+        .service(post::restore_post_api)
+        .service(post::insert_post_api)
+        .service(comment::get_comment_api)
+        .service(comment::count_comments_api)
+        .service(comment::insert_comment_api)
+        .service(comment::update_comment_api)
+        .service(comment::delete_comment_api)
+        .service(user::update_profile_api)
+        .service(user::replace_profile_api)
+        .service(user::deactivate_user_api)
+        .service(user::reactivate_user_api)
+        .service(block::block_user_api)
+        .service(block::unblock_user_api)
+        .service(user::update_privacy_api)
+        .service(user::get_notifications_api)
+        .service(user::update_notifications_api)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Identity;
+    use actix_web::http::StatusCode;
+    use actix_web::test;
+    use serde_json::{json, Value};
+
+    /// 사용자 `u1`(`sun30812`)과 `u2`(`guest`)가 있는 저장소를 만드는 메서드이다.
+    fn repository() -> Arc<MemoryRepository> {
+        let repository = Arc::new(MemoryRepository::default());
+        repository.add_user("u1", "sun30812");
+        repository.add_user("u2", "guest");
+        repository
+    }
+
+    /// 포스트 작성 요청의 본문이다.
+    fn new_post(title: &str) -> Value {
+        json!({"title": title, "language": "rust", "data": "fn main() {}"})
+    }
+
+    #[actix_web::test]
+    async fn insert_post_returns_author_name() {
+        let app = test::init_service(app(repository().into())).await;
+        let request = test::TestRequest::post()
+            .uri("/api/posts")
+            .insert_header(bearer("u1"))
+            .set_json(new_post("Hello"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let post: Value = test::read_body_json(response).await;
+        assert_eq!(post["user_name"], "sun30812");
+    }
+
+    #[actix_web::test]
+    async fn insert_post_ignores_user_id_in_body() {
+        let app = test::init_service(app(repository().into())).await;
+        let request = test::TestRequest::post()
+            .uri("/api/posts")
+            .set_json(new_post("Anonymous"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let mut body = new_post("Spoofed");
+        body["user_id"] = json!("u1");
+        let request = test::TestRequest::post()
+            .uri("/api/posts")
+            .insert_header(bearer("u2"))
+            .set_json(body)
+            .to_request();
+        let post: Value = test::read_body_json(test::call_service(&app, request).await).await;
+        assert_eq!(post["user_id"], "u2");
+        assert_eq!(post["user_name"], "guest");
+    }
+
+    #[actix_web::test]
+    async fn retried_insert_replays_first_response() {
+        let app = test::init_service(app(repository().into())).await;
+        let retry = || {
+            test::TestRequest::post()
+                .uri("/api/posts")
+                .insert_header(("Idempotency-Key", "retry-1"))
+                .insert_header(bearer("u1"))
+                .set_json(new_post("Retry"))
+                .to_request()
+        };
+        let first: Value = test::read_body_json(test::call_service(&app, retry()).await).await;
+        let replayed = test::call_service(&app, retry()).await;
+        assert_eq!(
+            replayed.headers().get("Idempotent-Replayed").unwrap(),
+            "true"
+        );
+        let second: Value = test::read_body_json(replayed).await;
+        assert_eq!(first["post_id"], second["post_id"]);
+    }
+
+    #[actix_web::test]
+    async fn only_author_can_update_and_delete_post() {
+        let app = test::init_service(app(repository().into())).await;
+        let request = test::TestRequest::post()
+            .uri("/api/posts")
+            .insert_header(bearer("u1"))
+            .set_json(new_post("Hello"))
+            .to_request();
+        let post: Value = test::read_body_json(test::call_service(&app, request).await).await;
+
+        let request = test::TestRequest::patch()
+            .uri(&format!("/api/posts/{}", post["post_id"]))
+            .insert_header(bearer("u2"))
+            .set_json(json!({"title": "Hacked", "version": 0}))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let request = test::TestRequest::delete()
+            .uri(&format!("/api/posts?post_id={}", post["post_id"]))
+            .insert_header(bearer("u1"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+
+    #[actix_web::test]
+    async fn only_token_owner_can_register_user() {
+        let app = test::init_service(app(repository().into())).await;
+        let register = |header: Option<&str>| {
+            let request = test::TestRequest::post().uri("/api/users?user_id=u3&user_name=newbie");
+            match header {
+                Some(user_id) => request.insert_header(bearer(user_id)),
+                None => request,
+            }
+            .to_request()
+        };
+        let response = test::call_service(&app, register(None)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = test::call_service(&app, register(Some("u1"))).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = test::call_service(&app, register(Some("u3"))).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    #[actix_web::test]
+    async fn stale_token_version_is_rejected() {
+        let app = test::init_service(app(repository().into())).await;
+        bearer("u1");
+        let exp = Utc::now().timestamp() as u64 + 3600;
+        let token = Identity::issue_token("u1", 1, exp).unwrap();
+        let request = test::TestRequest::post()
+            .uri("/api/posts")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .set_json(new_post("Hello"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_web::test]
+    async fn missing_post_returns_not_found_error() {
+        let app = test::init_service(app(repository().into())).await;
+        let request = test::TestRequest::get().uri("/api/posts/999").to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let error: Value = test::read_body_json(response).await;
+        assert_eq!(error["code"], "not_found");
+    }
+
+    #[actix_web::test]
+    async fn comments_of_missing_post_return_not_found_error() {
+        let app = test::init_service(app(repository().into())).await;
+        let request = test::TestRequest::get()
+            .uri("/api/comments/999")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn only_owner_can_delete_user() {
+        let app = test::init_service(app(repository().into())).await;
+        let delete = |user_id| {
+            test::TestRequest::delete()
+                .uri("/api/users?user_id=u1&user_name=sun30812")
+                .insert_header(bearer(user_id))
+                .to_request()
+        };
+        let response = test::call_service(&app, delete("u2")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = test::call_service(&app, delete("u1")).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let request = test::TestRequest::get().uri("/api/users/u1").to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
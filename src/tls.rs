@@ -0,0 +1,57 @@
+//! # HTTPS 관련 동작을 정의하는 모듈
+//!
+//! `tls`는 리버스 프록시 없이 배포하는 경우에도 코드뮤니티 서버가 직접 HTTPS로 요청을 받을 수 있도록
+//! 인증서와 개인 키를 읽어 TLS 설정을 만들기 위한 요소 및 메서드들로 이루어져 있다.
+
+use crate::config::TlsConfig;
+use rustls::{Certificate, PrivateKey, ServerConfig};
+use rustls_pemfile::Item;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// PEM 파일에 들어있는 항목들을 읽는 메서드이다.
+fn read_pem(path: &Path) -> Result<Vec<Item>, String> {
+    let file = File::open(path)
+        .map_err(|error| format!("{}을 읽을 수 없음: {}", path.display(), error))?;
+    rustls_pemfile::read_all(&mut BufReader::new(file))
+        .map_err(|error| format!("{}이 올바른 PEM 파일이 아님: {}", path.display(), error))
+}
+
+/// 인증서와 개인 키 파일로 HTTPS 서버 설정을 만드는 메서드이다.
+///
+/// 인증서 파일에는 서버 인증서부터 중간 인증서까지의 체인이, 개인 키 파일에는
+/// PKCS#8, PKCS#1(RSA), SEC1(EC) 형식 중 하나의 개인 키가 PEM 형식으로 들어있어야 한다.
+/// 문제가 있는 경우 원인을 설명하는 메세지를 반환한다.
+/// # 예제
+/// ```ignore
+/// let tls = tls::server_config(config.tls.as_ref().unwrap())?;
+/// server.bind_rustls((addr, port), tls)?;
+/// ```
+pub fn server_config(config: &TlsConfig) -> Result<ServerConfig, String> {
+    let certs: Vec<Certificate> = read_pem(&config.cert)?
+        .into_iter()
+        .filter_map(|item| match item {
+            Item::X509Certificate(cert) => Some(Certificate(cert)),
+            _ => None,
+        })
+        .collect();
+    if certs.is_empty() {
+        return Err(format!(
+            "{}에 인증서가 존재하지 않음",
+            config.cert.display()
+        ));
+    }
+    let key = read_pem(&config.key)?
+        .into_iter()
+        .find_map(|item| match item {
+            Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| format!("{}에 개인 키가 존재하지 않음", config.key.display()))?;
+    ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|error| format!("인증서와 개인 키가 올바르지 않음: {}", error))
+}
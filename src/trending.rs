@@ -0,0 +1,151 @@
+//! # 인기 포스트 관련 동작을 정의하는 모듈
+//!
+//! `trending`은 코드뮤니티에서 최근에 많은 관심을 받은 포스트를 집계하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 각 포스트의 점수는 공감 수, 댓글 수, 조회 수를 합한 뒤 작성된 지 오래될수록 낮아지도록 계산하며
+//! (Hacker News 방식), `scheduler`를 통해 주기적으로 `trending_score` 열에 저장된다.
+
+use crate::auth::Identity;
+use crate::block::BlockList;
+use crate::db;
+use crate::pagination::Pagination;
+use crate::post::Post;
+use crate::response;
+use crate::shadow_ban::ShadowBans;
+use crate::summary::{self, BodyRequest};
+use actix_web::{get, web, HttpRequest, Responder};
+use mysql::prelude::*;
+use mysql::*;
+use std::env;
+use std::time::Duration;
+
+/// 점수를 다시 계산하는 기본 간격(초)이다.
+const DEFAULT_REFRESH_SECONDS: u64 = 600;
+/// 공감 하나가 점수에 더하는 값이다.
+const LIKE_WEIGHT: f64 = 1.0;
+/// 댓글 하나가 점수에 더하는 값이다.
+const COMMENT_WEIGHT: f64 = 2.0;
+/// 조회 한 번이 점수에 더하는 값이다.
+const VIEW_WEIGHT: f64 = 0.1;
+/// 작성된 지 오래된 포스트의 점수가 줄어드는 정도이다. 클수록 빠르게 줄어든다.
+const GRAVITY: f64 = 1.8;
+
+/// 인기 포스트를 집계하는 객체이다.
+pub struct Trending;
+
+impl Trending {
+    /// 점수를 다시 계산하는 간격을 반환하는 메서드이다.
+    ///
+    /// `TRENDING_REFRESH_SECONDS` 환경변수로 변경할 수 있으며 기본값은 600초이다.
+    pub fn refresh_interval() -> Duration {
+        Duration::from_secs(
+            env::var("TRENDING_REFRESH_SECONDS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .filter(|seconds| *seconds > 0)
+                .unwrap_or(DEFAULT_REFRESH_SECONDS),
+        )
+    }
+    /// 모든 포스트의 점수를 다시 계산하여 저장하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn refresh() {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"update post
+            left join (select post_id, count(*) as comments from comment group by post_id) as counts
+            on post.post_id = counts.post_id
+            set post.trending_score =
+                (:like_weight * post.likes + :comment_weight * coalesce(counts.comments, 0) + :view_weight * post.views)
+                / pow(timestampdiff(hour, post.create_at, now()) + 2, :gravity)",
+            params! {
+                "like_weight" => LIKE_WEIGHT,
+                "comment_weight" => COMMENT_WEIGHT,
+                "view_weight" => VIEW_WEIGHT,
+                "gravity" => GRAVITY,
+            },
+        )
+        .unwrap();
+    }
+    /// 저장된 점수가 높은 순으로 포스트들을 반환하는 메서드이다.
+    ///
+    /// `page`를 통해 반환할 페이지를 지정할 수 있다.
+    /// # 예제
+    /// ```ignore
+    /// let page = Pagination { page: Some(1), per_page: Some(20) };
+    /// for post in Trending::get_posts(&page) {
+    ///     println!("인기 포스트의 제목은 {} 입니다.", post.title);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_posts(page: &Pagination) -> Vec<Post> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select post_id, user_id, title, language, data, likes, report_count,
+            date_format(create_at, '%Y-%m-%d %H:%i:%s'), post_type, accepted_comment_id,
+            date_format(updated_at, '%Y-%m-%d %H:%i:%s')
+            from post
+            where published and visibility = 'public' and deleted_at is null
+            order by trending_score desc, post_id desc
+            limit :limit offset :offset",
+            params! {
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(
+                post_id,
+                user_id,
+                title,
+                language,
+                data,
+                likes,
+                report_count,
+                create_at,
+                post_type,
+                accepted_comment_id,
+                updated_at,
+            )| {
+                Post::from_db(
+                    post_id,
+                    user_id,
+                    title,
+                    language,
+                    data,
+                    likes,
+                    report_count,
+                    create_at,
+                    post_type,
+                    accepted_comment_id,
+                    updated_at,
+                )
+            },
+        )
+        .unwrap()
+    }
+}
+
+#[get("/api/posts/trending")]
+pub async fn get_trending_api(
+    req: HttpRequest,
+    page: web::Query<Pagination>,
+    body: web::Query<BodyRequest>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> impl Responder {
+    let mut posts = Trending::get_posts(&page);
+    BlockList::default()
+        .extend(shadow_bans.hidden_from(Identity::from_header(&req).as_deref()))
+        .retain(&mut posts, |post| &post.user_id);
+    response::json(summary::apply(posts, body.body))
+}
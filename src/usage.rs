@@ -0,0 +1,175 @@
+//! # 사용자별 저장 공간 사용량 관련 동작을 정의하는 모듈
+//!
+//! `usage`는 코드뮤니티에서 사용자가 작성한 포스트와 업로드한 파일이 차지하는 용량을
+//! 집계하고, 설정된 한도를 넘지 않도록 제한하기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 한도는 `QUOTA_POST_BYTES`와 `QUOTA_UPLOAD_BYTES` 환경변수로 지정하며,
+//! 지정하지 않으면 제한하지 않는다.
+
+use crate::auth::Identity;
+use crate::db;
+use crate::error::ApiError;
+use crate::response;
+use actix_web::{get, web, HttpResponse};
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+use std::env;
+
+/// 사용자의 저장 공간 사용량을 나타내는 객체이다.
+#[derive(Serialize)]
+pub struct Usage {
+    /// 사용자가 작성한 포스트의 제목과 내용이 차지하는 용량(바이트)이다.
+    pub post_bytes: u64,
+    /// 사용자가 업로드한 파일이 차지하는 용량(바이트)이다.
+    pub upload_bytes: u64,
+    /// 포스트에 사용할 수 있는 최대 용량(바이트)이다. 제한이 없는 경우 `None`이다.
+    pub post_quota: Option<u64>,
+    /// 업로드한 파일에 사용할 수 있는 최대 용량(바이트)이다. 제한이 없는 경우 `None`이다.
+    pub upload_quota: Option<u64>,
+}
+
+/// 환경변수에 지정된 용량 한도를 반환하는 메서드이다.
+///
+/// 환경변수가 지정되지 않았거나 0인 경우 제한이 없는 것으로 보고 `None`을 반환한다.
+fn quota(name: &str) -> Option<u64> {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|value| *value > 0)
+}
+
+impl Usage {
+    /// 사용자의 현재 저장 공간 사용량을 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get(user_id: &str) -> Self {
+        let mut conn = db::get_conn();
+        let (post_bytes, upload_bytes) = conn
+            .exec_first(
+                r"select
+                (select cast(coalesce(sum(length(title) + length(data)), 0) as unsigned) from post where user_id = :user_id),
+                (select cast(coalesce(sum(bytes), 0) as unsigned) from upload where user_id = :user_id)",
+                params! {
+                    "user_id" => user_id,
+                },
+            )
+            .unwrap()
+            .unwrap_or((0, 0));
+        Self {
+            post_bytes,
+            upload_bytes,
+            post_quota: quota("QUOTA_POST_BYTES"),
+            upload_quota: quota("QUOTA_UPLOAD_BYTES"),
+        }
+    }
+    /// 사용자가 `bytes`만큼의 포스트를 더 작성할 수 있는지 확인하는 메서드이다.
+    ///
+    /// 포스트 용량에 제한이 없는 경우 DB에 접속하지 않고 바로 `true`를 반환한다.
+    pub fn can_post(user_id: &str, bytes: u64) -> bool {
+        match quota("QUOTA_POST_BYTES") {
+            Some(limit) => Self::get(user_id).post_bytes + bytes <= limit,
+            None => true,
+        }
+    }
+    /// 업로드 용량 한도를 넘지 않는 경우에만 사용자가 업로드할 파일을 기록하는 메서드이다.
+    ///
+    /// `key`에는 저장소 안에서의 파일 경로가, `bytes`에는 파일 크기가 들어간다.
+    /// 동시에 올린 파일들이 함께 한도를 넘지 않도록 사용자 행을 잠근 트랜잭션 안에서 사용량 확인과 기록을 함께 수행하며,
+    /// 한도를 넘어 기록하지 않은 경우 `false`를 반환한다.
+    /// 파일을 저장소에 저장하기 전에 호출하고, 저장에 실패한 경우 `remove_upload()`로 기록을 지운다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn reserve_upload(user_id: &str, key: &str, bytes: u64) -> Result<bool> {
+        let limit = quota("QUOTA_UPLOAD_BYTES");
+        db::transaction(|tx| {
+            let _: Option<u8> = tx.exec_first(
+                "select 1 from user where user_id = :user_id for update",
+                params! {
+                    "user_id" => user_id,
+                },
+            )?;
+            if let Some(limit) = limit {
+                let used: Option<u64> = tx.exec_first(
+                    r"select cast(coalesce(sum(bytes), 0) as unsigned)
+                    from upload where user_id = :user_id",
+                    params! {
+                        "user_id" => user_id,
+                    },
+                )?;
+                if used.unwrap_or(0) + bytes > limit {
+                    return Ok(false);
+                }
+            }
+            tx.exec_drop(
+                r"insert into upload(user_id, storage_key, bytes)
+                values(:user_id, :storage_key, :bytes)",
+                params! {
+                    "user_id" => user_id,
+                    "storage_key" => key,
+                    "bytes" => bytes,
+                },
+            )?;
+            Ok(true)
+        })
+    }
+    /// 사용자가 업로드한 파일 중 저장소 안에서의 경로가 `prefix`로 시작하는 파일들의 경로를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_keys(user_id: &str, prefix: &str) -> Result<Vec<String>> {
+        let mut conn = db::get_conn();
+        conn.exec(
+            r"select storage_key from upload
+            where user_id = :user_id and storage_key like concat(:prefix, '%')",
+            params! {
+                "user_id" => user_id,
+                "prefix" => prefix,
+            },
+        )
+    }
+    /// 업로드한 파일의 기록을 지우는 메서드이다.
+    ///
+    /// 저장소에서 파일을 지웠거나 저장하지 못한 경우 해당 파일이 용량에 포함되지 않도록 사용한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn remove_upload(key: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "delete from upload where storage_key = :storage_key",
+            params! {
+                "storage_key" => key,
+            },
+        )
+    }
+}
+
+#[get("/api/users/{user_id}/usage")]
+pub async fn get_usage_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if !identity.can_modify(&user_id) {
+        return Err(ApiError::Forbidden);
+    }
+    Ok(response::json(Usage::get(&user_id)))
+}
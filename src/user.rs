@@ -5,12 +5,51 @@
 //!
 //! `user`를 통해 사용자 이름을 확인하거나, 계정 탈퇴를 할 시 작업을
 //! 이곳에서 수행한다.
-use actix_web::{delete, get, patch, post, web, HttpResponse, Responder};
+use crate::auth::Identity;
+use crate::cache::Cache;
+use crate::db;
+use crate::email::{self, NotificationSettings};
+use crate::error::ApiError;
+use crate::outbox::Outbox;
+use crate::password;
+use crate::post::Post;
+use crate::repository::UserRepository;
+use crate::response;
+use crate::search_index::SearchIndex;
+use crate::storage::Storage;
+use crate::user_names::UserNames;
+use crate::validation::{self, FieldErrors};
+use crate::verification;
+use actix_web::web::Json;
+use actix_web::{delete, get, patch, post, put, web, HttpRequest, HttpResponse};
 use mysql::prelude::*;
 use mysql::*;
 use serde::{Deserialize, Serialize};
-use std::env;
-use std::path::Path;
+
+/// 비활성화된 사용자가 작성한 포스트와 댓글에 작성자 이름 대신 표시되는 이름이다.
+pub const DEACTIVATED_USER_NAME: &str = "비활성화된 사용자";
+/// 비활성화한 계정을 다시 활성화할 수 있는 기간(일)이다.
+pub const REACTIVATION_DAYS: u32 = 30;
+
+/// 이미 존재하는 `user_id`로 사용자를 등록하려 할 때 반환하는 오류 메시지이다.
+pub const USER_EXISTS: &str = "이미 존재하는 user_id입니다.";
+/// 다른 사용자가 사용하고 있는 메일 주소로 변경하려 할 때 반환하는 오류 메시지이다.
+pub const EMAIL_IN_USE: &str = "다른 사용자가 사용하고 있는 메일 주소입니다.";
+
+/// 사용자 한 명(`get_user`)을 가져오는 질의문이다.
+///
+/// 인증과 댓글 작성자 확인 등에서 자주 실행되므로 `post::FEED_QUERY`와 같이 상수로 두어
+/// 연결마다 캐시된 준비된 질의를 다시 사용한다.
+pub const USER_QUERY: &str = "select user_id, user_name, coalesce(banned_until > now(), false), date_format(banned_until, '%Y-%m-%d %H:%i:%s'), bio, avatar_url, website, preferred_language, reputation,
+(select count(*) from comment join post on post.accepted_comment_id = comment.comment_id where comment.user_id = user.user_id)
+from user where user_id = :user_id";
+
+/// 작성한 포스트가 공감을 받았을 때 늘어나는 평판 점수이다.
+pub const REPUTATION_PER_LIKE: i64 = 1;
+/// 작성한 답변이 채택되었을 때 늘어나는 평판 점수이다.
+pub const REPUTATION_PER_ACCEPTED_ANSWER: i64 = 15;
+/// 작성한 콘텐츠에 대한 신고가 받아들여졌을 때 늘어나는 평판 점수이다. 음수이므로 점수가 줄어든다.
+pub const REPUTATION_PER_UPHELD_REPORT: i64 = -10;
 
 /// 코드뮤니티에 쓰이는 사용자 객체이다.
 ///
@@ -18,17 +57,70 @@ use std::path::Path;
 /// 만일 `user_id`를 통해 사용자 이름을 받아오는 경우 `get_user()`를 활용하면 된다.
 /// # 예제
 /// ```
-/// let new_user = Post{"unique_id_for_user".to_string(), "sun30812".to_string()}
+/// use actix_web::web;
+/// use code_mmunity_server::user::User;
+/// let new_user = web::Query::<User>::from_query("user_id=unique_id_for_user&user_name=sun30812")
+///     .unwrap()
+///     .into_inner();
+/// assert_eq!(new_user.user_name, "sun30812");
 /// ```
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, Clone)]
 pub struct User {
     /// 사용자를 식별하는 고유 ID로 절대로 중복되서는 안된다.
     pub user_id: String,
     /// 사용자의 표시 이름이다.
     pub user_name: String,
+    /// 사용자가 현재 이용 정지 상태인지 여부이다.
+    #[serde(default)]
+    pub is_banned: bool,
+    /// 이용 정지가 해제되는 날짜이다. 이용 정지 상태가 아닌 경우 `None`이다.
+    #[serde(default)]
+    pub banned_until: Option<String>,
+    /// 사용자의 자기소개이다.
+    #[serde(default)]
+    pub bio: Option<String>,
+    /// 사용자의 프로필 이미지 주소이다.
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    /// 사용자의 웹사이트 주소이다.
+    #[serde(default)]
+    pub website: Option<String>,
+    /// 사용자가 주로 사용하는 프로그래밍 언어이다.
+    #[serde(default)]
+    pub preferred_language: Option<String>,
+    /// 사용자의 평판 점수이다.
+    ///
+    /// 작성한 포스트가 공감을 받거나 답변이 채택되면 늘어나고, 신고가 받아들여지면 줄어든다.
+    #[serde(default)]
+    pub reputation: i64,
+    /// 사용자가 작성한 댓글 중 질문 포스트의 답변으로 채택된 댓글 수이다.
+    #[serde(default)]
+    pub accepted_answers: u64,
 }
 
 impl User {
+    /// 등록하거나 변경할 사용자의 고유 ID와 이름이 조건에 맞는지 확인하는 메서드이다.
+    ///
+    /// 사용자 ID 형식이 올바르지 않거나 이름이 비어있거나 너무 긴 경우 항목별 이유를 담은
+    /// `ApiError::InvalidFields`를 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::user::User;
+    /// let user: User = serde_json::from_value(serde_json::json!({
+    ///     "user_id": "unique_id_for_user",
+    ///     "user_name": "sun30812",
+    /// }))
+    /// .unwrap();
+    /// assert!(user.validate().is_ok());
+    /// let user = User { user_id: "x'; drop table user; --".to_string(), ..user };
+    /// assert!(user.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), ApiError> {
+        let mut errors = FieldErrors::default();
+        errors.check_user_id(&self.user_id);
+        errors.check_user_name(&self.user_name);
+        errors.into_result()
+    }
     /// `user_id`를 통해 사용자 객체를 반환하는 메서드이다.
     ///
     /// 코드뮤니티의 `post`객체는 `user_id`만 가지고 있기 때문에 작성자를 확인하기 위해서는
@@ -36,13 +128,16 @@ impl User {
     /// `None`을 반환하기 때문에 예외처리가 가능하다.
     /// # 예제
     /// `user_id`로 사용자의 이름을 찾아서 출력하는 예제
-    /// ```
+    /// ```no_run
+    /// # use code_mmunity_server::user::User;
     /// let find_user = User::get_user("unique_id_for_user".to_string());
     /// match find_user {
     ///     Some(user) => println!("사용자의 이름은 {} 입니다.", user.user_name),
     ///     None => println!("존재하지 않는 사용자입니다.")
     /// }
     /// ```
+    /// `user_id`는 질의문에 직접 넣지 않고 매개변수로 전달하므로 악의적인 입력이 주어져도 SQL문으로 해석되지 않으며,
+    /// 사용자 고유 ID 형식이 아닌 경우 DB를 조회하지 않고 `None`을 반환한다.
     ///
     /// # Panics
     ///
@@ -51,58 +146,320 @@ impl User {
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
     pub fn get_user(user_id: String) -> Option<Self> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
+        if !validation::is_user_id(&user_id) {
+            return None;
+        }
+        let mut conn = db::get_read_conn();
         let result = conn
-            .query_first(format!("select * from user where user_id='{}'", user_id))
+            .exec_first(
+                USER_QUERY,
+                params! {
+                    "user_id" => &user_id,
+                },
+            )
             .unwrap()
-            .map(|(user_id, user_name)| User { user_id, user_name });
+            .map(
+                |(
+                    user_id,
+                    user_name,
+                    is_banned,
+                    banned_until,
+                    bio,
+                    avatar_url,
+                    website,
+                    preferred_language,
+                    reputation,
+                    accepted_answers,
+                ): (_, _, bool, Option<String>, _, _, _, _, _, _)| User {
+                    user_id,
+                    user_name,
+                    is_banned,
+                    banned_until: if is_banned { banned_until } else { None },
+                    bio,
+                    avatar_url,
+                    website,
+                    preferred_language,
+                    reputation,
+                    accepted_answers,
+                },
+            );
         result
     }
+    /// `user_id`를 통해 사용자의 이름만 반환하는 메서드이다.
+    ///
+    /// 포스트와 댓글의 작성자 이름을 확인할 때 사용하며, 최근에 조회한 이름은 `UserNames`에 저장해두고
+    /// DB에 접속하지 않고 반환한다. 존재하지 않는 사용자의 경우 `None`을 반환한다.
+    /// # 예제
+    /// ```ignore
+    /// let user_name = User::get_user_name("unique_id_for_user").expect("Unknown User");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_user_name(user_id: &str) -> Option<String> {
+        UserNames::shared().get_or_load(user_id, || {
+            let mut conn = db::get_conn();
+            conn.exec_first(
+                r"select if(deactivated_at is null, user_name, :deactivated_user_name)
+                from user where user_id = :user_id",
+                params! {
+                    "deactivated_user_name" => DEACTIVATED_USER_NAME,
+                    "user_id" => user_id,
+                },
+            )
+            .unwrap()
+        })
+    }
+    /// 사용자가 현재 이용 정지 상태인지 확인하는 메서드이다.
+    ///
+    /// 포스트나 댓글 작성, 공감과 같이 사용자가 콘텐츠를 생성하는 작업 전에 호출하여
+    /// 이용 정지된 사용자의 요청을 거부할 때 사용한다. 존재하지 않는 사용자의 경우 `false`를 반환한다.
+    /// Sql명령이 정상적으로 작동되지 않은 경우에 예외 처리를 할 수 있도록 `Result<bool>`로 값을 반환한다.
+    /// # 예제
+    /// ```no_run
+    /// # use code_mmunity_server::user::User;
+    /// if User::is_banned("unique_id_for_user").unwrap_or(true) {
+    ///     println!("이용 정지된 사용자입니다.");
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn is_banned(user_id: &str) -> Result<bool> {
+        let mut conn = db::get_conn();
+        let count: Option<u64> = conn.exec_first(
+            "select count(*) from user where user_id = :user_id and banned_until > now()",
+            params! {
+                "user_id" => user_id,
+            },
+        )?;
+        Ok(count.unwrap_or(0) > 0)
+    }
+    /// 사용자가 이용 정지되었거나 계정을 비활성화했거나 이메일 주소를 인증하지 않은 상태인지 확인하는 메서드이다.
+    ///
+    /// 포스트나 댓글 작성, 공감과 같이 사용자가 콘텐츠를 생성하는 작업 전에 호출하여
+    /// 콘텐츠를 생성할 수 없는 사용자의 요청을 거부할 때 사용한다. 존재하지 않는 사용자의 경우 `false`를 반환한다.
+    /// Sql명령이 정상적으로 작동되지 않은 경우에 예외 처리를 할 수 있도록 `Result<bool>`로 값을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn is_restricted(user_id: &str) -> Result<bool> {
+        let mut conn = db::get_conn();
+        let count: Option<u64> = conn.exec_first(
+            r"select count(*) from user
+            where user_id = :user_id
+            and (banned_until > now() or deactivated_at is not null or not verified)",
+            params! {
+                "user_id" => user_id,
+            },
+        )?;
+        Ok(count.unwrap_or(0) > 0)
+    }
+    /// 사용자가 계정을 비활성화한 상태인지 확인하는 메서드이다.
+    ///
+    /// 존재하지 않는 사용자의 경우 `false`를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn is_deactivated(user_id: &str) -> bool {
+        let mut conn = db::get_conn();
+        let count: Option<u64> = conn
+            .exec_first(
+                "select count(*) from user where user_id = :user_id and deactivated_at is not null",
+                params! {
+                    "user_id" => user_id,
+                },
+            )
+            .unwrap();
+        count.unwrap_or(0) > 0
+    }
+    /// 사용자의 인증 토큰 버전을 반환하는 메서드이다.
+    ///
+    /// 비밀번호를 재설정할 때마다 1씩 증가하며, 존재하지 않는 사용자의 경우 0을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn token_version(user_id: &str) -> u32 {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            "select token_version from user where user_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+        .unwrap_or(0)
+    }
+    /// 사용자의 계정을 비활성화할 때 사용되는 메서드
+    ///
+    /// 사용자가 작성한 콘텐츠는 그대로 남지만 작성자 이름은 `DEACTIVATED_USER_NAME`으로 표시되며,
+    /// `REACTIVATION_DAYS`일 안에 `reactivate()`로 다시 활성화할 수 있다.
+    /// 이미 비활성화된 계정인 경우 비활성화한 시각을 바꾸지 않는다.
+    /// 처리과정에 문제가 생겨서 처리가 불가능 한 경우 예외 처리를 할 수 있도록 `Result<()>`형을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn deactivate(user_id: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"update user
+            set deactivated_at = now()
+            where user_id = :user_id and deactivated_at is null",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+    }
+    /// 비활성화한 계정을 다시 활성화할 때 사용되는 메서드
+    ///
+    /// 비활성화한 지 `REACTIVATION_DAYS`일이 지나지 않은 경우에만 활성화하며, 활성화한 경우 `true`를 반환한다.
+    /// 비활성화된 계정이 아니거나 기간이 지난 경우 `false`를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn reactivate(user_id: &str) -> Result<bool> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"update user
+            set deactivated_at = null
+            where user_id = :user_id and deactivated_at > now() - interval :days day",
+            params! {
+                "user_id" => user_id,
+                "days" => REACTIVATION_DAYS,
+            },
+        )?;
+        Ok(conn.affected_rows() > 0)
+    }
+    /// 사용자가 운영자인지 확인하는 메서드이다.
+    ///
+    /// 운영자는 다른 사용자가 작성한 포스트나 댓글을 수정하거나 삭제할 수 있다.
+    /// 존재하지 않는 사용자의 경우 `false`를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn is_moderator(user_id: &str) -> bool {
+        let mut conn = db::get_conn();
+        let result: Option<bool> = conn
+            .exec_first(
+                "select is_moderator from user where user_id = :user_id",
+                params! {
+                    "user_id" => user_id,
+                },
+            )
+            .unwrap();
+        result.unwrap_or(false)
+    }
+    /// 사용자를 이용 정지시킬 때 사용되는 메서드
+    ///
+    /// `until`에는 `YYYY-MM-DD HH:MM:SS`형식으로 이용 정지가 해제되는 날짜를 지정한다.
+    /// `None`을 전달하면 영구적으로 이용 정지된다.
+    /// 이용 정지시킨 경우 `true`를, 존재하지 않는 사용자인 경우 `false`를 반환한다.
+    /// 처리과정에 문제가 생겨서 처리가 불가능 한 경우 예외 처리를 할 수 있도록 `Result<bool>`형을 반환한다.
+    ///
+    /// # 예제
+    /// ```no_run
+    /// # use code_mmunity_server::user::User;
+    /// User::ban("unique_id_for_user", Some("2022-12-25 00:00:00".to_string()))
+    ///     .expect("Sql작업 중 문제가 발생하였습니다.");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn ban(user_id: &str, until: Option<String>) -> Result<bool> {
+        db::transaction(|tx| {
+            // 이미 같은 날짜로 이용 정지된 사용자는 변경된 행이 없으므로 존재 여부를 먼저 확인한다.
+            let exists: Option<u8> = tx.exec_first(
+                "select 1 from user where user_id = :user_id for update",
+                params! {
+                    "user_id" => user_id,
+                },
+            )?;
+            if exists.is_none() {
+                return Ok(false);
+            }
+            tx.exec_drop(
+                r"update user
+                set banned_until = :banned_until
+                where user_id = :user_id",
+                params! {
+                    "banned_until" => until.unwrap_or_else(|| "9999-12-31 23:59:59".to_string()),
+                    "user_id" => user_id,
+                },
+            )?;
+            Ok(true)
+        })
+    }
+    /// 사용자의 이용 정지를 해제할 때 사용되는 메서드
+    ///
+    /// 처리과정에 문제가 생겨서 처리가 불가능 한 경우 예외 처리를 할 수 있도록 `Result<()>`형을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn unban(user_id: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"update user
+            set banned_until = null
+            where user_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+    }
     /// 새로운 사용자를 DB에 등록할 때 사용되는 메서드
     ///
     /// `new_user`에는 쿼리 스트링을 통해 `User` 구조체에 명시된 값을 받아 동작을 처리한다.
     /// 처리과정에 문제가 생겨서 처리가 불가능 한 경우 예외 처리를 할 수 있도록 `Result<()>`형을 반환한다.
+    /// 이미 존재하는 `user_id`인 경우 기존 사용자를 덮어쓰지 않고 중복 키 오류(1062)를 반환한다.
     ///
     /// # 예제
-    /// ```
-    /// let new_user = User {
-    ///     user_id: "unique_id_for_user".to_string(),
-    ///     user_name: "user_name".to_string()
-    /// };
+    /// ```no_run
+    /// # use actix_web::web;
+    /// # use code_mmunity_server::user::User;
+    /// let new_user = web::Query::<User>::from_query("user_id=unique_id_for_user&user_name=user_name").unwrap();
     /// match User::new_user(new_user) {
-    ///     Ok(_) => HttpResponse::Created(),
-    ///     Err(_) => HttpResponse::BadRequest(),
+    ///     Ok(_) => println!("사용자가 등록되었습니다."),
+    ///     Err(error) => println!("사용자를 등록하지 못했습니다: {}", error),
     /// }
     /// ```
     ///
@@ -113,63 +470,36 @@ impl User {
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
     pub fn new_user(new_user: web::Query<User>) -> Result<()> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
+        let mut conn = db::get_conn();
         conn.exec_drop(
-            r"replace into user
-            set user_id = :user_id, user_name = :user_name",
+            r"insert into user(user_id, user_name, bio, avatar_url, website, preferred_language)
+            values(:user_id, :user_name, :bio, :avatar_url, :website, :preferred_language)",
             params! {
                 "user_id" => new_user.user_id.clone(),
-                "user_name" => new_user.user_name.clone()
+                "user_name" => new_user.user_name.clone(),
+                "bio" => new_user.bio.clone(),
+                "avatar_url" => new_user.avatar_url.clone(),
+                "website" => new_user.website.clone(),
+                "preferred_language" => new_user.preferred_language.clone(),
             },
         )
     }
 
-    /// 사용자에 대한 정보를 변경할 때 사용되는 메서드
+    /// 사용자의 프로필 중 일부 항목만 변경할 때 사용되는 메서드
     ///
-    /// `new_user`에는 쿼리 스트링을 통해 `User` 구조체에 명시된 값을 받아 동작을 처리한다.
+    /// `request`에서 값이 주어진 항목만 변경되며, 빈 문자열을 전달하면 해당 항목을 비울 수 있다.
     /// 처리과정에 문제가 생겨서 처리가 불가능 한 경우 예외 처리를 할 수 있도록 `Result<()>`형을 반환한다.
     ///
     /// # 예제
-    /// ```
-    /// let modified_user = User {
-    ///     user_id: "unique_id_for_user".to_string(),
-    ///     user_name: "user_name".to_string()
+    /// ```ignore
+    /// let request = UpdateProfileRequest {
+    ///     user_name: None,
+    ///     bio: Some("Rust를 좋아합니다.".to_string()),
+    ///     avatar_url: None,
+    ///     website: None,
+    ///     preferred_language: Some("rust".to_string()),
     /// };
-    /// match User::update_user(modified_user) {
-    ///     Ok(_) => HttpResponse::Created(),
-    ///     Err(_) => HttpResponse::BadRequest(),
-    /// }
+    /// User::update_profile("unique_id_for_user", request).expect("Sql작업 중 문제가 발생하였습니다.");
     /// ```
     ///
     /// # Panics
@@ -178,46 +508,21 @@ impl User {
     /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
-    pub fn update_user(modified_user: web::Query<User>) -> Result<()> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
+    pub fn update_profile(user_id: &str, request: UpdateProfileRequest) -> Result<()> {
+        let mut conn = db::get_conn();
         conn.exec_drop(
             r"update user
-            set user_name = :user_name
+            set user_name = coalesce(:user_name, user_name), bio = coalesce(:bio, bio),
+            avatar_url = coalesce(:avatar_url, avatar_url), website = coalesce(:website, website),
+            preferred_language = coalesce(:preferred_language, preferred_language)
             where user_id = :user_id",
             params! {
-                "user_name" => modified_user.user_name.clone(),
-                "user_id" => modified_user.user_id.clone()
+                "user_name" => request.user_name,
+                "bio" => request.bio,
+                "avatar_url" => request.avatar_url,
+                "website" => request.website,
+                "preferred_language" => request.preferred_language,
+                "user_id" => user_id,
             },
         )
     }
@@ -225,17 +530,22 @@ impl User {
     /// 사용자를 DB에서 제거할 때 사용되는 메서드
     ///
     /// `deleted_user`에는 쿼리 스트링을 통해 `User` 구조체에 명시된 값을 받아 동작을 처리한다.
-    /// 처리과정에 문제가 생겨서 처리가 불가능 한 경우 예외 처리를 할 수 있도록 `Result<()>`형을 반환한다.
+    /// 사용자가 작성한 포스트와 댓글, 사용자의 공감 내역 및 사용자의 포스트에 달린 댓글과 공감 내역도 함께 제거되며,
+    /// 사용자가 공감했던 포스트의 공감 수는 그만큼 감소한다.
+    /// 사용자의 로그인 세션과 비밀번호 재설정 토큰도 함께 제거되므로 발급된 토큰은 더 이상 사용할 수 없다.
+    /// 사용자의 포스트에 첨부된 파일, 사용자가 하거나 사용자의 콘텐츠에 접수된 신고, 금칙어 검토 기록과
+    /// 사용자가 올린 파일의 업로드 기록도 함께 제거되며, 저장소에서 지워야 하는 파일들의 경로를 반환한다.
+    /// 모든 작업은 하나의 트랜잭션에서 수행되므로 중간에 실패하면 아무것도 제거되지 않는다.
+    /// 처리과정에 문제가 생겨서 처리가 불가능 한 경우 예외 처리를 할 수 있도록 `Result<Vec<String>>`형을 반환한다.
     ///
     /// # 예제
-    /// ```
-    /// let deleted_user = User {
-    ///     user_id: "unique_id_for_user".to_string(),
-    ///     user_name: "user_name".to_string()
-    /// };
+    /// ```no_run
+    /// # use actix_web::web;
+    /// # use code_mmunity_server::user::User;
+    /// let deleted_user = web::Query::<User>::from_query("user_id=unique_id_for_user&user_name=user_name").unwrap();
     /// match User::delete_user(deleted_user) {
-    ///     Ok(_) => HttpResponse::Created(),
-    ///     Err(_) => HttpResponse::BadRequest(),
+    ///     Ok(keys) => println!("사용자가 제거되었습니다. 지워야 하는 파일: {:?}", keys),
+    ///     Err(error) => println!("사용자를 제거하지 못했습니다: {}", error),
     /// }
     /// ```
     ///
@@ -245,83 +555,708 @@ impl User {
     /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
     /// - DB에 접속이 제한시간을 초과한 경우
     /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
-    pub fn delete_user(deleted_user: web::Query<User>) -> Result<()> {
-        let ssl = match env::var("USE_SSL") {
-            Ok(value) => {
-                if value == "true" {
-                    Some(SslOpts::default().with_root_cert_path(Some(Path::new(
-                        "./cert/DigiCertGlobalRootCA.crt.pem",
-                    ))))
-                } else {
-                    None
-                }
-            }
-            Err(_) => None,
-        };
-        let opts = OptsBuilder::new()
-            .ip_or_hostname(Some(
-                env::var("DB_SERVER").expect("DB_SERVER가 설정되지 않음"),
-            ))
-            .tcp_port(
-                env::var("DB_PORT")
-                    .expect("DB_PORT가 설정되지 않음")
-                    .parse::<u16>()
-                    .expect("DB_PORT가 올바른 형식이 아님"),
-            )
-            .user(Some(env::var("DB_USER").expect("DB_USER가 설정되지 않음")))
-            .pass(Some(
-                env::var("DB_PASSWD").expect("DB_PASSWD가 설정되지 않음"),
-            ))
-            .db_name(Some(
-                env::var("DB_DATABASE").expect("DB_DATABASE가 설정되지 않음"),
-            ))
-            .ssl_opts(ssl);
-        let pool = Pool::new(opts).unwrap();
-        let mut conn = pool.get_conn().unwrap();
+    pub fn delete_user(deleted_user: web::Query<User>) -> Result<Vec<String>> {
+        let user_id = deleted_user.user_id.clone();
+        db::transaction(|tx| {
+            tx.exec_drop(
+                r"update user join (
+                    select post.user_id, count(*) as liked from react join post on react.post_id = post.post_id
+                    where react.user_id = :user_id and react.react_kind = 'like' and post.user_id <> :user_id
+                    group by post.user_id
+                ) as author on user.user_id = author.user_id
+                set user.reputation = user.reputation - author.liked * :per_like",
+                params! {
+                    "user_id" => &user_id,
+                    "per_like" => REPUTATION_PER_LIKE,
+                },
+            )?;
+            tx.exec_drop(
+                r"update post join react on post.post_id = react.post_id
+                set post.likes = if(post.likes > 0, post.likes - 1, 0)
+                where react.user_id = :user_id and react.react_kind = 'like' and post.user_id <> :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from react
+                where user_id = :user_id or post_id in (select post_id from post where user_id = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"update post join comment on post.accepted_comment_id = comment.comment_id
+                set post.accepted_comment_id = null
+                where comment.user_id = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"update comment join comment_report on comment.comment_id = comment_report.comment_id
+                set comment.report_count = if(comment.report_count > 0, comment.report_count - 1, 0)
+                where comment_report.user_id = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from comment_report
+                where user_id = :user_id or comment_id in (
+                    select comment_id from comment
+                    where user_id = :user_id or post_id in (select post_id from post where user_id = :user_id)
+                )",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from flagged_content
+                where content_type = 'comment' and content_id in (
+                    select comment_id from comment
+                    where user_id = :user_id or post_id in (select post_id from post where user_id = :user_id)
+                )",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from comment
+                where user_id = :user_id or post_id in (select post_id from post where user_id = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from shortlink where post_id in (select post_id from post where user_id = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from bookmark
+                where user_id = :user_id or post_id in (select post_id from post where user_id = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from language_subscription where user_id = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from follow where follower_id = :user_id or followee_id = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from blocked where blocker_id = :user_id or blocked_id = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from message where conversation_id in
+                (select conversation_id from conversation where user_a = :user_id or user_b = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from conversation where user_a = :user_id or user_b = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            let keys: Vec<String> = tx.exec(
+                r"select storage_key from upload
+                where user_id = :user_id or exists (
+                    select 1 from post where post.user_id = :user_id
+                    and upload.storage_key like concat('attachments/', post.post_id, '/%')
+                )",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from upload
+                where user_id = :user_id or exists (
+                    select 1 from post where post.user_id = :user_id
+                    and upload.storage_key like concat('attachments/', post.post_id, '/%')
+                )",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from attachment
+                where user_id = :user_id or post_id in (select post_id from post where user_id = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from post_report
+                where user_id = :user_id or post_id in (select post_id from post where user_id = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from flagged_content
+                where content_type = 'post' and content_id in (select post_id from post where user_id = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from post_revision where post_id in (select post_id from post where user_id = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from post_tag where post_id in (select post_id from post where user_id = :user_id)",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from post where user_id = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from session where user_id = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                "delete from password_reset where user_id = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            tx.exec_drop(
+                r"delete from user
+            where user_id = :user_id",
+                params! {
+                    "user_id" => &user_id,
+                },
+            )?;
+            Ok(keys)
+        })
+    }
+    /// 사용자의 평판 점수를 `delta`만큼 변경하는 메서드이다.
+    ///
+    /// 공감, 답변 채택, 신고 처리와 같이 평판에 영향을 주는 작업에서 `REPUTATION_PER_*` 상수와 함께 호출한다.
+    /// 점수를 줄이려면 음수를 전달한다.
+    /// # 예제
+    /// ```ignore
+    /// User::add_reputation("unique_id_for_user", REPUTATION_PER_UPHELD_REPORT)?;
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn add_reputation(user_id: &str, delta: i64) -> Result<()> {
+        let mut conn = db::get_conn();
         conn.exec_drop(
-            r"delete from user
-        where user_id = :user_id",
+            "update user set reputation = reputation + :delta where user_id = :user_id",
             params! {
-                "user_id" => deleted_user.user_id.clone(),
+                "user_id" => user_id,
+                "delta" => delta,
             },
         )
     }
+    /// 사용자가 공감한 포스트 목록을 다른 사용자에게 공개하는지 확인하는 메서드이다.
+    ///
+    /// 존재하지 않는 사용자의 경우 `None`을 반환하기 때문에 예외처리가 가능하다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn is_likes_public(user_id: &str) -> Option<bool> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            "select likes_public from user where user_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+    }
+    /// 사용자가 공감한 포스트 목록의 공개 여부를 변경할 때 사용되는 메서드
+    ///
+    /// 처리과정에 문제가 생겨서 처리가 불가능 한 경우 예외 처리를 할 수 있도록 `Result<()>`형을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn set_likes_public(user_id: &str, likes_public: bool) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            r"update user
+            set likes_public = :likes_public
+            where user_id = :user_id",
+            params! {
+                "likes_public" => likes_public,
+                "user_id" => user_id,
+            },
+        )
+    }
+    /// 사용자의 메일 알림 설정을 반환하는 메서드이다.
+    ///
+    /// 존재하지 않는 사용자의 경우 `None`을 반환하기 때문에 예외처리가 가능하다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn get_notification_settings(user_id: &str) -> Option<NotificationSettings> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            r"select email, notify_comments, notify_post_removed, notify_messages
+            from user where user_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+        .unwrap()
+        .map(
+            |(email, comments, post_removed, messages)| NotificationSettings {
+                email,
+                comments,
+                post_removed,
+                messages,
+            },
+        )
+    }
+    /// 사용자의 메일 알림 설정을 변경할 때 사용되는 메서드이다.
+    ///
+    /// `request`에서 값이 주어진 항목만 변경되며, 메일 주소로 빈 문자열이 주어진 경우 메일 주소를 지운다.
+    /// 비밀번호로 가입한 계정의 메일 주소는 로그인에 사용되므로 바뀌지 않는다.
+    /// 이메일 주소 인증을 사용하는 경우 메일 주소가 새 주소로 바뀌면 인증되지 않은 상태가 되며, 이때 `true`를 반환한다.
+    /// 처리과정에 문제가 생겨서 처리가 불가능 한 경우 예외 처리를 할 수 있도록 `Result<bool>`형을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn update_notification_settings(
+        user_id: &str,
+        request: UpdateNotificationsRequest,
+    ) -> Result<bool> {
+        db::transaction(|tx| {
+            let current: Option<(Option<String>, bool)> = tx.exec_first(
+                "select email, password_hash is not null from user where user_id = :user_id for update",
+                params! {
+                    "user_id" => user_id,
+                },
+            )?;
+            let Some((current_email, password_account)) = current else {
+                return Ok(false);
+            };
+            let reverify = verification::is_enabled()
+                && !password_account
+                && request.email.as_deref().is_some_and(|address| {
+                    !address.is_empty() && current_email.as_deref() != Some(address)
+                });
+            tx.exec_drop(
+                r"update user
+                set email = if(:email is null or password_hash is not null, email, nullif(:email, '')),
+                notify_comments = coalesce(:comments, notify_comments),
+                notify_post_removed = coalesce(:post_removed, notify_post_removed),
+                notify_messages = coalesce(:messages, notify_messages),
+                verified = if(:reverify, false, verified)
+                where user_id = :user_id",
+                params! {
+                    "email" => request.email,
+                    "comments" => request.comments,
+                    "post_removed" => request.post_removed,
+                    "messages" => request.messages,
+                    "reverify" => reverify,
+                    "user_id" => user_id,
+                },
+            )?;
+            Ok(reverify)
+        })
+    }
 }
 
-#[post("/api/users")]
-pub async fn new_user_api(new_user: web::Query<User>) -> impl Responder {
-    println!("POST /api/users");
-    match User::new_user(new_user) {
-        Ok(_) => HttpResponse::Created(),
-        Err(_) => HttpResponse::BadRequest(),
+/// JSON 을 통해 사용자 프로필의 변경할 내용을 받을 때 필요한 구조체이다.
+///
+/// 값이 주어지지 않은 항목은 변경되지 않는다.
+#[derive(Deserialize, Default)]
+pub struct UpdateProfileRequest {
+    /// 사용자의 새 표시 이름이다.
+    pub user_name: Option<String>,
+    /// 사용자의 새 자기소개이다.
+    pub bio: Option<String>,
+    /// 사용자의 새 프로필 이미지 주소이다.
+    pub avatar_url: Option<String>,
+    /// 사용자의 새 웹사이트 주소이다.
+    pub website: Option<String>,
+    /// 사용자가 주로 사용하는 새 프로그래밍 언어이다.
+    pub preferred_language: Option<String>,
+}
+
+impl UpdateProfileRequest {
+    /// 변경할 이름이 사용자를 등록할 때와 같은 조건에 맞는지 확인하는 메서드이다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::user::UpdateProfileRequest;
+    /// let request = UpdateProfileRequest { user_name: Some(" ".to_string()), ..Default::default() };
+    /// assert!(request.validate().is_err());
+    /// assert!(UpdateProfileRequest::default().validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> std::result::Result<(), ApiError> {
+        let mut errors = FieldErrors::default();
+        if let Some(user_name) = &self.user_name {
+            errors.check_user_name(user_name);
+        }
+        errors.into_result()
     }
 }
 
-#[patch("/api/users")]
-pub async fn update_user_api(modified_user: web::Query<User>) -> impl Responder {
-    println!("UPDATE /api/users");
-    match User::update_user(modified_user) {
-        Ok(_) => HttpResponse::Ok(),
-        Err(_) => HttpResponse::BadRequest(),
+/// JSON 을 통해 사용자 프로필 전체를 바꿀 때 필요한 구조체이다.
+///
+/// 값이 주어지지 않은 항목은 비워진다.
+#[derive(Deserialize)]
+pub struct ReplaceProfileRequest {
+    /// 사용자의 새 표시 이름이다.
+    pub user_name: String,
+    /// 사용자의 새 자기소개이다.
+    pub bio: Option<String>,
+    /// 사용자의 새 프로필 이미지 주소이다.
+    pub avatar_url: Option<String>,
+    /// 사용자의 새 웹사이트 주소이다.
+    pub website: Option<String>,
+    /// 사용자가 주로 사용하는 새 프로그래밍 언어이다.
+    pub preferred_language: Option<String>,
+}
+
+impl From<ReplaceProfileRequest> for UpdateProfileRequest {
+    fn from(request: ReplaceProfileRequest) -> Self {
+        Self {
+            user_name: Some(request.user_name),
+            bio: Some(request.bio.unwrap_or_default()),
+            avatar_url: Some(request.avatar_url.unwrap_or_default()),
+            website: Some(request.website.unwrap_or_default()),
+            preferred_language: Some(request.preferred_language.unwrap_or_default()),
+        }
     }
 }
 
+/// 쿼리 스트링을 통해 사용자의 공개 설정을 변경할 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct PrivacyRequest {
+    /// 공감한 포스트 목록을 다른 사용자에게 공개할지 여부이다.
+    pub likes_public: bool,
+}
+
+/// JSON 을 통해 사용자의 메일 알림 설정을 변경할 때 필요한 구조체이다.
+///
+/// 값이 주어지지 않은 항목은 변경되지 않는다.
+#[derive(Deserialize)]
+pub struct UpdateNotificationsRequest {
+    /// 알림 메일을 받을 새 주소이다. 빈 문자열인 경우 메일 주소를 지운다.
+    /// 비밀번호로 가입한 계정은 로그인에 사용하는 주소이므로 바꿀 수 없다.
+    pub email: Option<String>,
+    /// 작성한 포스트에 댓글이 달렸을 때 메일을 받을지 여부이다.
+    pub comments: Option<bool>,
+    /// 작성한 포스트가 관리자에 의해 삭제되었을 때 메일을 받을지 여부이다.
+    pub post_removed: Option<bool>,
+    /// 다른 사용자에게 메시지를 받았을 때 메일을 받을지 여부이다.
+    pub messages: Option<bool>,
+}
+
+#[post("/api/users")]
+pub async fn new_user_api(
+    identity: Identity,
+    new_user: web::Query<User>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    // 이메일 인증 없이 작성 가능한 사용자가 만들어지므로 인증 토큰의 사용자 본인만 등록할 수 있다.
+    if identity.user_id != new_user.user_id {
+        return Err(ApiError::Forbidden);
+    }
+    new_user.validate()?;
+    let user = new_user.into_inner();
+    users.create(user.clone())?;
+    Ok(response::created(
+        format!("/api/users/{}", user.user_id),
+        user,
+    ))
+}
+
 #[get("/api/users/{user_id}")]
-pub async fn get_user_api(user_id: web::Path<String>) -> impl Responder {
-    println!("GET /api/users");
-    match User::get_user(user_id.clone()) {
-        Some(result) => HttpResponse::Ok()
-            .insert_header(("Content-Type", "application/json;charset=utf-8"))
-            .json(result),
-        None => HttpResponse::NotFound().body("Can not found user with id."),
+pub async fn get_user_api(
+    user_id: web::Path<String>,
+    cache: web::Data<Cache>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if users.is_deactivated(&user_id) {
+        return Err(user_not_found());
+    }
+    let user = cache
+        .user(&user_id, || users.get(&user_id))
+        .ok_or_else(user_not_found)?;
+    Ok(response::json(user))
+}
+
+#[patch("/api/users/{user_id}")]
+pub async fn update_profile_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    request: Json<UpdateProfileRequest>,
+    cache: web::Data<Cache>,
+    user_names: web::Data<UserNames>,
+    search_index: web::Data<SearchIndex>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    update_profile(
+        &identity,
+        &user_id,
+        request.into_inner(),
+        &cache,
+        &user_names,
+        &search_index,
+        &users,
+    )
+}
+
+#[put("/api/users/{user_id}")]
+pub async fn replace_profile_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    request: Json<ReplaceProfileRequest>,
+    cache: web::Data<Cache>,
+    user_names: web::Data<UserNames>,
+    search_index: web::Data<SearchIndex>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    update_profile(
+        &identity,
+        &user_id,
+        request.into_inner().into(),
+        &cache,
+        &user_names,
+        &search_index,
+        &users,
+    )
+}
+
+/// 본인 또는 관리자인지 확인한 뒤 존재하는 사용자의 프로필을 변경하는 메서드이다.
+///
+/// 이름이 바뀐 경우 작성자의 이름이 함께 저장된 포스트와 홈 피드의 캐시를 지우고,
+/// 사용자가 작성한 포스트들을 검색 색인에 다시 반영한다.
+fn update_profile(
+    identity: &Identity,
+    user_id: &str,
+    request: UpdateProfileRequest,
+    cache: &Cache,
+    user_names: &UserNames,
+    search_index: &SearchIndex,
+    users: &web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if !identity.can_modify(user_id) {
+        return Err(ApiError::Forbidden);
+    }
+    request.validate()?;
+    let Some(user) = users.get(user_id) else {
+        return Err(ApiError::NotFound(
+            "요청한 user_id는 존재하지 않는 사용자입니다.".to_string(),
+        ));
+    };
+    let renamed = request
+        .user_name
+        .as_ref()
+        .is_some_and(|user_name| *user_name != user.user_name);
+    users.update_profile(user_id, request)?;
+    user_names.invalidate(user_id);
+    if renamed {
+        cache.invalidate_author(user_id, || Post::get_ids_by_user(user_id));
+        search_index.sync_author(user_id);
+    } else {
+        cache.invalidate_user(user_id);
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[post("/api/users/{user_id}/deactivate")]
+pub async fn deactivate_user_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    cache: web::Data<Cache>,
+    user_names: web::Data<UserNames>,
+    search_index: web::Data<SearchIndex>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if identity.user_id != *user_id {
+        return Err(ApiError::Forbidden);
+    }
+    users.deactivate(&user_id)?;
+    user_names.invalidate(&user_id);
+    cache.invalidate_author(&user_id, || Post::get_ids_by_user(&user_id));
+    search_index.sync_author(&user_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[post("/api/users/{user_id}/reactivate")]
+pub async fn reactivate_user_api(
+    req: HttpRequest,
+    user_id: web::Path<String>,
+    cache: web::Data<Cache>,
+    user_names: web::Data<UserNames>,
+    search_index: web::Data<SearchIndex>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let Some(requester_id) = Identity::from_header(&req) else {
+        return Err(ApiError::Unauthorized);
+    };
+    if requester_id != *user_id {
+        return Err(ApiError::Forbidden);
+    }
+    if !users.reactivate(&user_id)? {
+        return Err(ApiError::Conflict(format!(
+            "비활성화된 계정이 아니거나 비활성화한 지 {}일이 지나 다시 활성화할 수 없습니다.",
+            REACTIVATION_DAYS
+        )));
+    }
+    user_names.invalidate(&user_id);
+    cache.invalidate_author(&user_id, || Post::get_ids_by_user(&user_id));
+    search_index.sync_author(&user_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[patch("/api/users/{user_id}/privacy")]
+pub async fn update_privacy_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    request: web::Query<PrivacyRequest>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if !identity.can_modify(&user_id) {
+        return Err(ApiError::Forbidden);
     }
+    users.set_likes_public(&user_id, request.likes_public)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[get("/api/users/{user_id}/notifications")]
+pub async fn get_notifications_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    users: web::Data<dyn UserRepository>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if !identity.can_modify(&user_id) {
+        return Err(ApiError::Forbidden);
+    }
+    let settings = users
+        .get_notification_settings(&user_id)
+        .ok_or_else(user_not_found)?;
+    Ok(response::json(settings))
+}
+
+#[patch("/api/users/{user_id}/notifications")]
+pub async fn update_notifications_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    request: Json<UpdateNotificationsRequest>,
+    users: web::Data<dyn UserRepository>,
+    outbox: web::Data<Outbox>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if !identity.can_modify(&user_id) {
+        return Err(ApiError::Forbidden);
+    }
+    if request
+        .email
+        .as_deref()
+        .is_some_and(|address| !address.is_empty() && !email::is_address(address))
+    {
+        return Err(ApiError::Validation(
+            "메일 주소 형식이 올바르지 않습니다.".to_string(),
+        ));
+    }
+    if request.email.is_some() && user_id.starts_with(password::USER_ID_PREFIX) {
+        return Err(ApiError::Validation(
+            "비밀번호로 가입한 계정의 메일 주소는 로그인에 사용되므로 바꿀 수 없습니다."
+                .to_string(),
+        ));
+    }
+    let address = request.email.clone();
+    if users.update_notification_settings(&user_id, request.into_inner())? {
+        if let Some(address) = address {
+            email::send_verification(outbox, user_id.into_inner(), address);
+        }
+    }
+    Ok(HttpResponse::Ok().finish())
 }
 
 #[delete("/api/users")]
-pub async fn delete_user_api(deleted_user: web::Query<User>) -> impl Responder {
-    println!("DELETE /api/users");
-    match User::delete_user(deleted_user) {
-        Ok(_) => HttpResponse::Ok(),
-        Err(_) => HttpResponse::BadRequest(),
+pub async fn delete_user_api(
+    identity: Identity,
+    deleted_user: web::Query<User>,
+    cache: web::Data<Cache>,
+    user_names: web::Data<UserNames>,
+    users: web::Data<dyn UserRepository>,
+    storage: web::Data<Storage>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let user_id = deleted_user.user_id.clone();
+    if !identity.can_modify(&user_id) {
+        return Err(ApiError::Forbidden);
+    }
+    let keys = users.delete(deleted_user.into_inner())?;
+    cache.invalidate_user(&user_id);
+    user_names.invalidate(&user_id);
+    // 업로드 기록은 이미 지워졌으므로 저장소에서 지우지 못한 파일은 경고로만 남긴다.
+    for key in &keys {
+        match storage.delete(key).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+            Err(error) => tracing::warn!(%error, key, "탈퇴한 사용자의 파일을 지우지 못했습니다."),
+        }
+    }
+    Ok(response::no_content())
+}
+
+/// 사용자가 존재하지 않을 때 반환하는 오류이다.
+pub(crate) fn user_not_found() -> ApiError {
+    ApiError::NotFound("요청한 user_id는 존재하지 않는 사용자입니다.".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_user_rejects_hostile_user_id() {
+        for hostile in [
+            "' or '1'='1",
+            "x' union select * from post -- ",
+            "x'; drop table user; --",
+            "\\' or 1=1 #",
+        ] {
+            assert!(User::get_user(hostile.to_string()).is_none());
+        }
     }
 }
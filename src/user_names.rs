@@ -0,0 +1,115 @@
+//! # 사용자 이름 캐시 관련 동작을 정의하는 모듈
+//!
+//! `user_names`는 포스트와 댓글을 불러올 때마다 작성자의 이름을 DB에서 다시 조회하지 않도록
+//! 최근에 조회한 사용자 이름을 메모리에 저장해두기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 저장된 이름은 `USER_NAME_CACHE_SECONDS`초(기본값 60초)가 지나면 다시 조회하며,
+//! 최대 `USER_NAME_CACHE_SIZE`명(기본값 1024명)까지 저장하고 초과하면 가장 오래 사용되지 않은 이름부터 지운다.
+//! 사용자의 이름이 바뀌거나 사용자가 탈퇴하면 저장된 이름을 바로 지운다.
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// 이름을 저장해두는 기본 시간(초)이다.
+const DEFAULT_CACHE_SECONDS: u64 = 60;
+/// 저장할 수 있는 기본 사용자 수이다.
+const DEFAULT_CACHE_SIZE: usize = 1024;
+
+/// 서버의 모든 워커가 공유하는 사용자 이름 캐시이다.
+static SHARED: OnceLock<Arc<UserNames>> = OnceLock::new();
+
+/// 저장된 사용자 이름 하나의 정보이다.
+struct Entry {
+    /// 사용자의 이름이다.
+    user_name: String,
+    /// 이름을 DB에서 조회한 시각이다.
+    loaded_at: Instant,
+    /// 이름을 마지막으로 사용한 시각이다.
+    used_at: Instant,
+}
+
+/// 최근에 조회한 사용자 이름을 저장하는 객체이다.
+pub struct UserNames {
+    /// 이름을 저장해두는 시간이다.
+    ttl: Duration,
+    /// 저장할 수 있는 최대 사용자 수이다.
+    capacity: usize,
+    /// 사용자 고유 ID별로 저장된 이름이다.
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl UserNames {
+    /// 환경변수를 통해 객체를 생성하는 메서드이다.
+    ///
+    /// `USER_NAME_CACHE_SECONDS`와 `USER_NAME_CACHE_SIZE`가 주어지지 않았거나 올바르지 않은 경우 기본값을 사용한다.
+    pub fn from_env() -> Self {
+        let seconds = env::var("USER_NAME_CACHE_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_CACHE_SECONDS);
+        let capacity = env::var("USER_NAME_CACHE_SIZE")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_CACHE_SIZE);
+        Self {
+            ttl: Duration::from_secs(seconds),
+            capacity,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+    /// 서버의 모든 워커가 공유하는 객체를 반환하는 메서드이다.
+    ///
+    /// 처음 호출될 때 환경변수를 통해 객체를 생성한다. 앱 상태에 등록할 때도 이 객체를 사용해야
+    /// 핸들러에서 지운 이름이 포스트와 댓글을 불러올 때 반영된다.
+    pub fn shared() -> Arc<Self> {
+        SHARED.get_or_init(|| Arc::new(Self::from_env())).clone()
+    }
+    /// 저장된 사용자 이름을 반환하고, 없는 경우 `load`로 조회한 이름을 저장한 뒤 반환하는 메서드이다.
+    ///
+    /// `load`가 `None`을 반환한 경우 저장하지 않는다.
+    pub fn get_or_load(
+        &self,
+        user_id: &str,
+        load: impl FnOnce() -> Option<String>,
+    ) -> Option<String> {
+        if self.capacity == 0 || self.ttl.is_zero() {
+            return load();
+        }
+        let now = Instant::now();
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(user_id) {
+            if now.duration_since(entry.loaded_at) < self.ttl {
+                entry.used_at = now;
+                return Some(entry.user_name.clone());
+            }
+        }
+        let user_name = load()?;
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity && !entries.contains_key(user_id) {
+            entries.retain(|_, entry| now.duration_since(entry.loaded_at) < self.ttl);
+            if entries.len() >= self.capacity {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.used_at)
+                    .map(|(user_id, _)| user_id.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(
+            user_id.to_string(),
+            Entry {
+                user_name: user_name.clone(),
+                loaded_at: now,
+                used_at: now,
+            },
+        );
+        Some(user_name)
+    }
+    /// 사용자의 이름이 바뀌었거나 사용자가 탈퇴했을 때 저장된 이름을 지우는 메서드이다.
+    pub fn invalidate(&self, user_id: &str) {
+        self.entries.lock().unwrap().remove(user_id);
+    }
+}
@@ -0,0 +1,121 @@
+//! # 버전이 붙은 API(`/api/v1`)를 정의하는 모듈
+//!
+//! `v1`은 목록 API의 응답을 `{ "items": [...], "total": 42, "page": 1, "per_page": 20, "next": 2 }` 형태로 감싸서
+//! 클라이언트가 전체 항목 수를 보고 페이지 이동 버튼을 그릴 수 있도록 하는 핸들러들로 이루어져 있다.
+//!
+//! 기존 `/api` 아래의 목록 API는 응답 형태가 바뀌지 않도록 그대로 두었으며, 요청 옵션은 같다.
+//! 전체 항목 수는 목록과 같은 조건으로 `count(*)`를 수행하여 계산한다.
+
+use crate::auth::Identity;
+use crate::block::BlockList;
+use crate::bookmark::Bookmark;
+use crate::cache::Cache;
+use crate::error::ApiError;
+use crate::fields::FieldSet;
+use crate::follow::Follow;
+use crate::pagination::{Page, Pagination};
+use crate::post::{HomeFeedRequest, Post};
+use crate::ranking::Ranker;
+use crate::repository::UserRepository;
+use crate::response;
+use crate::shadow_ban::ShadowBans;
+use crate::summary::{self, BodyRequest};
+use crate::tag::Tag;
+use actix_web::{get, web, HttpResponse};
+
+/// `/api/v1` 아래의 API들을 등록하는 메서드이다.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1")
+            .service(get_posts_api)
+            .service(get_feed_api)
+            .service(get_bookmarks_api)
+            .service(get_tags_api),
+    );
+}
+
+#[get("/posts")]
+pub async fn get_posts_api(
+    request: web::Query<HomeFeedRequest>,
+    page: web::Query<Pagination>,
+    ranker: web::Data<dyn Ranker>,
+    cache: web::Data<Cache>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let fields = request
+        .fields
+        .as_deref()
+        .map(FieldSet::parse)
+        .transpose()
+        .map_err(|message| ApiError::InvalidFields([("fields", message)].into()))?;
+    let (posts, total) = match request.tag.as_deref().map(Tag::normalize) {
+        Some(Some(tag)) => (
+            cache.feed(None, Some(&tag), &page, || Tag::get_posts(&tag, &page)),
+            Post::count(Some(&tag)),
+        ),
+        Some(None) => (Vec::new(), 0),
+        None => (
+            cache.feed(request.viewer_id.as_deref(), None, &page, || {
+                ranker.rank(request.viewer_id.as_deref(), &page)
+            }),
+            Post::count(None),
+        ),
+    };
+    let mut posts = summary::apply(posts, request.body);
+    BlockList::default()
+        .extend(shadow_bans.hidden_from(request.viewer_id.as_deref()))
+        .retain(&mut posts, |post| &post.user_id);
+    let mut builder = HttpResponse::Ok();
+    builder.insert_header(("Content-Type", response::JSON_CONTENT_TYPE));
+    Ok(match fields {
+        Some(fields) => builder.json(Page::new(
+            posts.iter().map(|post| fields.pick(post)).collect(),
+            total,
+            &page,
+        )),
+        None => builder.json(Page::new(posts, total, &page)),
+    })
+}
+
+#[get("/feed")]
+pub async fn get_feed_api(
+    identity: Identity,
+    page: web::Query<Pagination>,
+    body: web::Query<BodyRequest>,
+    users: web::Data<dyn UserRepository>,
+    shadow_bans: web::Data<ShadowBans>,
+) -> HttpResponse {
+    let mut posts = Follow::get_feed(&identity.user_id, &page);
+    BlockList::load(users.get_ref(), Some(&identity.user_id))
+        .extend(shadow_bans.hidden_from(Some(&identity.user_id)))
+        .retain(&mut posts, |post| &post.user_id);
+    let posts = summary::apply(posts, body.body);
+    response::json(Page::new(
+        posts,
+        Follow::count_feed(&identity.user_id),
+        &page,
+    ))
+}
+
+#[get("/users/{user_id}/bookmarks")]
+pub async fn get_bookmarks_api(
+    identity: Identity,
+    user_id: web::Path<String>,
+    page: web::Query<Pagination>,
+    body: web::Query<BodyRequest>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if identity.user_id != *user_id {
+        return Err(ApiError::Forbidden);
+    }
+    let posts = summary::apply(Bookmark::get_posts(&user_id, &page), body.body);
+    Ok(response::json(Page::new(
+        posts,
+        Bookmark::count_posts(&user_id),
+        &page,
+    )))
+}
+
+#[get("/tags")]
+pub async fn get_tags_api(page: web::Query<Pagination>) -> HttpResponse {
+    response::json(Page::new(Tag::get_tags(&page), Tag::count_tags(), &page))
+}
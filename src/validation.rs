@@ -0,0 +1,228 @@
+//! # 입력값 검증 관련 동작을 정의하는 모듈
+//!
+//! `validation`은 코드뮤니티의 각 모듈에서 사용자가 보낸 입력값을 DB에 저장하기 전에
+//! 공통으로 확인하는 메서드들로 이루어져 있다.
+//!
+//! 쓰기 요청의 입력값은 `FieldErrors`에 항목별로 모은 뒤 한 번에 `422 Unprocessable Entity`로 응답하므로,
+//! 클라이언트는 잘못된 항목을 모두 한 번에 알 수 있다.
+
+use crate::email;
+use crate::error::ApiError;
+use crate::language;
+use std::collections::BTreeMap;
+
+/// 포스트 제목의 최대 글자 수이다.
+pub const MAX_TITLE_CHARS: usize = 120;
+/// 포스트 내용의 최대 글자 수이다.
+pub const MAX_POST_CHARS: usize = 100_000;
+/// 댓글 내용의 최대 글자 수이다.
+pub const MAX_COMMENT_CHARS: usize = 10_000;
+/// 메시지 내용의 최대 글자 수이다.
+pub const MAX_MESSAGE_CHARS: usize = 5_000;
+/// 공지 내용의 최대 글자 수이다.
+pub const MAX_ANNOUNCEMENT_CHARS: usize = 1_000;
+/// 사용자 고유 ID의 최대 글자 수이다.
+pub const MAX_USER_ID_CHARS: usize = 128;
+/// 사용자 이름의 최대 글자 수이다.
+pub const MAX_USER_NAME_CHARS: usize = 64;
+/// 이메일 주소의 최대 글자 수이다.
+pub const MAX_EMAIL_CHARS: usize = 254;
+/// 비밀번호의 최소 글자 수이다.
+pub const MIN_PASSWORD_CHARS: usize = 8;
+/// 비밀번호의 최대 글자 수이다.
+pub const MAX_PASSWORD_CHARS: usize = 128;
+
+/// 눈에 보이지 않지만 `char::is_whitespace()`로는 공백으로 취급되지 않는 문자들이다.
+const INVISIBLE_CHARS: [char; 5] = ['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
+/// 문자열이 비어있거나 공백 및 폭이 없는 문자로만 이루어져 있는지 확인하는 메서드이다.
+///
+/// 포스트나 댓글처럼 내용이 있어야 하는 항목을 저장하기 전에 호출한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::validation::is_blank;
+/// assert!(is_blank(""));
+/// assert!(is_blank(" \n\t"));
+/// assert!(is_blank("\u{200B}\u{FEFF} "));
+/// assert!(!is_blank(" fn main() {} "));
+/// ```
+pub fn is_blank(text: &str) -> bool {
+    text.chars()
+        .all(|c| c.is_whitespace() || INVISIBLE_CHARS.contains(&c))
+}
+/// 문자열이 `YYYY-MM-DD HH:MM:SS` 형식의 날짜 및 시각인지 확인하는 메서드이다.
+///
+/// DB의 `DATETIME` 열에 저장할 값을 사용자에게 받을 때 호출한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::validation::is_datetime;
+/// assert!(is_datetime("2022-10-09 21:30:00"));
+/// assert!(!is_datetime("2022-13-09 21:30:00"));
+/// assert!(!is_datetime("2022-10-09T21:30:00Z"));
+/// ```
+pub fn is_datetime(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if bytes.len() != 19 {
+        return false;
+    }
+    let separators = [(4, b'-'), (7, b'-'), (10, b' '), (13, b':'), (16, b':')];
+    if separators.iter().any(|(index, c)| bytes[*index] != *c) {
+        return false;
+    }
+    let number = |start: usize, end: usize| -> Option<u32> {
+        let part = &text[start..end];
+        if part.bytes().all(|c| c.is_ascii_digit()) {
+            part.parse().ok()
+        } else {
+            None
+        }
+    };
+    matches!(
+        (
+            number(0, 4),
+            number(5, 7),
+            number(8, 10),
+            number(11, 13),
+            number(14, 16),
+            number(17, 19),
+        ),
+        (
+            Some(_),
+            Some(1..=12),
+            Some(1..=31),
+            Some(0..=23),
+            Some(0..=59),
+            Some(0..=59)
+        )
+    )
+}
+/// 문자열이 사용자 고유 ID 형식인지 확인하는 메서드이다.
+///
+/// 인증 서비스가 발급한 ID를 그대로 사용하므로 영문자, 숫자와 `-`, `_`, `.`, `|`, `:`, `@`만 허용하며,
+/// 비어있거나 `MAX_USER_ID_CHARS`자를 넘을 수 없다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::validation::is_user_id;
+/// assert!(is_user_id("auth0|5f7c8ec7c33c6c004bbafe82"));
+/// assert!(is_user_id("unique_id_for_user"));
+/// assert!(!is_user_id(""));
+/// assert!(!is_user_id("' or '1'='1"));
+/// ```
+pub fn is_user_id(user_id: &str) -> bool {
+    !user_id.is_empty()
+        && user_id.len() <= MAX_USER_ID_CHARS
+        && user_id
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || b"-_.|:@".contains(&c))
+}
+
+/// 입력값이 올바르지 않은 항목과 그 이유를 모으는 객체이다.
+///
+/// 항목마다 처음 발견된 이유만 남긴다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::validation::FieldErrors;
+/// let mut errors = FieldErrors::default();
+/// errors.check("title", false, "제목은 비어있을 수 없습니다.");
+/// errors.check("title", false, "제목이 너무 깁니다.");
+/// errors.check("data", true, "내용은 비어있을 수 없습니다.");
+/// let error = errors.into_result().unwrap_err();
+/// assert_eq!(error.code(), "validation_failed");
+/// assert_eq!(
+///     error.details().unwrap(),
+///     serde_json::json!({ "title": "제목은 비어있을 수 없습니다." })
+/// );
+/// ```
+#[derive(Default, Debug)]
+pub struct FieldErrors(BTreeMap<&'static str, String>);
+
+impl FieldErrors {
+    /// `valid`가 `false`인 경우 `field` 항목이 올바르지 않은 이유로 `message`를 기록하는 메서드이다.
+    pub fn check(&mut self, field: &'static str, valid: bool, message: impl Into<String>) {
+        if !valid {
+            self.0.entry(field).or_insert_with(|| message.into());
+        }
+    }
+    /// 올바르지 않은 항목이 없는지 확인하는 메서드이다.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// 올바르지 않은 항목이 있는 경우 항목별 이유를 담은 `ApiError::InvalidFields`를 반환하는 메서드이다.
+    pub fn into_result(self) -> Result<(), ApiError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::InvalidFields(self.0))
+        }
+    }
+    /// 제목 항목을 확인하는 메서드이다. 비어있거나 `MAX_TITLE_CHARS`자를 넘을 수 없다.
+    pub fn check_title(&mut self, title: &str) {
+        self.check("title", !is_blank(title), "제목은 비어있을 수 없습니다.");
+        self.check(
+            "title",
+            title.chars().count() <= MAX_TITLE_CHARS,
+            format!("제목은 {}자를 넘을 수 없습니다.", MAX_TITLE_CHARS),
+        );
+    }
+    /// 내용 항목을 확인하는 메서드이다. 비어있거나 `max_chars`자를 넘을 수 없다.
+    pub fn check_data(&mut self, data: &str, max_chars: usize) {
+        self.check("data", !is_blank(data), "내용은 비어있을 수 없습니다.");
+        self.check(
+            "data",
+            data.chars().count() <= max_chars,
+            format!("내용은 {}자를 넘을 수 없습니다.", max_chars),
+        );
+    }
+    /// 프로그래밍 언어 항목을 확인하는 메서드이다.
+    pub fn check_language(&mut self, name: &str) {
+        self.check(
+            "language",
+            language::is_supported(name),
+            format!(
+                "언어는 {} 중 하나여야 합니다.",
+                language::supported().join(", ")
+            ),
+        );
+    }
+    /// 사용자 이름 항목을 확인하는 메서드이다.
+    pub fn check_user_name(&mut self, user_name: &str) {
+        self.check(
+            "user_name",
+            !is_blank(user_name),
+            "이름은 비어있을 수 없습니다.",
+        );
+        self.check(
+            "user_name",
+            user_name.chars().count() <= MAX_USER_NAME_CHARS,
+            format!("이름은 {}자를 넘을 수 없습니다.", MAX_USER_NAME_CHARS),
+        );
+    }
+    /// 이메일 주소 항목을 확인하는 메서드이다. `MAX_EMAIL_CHARS`자를 넘을 수 없다.
+    pub fn check_email(&mut self, email: &str) {
+        self.check(
+            "email",
+            email::is_address(email) && email.chars().count() <= MAX_EMAIL_CHARS,
+            "이메일 주소 형식이 올바르지 않습니다.",
+        );
+    }
+    /// 비밀번호 항목을 확인하는 메서드이다. `MIN_PASSWORD_CHARS`자 이상 `MAX_PASSWORD_CHARS`자 이하여야 한다.
+    pub fn check_password(&mut self, password: &str) {
+        let chars = password.chars().count();
+        self.check(
+            "password",
+            (MIN_PASSWORD_CHARS..=MAX_PASSWORD_CHARS).contains(&chars),
+            format!(
+                "비밀번호는 {}자 이상 {}자 이하여야 합니다.",
+                MIN_PASSWORD_CHARS, MAX_PASSWORD_CHARS
+            ),
+        );
+    }
+    /// 사용자 고유 ID 항목을 확인하는 메서드이다.
+    pub fn check_user_id(&mut self, user_id: &str) {
+        self.check(
+            "user_id",
+            is_user_id(user_id),
+            "사용자 ID 형식이 올바르지 않습니다.",
+        );
+    }
+}
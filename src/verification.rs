@@ -0,0 +1,184 @@
+//! # 이메일 주소 인증 관련 동작을 정의하는 모듈
+//!
+//! `verification`은 이메일과 비밀번호로 가입한 사용자가 가입할 때 적은 이메일 주소를 실제로 사용하는지 확인하기 위한
+//! 요소 및 메서드들로 이루어져 있다.
+//!
+//! 가입하면 `AUTH_SECRET`으로 서명된 인증 토큰을 담은 메일이 보내지며, 토큰으로 `POST /api/auth/verify`를 요청하면
+//! `user` 테이블의 `verified`가 `true`가 된다. 인증하지 않은 사용자는 조회는 할 수 있지만 포스트, 댓글, 공감, 메시지를 작성할 수 없다.
+//! 토큰은 DB에 저장되지 않고 서명과 만료 시각(`TOKEN_TTL_SECONDS`)으로만 확인한다.
+//! `AUTH_SECRET`이나 `SMTP_URL`이 지정되지 않아 메일을 보낼 수 없는 경우 가입한 사용자는 바로 인증된 것으로 처리된다.
+
+use crate::auth::Identity;
+use crate::db;
+use crate::email;
+use crate::error::ApiError;
+use crate::outbox::Outbox;
+use crate::response;
+use crate::webhook::signature;
+use actix_web::web::Json;
+use actix_web::{post, web, HttpResponse};
+use chrono::Utc;
+use mysql::prelude::*;
+use mysql::*;
+use serde::Deserialize;
+use std::env;
+
+/// 인증 토큰을 사용할 수 있는 시간(초)이다.
+pub const TOKEN_TTL_SECONDS: i64 = 24 * 60 * 60;
+
+/// JSON 을 통해 이메일 주소 인증 요청을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct VerifyRequest {
+    /// 인증할 사용자의 고유 ID이다.
+    pub user_id: String,
+    /// 메일로 받은 인증 토큰이다.
+    pub token: String,
+}
+
+/// 토큰에 서명할 때 사용하는 비밀 값을 반환하는 메서드이다. `AUTH_SECRET`이 지정되지 않은 경우 `None`을 반환한다.
+fn secret() -> Option<String> {
+    env::var("AUTH_SECRET")
+        .ok()
+        .filter(|secret| !secret.is_empty())
+}
+
+/// 이메일 주소 인증을 사용할 수 있는지 확인하는 메서드이다.
+///
+/// 토큰에 서명할 `AUTH_SECRET`과 메일을 보낼 `SMTP_URL`이 모두 지정된 경우 `true`를 반환한다.
+pub fn is_enabled() -> bool {
+    secret().is_some() && env::var("SMTP_URL").is_ok_and(|url| !url.is_empty())
+}
+
+/// 서명할 내용이다. 다른 용도의 서명과 구분되도록 용도를 앞에 붙인다.
+fn message(user_id: &str, expires: i64) -> String {
+    format!("verify-email\n{}\n{}", user_id, expires)
+}
+
+/// `user_id` 사용자의 인증 토큰(`{만료 시각}.{서명}`)을 만드는 메서드이다.
+///
+/// `expires`는 토큰이 만료되는 시각(UNIX 시간)이다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::verification::{check_token, sign_token};
+/// let token = sign_token("secret", "password|abc", 2000);
+/// assert!(token.starts_with("2000."));
+/// assert!(check_token("secret", "password|abc", &token, 1000));
+/// assert!(!check_token("secret", "password|abc", &token, 3000));
+/// assert!(!check_token("secret", "password|other", &token, 1000));
+/// assert!(!check_token("other", "password|abc", &token, 1000));
+/// assert!(!check_token("secret", "password|abc", "9999.00", 1000));
+/// ```
+pub fn sign_token(secret: &str, user_id: &str, expires: i64) -> String {
+    format!(
+        "{}.{}",
+        expires,
+        signature(secret, &message(user_id, expires))
+    )
+}
+
+/// 인증 토큰이 `user_id` 사용자의 것이며 `now`(UNIX 시간)에 만료되지 않았는지 확인하는 메서드이다.
+///
+/// 서명은 걸리는 시간으로 값을 추측할 수 없도록 모든 글자를 비교한다.
+pub fn check_token(secret: &str, user_id: &str, token: &str, now: i64) -> bool {
+    let Some((expires, given)) = token.split_once('.') else {
+        return false;
+    };
+    let Ok(expires) = expires.parse::<i64>() else {
+        return false;
+    };
+    let expected = signature(secret, &message(user_id, expires));
+    expires > now
+        && expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// 지금부터 `TOKEN_TTL_SECONDS`초동안 사용할 수 있는 인증 토큰을 만드는 메서드이다.
+///
+/// `AUTH_SECRET`이 지정되지 않은 경우 `None`을 반환한다.
+pub fn new_token(user_id: &str) -> Option<String> {
+    Some(sign_token(
+        &secret()?,
+        user_id,
+        Utc::now().timestamp() + TOKEN_TTL_SECONDS,
+    ))
+}
+
+/// 이메일 주소 인증을 처리하는 객체이다.
+pub struct Verification;
+
+impl Verification {
+    /// 사용자를 인증된 상태로 바꾸는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn mark_verified(user_id: &str) -> Result<()> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "update user set verified = true where user_id = :user_id",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+    }
+    /// 인증하지 않은 사용자의 이메일 주소를 반환하는 메서드이다.
+    ///
+    /// 이미 인증했거나 이메일 주소가 없는 사용자인 경우 `None`을 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn pending_address(user_id: &str) -> Result<Option<String>> {
+        let mut conn = db::get_conn();
+        conn.exec_first(
+            r"select email from user
+            where user_id = :user_id and not verified and email is not null",
+            params! {
+                "user_id" => user_id,
+            },
+        )
+    }
+}
+
+#[post("/api/auth/verify")]
+pub async fn verify_email_api(
+    request: Json<VerifyRequest>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    let now = Utc::now().timestamp();
+    if !secret().is_some_and(|secret| check_token(&secret, &request.user_id, &request.token, now)) {
+        return Err(ApiError::BadRequest(
+            "인증 토큰이 올바르지 않거나 만료되었습니다.".to_string(),
+        ));
+    }
+    Verification::mark_verified(&request.user_id)?;
+    Ok(response::no_content())
+}
+
+#[post("/api/auth/verify/resend")]
+pub async fn resend_verification_api(
+    identity: Identity,
+    outbox: web::Data<Outbox>,
+) -> std::result::Result<HttpResponse, ApiError> {
+    if !is_enabled() {
+        return Err(ApiError::Unavailable(
+            "이메일 주소 인증을 사용할 수 없습니다.".to_string(),
+        ));
+    }
+    match Verification::pending_address(&identity.user_id)? {
+        Some(address) => {
+            email::send_verification(outbox, identity.user_id, address);
+            Ok(HttpResponse::Accepted().finish())
+        }
+        None => Err(ApiError::Conflict("이미 인증된 계정입니다.".to_string())),
+    }
+}
@@ -0,0 +1,391 @@
+//! # 웹훅 관련 동작을 정의하는 모듈
+//!
+//! `webhook`은 포스트 작성, 포스트 신고, 사용자 가입과 같은 일이 일어났을 때 관리자가 등록한 외부 주소로
+//! 서명된 JSON을 보내기 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 이벤트가 발생하면 해당 이벤트를 구독한 웹훅마다 전송 기록(`webhook_delivery`)이 만들어지고,
+//! `scheduler`에서 주기적으로 실행되는 `deliver_pending()`이 이를 전송한다.
+//! 전송에 실패하면 `Outbox::backoff()`에 따라 간격을 늘려가며 `MAX_ATTEMPTS`번까지 다시 전송한다.
+//!
+//! 요청 본문은 웹훅을 등록할 때 발급된 비밀 값으로 HMAC-SHA256 서명되며, 서명은
+//! `X-Codemmunity-Signature: sha256=<16진수>` 헤더에 담긴다.
+
+use crate::db;
+use crate::outbox::Outbox;
+use crate::pagination::Pagination;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use mysql::prelude::*;
+use mysql::*;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// 전송을 시도하는 최대 횟수이다. 이 횟수를 넘으면 전송 기록이 `failed` 상태가 된다.
+const MAX_ATTEMPTS: u32 = 8;
+/// 한 번에 전송할 최대 전송 기록 수이다.
+const DELIVERY_BATCH_SIZE: u32 = 50;
+/// 웹훅 주소의 응답을 기다리는 시간이다.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// 전송 기록에 저장할 실패 사유의 최대 길이이다.
+const MAX_ERROR_LENGTH: usize = 1024;
+
+/// 웹훅으로 알릴 수 있는 이벤트이다.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// 새 포스트가 작성된 경우
+    PostCreated,
+    /// 포스트가 신고된 경우
+    PostReported,
+    /// 새 사용자가 가입한 경우
+    UserRegistered,
+}
+
+impl WebhookEvent {
+    /// DB와 요청 본문에 사용되는 이벤트 이름을 반환하는 메서드이다.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PostCreated => "post_created",
+            Self::PostReported => "post_reported",
+            Self::UserRegistered => "user_registered",
+        }
+    }
+}
+
+/// 관리자가 등록한 웹훅이다.
+#[derive(Serialize)]
+pub struct Webhook {
+    /// 웹훅의 고유 ID이다.
+    pub webhook_id: u64,
+    /// 이벤트를 전송할 주소이다.
+    pub url: String,
+    /// 구독한 이벤트 이름들이다.
+    pub events: Vec<String>,
+    /// 웹훅이 등록된 날짜이다.
+    pub create_at: DateTime<Utc>,
+}
+
+/// 웹훅을 등록한 결과이다. 비밀 값은 등록할 때 한 번만 반환된다.
+#[derive(Serialize)]
+pub struct RegisteredWebhook {
+    /// 등록된 웹훅이다.
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    /// 요청 본문의 서명에 사용되는 비밀 값이다.
+    pub secret: String,
+}
+
+/// JSON 을 통해 등록할 웹훅을 받을 때 필요한 구조체이다.
+#[derive(Deserialize)]
+pub struct WebhookRequest {
+    /// 이벤트를 전송할 주소이다. `http://` 또는 `https://`로 시작해야 한다.
+    pub url: String,
+    /// 구독할 이벤트들이다.
+    pub events: Vec<WebhookEvent>,
+}
+
+/// 웹훅 전송 기록 하나의 정보이다.
+#[derive(Serialize)]
+pub struct WebhookDelivery {
+    /// 전송 기록의 고유 ID이다. 요청의 `X-Codemmunity-Delivery` 헤더 값과 같다.
+    pub delivery_id: u64,
+    /// 이벤트 이름이다.
+    pub event: String,
+    /// 전송 상태(`pending`, `delivered`, `failed`)이다.
+    pub status: String,
+    /// 지금까지 전송을 시도한 횟수이다.
+    pub attempts: u32,
+    /// 마지막 전송에서 받은 HTTP 상태 코드이다.
+    pub response_status: Option<u16>,
+    /// 마지막으로 전송에 실패한 사유이다.
+    pub last_error: Option<String>,
+    /// 이벤트가 발생한 날짜이다.
+    pub create_at: DateTime<Utc>,
+    /// 전송에 성공한 날짜이다.
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// DB에서 읽은 웹훅 전송 기록이다.
+///
+/// 전송 ID, 이벤트, 상태, 시도 횟수, 응답 상태 코드, 마지막 오류, 생성 시각, 전송 완료 시각 순이다.
+type DeliveryRow = (
+    u64,
+    String,
+    String,
+    u32,
+    Option<u16>,
+    Option<String>,
+    String,
+    Option<String>,
+);
+
+impl Webhook {
+    /// 웹훅을 등록하고, 등록된 웹훅과 서명에 사용할 비밀 값을 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn register(request: &WebhookRequest) -> Result<RegisteredWebhook> {
+        let secret = Uuid::new_v4().simple().to_string();
+        let events: Vec<String> = request
+            .events
+            .iter()
+            .map(|event| event.as_str().to_string())
+            .collect();
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "insert into webhook(url, secret, events) values(:url, :secret, :events)",
+            params! {
+                "url" => &request.url,
+                "secret" => &secret,
+                "events" => events.join(","),
+            },
+        )?;
+        Ok(RegisteredWebhook {
+            webhook: Webhook {
+                webhook_id: conn.last_insert_id(),
+                url: request.url.clone(),
+                events,
+                create_at: Utc::now(),
+            },
+            secret,
+        })
+    }
+    /// 등록된 모든 웹훅을 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn list() -> Vec<Self> {
+        let mut conn = db::get_conn();
+        conn.query_map(
+            r"select webhook_id, url, events, date_format(create_at, '%Y-%m-%d %H:%i:%s')
+            from webhook order by webhook_id",
+            |(webhook_id, url, events, create_at): (u64, String, String, String)| Self {
+                webhook_id,
+                url,
+                events: events.split(',').map(str::to_string).collect(),
+                create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+            },
+        )
+        .unwrap()
+    }
+    /// 웹훅과 해당 웹훅의 전송 기록을 제거하는 메서드이다.
+    ///
+    /// 존재하지 않는 웹훅인 경우 `false`를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn remove(webhook_id: u64) -> Result<bool> {
+        let mut conn = db::get_conn();
+        let mut tx = conn.start_transaction(TxOpts::default())?;
+        tx.exec_drop(
+            "delete from webhook_delivery where webhook_id = :webhook_id",
+            params! {
+                "webhook_id" => webhook_id,
+            },
+        )?;
+        tx.exec_drop(
+            "delete from webhook where webhook_id = :webhook_id",
+            params! {
+                "webhook_id" => webhook_id,
+            },
+        )?;
+        let removed = tx.affected_rows() > 0;
+        tx.commit()?;
+        Ok(removed)
+    }
+    /// 웹훅의 전송 기록을 최신순으로 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn deliveries(webhook_id: u64, page: &Pagination) -> Vec<WebhookDelivery> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select delivery_id, event, status, attempts, response_status, last_error,
+            date_format(create_at, '%Y-%m-%d %H:%i:%s'), date_format(delivered_at, '%Y-%m-%d %H:%i:%s')
+            from webhook_delivery where webhook_id = :webhook_id
+            order by delivery_id desc limit :limit offset :offset",
+            params! {
+                "webhook_id" => webhook_id,
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(
+                delivery_id,
+                event,
+                status,
+                attempts,
+                response_status,
+                last_error,
+                create_at,
+                delivered_at,
+            ): DeliveryRow| {
+                WebhookDelivery {
+                    delivery_id,
+                    event,
+                    status,
+                    attempts,
+                    response_status,
+                    last_error,
+                    create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+                    delivered_at: delivered_at.as_deref().and_then(db::parse_datetime),
+                }
+            },
+        )
+        .unwrap()
+    }
+}
+
+/// 요청 본문의 HMAC-SHA256 서명을 16진수 문자열로 반환하는 메서드이다.
+///
+/// 웹훅을 받는 쪽에서는 같은 방법으로 계산한 값과 `X-Codemmunity-Signature` 헤더의 값을 비교하면 된다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::webhook::signature;
+/// assert_eq!(
+///     signature("key", "The quick brown fox jumps over the lazy dog"),
+///     "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+/// );
+/// ```
+pub fn signature(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC은 모든 길이의 키를 사용할 수 있음");
+    mac.update(body.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// `event`를 구독한 모든 웹훅에 보낼 전송 기록을 만드는 메서드이다.
+///
+/// 요청 본문은 `{"event": ..., "occurred_at": ..., "data": ...}` 형태이며, 다시 전송할 때도 같은 본문을 사용하도록
+/// 전송 기록에 저장된다. 실제 전송은 `deliver_pending()`에서 이루어지므로 요청 처리가 느려지지 않으며,
+/// 전송 기록을 만들지 못한 경우에도 요청은 실패하지 않고 로그만 남긴다.
+/// # 예제
+/// ```ignore
+/// webhook::emit(WebhookEvent::PostCreated, &post);
+/// ```
+pub fn emit<T: Serialize>(event: WebhookEvent, data: &T) {
+    let payload = serde_json::json!({
+        "event": event.as_str(),
+        "occurred_at": Utc::now(),
+        "data": data,
+    })
+    .to_string();
+    let result = db::get_conn().exec_drop(
+        r"insert into webhook_delivery(webhook_id, event, payload)
+        select webhook_id, :event, :payload from webhook where find_in_set(:event, events)",
+        params! {
+            "event" => event.as_str(),
+            "payload" => payload,
+        },
+    );
+    if let Err(error) = result {
+        tracing::error!(%error, event = event.as_str(), "웹훅 전송 기록을 만들지 못했습니다.");
+    }
+}
+
+/// 전송할 시각이 된 전송 기록들을 웹훅 주소로 전송하는 메서드이다.
+///
+/// `scheduler`에서 주기적으로 호출한다. 2xx 응답을 받으면 `delivered` 상태가 되고,
+/// 그 외의 응답이나 접속 실패는 다음 전송 시각을 늦춘 뒤 다시 시도하며 `MAX_ATTEMPTS`번 실패하면 `failed` 상태가 된다.
+///
+/// # Panics
+///
+/// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+/// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+/// - DB에 접속이 제한시간을 초과한 경우
+/// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+pub fn deliver_pending() {
+    let mut conn = db::get_conn();
+    let pending: Vec<(u64, String, String, String, String, u32)> = conn
+        .exec(
+            r"select delivery_id, url, secret, event, payload, attempts
+            from webhook_delivery join webhook on webhook.webhook_id = webhook_delivery.webhook_id
+            where status = 'pending' and next_attempt_at <= now()
+            order by delivery_id limit :limit",
+            params! {
+                "limit" => DELIVERY_BATCH_SIZE,
+            },
+        )
+        .unwrap();
+    if pending.is_empty() {
+        return;
+    }
+    let agent: ureq::Agent = ureq::Agent::config_builder()
+        .timeout_global(Some(DELIVERY_TIMEOUT))
+        .http_status_as_error(false)
+        .build()
+        .into();
+    for (delivery_id, url, secret, event, payload, attempts) in pending {
+        let attempts = attempts + 1;
+        let result = agent
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("X-Codemmunity-Event", &event)
+            .header("X-Codemmunity-Delivery", delivery_id.to_string())
+            .header(
+                "X-Codemmunity-Signature",
+                format!("sha256={}", signature(&secret, &payload)),
+            )
+            .send(&payload);
+        let (response_status, error) = match result {
+            Ok(response) if response.status().is_success() => {
+                (Some(response.status().as_u16()), None)
+            }
+            Ok(response) => (
+                Some(response.status().as_u16()),
+                Some(format!("HTTP {}", response.status())),
+            ),
+            Err(error) => (None, Some(error.to_string())),
+        };
+        let status = match &error {
+            None => "delivered",
+            Some(_) if attempts >= MAX_ATTEMPTS => "failed",
+            Some(_) => "pending",
+        };
+        if let Some(error) = &error {
+            tracing::warn!(
+                delivery_id,
+                attempts,
+                error = error.as_str(),
+                "웹훅을 전송하지 못했습니다."
+            );
+        }
+        conn.exec_drop(
+            r"update webhook_delivery
+            set status = :status, attempts = :attempts, response_status = :response_status,
+            last_error = :last_error, next_attempt_at = now() + interval :backoff second,
+            delivered_at = if(:status = 'delivered', now(), null)
+            where delivery_id = :delivery_id",
+            params! {
+                "status" => status,
+                "attempts" => attempts,
+                "response_status" => response_status,
+                "last_error" => error.map(|error| error.chars().take(MAX_ERROR_LENGTH).collect::<String>()),
+                "backoff" => Outbox::backoff(attempts).as_secs(),
+                "delivery_id" => delivery_id,
+            },
+        )
+        .unwrap();
+    }
+}
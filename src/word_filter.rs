@@ -0,0 +1,378 @@
+//! # 금칙어 필터 관련 동작을 정의하는 모듈
+//!
+//! `word_filter`는 포스트와 댓글을 작성하거나 수정할 때 관리자가 등록한 금칙어가 포함되어 있는지 확인하고,
+//! 설정에 따라 요청을 거부하거나(`reject`), 금칙어를 `*`로 가리거나(`mask`), 그대로 저장한 뒤
+//! 관리자가 검토할 수 있도록 기록하기(`flag`) 위한 요소 및 메서드들로 이루어져 있다.
+//!
+//! 금칙어는 `blocked_word` 테이블에 저장되며 서버 시작 시 메모리로 읽어둔다.
+//! 대소문자를 구분하지 않고 글자와 숫자로 이루어진 단어 단위로 비교하므로,
+//! 금칙어가 다른 단어의 일부로 들어있는 경우에는 걸러지지 않는다.
+
+use crate::db;
+use crate::error::ApiError;
+use crate::pagination::Pagination;
+use crate::validation::FieldErrors;
+use chrono::{DateTime, Utc};
+use mysql::prelude::*;
+use mysql::*;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::env;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+/// 금칙어의 최대 글자 수이다.
+pub const MAX_WORD_CHARS: usize = 64;
+
+/// 금칙어가 포함된 콘텐츠를 처리하는 방식이다.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterMode {
+    /// 요청을 `422`로 거부한다.
+    #[default]
+    Reject,
+    /// 금칙어를 같은 길이의 `*`로 바꾸어 저장한다.
+    Mask,
+    /// 그대로 저장한 뒤 관리자가 검토할 수 있도록 기록한다.
+    Flag,
+}
+
+impl FromStr for FilterMode {
+    type Err = ();
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        match value {
+            "reject" => Ok(Self::Reject),
+            "mask" => Ok(Self::Mask),
+            "flag" => Ok(Self::Flag),
+            _ => Err(()),
+        }
+    }
+}
+
+/// 금칙어가 발견된 콘텐츠의 종류이다.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ContentKind {
+    /// 포스트
+    Post,
+    /// 댓글
+    Comment,
+}
+
+impl ContentKind {
+    /// DB에 저장되는 콘텐츠 종류의 이름을 반환하는 메서드이다.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Post => "post",
+            Self::Comment => "comment",
+        }
+    }
+}
+
+/// 금칙어가 발견되어 관리자의 검토가 필요한 콘텐츠의 기록이다.
+#[derive(Serialize)]
+pub struct FlaggedContent {
+    /// 기록의 고유 ID이다.
+    pub flag_id: u64,
+    /// 콘텐츠의 종류(`post` 또는 `comment`)이다.
+    pub content_type: String,
+    /// 포스트 또는 댓글의 고유 ID이다.
+    pub content_id: u64,
+    /// 발견된 금칙어들이다.
+    pub words: Vec<String>,
+    /// 기록된 시각이다.
+    pub create_at: DateTime<Utc>,
+}
+
+/// 관리자 API로 현재 필터 설정을 반환할 때 사용하는 구조체이다.
+#[derive(Serialize)]
+pub struct FilterSettings {
+    /// 금칙어가 포함된 콘텐츠를 처리하는 방식이다.
+    pub mode: FilterMode,
+    /// 등록된 금칙어들이다.
+    pub words: Vec<String>,
+}
+
+/// 금칙어 목록과 처리 방식을 가진 필터이다.
+///
+/// 서버 시작 시 `from_env()`를 통해 한 번만 생성한 뒤 모든 워커가 공유하여 사용한다.
+pub struct WordFilter {
+    /// 금칙어가 포함된 콘텐츠를 처리하는 방식이다.
+    mode: FilterMode,
+    /// 소문자로 저장된 금칙어들이다.
+    words: RwLock<BTreeSet<String>>,
+}
+
+/// 문자열에서 글자와 숫자로 이루어진 단어들의 시작과 끝 위치(바이트)를 반환하는 메서드이다.
+fn tokens(text: &str) -> Vec<(usize, usize)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(begin)) => {
+                tokens.push((begin, index));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        tokens.push((begin, text.len()));
+    }
+    tokens
+}
+
+/// 금칙어를 저장되는 형태로 바꾸는 메서드이다.
+///
+/// 앞뒤 공백을 제거하고 소문자로 바꾸며, 비어있거나 너무 길거나 글자와 숫자 외의 문자가 포함된 경우 `None`을 반환한다.
+/// # 예제
+/// ```
+/// use code_mmunity_server::word_filter::normalize;
+/// assert_eq!(normalize(" Darn "), Some("darn".to_string()));
+/// assert_eq!(normalize("two words"), None);
+/// assert_eq!(normalize(""), None);
+/// ```
+pub fn normalize(word: &str) -> Option<String> {
+    let word = word.trim().to_lowercase();
+    if word.is_empty()
+        || word.chars().count() > MAX_WORD_CHARS
+        || !word.chars().all(char::is_alphanumeric)
+    {
+        return None;
+    }
+    Some(word)
+}
+
+impl WordFilter {
+    /// 처리 방식과 금칙어들로 필터를 생성하는 메서드이다.
+    ///
+    /// 저장되는 형태가 아닌 금칙어는 무시된다.
+    pub fn new(mode: FilterMode, words: &[&str]) -> Self {
+        Self {
+            mode,
+            words: RwLock::new(words.iter().filter_map(|word| normalize(word)).collect()),
+        }
+    }
+    /// 환경변수를 통해 금칙어가 없는 필터를 생성하는 메서드이다.
+    ///
+    /// `WORD_FILTER_MODE` 환경변수로 처리 방식(`reject`, `mask`, `flag`)을 변경할 수 있으며 기본값은 `reject`이다.
+    /// 금칙어는 `load()`로 DB에서 읽어온다.
+    pub fn from_env() -> Self {
+        let mode = env::var("WORD_FILTER_MODE")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_default();
+        Self::new(mode, &[])
+    }
+    /// 현재 처리 방식과 등록된 금칙어들을 반환하는 메서드이다.
+    pub fn settings(&self) -> FilterSettings {
+        FilterSettings {
+            mode: self.mode,
+            words: self.words.read().unwrap().iter().cloned().collect(),
+        }
+    }
+    /// 문자열에 포함된 금칙어들을 중복 없이 반환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::word_filter::{FilterMode, WordFilter};
+    /// let filter = WordFilter::new(FilterMode::Mask, &["darn"]);
+    /// assert_eq!(filter.find("Darn it, darn!"), vec!["darn"]);
+    /// assert!(filter.find("darnedest").is_empty());
+    /// ```
+    pub fn find(&self, text: &str) -> Vec<String> {
+        let words = self.words.read().unwrap();
+        if words.is_empty() {
+            return Vec::new();
+        }
+        let found: BTreeSet<String> = tokens(text)
+            .into_iter()
+            .map(|(start, end)| text[start..end].to_lowercase())
+            .filter(|token| words.contains(token))
+            .collect();
+        found.into_iter().collect()
+    }
+    /// 문자열에 포함된 금칙어들을 같은 글자 수의 `*`로 바꾼 문자열을 반환하는 메서드이다.
+    ///
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::word_filter::{FilterMode, WordFilter};
+    /// let filter = WordFilter::new(FilterMode::Mask, &["darn"]);
+    /// assert_eq!(filter.mask("Darn it, darnedest!"), "**** it, darnedest!");
+    /// ```
+    pub fn mask(&self, text: &str) -> String {
+        let words = self.words.read().unwrap();
+        let mut masked = String::with_capacity(text.len());
+        let mut last = 0;
+        for (start, end) in tokens(text) {
+            if words.contains(&text[start..end].to_lowercase()) {
+                masked.push_str(&text[last..start]);
+                masked.push_str(&"*".repeat(text[start..end].chars().count()));
+                last = end;
+            }
+        }
+        masked.push_str(&text[last..]);
+        masked
+    }
+    /// 작성하거나 수정할 항목들에 금칙어가 있는지 확인하고 처리 방식에 따라 처리하는 메서드이다.
+    ///
+    /// `reject`인 경우 금칙어가 포함된 항목별 이유를 담은 `ApiError::InvalidFields`를 반환하고,
+    /// `mask`인 경우 항목의 금칙어를 `*`로 바꾼다.
+    /// `flag`인 경우 항목을 바꾸지 않고 발견된 금칙어들을 반환하므로, 콘텐츠를 저장한 뒤 `flag()`로 기록해야 한다.
+    /// 그 외의 경우 빈 목록을 반환한다.
+    /// # 예제
+    /// ```
+    /// use code_mmunity_server::word_filter::{FilterMode, WordFilter};
+    /// let mut title = "Darn bug".to_string();
+    /// let mut data = "fn main() {}".to_string();
+    /// let filter = WordFilter::new(FilterMode::Reject, &["darn"]);
+    /// assert!(filter.screen(&mut [("title", &mut title), ("data", &mut data)]).is_err());
+    /// let filter = WordFilter::new(FilterMode::Mask, &["darn"]);
+    /// filter.screen(&mut [("title", &mut title), ("data", &mut data)]).unwrap();
+    /// assert_eq!(title, "**** bug");
+    /// ```
+    pub fn screen(
+        &self,
+        fields: &mut [(&'static str, &mut String)],
+    ) -> std::result::Result<Vec<String>, ApiError> {
+        let mut errors = FieldErrors::default();
+        let mut flagged = BTreeSet::new();
+        for (field, text) in fields.iter_mut() {
+            let field: &'static str = field;
+            let found = self.find(text);
+            if found.is_empty() {
+                continue;
+            }
+            match self.mode {
+                FilterMode::Reject => errors.check(
+                    field,
+                    false,
+                    format!(
+                        "허용되지 않는 단어가 포함되어 있습니다: {}",
+                        found.join(", ")
+                    ),
+                ),
+                FilterMode::Mask => **text = self.mask(text),
+                FilterMode::Flag => flagged.extend(found),
+            }
+        }
+        errors.into_result()?;
+        Ok(flagged.into_iter().collect())
+    }
+    /// DB에 저장된 금칙어들을 읽어오고 읽어온 금칙어 수를 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn load(&self) -> Result<usize> {
+        let mut conn = db::get_conn();
+        let words: Vec<String> = conn.query("select word from blocked_word")?;
+        let mut loaded = self.words.write().unwrap();
+        *loaded = words.into_iter().collect();
+        Ok(loaded.len())
+    }
+    /// 금칙어를 등록하는 메서드이다. 이미 등록된 금칙어인 경우 `false`를 반환한다.
+    ///
+    /// `word`는 `normalize()`로 저장되는 형태로 바꾼 값이어야 한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn add(&self, word: &str) -> Result<bool> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "insert ignore into blocked_word(word) values(:word)",
+            params! {
+                "word" => word,
+            },
+        )?;
+        self.words.write().unwrap().insert(word.to_string());
+        Ok(conn.affected_rows() > 0)
+    }
+    /// 금칙어를 제거하는 메서드이다. 등록되지 않은 금칙어인 경우 `false`를 반환한다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn remove(&self, word: &str) -> Result<bool> {
+        let mut conn = db::get_conn();
+        conn.exec_drop(
+            "delete from blocked_word where word = :word",
+            params! {
+                "word" => word,
+            },
+        )?;
+        self.words.write().unwrap().remove(word);
+        Ok(conn.affected_rows() > 0)
+    }
+    /// 금칙어가 발견된 콘텐츠를 관리자가 검토할 수 있도록 기록하는 메서드이다.
+    ///
+    /// `words`가 비어있는 경우 아무 작업도 하지 않는다. 기록에 실패해도 콘텐츠는 이미 저장되었으므로 로그만 남긴다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn flag(kind: ContentKind, content_id: u64, words: &[String]) {
+        if words.is_empty() {
+            return;
+        }
+        let mut conn = db::get_conn();
+        if let Err(error) = conn.exec_drop(
+            r"insert into flagged_content(content_type, content_id, words)
+            values(:content_type, :content_id, :words)",
+            params! {
+                "content_type" => kind.as_str(),
+                "content_id" => content_id,
+                "words" => words.join(","),
+            },
+        ) {
+            tracing::error!(%error, content_id, "금칙어가 발견된 콘텐츠를 기록하지 못했습니다.");
+        }
+    }
+    /// 검토가 필요한 콘텐츠 기록들을 최신순으로 반환하는 메서드이다.
+    ///
+    /// # Panics
+    ///
+    /// 해당 메서드는 아래와 같은 경우 패닉이 발생한다.
+    /// - DB접속에 필요한 환경변수가 주어지지 않은 경우
+    /// - DB에 접속이 제한시간을 초과한 경우
+    /// - DB 서버 접속에 SSL을 사용하는데 인증서 파일이 존재하지 않는 경우
+    pub fn flagged(page: &Pagination) -> Vec<FlaggedContent> {
+        let mut conn = db::get_conn();
+        conn.exec_map(
+            r"select flag_id, content_type, content_id, words, date_format(create_at, '%Y-%m-%d %H:%i:%s')
+            from flagged_content order by flag_id desc limit :limit offset :offset",
+            params! {
+                "limit" => page.limit(),
+                "offset" => page.offset(),
+            },
+            |(flag_id, content_type, content_id, words, create_at): (
+                u64,
+                String,
+                u64,
+                String,
+                String,
+            )| FlaggedContent {
+                flag_id,
+                content_type,
+                content_id,
+                words: words.split(',').map(str::to_string).collect(),
+                create_at: db::parse_datetime(&create_at).unwrap_or_default(),
+            },
+        )
+        .unwrap()
+    }
+}
@@ -0,0 +1,513 @@
+//! # 실제 MySQL 서버를 사용하는 통합 테스트
+//!
+//! Docker로 MySQL 컨테이너를 띄운 뒤 마이그레이션을 적용하고 포스트, 사용자, 댓글, 공감의
+//! 작성부터 삭제까지의 흐름을 확인한다. 마이그레이션과 코드가 사용하는 스키마가 서로 달라진 경우를 찾기 위한 것으로,
+//! Docker가 필요하므로 `mysql-tests` 기능을 켠 경우에만 실행된다.
+//!
+//! ```text
+//! cargo test --features mysql-tests --test mysql
+//! ```
+#![cfg(feature = "mysql-tests")]
+
+use actix_web::web;
+use code_mmunity_server::comment::{CommentRequest, CommentSort};
+use code_mmunity_server::config::{DbConfig, DbDriver};
+use code_mmunity_server::db;
+use code_mmunity_server::likes::{LikeMode, LikeRequest};
+use code_mmunity_server::migrate;
+use code_mmunity_server::password::{PasswordAccount, RegisterRequest, ResetRequest};
+use code_mmunity_server::post::{self, Post, PostRequest, PostType, UpdatePostRequest, Visibility};
+use code_mmunity_server::repository::{
+    CommentRepository, MySqlRepository, PostRepository, UserRepository,
+};
+use code_mmunity_server::user::{self, UpdateNotificationsRequest, UpdateProfileRequest, User};
+use mysql::prelude::*;
+use mysql::{params, Conn, OptsBuilder, Row};
+use std::sync::Mutex;
+use std::time::Instant;
+use testcontainers_modules::mysql::Mysql;
+use testcontainers_modules::testcontainers::runners::SyncRunner;
+use testcontainers_modules::testcontainers::Container;
+
+/// `statement_cache_latency`에서 각 질의를 실행하는 횟수이다.
+const BENCH_ROUNDS: u32 = 2000;
+
+/// 모든 테스트가 함께 사용하는 MySQL 컨테이너이다. 테스트 프로세스가 끝날 때까지 유지된다.
+static CONTAINER: Mutex<Option<Container<Mysql>>> = Mutex::new(None);
+
+/// MySQL 컨테이너를 띄우고 마이그레이션을 적용하는 메서드이다. 이미 띄운 경우 아무 작업도 하지 않는다.
+fn setup() {
+    let mut container = CONTAINER.lock().unwrap();
+    if container.is_some() {
+        return;
+    }
+    let mysql = Mysql::default()
+        .start()
+        .expect("MySQL 컨테이너를 시작할 수 없음");
+    db::init(DbConfig {
+        driver: DbDriver::MySql,
+        server: mysql.get_host().unwrap().to_string(),
+        port: mysql.get_host_port_ipv4(3306).unwrap(),
+        user: "root".to_string(),
+        password: String::new(),
+        database: "test".to_string(),
+        ssl_cert: None,
+        migrate_on_start: false,
+        auto_create: false,
+        pool_min: 1,
+        pool_max: 4,
+        connect_timeout: None,
+        query_timeout: None,
+        replica_url: None,
+        stmt_cache_size: 16,
+    });
+    // `DB_AUTO_CREATE`로 준비한 DB와 같은 스키마에서 확인한다.
+    let versions = migrate::bootstrap().expect("마이그레이션을 적용할 수 없음");
+    assert_eq!(versions.len(), migrate::MIGRATIONS.len());
+    *container = Some(mysql);
+}
+
+/// 테스트에 사용할 사용자를 등록하는 메서드이다.
+fn add_user(user_id: &str, user_name: &str) -> User {
+    let user: User = serde_json::from_value(serde_json::json!({
+        "user_id": user_id,
+        "user_name": user_name,
+    }))
+    .unwrap();
+    UserRepository::create(&MySqlRepository, user.clone()).unwrap();
+    user
+}
+
+/// 테스트에 사용할 포스트를 등록하는 메서드이다.
+fn add_post(user_id: &str, post_type: PostType) -> Post {
+    PostRepository::create(
+        &MySqlRepository,
+        &PostRequest {
+            user_id: user_id.to_string(),
+            title: "Hello".to_string(),
+            language: "rust".to_string(),
+            data: "fn main() {}".to_string(),
+            post_type,
+            tags: vec!["Rust".to_string(), "cli".to_string()],
+            visibility: Visibility::Public,
+            publish_at: None,
+            bot: false,
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn migrations_match_code_schema() {
+    setup();
+    assert!(migrate::run().unwrap().is_empty());
+    assert!(migrate::bootstrap().unwrap().is_empty());
+}
+
+#[test]
+fn bootstrap_applies_added_columns() {
+    setup();
+    let mut conn = db::get_conn();
+    let columns: Vec<(String, String)> = conn
+        .query(
+            "select table_name, column_name from information_schema.columns where table_schema = database()",
+        )
+        .unwrap();
+    for migration in migrate::MIGRATIONS {
+        for statement in migrate::statements(migration.sql) {
+            let Some(rest) = statement.strip_prefix("alter table ") else {
+                continue;
+            };
+            let table = rest.split_whitespace().next().unwrap();
+            for added in rest.split("add column ").skip(1) {
+                let column = added.split_whitespace().next().unwrap();
+                assert!(
+                    columns.contains(&(table.to_string(), column.to_string())),
+                    "V{}의 {}.{} 열이 없음",
+                    migration.version,
+                    table,
+                    column
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn user_crud() {
+    setup();
+    let repository = MySqlRepository;
+    let user = add_user("it-user", "before");
+    let found = UserRepository::get(&repository, "it-user").unwrap();
+    assert_eq!(found.user_name, "before");
+    assert!(!found.is_banned);
+    assert!(!repository.is_moderator("it-user"));
+
+    repository
+        .update_profile(
+            "it-user",
+            UpdateProfileRequest {
+                user_name: Some("after".to_string()),
+                bio: Some("hello".to_string()),
+                avatar_url: None,
+                website: None,
+                preferred_language: Some("rust".to_string()),
+            },
+        )
+        .unwrap();
+    let found = UserRepository::get(&repository, "it-user").unwrap();
+    assert_eq!(found.user_name, "after");
+    assert_eq!(found.bio.as_deref(), Some("hello"));
+    assert_eq!(found.preferred_language.as_deref(), Some("rust"));
+
+    repository
+        .update_notification_settings(
+            "it-user",
+            UpdateNotificationsRequest {
+                email: Some("it-user@example.com".to_string()),
+                comments: Some(false),
+                post_removed: None,
+                messages: None,
+            },
+        )
+        .unwrap();
+    let settings = repository.get_notification_settings("it-user").unwrap();
+    assert_eq!(settings.email.as_deref(), Some("it-user@example.com"));
+    assert!(!settings.comments);
+    assert!(settings.post_removed);
+
+    UserRepository::delete(&repository, user).unwrap();
+    assert!(UserRepository::get(&repository, "it-user").is_none());
+}
+
+#[test]
+fn post_crud() {
+    setup();
+    let repository = MySqlRepository;
+    add_user("it-post-owner", "owner");
+    let post = add_post("it-post-owner", PostType::Snippet);
+    let post_id = post.post_id.to_string();
+    let found = PostRepository::get(&repository, &post_id).unwrap();
+    assert_eq!(found.user_name, "owner");
+    assert_eq!(found.tags, vec!["cli", "rust"]);
+    assert_eq!(found.version, 0);
+    assert!(!found.bot);
+    assert!(repository.is_published(post.post_id));
+    assert_eq!(
+        PostRepository::get_owner(&repository, &post_id).as_deref(),
+        Some("it-post-owner")
+    );
+
+    let update = |version| UpdatePostRequest {
+        title: Some("Updated".to_string()),
+        language: None,
+        data: None,
+        visibility: None,
+        version,
+    };
+    assert!(PostRepository::update(&repository, &post_id, update(0)).unwrap());
+    assert!(!PostRepository::update(&repository, &post_id, update(0)).unwrap());
+    let found = PostRepository::get(&repository, &post_id).unwrap();
+    assert_eq!(found.title, "Updated");
+    assert_eq!(found.version, 1);
+    assert!(found.updated_at.is_some());
+
+    PostRepository::delete(&repository, &post_id).unwrap();
+    assert!(PostRepository::get(&repository, &post_id).is_none());
+    assert_eq!(
+        repository.get_deleted_owner(post.post_id).as_deref(),
+        Some("it-post-owner")
+    );
+    repository.restore(post.post_id).unwrap();
+    assert!(PostRepository::get(&repository, &post_id).is_some());
+}
+
+#[test]
+fn comment_crud() {
+    setup();
+    let repository = MySqlRepository;
+    add_user("it-question-owner", "asker");
+    add_user("it-commenter", "answerer");
+    let post = add_post("it-question-owner", PostType::Question);
+    let comment = CommentRepository::create(
+        &repository,
+        &CommentRequest {
+            post_id: post.post_id as u32,
+            user_id: "it-commenter".to_string(),
+            data: "Use cargo".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(comment.user_name, "answerer");
+    assert_eq!(
+        repository.get_post_id(comment.comment_id),
+        Some(post.post_id)
+    );
+
+    assert!(CommentRepository::update(
+        &repository,
+        comment.comment_id,
+        "Use cargo run".to_string(),
+        0
+    )
+    .unwrap());
+    repository
+        .set_accepted_answer(post.post_id, Some(comment.comment_id))
+        .unwrap();
+    let comments = repository.get_by_post(post.post_id as u32, CommentSort::Newest);
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].data, "Use cargo run");
+    assert!(comments[0].is_accepted);
+    assert_eq!(
+        UserRepository::get(&repository, "it-commenter")
+            .unwrap()
+            .accepted_answers,
+        1
+    );
+
+    assert_eq!(
+        CommentRepository::delete(&repository, comment.comment_id).unwrap(),
+        Some(post.post_id as u32)
+    );
+    assert!(repository
+        .get_by_post(post.post_id as u32, CommentSort::Newest)
+        .is_empty());
+    assert_eq!(
+        PostRepository::get(&repository, &post.post_id.to_string())
+            .unwrap()
+            .accepted_comment_id,
+        None
+    );
+}
+
+#[test]
+fn delete_user_leaves_no_rows() {
+    setup();
+    let repository = MySqlRepository;
+    let user = add_user("it-leaving", "leaving");
+    add_user("it-staying", "staying");
+    let own_post = add_post("it-leaving", PostType::Snippet);
+    let other_post = add_post("it-staying", PostType::Snippet);
+    let comment = |post_id: u64, user_id: &str| {
+        CommentRepository::create(
+            &repository,
+            &CommentRequest {
+                post_id: post_id as u32,
+                user_id: user_id.to_string(),
+                data: "Nice".to_string(),
+            },
+        )
+        .unwrap()
+    };
+    let own_comment = comment(other_post.post_id, "it-leaving");
+    let reply = comment(own_post.post_id, "it-staying");
+    let other_comment = comment(other_post.post_id, "it-staying");
+    let mut conn = db::get_conn();
+    conn.exec_drop(
+        r"insert into attachment(post_id, user_id, file_name, content_type, bytes, url)
+        values(:post_id, 'it-leaving', 'a.png', 'image/png', 1, '/uploads/a.png')",
+        params! { "post_id" => own_post.post_id },
+    )
+    .unwrap();
+    conn.exec_drop(
+        r"insert into upload(user_id, storage_key, bytes) values
+        ('it-leaving', 'avatars/it-leaving.png', 1),
+        ('it-staying', concat('attachments/', :post_id, '/b.png'), 1)",
+        params! { "post_id" => own_post.post_id },
+    )
+    .unwrap();
+    conn.exec_drop(
+        r"insert into post_report(post_id, user_id, reason) values
+        (:own_post, 'it-staying', 'spam'), (:other_post, 'it-leaving', 'spam')",
+        params! {
+            "own_post" => own_post.post_id,
+            "other_post" => other_post.post_id,
+        },
+    )
+    .unwrap();
+    conn.exec_drop(
+        r"insert into comment_report(comment_id, user_id, reason) values
+        (:own_comment, 'it-staying', 'spam'), (:other_comment, 'it-leaving', 'spam')",
+        params! {
+            "own_comment" => own_comment.comment_id,
+            "other_comment" => other_comment.comment_id,
+        },
+    )
+    .unwrap();
+    conn.exec_drop(
+        "update comment set report_count = 1 where comment_id = :comment_id",
+        params! { "comment_id" => other_comment.comment_id },
+    )
+    .unwrap();
+    conn.exec_drop(
+        r"insert into flagged_content(content_type, content_id, words) values
+        ('post', :own_post, 'bad'), ('comment', :reply, 'bad')",
+        params! {
+            "own_post" => own_post.post_id,
+            "reply" => reply.comment_id,
+        },
+    )
+    .unwrap();
+
+    let mut keys = UserRepository::delete(&repository, user).unwrap();
+    keys.sort();
+    assert_eq!(
+        keys,
+        vec![
+            format!("attachments/{}/b.png", own_post.post_id),
+            "avatars/it-leaving.png".to_string(),
+        ]
+    );
+    let mut remaining = |sql: &str| -> u64 {
+        conn.exec_first(
+            sql,
+            params! {
+                "user_id" => "it-leaving",
+                "post_id" => own_post.post_id,
+                "comment_id" => own_comment.comment_id,
+                "reply_id" => reply.comment_id,
+            },
+        )
+        .unwrap()
+        .unwrap()
+    };
+    for sql in [
+        "select count(*) from user where user_id = :user_id",
+        "select count(*) from post where user_id = :user_id or post_id = :post_id",
+        "select count(*) from comment where user_id = :user_id or post_id = :post_id",
+        "select count(*) from attachment where user_id = :user_id or post_id = :post_id",
+        "select count(*) from upload where user_id = :user_id or storage_key like concat('attachments/', :post_id, '/%')",
+        "select count(*) from post_report where user_id = :user_id or post_id = :post_id",
+        "select count(*) from comment_report where user_id = :user_id or comment_id in (:comment_id, :reply_id)",
+        r"select count(*) from flagged_content
+        where (content_type = 'post' and content_id = :post_id)
+        or (content_type = 'comment' and content_id in (:comment_id, :reply_id))",
+    ] {
+        assert_eq!(remaining(sql), 0, "{}", sql);
+    }
+    let report_count: u64 = conn
+        .exec_first(
+            "select report_count from comment where comment_id = :comment_id",
+            params! { "comment_id" => other_comment.comment_id },
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(report_count, 0);
+}
+
+#[test]
+fn likes_update_counts() {
+    setup();
+    add_user("it-like-owner", "owner");
+    add_user("it-liker", "liker");
+    let post = add_post("it-like-owner", PostType::Snippet);
+    let like = |mode| {
+        LikeRequest::modify_likes(
+            "it-liker",
+            web::Query(LikeRequest {
+                post_id: post.post_id as u32,
+                mode,
+            }),
+        )
+    };
+    assert_eq!(like(LikeMode::Increment).unwrap(), Some(1));
+    assert_eq!(Post::get_counts(post.post_id).unwrap().likes, 1);
+    assert_eq!(like(LikeMode::Decrement).unwrap(), Some(0));
+    assert_eq!(like(LikeMode::Decrement).unwrap(), Some(0));
+    assert_eq!(Post::get_counts(post.post_id).unwrap().likes, 0);
+    let missing = LikeRequest::modify_likes(
+        "it-liker",
+        web::Query(LikeRequest {
+            post_id: u32::MAX,
+            mode: LikeMode::Increment,
+        }),
+    );
+    assert_eq!(missing.unwrap(), None);
+}
+
+#[test]
+fn password_reset_bumps_token_version() {
+    setup();
+    let user_id = PasswordAccount::register(
+        &RegisterRequest {
+            email: "it-reset@example.com".to_string(),
+            password: "correct horse battery staple".to_string(),
+            user_name: "reset".to_string(),
+        },
+        true,
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(User::token_version(&user_id), 0);
+    let token = PasswordAccount::issue_reset("it-reset@example.com")
+        .unwrap()
+        .unwrap();
+    assert!(PasswordAccount::reset(&ResetRequest {
+        token,
+        password: "another horse battery staple".to_string(),
+    })
+    .unwrap());
+    assert_eq!(User::token_version(&user_id), 1);
+}
+
+/// 준비된 질의 캐시를 사용할 때와 사용하지 않을 때의 포스트 목록, 사용자 조회 평균 지연 시간을 비교한다.
+///
+/// 오래 걸리므로 기본으로는 실행되지 않는다.
+/// ```text
+/// cargo test --features mysql-tests --test mysql -- --ignored --nocapture statement_cache_latency
+/// ```
+#[test]
+#[ignore]
+fn statement_cache_latency() {
+    setup();
+    add_user("it-bench-user", "bench");
+    for _ in 0..20 {
+        add_post("it-bench-user", PostType::Snippet);
+    }
+    let (host, port) = {
+        let container = CONTAINER.lock().unwrap();
+        let mysql = container.as_ref().unwrap();
+        (
+            mysql.get_host().unwrap().to_string(),
+            mysql.get_host_port_ipv4(3306).unwrap(),
+        )
+    };
+    let measure = |stmt_cache_size: usize| {
+        let mut conn = Conn::new(
+            OptsBuilder::new()
+                .ip_or_hostname(Some(host.clone()))
+                .tcp_port(port)
+                .user(Some("root"))
+                .db_name(Some("test"))
+                .stmt_cache_size(stmt_cache_size),
+        )
+        .unwrap();
+        let started = Instant::now();
+        for _ in 0..BENCH_ROUNDS {
+            let _: Vec<Row> = conn
+                .exec(
+                    post::FEED_QUERY,
+                    params! {
+                        "limit" => 20,
+                        "offset" => 0,
+                    },
+                )
+                .unwrap();
+            let _: Option<Row> = conn
+                .exec_first(
+                    user::USER_QUERY,
+                    params! {
+                        "user_id" => "it-bench-user",
+                    },
+                )
+                .unwrap();
+        }
+        started.elapsed() / BENCH_ROUNDS
+    };
+    let uncached = measure(0);
+    let cached = measure(256);
+    println!(
+        "준비된 질의 캐시 미사용: {:?}, 사용: {:?}",
+        uncached, cached
+    );
+}